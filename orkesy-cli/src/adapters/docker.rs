@@ -1,27 +1,32 @@
 #![cfg(feature = "docker")]
 
-use std::collections::BTreeMap;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use bollard::Docker;
 use bollard::container::{
-    Config, CreateContainerOptions, LogsOptions, RemoveContainerOptions, StartContainerOptions,
-    StopContainerOptions,
+    Config, CreateContainerOptions, InspectContainerOptions, KillContainerOptions, LogOutput,
+    LogsOptions, RemoveContainerOptions, StartContainerOptions, StopContainerOptions,
 };
+use bollard::image::CreateImageOptions;
+use bollard::models::{ContainerState, ContainerStateStatusEnum, HostConfig, PortBinding};
+use bollard::system::EventsOptions;
 use futures_util::StreamExt;
 use tokio::sync::{broadcast, mpsc};
 
 use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, LogStream};
-use orkesy_core::unit::{Unit, UnitId, UnitKind, UnitMetrics, UnitStatus};
+use orkesy_core::unit::{DEFAULT_STOP_TIMEOUT_MS, Unit, UnitId, UnitKind, UnitMetrics, UnitStatus};
+
+/// Label put on every container the adapter creates, holding the unit id
+const UNIT_LABEL: &str = "orkesy.unit";
 
 pub struct DockerAdapter {
     client: Option<Docker>,
     units: BTreeMap<UnitId, Unit>,
+    /// Container id per running unit (the container name for units started
+    /// through a `docker ...` command)
     containers: BTreeMap<UnitId, String>,
-    next_id: Arc<AtomicU64>,
 }
 
 impl DockerAdapter {
@@ -30,7 +35,6 @@ impl DockerAdapter {
             client: None,
             units: BTreeMap::new(),
             containers: BTreeMap::new(),
-            next_id: Arc::new(AtomicU64::new(1)),
         }
     }
 
@@ -82,27 +86,49 @@ impl DockerAdapter {
         Ok(())
     }
 
-    /// Start a container for a unit
+    /// Handles `AdapterCommand::Start`; units that aren't docker units are ignored
+    async fn start(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        if !self.units.contains_key(id) {
+            return; // Not a docker unit
+        }
+
+        if self.containers.contains_key(id) {
+            self.emit_log(event_tx, id, "[warn] already running".into());
+            return;
+        }
+
+        self.emit_status(event_tx, id, UnitStatus::Starting);
+
+        match self.start_container(id, event_tx).await {
+            Ok(status) => {
+                self.emit_status(event_tx, id, status);
+            }
+            Err(e) => {
+                self.emit_status(event_tx, id, UnitStatus::Errored { message: e.clone() });
+                self.emit_log(event_tx, id, format!("[error] {}", e));
+            }
+        }
+    }
+
+    /// Start a container for a unit, returning the state it is in after
+    /// starting
     async fn start_container(
         &mut self,
         id: &UnitId,
         event_tx: &broadcast::Sender<AdapterEvent>,
-    ) -> Result<(), String> {
-        let client = self.client.as_ref().ok_or("Docker not connected")?;
+    ) -> Result<UnitStatus, String> {
+        let client = self.client.clone().ok_or("Docker not connected")?;
         let unit = self
             .units
             .get(id)
+            .cloned()
             .ok_or_else(|| format!("unit not found: {}", id))?;
 
-        // For docker units, the start command is typically "docker compose up" or similar
-        // We'll run it as a shell command rather than creating a container directly
-        // This is more flexible and matches how users typically define docker units
-
-        // Container name
         let container_name = format!("orkesy-{}", id);
 
-        // If start command looks like a docker command, run it via shell
-        if unit.start.starts_with("docker ") {
+        // Without an image, a `docker ...` start command (e.g. `docker compose
+        // up -d postgres`) is run through the shell instead
+        if unit.docker_image.is_none() && unit.start.starts_with("docker ") {
             self.emit_log(event_tx, id, format!("$ {}", unit.start));
 
             let output = tokio::process::Command::new("sh")
@@ -154,7 +180,6 @@ impl DockerAdapter {
                 let tx = event_tx.clone();
                 let unit_id = id.clone();
                 let logs_cmd = logs_cmd.clone();
-                let next_id = self.next_id.clone();
 
                 tokio::spawn(async move {
                     let mut child = match tokio::process::Command::new("sh")
@@ -190,58 +215,80 @@ impl DockerAdapter {
                 });
             }
 
-            return Ok(());
+            return Ok(UnitStatus::Running);
         }
 
-        // Otherwise, create an actual Docker container
-        // Parse the start command to get image and command
-        let parts: Vec<&str> = unit.start.split_whitespace().collect();
-        let image = parts.first().copied().unwrap_or("busybox");
-        let cmd: Vec<&str> = if parts.len() > 1 {
-            parts[1..].to_vec()
-        } else {
-            vec![]
+        let (image, cmd) = match &unit.docker_image {
+            Some(image) => (image.clone(), container_command(&unit)),
+            // Legacy form: `start: <image> [args...]`
+            None => {
+                let mut parts = unit.start.split_whitespace().map(String::from);
+                let image = parts.next().unwrap_or_else(|| "busybox".into());
+                (image, parts.collect())
+            }
         };
-
+        let (exposed_ports, port_bindings) = parse_ports(&unit.docker_ports)?;
         let env: Vec<String> = unit
             .env
             .iter()
             .map(|(k, v)| format!("{}={}", k, v))
             .collect();
 
+        if client.inspect_image(&image).await.is_err() {
+            self.emit_log(event_tx, id, format!("pulling {}...", image));
+            let options = CreateImageOptions {
+                from_image: image.clone(),
+                ..Default::default()
+            };
+            let mut pull = client.create_image(Some(options), None, None);
+            while let Some(progress) = pull.next().await {
+                progress.map_err(|e| format!("failed to pull {}: {}", image, e))?;
+            }
+        }
+
+        // A container left behind by an earlier session would clash by name
+        let _ = client
+            .remove_container(
+                &container_name,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await;
+
         let container_config = Config {
-            image: Some(image),
-            cmd: if cmd.is_empty() { None } else { Some(cmd) },
-            env: Some(env.iter().map(|s| s.as_str()).collect()),
+            image: Some(image.clone()),
+            cmd: (!cmd.is_empty()).then_some(cmd),
+            env: Some(env),
+            exposed_ports: Some(exposed_ports),
+            labels: Some(HashMap::from([(UNIT_LABEL.to_string(), id.clone())])),
+            host_config: Some(HostConfig {
+                port_bindings: Some(port_bindings),
+                ..Default::default()
+            }),
             ..Default::default()
         };
-
-        // Create container
         let create_options = CreateContainerOptions {
             name: container_name.clone(),
             platform: None,
         };
-
         let response = client
             .create_container(Some(create_options), container_config)
             .await
             .map_err(|e| format!("failed to create container: {}", e))?;
-
         let container_id = response.id;
-        self.containers.insert(id.clone(), container_id.clone());
 
-        // Start container
         client
             .start_container(&container_id, None::<StartContainerOptions<String>>)
             .await
             .map_err(|e| format!("failed to start container: {}", e))?;
+        self.containers.insert(id.clone(), container_id.clone());
 
-        // Spawn log streaming task
         let log_tx = event_tx.clone();
-        let service_id = id.clone();
+        let unit_id = id.clone();
         let log_client = client.clone();
         let log_container_id = container_id.clone();
-
         tokio::spawn(async move {
             let options = LogsOptions::<String> {
                 follow: true,
@@ -249,25 +296,33 @@ impl DockerAdapter {
                 stderr: true,
                 ..Default::default()
             };
-
-            let mut stream = log_client.logs(&log_container_id, Some(options));
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(log) => {
-                        let text = log.to_string();
-                        let _ = log_tx.send(AdapterEvent::LogLine {
-                            id: service_id.clone(),
-                            stream: LogStream::Stdout,
-                            text,
-                        });
-                    }
-                    Err(_) => break,
+            let mut logs = log_client.logs(&log_container_id, Some(options));
+            while let Some(Ok(frame)) = logs.next().await {
+                let (stream, bytes) = match frame {
+                    LogOutput::StdErr { message } => (LogStream::Stderr, message),
+                    LogOutput::StdOut { message }
+                    | LogOutput::Console { message }
+                    | LogOutput::StdIn { message } => (LogStream::Stdout, message),
+                };
+                for line in String::from_utf8_lossy(&bytes).lines() {
+                    let _ = log_tx.send(AdapterEvent::LogLine {
+                        id: unit_id.clone(),
+                        stream,
+                        text: line.to_string(),
+                    });
                 }
             }
         });
 
-        Ok(())
+        let state = client
+            .inspect_container(&container_id, None::<InspectContainerOptions>)
+            .await
+            .ok()
+            .and_then(|c| c.state);
+        Ok(state
+            .as_ref()
+            .map(unit_status)
+            .unwrap_or(UnitStatus::Running))
     }
 
     /// Stop a container
@@ -294,30 +349,210 @@ impl DockerAdapter {
         }
 
         // Otherwise try to stop via Docker API
+        let stop_timeout_secs = unit
+            .map(|u| u.stop_timeout_ms)
+            .unwrap_or(DEFAULT_STOP_TIMEOUT_MS)
+            .div_ceil(1000);
         let client = self.client.as_ref().ok_or("Docker not connected")?;
 
         if let Some(container_id) = self.containers.remove(id) {
-            let options = StopContainerOptions { t: 10 };
+            // Docker sends SIGTERM, then SIGKILL after `t` seconds
+            let options = StopContainerOptions {
+                t: stop_timeout_secs as i64,
+            };
 
             client
                 .stop_container(&container_id, Some(options))
                 .await
                 .map_err(|e| format!("failed to stop container: {}", e))?;
 
-            // Remove container
-            let remove_options = RemoveContainerOptions {
-                force: true,
-                ..Default::default()
-            };
-
-            client
-                .remove_container(&container_id, Some(remove_options))
-                .await
-                .ok(); // Ignore removal errors
+            remove_container(client, &container_id).await;
         }
 
         Ok(())
     }
+
+    /// Kill a container with SIGKILL and remove it
+    async fn kill_container(&mut self, id: &UnitId) -> Result<(), String> {
+        let client = self.client.as_ref().ok_or("Docker not connected")?;
+        let Some(container_id) = self.containers.remove(id) else {
+            return Err("container not running".into());
+        };
+
+        let options = KillContainerOptions { signal: "SIGKILL" };
+        let killed = client
+            .kill_container(&container_id, Some(options))
+            .await
+            .map_err(|e| format!("failed to kill container: {}", e));
+        remove_container(client, &container_id).await;
+        killed
+    }
+
+    /// A container stopped without going through the adapter, e.g. it
+    /// crashed or was removed with `docker kill`
+    async fn container_died(
+        &mut self,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+        died: ContainerDied,
+    ) {
+        // Containers stopped by the adapter are untracked before they die
+        if self.containers.get(&died.unit_id) != Some(&died.container_id) {
+            return;
+        }
+        self.containers.remove(&died.unit_id);
+        if let Some(client) = &self.client {
+            remove_container(client, &died.container_id).await;
+        }
+
+        self.emit_status(
+            event_tx,
+            &died.unit_id,
+            UnitStatus::Exited { code: died.code },
+        );
+        self.emit_log(
+            event_tx,
+            &died.unit_id,
+            format!("container exited with code: {:?}", died.code),
+        );
+    }
+}
+
+/// Runs a `DockerAdapter` for the docker units among `units` next to another
+/// adapter, which should read the returned receiver (see
+/// [`super::route_commands`]). Commands for docker units go to the other
+/// adapter if Docker is unreachable.
+pub fn split_docker_commands(
+    command_rx: mpsc::Receiver<AdapterCommand>,
+    units: &[Unit],
+    event_tx: broadcast::Sender<AdapterEvent>,
+) -> mpsc::Receiver<AdapterCommand> {
+    let docker_ids: BTreeSet<UnitId> = units
+        .iter()
+        .filter(|u| u.kind == UnitKind::Docker)
+        .map(|u| u.id.clone())
+        .collect();
+    if docker_ids.is_empty() {
+        return command_rx;
+    }
+
+    let (docker_tx, docker_rx) = mpsc::channel(100);
+    let docker_units = units.to_vec();
+    tokio::spawn(async move {
+        DockerAdapter::new()
+            .run(docker_rx, event_tx, docker_units)
+            .await;
+    });
+    super::route_commands(command_rx, docker_ids, docker_tx)
+}
+
+/// Removes a stopped container, ignoring errors (it may already be gone)
+async fn remove_container(client: &Docker, container_id: &str) {
+    let options = RemoveContainerOptions {
+        force: true,
+        ..Default::default()
+    };
+    let _ = client.remove_container(container_id, Some(options)).await;
+}
+
+/// Command for an image-based unit: `start` split on whitespace, or run
+/// through `sh -c` with `shell: true`. Empty keeps the image's command.
+fn container_command(unit: &Unit) -> Vec<String> {
+    if unit.start.trim().is_empty() {
+        Vec::new()
+    } else if unit.uses_shell() {
+        vec!["sh".into(), "-c".into(), unit.start.clone()]
+    } else {
+        unit.start.split_whitespace().map(String::from).collect()
+    }
+}
+
+type ExposedPorts = HashMap<String, HashMap<(), ()>>;
+type PortBindings = HashMap<String, Option<Vec<PortBinding>>>;
+
+/// Parses `docker_ports` entries (`8080:80`, `5353:53/udp`, or `80` to
+/// publish a port on the same host port)
+fn parse_ports(ports: &[String]) -> Result<(ExposedPorts, PortBindings), String> {
+    let mut exposed = HashMap::new();
+    let mut bindings = HashMap::new();
+    for spec in ports {
+        let (ports, protocol) = spec.split_once('/').unwrap_or((spec, "tcp"));
+        let (host, container) = ports.split_once(':').unwrap_or((ports, ports));
+        if host.parse::<u16>().is_err() || container.parse::<u16>().is_err() {
+            return Err(format!("invalid docker_ports entry: {}", spec));
+        }
+
+        let key = format!("{}/{}", container, protocol);
+        exposed.insert(key.clone(), HashMap::new());
+        bindings.insert(
+            key,
+            Some(vec![PortBinding {
+                host_ip: None,
+                host_port: Some(host.to_string()),
+            }]),
+        );
+    }
+    Ok((exposed, bindings))
+}
+
+/// Maps Docker's container state onto the unit lifecycle
+fn unit_status(state: &ContainerState) -> UnitStatus {
+    match state.status {
+        Some(ContainerStateStatusEnum::CREATED | ContainerStateStatusEnum::RESTARTING) => {
+            UnitStatus::Starting
+        }
+        Some(ContainerStateStatusEnum::RUNNING | ContainerStateStatusEnum::PAUSED) => {
+            UnitStatus::Running
+        }
+        Some(ContainerStateStatusEnum::REMOVING) => UnitStatus::Stopping,
+        Some(ContainerStateStatusEnum::EXITED) => UnitStatus::Exited {
+            code: state.exit_code.map(|c| c as i32),
+        },
+        Some(ContainerStateStatusEnum::DEAD) => UnitStatus::Errored {
+            message: state
+                .error
+                .clone()
+                .filter(|e| !e.is_empty())
+                .unwrap_or_else(|| "container is dead".into()),
+        },
+        Some(ContainerStateStatusEnum::EMPTY) | None => UnitStatus::Unknown,
+    }
+}
+
+/// A `die` event for a container started by this adapter
+struct ContainerDied {
+    unit_id: UnitId,
+    container_id: String,
+    code: Option<i32>,
+}
+
+/// Forwards `die` events of orkesy containers until the event stream ends
+async fn watch_container_events(client: Docker, died_tx: mpsc::Sender<ContainerDied>) {
+    let options = EventsOptions::<String> {
+        filters: HashMap::from([
+            ("type".to_string(), vec!["container".to_string()]),
+            ("event".to_string(), vec!["die".to_string()]),
+            ("label".to_string(), vec![UNIT_LABEL.to_string()]),
+        ]),
+        ..Default::default()
+    };
+    let mut events = client.events(Some(options));
+    while let Some(Ok(event)) = events.next().await {
+        let Some(actor) = event.actor else {
+            continue;
+        };
+        let attributes = actor.attributes.unwrap_or_default();
+        let (Some(container_id), Some(unit_id)) = (actor.id, attributes.get(UNIT_LABEL)) else {
+            continue;
+        };
+        let died = ContainerDied {
+            unit_id: unit_id.clone(),
+            container_id,
+            code: attributes.get("exitCode").and_then(|c| c.parse().ok()),
+        };
+        if died_tx.send(died).await.is_err() {
+            return;
+        }
+    }
 }
 
 impl Default for DockerAdapter {
@@ -357,8 +592,24 @@ impl Adapter for DockerAdapter {
 
         self.emit_log(&event_tx, "docker", "connected to Docker".into());
 
+        let (died_tx, mut died_rx) = mpsc::channel(16);
+        if let Some(client) = self.client.clone() {
+            tokio::spawn(watch_container_events(client, died_tx));
+        }
+
         // Main command loop
-        while let Some(cmd) = command_rx.recv().await {
+        loop {
+            let cmd = tokio::select! {
+                cmd = command_rx.recv() => match cmd {
+                    Some(cmd) => cmd,
+                    None => break,
+                },
+                Some(died) = died_rx.recv() => {
+                    self.container_died(&event_tx, died).await;
+                    continue;
+                }
+            };
+
             match cmd {
                 AdapterCommand::Shutdown => {
                     // Stop all containers
@@ -369,30 +620,13 @@ impl Adapter for DockerAdapter {
                     break;
                 }
 
-                AdapterCommand::Start { id } => {
-                    if !self.units.contains_key(&id) {
-                        continue; // Not a docker unit
-                    }
-
-                    if self.containers.contains_key(&id) {
-                        self.emit_log(&event_tx, &id, "[warn] already running".into());
-                        continue;
-                    }
-
-                    self.emit_status(&event_tx, &id, UnitStatus::Starting);
+                AdapterCommand::Start { id } => self.start(&id, &event_tx).await,
 
-                    match self.start_container(&id, &event_tx).await {
-                        Ok(()) => {
-                            self.emit_status(&event_tx, &id, UnitStatus::Running);
-                        }
-                        Err(e) => {
-                            self.emit_status(
-                                &event_tx,
-                                &id,
-                                UnitStatus::Errored { message: e.clone() },
-                            );
-                            self.emit_log(&event_tx, &id, format!("[error] {}", e));
-                        }
+                // Containers are started in the order given; dependency
+                // ordering is left to the process adapter
+                AdapterCommand::StartMany { ids } => {
+                    for id in ids {
+                        self.start(&id, &event_tx).await;
                     }
                 }
 
@@ -424,21 +658,8 @@ impl Adapter for DockerAdapter {
                     let _ = self.stop_container(&id).await;
                     tokio::time::sleep(Duration::from_millis(100)).await;
 
-                    self.emit_status(&event_tx, &id, UnitStatus::Starting);
-                    match self.start_container(&id, &event_tx).await {
-                        Ok(()) => {
-                            self.emit_status(&event_tx, &id, UnitStatus::Running);
-                            self.emit_log(&event_tx, &id, "restarted".into());
-                        }
-                        Err(e) => {
-                            self.emit_status(
-                                &event_tx,
-                                &id,
-                                UnitStatus::Errored { message: e.clone() },
-                            );
-                            self.emit_log(&event_tx, &id, format!("[error] restart failed: {}", e));
-                        }
-                    }
+                    self.start(&id, &event_tx).await;
+                    self.emit_log(&event_tx, &id, "restarted".into());
                 }
 
                 AdapterCommand::Kill { id } => {
@@ -446,18 +667,15 @@ impl Adapter for DockerAdapter {
                         continue;
                     }
 
-                    // Force remove container
-                    if let Some(container_id) = self.containers.remove(&id) {
-                        if let Some(client) = &self.client {
-                            let options = RemoveContainerOptions {
-                                force: true,
-                                ..Default::default()
-                            };
-                            let _ = client.remove_container(&container_id, Some(options)).await;
+                    match self.kill_container(&id).await {
+                        Ok(()) => {
+                            self.emit_status(&event_tx, &id, UnitStatus::Stopped);
+                            self.emit_log(&event_tx, &id, "killed".into());
+                        }
+                        Err(e) => {
+                            self.emit_log(&event_tx, &id, format!("[warn] {}", e));
                         }
                     }
-                    self.emit_status(&event_tx, &id, UnitStatus::Stopped);
-                    self.emit_log(&event_tx, &id, "killed".into());
                 }
 
                 AdapterCommand::Toggle { id } => {
@@ -471,20 +689,7 @@ impl Adapter for DockerAdapter {
                         let _ = self.stop_container(&id).await;
                         self.emit_status(&event_tx, &id, UnitStatus::Stopped);
                     } else {
-                        self.emit_status(&event_tx, &id, UnitStatus::Starting);
-                        match self.start_container(&id, &event_tx).await {
-                            Ok(()) => {
-                                self.emit_status(&event_tx, &id, UnitStatus::Running);
-                            }
-                            Err(e) => {
-                                self.emit_status(
-                                    &event_tx,
-                                    &id,
-                                    UnitStatus::Errored { message: e.clone() },
-                                );
-                                self.emit_log(&event_tx, &id, format!("[error] {}", e));
-                            }
-                        }
+                        self.start(&id, &event_tx).await;
                     }
                 }
 
@@ -494,6 +699,14 @@ impl Adapter for DockerAdapter {
                     }
                 }
 
+                AdapterCommand::AddNote { id, text } => {
+                    let _ = event_tx.send(AdapterEvent::UserNote {
+                        id,
+                        text,
+                        at: SystemTime::now(),
+                    });
+                }
+
                 AdapterCommand::Install { id } => {
                     // Docker units typically don't have install steps
                     if self.units.contains_key(&id) {
@@ -501,6 +714,23 @@ impl Adapter for DockerAdapter {
                     }
                 }
 
+                AdapterCommand::GetLogs { reply_tx, .. } => {
+                    // Logs are streamed from `docker logs`, not buffered here
+                    let _ = reply_tx.send(Vec::new());
+                }
+
+                AdapterCommand::Watch { id, .. } => {
+                    if self.units.contains_key(&id) {
+                        self.emit_log(
+                            &event_tx,
+                            &id,
+                            "[warn] watches are not supported for docker units".into(),
+                        );
+                    }
+                }
+
+                AdapterCommand::CancelWatch { .. } => {}
+
                 AdapterCommand::Exec { id, cmd } => {
                     if !self.units.contains_key(&id) {
                         continue;
@@ -0,0 +1,84 @@
+//! Forwards unit log lines to the systemd journal via `libsystemd`, so
+//! `journalctl -t <identifier>` shows them alongside system logs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use orkesy_core::log_filter::{LogLevel, detect_level};
+use orkesy_core::state::LogStream;
+use orkesy_core::unit::LogSinkConfig;
+
+use crate::ui::ansi::strip_ansi;
+
+pub struct LogSink {
+    identifier: String,
+    /// Set after the first failed send, so a missing journal is reported once
+    failed: AtomicBool,
+}
+
+impl LogSink {
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    pub fn open(config: &LogSinkConfig) -> Result<Self, String> {
+        use libsystemd::logging::SD_JOURNAL_SOCK_PATH;
+
+        let LogSinkConfig::Journal { identifier } = config;
+        if !std::path::Path::new(SD_JOURNAL_SOCK_PATH).exists() {
+            return Err(format!("journal socket {} not found", SD_JOURNAL_SOCK_PATH));
+        }
+        Ok(Self {
+            identifier: identifier.clone(),
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    #[cfg(not(all(feature = "systemd", target_os = "linux")))]
+    pub fn open(config: &LogSinkConfig) -> Result<Self, String> {
+        let _ = config;
+        Err("the journal log sink needs a Linux build with the `systemd` feature".to_string())
+    }
+
+    /// Returns the error of the first failed send; later failures are silent
+    pub fn send(&self, unit_id: &str, stream: LogStream, text: &str) -> Option<String> {
+        let message = strip_ansi(text);
+        let level = detect_level(&message);
+        let stream = match stream {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+            LogStream::System => "system",
+        };
+        let fields = [
+            ("SYSLOG_IDENTIFIER", self.identifier.as_str()),
+            ("ORKESY_UNIT", unit_id),
+            ("ORKESY_STREAM", stream),
+        ];
+
+        match self.write(level, &message, &fields) {
+            Err(e) if !self.failed.swap(true, Ordering::Relaxed) => {
+                Some(format!("journal send failed: {}", e))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    fn write(&self, level: LogLevel, message: &str, fields: &[(&str, &str)]) -> Result<(), String> {
+        use libsystemd::logging::{Priority, journal_send};
+
+        let priority = match level {
+            LogLevel::Error => Priority::Error,
+            LogLevel::Warn => Priority::Warning,
+            LogLevel::Info => Priority::Info,
+            LogLevel::Debug => Priority::Debug,
+        };
+        journal_send(priority, message, fields.iter().copied()).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(all(feature = "systemd", target_os = "linux")))]
+    fn write(
+        &self,
+        _level: LogLevel,
+        _message: &str,
+        _fields: &[(&str, &str)],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -1,9 +1,181 @@
+mod journal;
 mod process;
+mod ssh;
 
 #[cfg(feature = "docker")]
 mod docker;
 
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use tokio::sync::{broadcast, mpsc};
+
+use orkesy_core::adapter::{AdapterCommand, AdapterEvent};
+use orkesy_core::unit::{Unit, UnitId};
+
 pub use process::{ProcessAdapter, format_bytes};
 
-#[cfg(feature = "docker")]
-pub use docker::DockerAdapter;
+/// Hands the commands for docker units (when compiled in) and ssh units to
+/// their own adapters, spawned here, and returns the rest for a
+/// `ProcessAdapter`. Every adapter entry point goes through this, so a
+/// remote unit is never started on this machine.
+pub fn split_unit_commands(
+    command_rx: mpsc::Receiver<AdapterCommand>,
+    units: &[Unit],
+    event_tx: broadcast::Sender<AdapterEvent>,
+    global_env_file: Option<PathBuf>,
+) -> mpsc::Receiver<AdapterCommand> {
+    #[cfg(feature = "docker")]
+    let command_rx = docker::split_docker_commands(command_rx, units, event_tx.clone());
+    ssh::split_ssh_commands(command_rx, units, event_tx, global_env_file)
+}
+
+/// Hands the commands for `ids` to `routed_tx` and everything else to the
+/// returned receiver, which another adapter should read. `StartMany` is
+/// split between the two and both get `Shutdown`; routed units aren't
+/// ordered against other units' dependencies. Once `routed_tx` is closed,
+/// commands for `ids` fall through to the returned receiver.
+fn route_commands(
+    mut command_rx: mpsc::Receiver<AdapterCommand>,
+    ids: BTreeSet<UnitId>,
+    routed_tx: mpsc::Sender<AdapterCommand>,
+) -> mpsc::Receiver<AdapterCommand> {
+    let (other_tx, other_rx) = mpsc::channel(100);
+    tokio::spawn(async move {
+        while let Some(cmd) = command_rx.recv().await {
+            let cmd = match cmd {
+                AdapterCommand::StartMany { ids: start } => {
+                    let (routed, other): (Vec<UnitId>, Vec<UnitId>) =
+                        start.into_iter().partition(|id| ids.contains(id));
+                    if !routed.is_empty() {
+                        let _ = routed_tx
+                            .send(AdapterCommand::StartMany { ids: routed })
+                            .await;
+                    }
+                    AdapterCommand::StartMany { ids: other }
+                }
+                AdapterCommand::Shutdown => {
+                    let _ = routed_tx.send(AdapterCommand::Shutdown).await;
+                    AdapterCommand::Shutdown
+                }
+                cmd if cmd.unit_id().is_some_and(|id| ids.contains(id)) => {
+                    match routed_tx.send(cmd).await {
+                        Ok(()) => continue,
+                        Err(mpsc::error::SendError(cmd)) => cmd,
+                    }
+                }
+                cmd => cmd,
+            };
+            if other_tx.send(cmd).await.is_err() {
+                break;
+            }
+        }
+    });
+    other_rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orkesy_core::config::OrkesyConfig;
+
+    fn unit_ids(ids: &[&str]) -> Vec<UnitId> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    /// The unit a command targets, or the ids of a `StartMany`
+    fn describe(cmd: Option<AdapterCommand>) -> String {
+        match cmd {
+            Some(AdapterCommand::StartMany { ids }) => format!("start {}", ids.join(",")),
+            Some(AdapterCommand::Shutdown) => "shutdown".to_string(),
+            Some(cmd) => cmd.unit_id().unwrap_or_default().to_string(),
+            None => "closed".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_commands() {
+        let (command_tx, command_rx) = mpsc::channel(8);
+        let (routed_tx, mut routed_rx) = mpsc::channel(8);
+        let remote = unit_ids(&["remote"]).into_iter().collect();
+        let mut other_rx = route_commands(command_rx, remote, routed_tx);
+
+        for cmd in [
+            AdapterCommand::Stop {
+                id: "remote".into(),
+            },
+            AdapterCommand::Stop { id: "api".into() },
+            AdapterCommand::StartMany {
+                ids: unit_ids(&["db", "remote", "api"]),
+            },
+            AdapterCommand::StartMany {
+                ids: unit_ids(&["api"]),
+            },
+            AdapterCommand::Shutdown,
+        ] {
+            command_tx.send(cmd).await.unwrap();
+        }
+
+        let mut routed = Vec::new();
+        for _ in 0..3 {
+            routed.push(describe(routed_rx.recv().await));
+        }
+        assert_eq!(routed, ["remote", "start remote", "shutdown"]);
+
+        let mut other = Vec::new();
+        for _ in 0..4 {
+            other.push(describe(other_rx.recv().await));
+        }
+        assert_eq!(other, ["api", "start db,api", "start api", "shutdown"]);
+    }
+
+    #[tokio::test]
+    async fn test_route_commands_falls_through_once_routed_closes() {
+        let (command_tx, command_rx) = mpsc::channel(8);
+        let (routed_tx, routed_rx) = mpsc::channel(8);
+        let remote = unit_ids(&["remote"]).into_iter().collect();
+        let mut other_rx = route_commands(command_rx, remote, routed_tx);
+        drop(routed_rx);
+
+        command_tx
+            .send(AdapterCommand::Stop {
+                id: "remote".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(describe(other_rx.recv().await), "remote");
+    }
+
+    #[tokio::test]
+    async fn test_split_unit_commands_keeps_ssh_units_off_the_process_adapter() {
+        let config = OrkesyConfig::parse(
+            r#"
+services:
+  api:
+    command: ["node", "server.js"]
+  remote:
+    command: ["./worker"]
+    ssh: { host: build.example.com }
+"#,
+        )
+        .unwrap();
+        let (command_tx, command_rx) = mpsc::channel(8);
+        let (event_tx, _) = broadcast::channel(64);
+        let mut local_rx = split_unit_commands(command_rx, &config.to_units(), event_tx, None);
+
+        for cmd in [
+            AdapterCommand::ClearLogs {
+                id: "remote".into(),
+            },
+            AdapterCommand::ClearLogs { id: "api".into() },
+            AdapterCommand::StartMany {
+                ids: unit_ids(&["remote", "api"]),
+            },
+        ] {
+            command_tx.send(cmd).await.unwrap();
+        }
+
+        assert_eq!(describe(local_rx.recv().await), "api");
+        assert_eq!(describe(local_rx.recv().await), "start api");
+    }
+}
@@ -1,34 +1,290 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use async_trait::async_trait;
 use sysinfo::{Pid, System};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::{RwLock, broadcast, mpsc};
+use tokio::task::JoinHandle;
 
 #[cfg(unix)]
 #[allow(unused_imports)]
 use std::os::unix::process::CommandExt;
 
-use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, LogStream};
-use orkesy_core::unit::{StopBehavior, StopSignal, Unit, UnitId, UnitMetrics, UnitStatus};
+use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, CompareOp, LogStream};
+use orkesy_core::env_file;
+use orkesy_core::log_filter::SecretMasker;
+use orkesy_core::model::HealthStatus;
+
+use super::journal::LogSink;
+use crate::health;
+use crate::sampler::process_io;
+use orkesy_core::state::{LogLine, LogStore, WatchInfo, truncate_log_line};
+use orkesy_core::unit::{
+    DEFAULT_STOP_TIMEOUT_MS, EdgeKind, HealthCheck, METRIC_CPU_PERCENT, METRIC_MEMORY_BYTES,
+    StopBehavior, StopSignal, Unit, UnitEdge, UnitId, UnitKind, UnitMetrics, UnitStatus,
+    interpolate, topological_order,
+};
+
+/// Lines kept per unit for `AdapterCommand::GetLogs`
+const LOG_BUFFER_LINES: usize = 1_000;
+
+/// Upper bound on how often `ready_when` is probed, so a health-style
+/// `interval_ms` doesn't hold dependents back for seconds
+const READY_POLL_INTERVAL_MAX: Duration = Duration::from_millis(500);
+
+/// An alert threshold fires again only after the metric stayed below it this long
+const ALERT_REARM_AFTER: Duration = Duration::from_secs(30);
+
+/// How often a `Watch` checks the latest metrics of its unit
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A registered `AdapterCommand::Watch`
+struct WatchHandle {
+    unit_id: UnitId,
+    info: WatchInfo,
+    then: AdapterCommand,
+    task: JoinHandle<()>,
+}
+
+/// Tracks the latest `MetricsUpdated` for `unit_id` and sends `watch_id` on
+/// `fired_tx` each time the condition starts to hold. It re-arms once the
+/// condition is false again, or the unit changes status (e.g. after a restart).
+async fn run_watch(
+    watch_id: String,
+    unit_id: UnitId,
+    metric: String,
+    op: CompareOp,
+    threshold: f64,
+    mut event_rx: broadcast::Receiver<AdapterEvent>,
+    fired_tx: mpsc::Sender<String>,
+) {
+    let mut latest: Option<f64> = None;
+    let mut armed = true;
+    let mut poll = tokio::time::interval(WATCH_POLL_INTERVAL);
+    poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            event = event_rx.recv() => match event {
+                Ok(AdapterEvent::MetricsUpdated { id, metrics }) if id == unit_id => {
+                    latest = metrics.value(&metric);
+                }
+                Ok(AdapterEvent::StatusChanged { id, .. }) if id == unit_id => {
+                    latest = None;
+                    armed = true;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+                _ => {}
+            },
+
+            _ = poll.tick() => {
+                let matched = latest.is_some_and(|value| op.matches(value, threshold));
+                if matched && armed {
+                    armed = false;
+                    if fired_tx.send(watch_id.clone()).await.is_err() {
+                        break;
+                    }
+                } else if !matched {
+                    armed = true;
+                }
+            }
+        }
+    }
+}
+
+/// Debounce state for one unit threshold
+struct AlertState {
+    armed: bool,
+    below_since: Option<Instant>,
+}
+
+impl Default for AlertState {
+    fn default() -> Self {
+        Self {
+            armed: true,
+            below_since: None,
+        }
+    }
+}
 
 struct ProcessHandle {
-    child: Child,
+    /// `None` for a process adopted from the unit's `pid_file`
+    child: Option<Child>,
+    pid: Option<u32>,
     pgid: i32,
     started_at: std::time::Instant,
 }
 
+impl ProcessHandle {
+    /// Exit code once the process has exited (`Some(None)` when unknown)
+    fn try_exit(&mut self) -> Option<Option<i32>> {
+        match &mut self.child {
+            Some(child) => child.try_wait().ok().flatten().map(|status| status.code()),
+            None => match self.pid {
+                Some(pid) if pid_alive(pid) => None,
+                _ => Some(None),
+            },
+        }
+    }
+
+    /// Signals the process group, or the process itself when adopted.
+    /// Returns false if there is nothing to signal.
+    #[cfg(unix)]
+    fn signal(&self, signal: i32) -> bool {
+        if self.pgid > 0 {
+            unsafe { libc::killpg(self.pgid, signal) };
+            true
+        } else if let (None, Some(pid)) = (&self.child, self.pid) {
+            unsafe { libc::kill(pid as i32, signal) };
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn kill(&mut self) {
+        match &mut self.child {
+            Some(child) => {
+                let _ = child.kill().await;
+            }
+            None =>
+            {
+                #[cfg(unix)]
+                if let Some(pid) = self.pid {
+                    unsafe { libc::kill(pid as i32, libc::SIGKILL) };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    // Signal 0 only checks that the process exists and can be signalled
+    pid > 0 && unsafe { libc::kill(pid as i32, 0) } == 0
+}
+
+#[cfg(not(unix))]
+fn pid_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Opens a pseudo-terminal pair as `(master, slave)`, both close-on-exec
+#[cfg(unix)]
+fn open_pty() -> std::io::Result<(std::os::fd::OwnedFd, std::os::fd::OwnedFd)> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    let (mut master, mut slave) = (-1, -1);
+    let mut size = libc::winsize {
+        ws_row: 24,
+        ws_col: 80,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    let rc = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &raw mut size,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let fds = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    for fd in [&fds.0, &fds.1] {
+        unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) };
+    }
+    Ok(fds)
+}
+
+fn read_pid_file(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Polls a unit's `ready_when` check against `host` until it passes or
+/// `timeout` runs out, reporting the outcome as a system log line (and
+/// `ReadinessReached`)
+pub(super) async fn wait_ready(
+    id: UnitId,
+    check: HealthCheck,
+    host: String,
+    timeout: Duration,
+    event_tx: broadcast::Sender<AdapterEvent>,
+) -> bool {
+    let started = Instant::now();
+    let interval = Duration::from_millis(check.interval_ms()).min(READY_POLL_INTERVAL_MAX);
+    loop {
+        if matches!(health::probe(&check, &host).await, HealthStatus::Healthy) {
+            let _ = event_tx.send(AdapterEvent::LogLine {
+                id: id.clone(),
+                stream: LogStream::System,
+                text: format!(
+                    "[ready] ready after {:.1}s",
+                    started.elapsed().as_secs_f64()
+                ),
+            });
+            let _ = event_tx.send(AdapterEvent::ReadinessReached { id });
+            return true;
+        }
+        if started.elapsed() >= timeout {
+            let _ = event_tx.send(AdapterEvent::LogLine {
+                id,
+                stream: LogStream::System,
+                text: format!("[warn] not ready after {}s", timeout.as_secs()),
+            });
+            return false;
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// A unit from a `StartMany` batch that starts once `waiting` is empty
+struct PendingStart {
+    id: UnitId,
+    waiting: BTreeSet<UnitId>,
+}
+
 pub struct ProcessAdapter {
     units: BTreeMap<UnitId, Unit>,
     processes: BTreeMap<UnitId, ProcessHandle>,
     next_id: Arc<AtomicU64>,
     sys: Arc<RwLock<System>>,
     last_metrics: BTreeMap<UnitId, UnitMetrics>,
+    logs: LogStore,
+    /// Keyed by unit and index into `ResourceAlerts::thresholds`
+    alerts: BTreeMap<(UnitId, usize), AlertState>,
+    /// Keyed by watch id (`w1`, `w2`, ...)
+    watches: BTreeMap<String, WatchHandle>,
+    next_watch_id: u64,
+    /// Set while `run` is active; watch tasks report fired watch ids here
+    watch_fired_tx: Option<mpsc::Sender<String>>,
+    /// Log what unit commands would do instead of running them
+    dry_run: bool,
+    /// Redacts each unit's secret env values from its log lines
+    maskers: BTreeMap<UnitId, Arc<SecretMasker>>,
+    /// Units with a `log_sink`, e.g. the systemd journal
+    sinks: BTreeMap<UnitId, Arc<LogSink>>,
+    /// Dependency edges used to order `AdapterCommand::StartMany`
+    edges: Vec<UnitEdge>,
+    /// `ready_when` polling of started units
+    readiness: BTreeMap<UnitId, JoinHandle<()>>,
+    /// Set while `run` is active; readiness tasks report whether they passed
+    ready_tx: Option<mpsc::Sender<(UnitId, tokio::task::Id, bool)>>,
+    /// Units whose `ready_when` timed out since they were last started
+    not_ready: BTreeSet<UnitId>,
+    /// `StartMany` units waiting for dependencies to become ready
+    pending: Vec<PendingStart>,
+    /// Config-wide `global_env_file`
+    global_env_file: Option<PathBuf>,
 }
 
 impl ProcessAdapter {
@@ -39,9 +295,106 @@ impl ProcessAdapter {
             next_id: Arc::new(AtomicU64::new(1)),
             sys: Arc::new(RwLock::new(System::new())),
             last_metrics: BTreeMap::new(),
+            logs: LogStore::new(LOG_BUFFER_LINES),
+            alerts: BTreeMap::new(),
+            watches: BTreeMap::new(),
+            next_watch_id: 1,
+            watch_fired_tx: None,
+            dry_run: false,
+            maskers: BTreeMap::new(),
+            sinks: BTreeMap::new(),
+            edges: Vec::new(),
+            readiness: BTreeMap::new(),
+            ready_tx: None,
+            not_ready: BTreeSet::new(),
+            pending: Vec::new(),
+            global_env_file: None,
         }
     }
 
+    /// Dependency edges between units, usually `OrkesyConfig::to_edges`
+    pub fn with_edges(mut self, edges: Vec<UnitEdge>) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// `.env` file loaded for every unit before its own `env_file`
+    pub fn with_global_env_file(mut self, path: Option<PathBuf>) -> Self {
+        self.global_env_file = path;
+        self
+    }
+
+    /// In dry-run mode unit commands only emit a `[dry-run]` log line
+    /// describing what they would do; nothing is spawned or signalled.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// What a unit command would do, for dry-run mode. `None` for commands
+    /// without side effects, which run normally.
+    fn describe_dry_run(&self, cmd: &AdapterCommand) -> Option<(UnitId, String)> {
+        let describe_start = |id: &str| {
+            let Some(unit) = self.units.get(id) else {
+                return format!("unit not found: {}", id);
+            };
+            if let Some(pid) = unit
+                .pid_file
+                .as_deref()
+                .and_then(read_pid_file)
+                .filter(|pid| pid_alive(*pid))
+            {
+                return format!("would adopt pid:{} from pid_file", pid);
+            }
+            let cwd = unit
+                .cwd
+                .as_ref()
+                .map(|cwd| format!(" (in {})", cwd.display()))
+                .unwrap_or_default();
+            let shell = if unit.uses_shell() { "sh -c " } else { "" };
+            match &unit.kind {
+                UnitKind::Ssh { host, .. } => {
+                    format!("would run on {} over ssh: {}{}", host, shell, unit.start)
+                }
+                UnitKind::Docker => format!(
+                    "would run a container from {}",
+                    unit.docker_image.as_deref().unwrap_or("(no image)")
+                ),
+                _ => format!("would run: {}{}{}", shell, unit.start, cwd),
+            }
+        };
+        let describe_stop = |id: &str| match self.units.get(id).map(|u| &u.stop) {
+            Some(StopBehavior::Command(command)) => format!("would stop with: {}", command),
+            Some(StopBehavior::Signal(sig)) => format!(
+                "would send {}",
+                match sig {
+                    StopSignal::SigInt => "SIGINT",
+                    StopSignal::SigTerm => "SIGTERM",
+                    StopSignal::SigKill => "SIGKILL",
+                }
+            ),
+            None => format!("unit not found: {}", id),
+        };
+
+        let (id, text) = match cmd {
+            AdapterCommand::Start { id } => (id, describe_start(id)),
+            AdapterCommand::Stop { id } => (id, describe_stop(id)),
+            AdapterCommand::Restart { id } => (
+                id,
+                format!("{}, then {}", describe_stop(id), describe_start(id)),
+            ),
+            AdapterCommand::Kill { id } => (id, "would kill the process group".to_string()),
+            AdapterCommand::Toggle { id } if self.processes.contains_key(id) => {
+                (id, describe_stop(id))
+            }
+            AdapterCommand::Toggle { id } => (id, describe_start(id)),
+            AdapterCommand::Exec { id, cmd } => (id, format!("would exec: {}", cmd.join(" "))),
+            AdapterCommand::Install { id } => (id, "would install dependencies".to_string()),
+            _ => return None,
+        };
+        Some((id.clone(), text))
+    }
+
     async fn collect_metrics(&self, pid: u32, uptime_secs: u64) -> UnitMetrics {
         let mut sys = self.sys.write().await;
         sys.refresh_processes(
@@ -49,12 +402,15 @@ impl ProcessAdapter {
             true,
         );
 
+        let io = process_io(pid).unwrap_or_default();
         if let Some(process) = sys.process(Pid::from_u32(pid)) {
             UnitMetrics {
                 cpu_percent: process.cpu_usage(),
                 memory_bytes: process.memory(),
                 uptime_secs,
                 pid: Some(pid),
+                io_read_bytes: io.read_bytes,
+                io_write_bytes: io.write_bytes,
             }
         } else {
             UnitMetrics {
@@ -62,12 +418,88 @@ impl ProcessAdapter {
                 memory_bytes: 0,
                 uptime_secs,
                 pid: Some(pid),
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Emits `ResourceWarning` for thresholds the sample crossed, unless the
+    /// metric dipped below the threshold only briefly since the last one.
+    fn check_alerts(
+        &mut self,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+        id: &UnitId,
+        metrics: &UnitMetrics,
+    ) {
+        let Some(alerts) = self.units.get(id).and_then(|u| u.alerts.as_ref()) else {
+            return;
+        };
+
+        let now = Instant::now();
+        for (idx, (metric, threshold)) in alerts.thresholds().into_iter().enumerate() {
+            let Some(value) = metrics.value(metric) else {
+                continue;
+            };
+            let state = self.alerts.entry((id.clone(), idx)).or_default();
+            if value >= threshold {
+                state.below_since = None;
+                if state.armed {
+                    state.armed = false;
+                    let _ = event_tx.send(AdapterEvent::ResourceWarning {
+                        id: id.clone(),
+                        metric: metric.to_string(),
+                        value,
+                        threshold,
+                    });
+                }
+            } else {
+                let since = *state.below_since.get_or_insert(now);
+                if now.duration_since(since) >= ALERT_REARM_AFTER {
+                    state.armed = true;
+                }
             }
         }
     }
 
     fn emit(&self, event_tx: &broadcast::Sender<AdapterEvent>, event: AdapterEvent) {
+        let event = self.mask_event(event);
+        let sink_error = match &event {
+            AdapterEvent::LogLine { id, stream, text } => self
+                .sinks
+                .get(id)
+                .and_then(|sink| sink.send(id, *stream, text))
+                .map(|e| (id.clone(), e)),
+            _ => None,
+        };
         let _ = event_tx.send(event);
+        if let Some((id, e)) = sink_error {
+            self.emit_log(event_tx, &id, format!("[log-sink] {}", e));
+        }
+    }
+
+    /// Redacts secrets from log text and error messages of a unit's events
+    fn mask_event(&self, event: AdapterEvent) -> AdapterEvent {
+        let masker = |id: &str| self.maskers.get(id).filter(|m| !m.is_empty());
+        match event {
+            AdapterEvent::LogLine { id, stream, text } => match masker(&id) {
+                Some(m) => {
+                    let text = m.mask(&text);
+                    AdapterEvent::LogLine { id, stream, text }
+                }
+                None => AdapterEvent::LogLine { id, stream, text },
+            },
+            AdapterEvent::StatusChanged {
+                id,
+                status: UnitStatus::Errored { message },
+            } => {
+                let message = masker(&id).map(|m| m.mask(&message)).unwrap_or(message);
+                AdapterEvent::StatusChanged {
+                    id,
+                    status: UnitStatus::Errored { message },
+                }
+            }
+            event => event,
+        }
     }
 
     fn emit_log(&self, event_tx: &broadcast::Sender<AdapterEvent>, id: &str, text: String) {
@@ -105,22 +537,88 @@ impl ProcessAdapter {
             .units
             .get(id)
             .ok_or_else(|| format!("unit not found: {}", id))?;
+        let env = self.unit_env(unit, event_tx);
+
+        // `${VAR}` references see the inherited environment and the unit's
+        let mut scope: BTreeMap<String, String> = std::env::vars().collect();
+        scope.extend(env.clone());
+        let env = env
+            .into_iter()
+            .map(|(key, value)| match interpolate(&value, &scope) {
+                Ok(value) => Ok((key, value)),
+                Err(e) => Err(format!("env {}: {}", key, e)),
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        scope.extend(env.clone());
+        self.maskers
+            .insert(id.clone(), Arc::new(SecretMasker::from_env(&env)));
+        let unit = &self.units[id];
+
+        if let Some(pid) = unit
+            .pid_file
+            .as_deref()
+            .and_then(read_pid_file)
+            .filter(|pid| pid_alive(*pid))
+        {
+            self.processes.insert(
+                id.clone(),
+                ProcessHandle {
+                    child: None,
+                    pid: Some(pid),
+                    pgid: -1,
+                    started_at: std::time::Instant::now(),
+                },
+            );
+            self.emit_log(
+                event_tx,
+                id,
+                format!("[pid-file] adopted existing process pid:{}", pid),
+            );
+            return Ok(());
+        }
 
         if unit.start.trim().is_empty() {
             return Err("empty start command".into());
         }
 
-        #[cfg(unix)]
-        let mut cmd = {
-            let mut c = Command::new("sh");
-            c.arg("-c");
-            c.arg(&unit.start);
-            c
+        // A shell expands variables itself
+        let start = if unit.uses_shell() {
+            unit.start.clone()
+        } else {
+            interpolate(&unit.start, &scope).map_err(|e| format!("start command: {}", e))?
         };
-        #[cfg(windows)]
-        let mut cmd = {
-            let mut c = Command::new("cmd");
-            c.args(["/C", &unit.start]);
+
+        let mut cmd = if unit.uses_shell() {
+            #[cfg(unix)]
+            let c = {
+                let mut c = Command::new("sh");
+                c.arg("-c");
+                c.arg(&start);
+                c
+            };
+            #[cfg(windows)]
+            let c = {
+                let mut c = Command::new("cmd");
+                c.args(["/C", &start]);
+                c
+            };
+            c
+        } else {
+            if unit.has_shell_syntax() {
+                self.emit_log(
+                    event_tx,
+                    id,
+                    format!(
+                        "warning: start command contains shell syntax but `shell` is not enabled; \
+                         it will run without a shell: {}",
+                        unit.start
+                    ),
+                );
+            }
+            let mut parts = start.split_whitespace();
+            let program = parts.next().unwrap_or_default();
+            let mut c = Command::new(program);
+            c.args(parts);
             c
         };
 
@@ -128,9 +626,7 @@ impl ProcessAdapter {
             cmd.current_dir(cwd);
         }
 
-        for (k, v) in &unit.env {
-            cmd.env(k, v);
-        }
+        cmd.envs(&env);
 
         #[cfg(unix)]
         unsafe {
@@ -140,53 +636,102 @@ impl ProcessAdapter {
             });
         }
 
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-        cmd.stdin(Stdio::null());
+        if let Some(limits) = unit.ulimits.clone().filter(|l| !l.is_empty()) {
+            #[cfg(target_os = "linux")]
+            {
+                crate::platform::linux::check_ulimits(&limits)?;
+                unsafe {
+                    cmd.pre_exec(move || {
+                        crate::platform::linux::apply_ulimits(&limits)
+                            .map_err(std::io::Error::other)
+                    });
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            self.emit_log(
+                event_tx,
+                id,
+                format!(
+                    "warning: ulimits are not supported on this platform; ignoring {:?}",
+                    limits
+                ),
+            );
+        }
+
+        #[cfg(unix)]
+        let pty_master = if unit.pty {
+            let (master, slave) = open_pty().map_err(|e| format!("could not open pty: {}", e))?;
+            let (stdout, stderr) = (
+                slave.try_clone().map_err(|e| e.to_string())?,
+                slave.try_clone().map_err(|e| e.to_string())?,
+            );
+            cmd.stdin(Stdio::from(slave));
+            cmd.stdout(Stdio::from(stdout));
+            cmd.stderr(Stdio::from(stderr));
+            if !unit.env.contains_key("TERM") && std::env::var_os("TERM").is_none() {
+                cmd.env("TERM", "xterm-256color");
+            }
+            unsafe {
+                cmd.pre_exec(|| {
+                    // Make the pty the controlling terminal of the new session
+                    libc::ioctl(0, libc::TIOCSCTTY as _, 0);
+                    Ok(())
+                });
+            }
+            Some(master)
+        } else {
+            None
+        };
+        #[cfg(not(unix))]
+        let pty_master: Option<std::fs::File> = {
+            if unit.pty {
+                self.emit_log(
+                    event_tx,
+                    id,
+                    "[warn] pty is not supported on this platform; using pipes".into(),
+                );
+            }
+            None
+        };
+
+        if pty_master.is_none() {
+            cmd.stdout(Stdio::piped());
+            cmd.stderr(Stdio::piped());
+            cmd.stdin(Stdio::null());
+        }
 
+        let max_line = unit.log_line_limit();
+        let pid_file = unit.pid_file.clone();
         let mut child = cmd.spawn().map_err(|e| e.to_string())?;
-        let pgid = child.id().map(|pid| pid as i32).unwrap_or(-1);
+        let pid = child.id();
+        let pgid = pid.map(|pid| pid as i32).unwrap_or(-1);
+
+        if let (Some(path), Some(pid)) = (&pid_file, pid)
+            && let Err(e) = std::fs::write(path, format!("{}\n", pid))
+        {
+            self.emit_log(
+                event_tx,
+                id,
+                format!("[pid-file] could not write {}: {}", path.display(), e),
+            );
+        }
 
+        if let Some(master) = pty_master {
+            let master = tokio::fs::File::from_std(std::fs::File::from(master));
+            self.forward_logs(id, LogStream::Stdout, master, max_line, event_tx);
+        }
         if let Some(stdout) = child.stdout.take() {
-            let tx = event_tx.clone();
-            let unit_id = id.clone();
-            let next_id = self.next_id.clone();
-            tokio::spawn(async move {
-                let reader = BufReader::new(stdout);
-                let mut lines = reader.lines();
-                while let Ok(Some(line)) = lines.next_line().await {
-                    let _ = next_id.fetch_add(1, Ordering::SeqCst);
-                    let _ = tx.send(AdapterEvent::LogLine {
-                        id: unit_id.clone(),
-                        stream: LogStream::Stdout,
-                        text: line,
-                    });
-                }
-            });
+            self.forward_logs(id, LogStream::Stdout, stdout, max_line, event_tx);
         }
-
         if let Some(stderr) = child.stderr.take() {
-            let tx = event_tx.clone();
-            let unit_id = id.clone();
-            let next_id = self.next_id.clone();
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                let mut lines = reader.lines();
-                while let Ok(Some(line)) = lines.next_line().await {
-                    let _ = next_id.fetch_add(1, Ordering::SeqCst);
-                    let _ = tx.send(AdapterEvent::LogLine {
-                        id: unit_id.clone(),
-                        stream: LogStream::Stderr,
-                        text: line,
-                    });
-                }
-            });
+            self.forward_logs(id, LogStream::Stderr, stderr, max_line, event_tx);
         }
 
         self.processes.insert(
             id.clone(),
             ProcessHandle {
-                child,
+                child: Some(child),
+                pid,
                 pgid,
                 started_at: std::time::Instant::now(),
             },
@@ -195,14 +740,86 @@ impl ProcessAdapter {
         Ok(())
     }
 
+    /// The unit's `env` over its env files (`global_env_file` first). A file
+    /// that can't be loaded is skipped with a system log line.
+    fn unit_env(
+        &self,
+        unit: &Unit,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+    ) -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        for path in self.global_env_file.iter().chain(&unit.env_file) {
+            match env_file::load(path) {
+                Ok(vars) => env.extend(vars),
+                Err(e) => self.emit_log(
+                    event_tx,
+                    &unit.id,
+                    format!("[env_file] skipped {}: {}", path.display(), e),
+                ),
+            }
+        }
+        env.extend(unit.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env
+    }
+
+    /// Forwards each line read from a unit's output as a `LogLine` on `stream`
+    fn forward_logs<R>(
+        &self,
+        id: &UnitId,
+        stream: LogStream,
+        reader: R,
+        max_line: usize,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+    ) where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let tx = event_tx.clone();
+        let unit_id = id.clone();
+        let next_id = self.next_id.clone();
+        let masker = self.maskers.get(id).cloned().unwrap_or_default();
+        let sink = self.sinks.get(id).cloned();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            // A pty master reports EIO once the child has exited, ending the loop
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = next_id.fetch_add(1, Ordering::SeqCst);
+                // Terminals translate `\n` to `\r\n`
+                let line = line.strip_suffix('\r').unwrap_or(&line);
+                let text = truncate_log_line(masker.mask(line), max_line);
+                let sink_error = sink.as_ref().and_then(|s| s.send(&unit_id, stream, &text));
+                let _ = tx.send(AdapterEvent::LogLine {
+                    id: unit_id.clone(),
+                    stream,
+                    text,
+                });
+                if let Some(e) = sink_error {
+                    let _ = tx.send(AdapterEvent::LogLine {
+                        id: unit_id.clone(),
+                        stream: LogStream::System,
+                        text: format!("[log-sink] {}", e),
+                    });
+                }
+            }
+        });
+    }
+
     async fn stop_unit(&mut self, id: &UnitId, force: bool) -> Result<(), String> {
         let unit = self.units.get(id);
         let stop_behavior = unit
             .map(|u| u.stop.clone())
             .unwrap_or(StopBehavior::Signal(StopSignal::SigTerm));
+        let stop_timeout = Duration::from_millis(
+            unit.map(|u| u.stop_timeout_ms)
+                .unwrap_or(DEFAULT_STOP_TIMEOUT_MS),
+        );
 
+        if let Some(task) = self.readiness.remove(id) {
+            task.abort();
+        }
         if let Some(mut handle) = self.processes.remove(id) {
             self.last_metrics.remove(id);
+            self.alerts.retain(|(unit_id, _), _| unit_id != id);
+            self.remove_pid_file(id);
             match &stop_behavior {
                 StopBehavior::Signal(sig) if !force => {
                     #[cfg(unix)]
@@ -213,29 +830,26 @@ impl ProcessAdapter {
                             StopSignal::SigKill => libc::SIGKILL,
                         };
 
-                        if handle.pgid > 0 {
-                            unsafe {
-                                libc::killpg(handle.pgid, signal);
-                            }
-
+                        if handle.signal(signal) {
                             if signal != libc::SIGKILL {
-                                tokio::time::sleep(Duration::from_millis(500)).await;
+                                let deadline = Instant::now() + stop_timeout;
+                                while handle.try_exit().is_none() && Instant::now() < deadline {
+                                    tokio::time::sleep(Duration::from_millis(50)).await;
+                                }
 
-                                if handle.child.try_wait().ok().flatten().is_none() {
-                                    unsafe {
-                                        libc::killpg(handle.pgid, libc::SIGKILL);
-                                    }
+                                if handle.try_exit().is_none() {
+                                    handle.signal(libc::SIGKILL);
                                 }
                             }
                         } else {
-                            let _ = handle.child.kill().await;
+                            handle.kill().await;
                         }
                     }
 
                     #[cfg(windows)]
                     {
-                        let _ = sig; // Suppress unused warning
-                        let _ = handle.child.kill().await;
+                        let _ = (sig, stop_timeout); // Suppress unused warning
+                        handle.kill().await;
                     }
                 }
 
@@ -247,30 +861,22 @@ impl ProcessAdapter {
 
                     if let Err(e) = output {
                         #[cfg(unix)]
-                        if handle.pgid > 0 {
-                            unsafe {
-                                libc::killpg(handle.pgid, libc::SIGKILL);
-                            }
-                        } else {
-                            let _ = handle.child.kill().await;
+                        if !handle.signal(libc::SIGKILL) {
+                            handle.kill().await;
                         }
                         #[cfg(windows)]
-                        let _ = handle.child.kill().await;
+                        handle.kill().await;
                         return Err(format!("stop command failed: {}, killed process", e));
                     }
                 }
 
                 _ => {
                     #[cfg(unix)]
-                    if handle.pgid > 0 {
-                        unsafe {
-                            libc::killpg(handle.pgid, libc::SIGKILL);
-                        }
-                    } else {
-                        let _ = handle.child.kill().await;
+                    if !handle.signal(libc::SIGKILL) {
+                        handle.kill().await;
                     }
                     #[cfg(windows)]
-                    let _ = handle.child.kill().await;
+                    handle.kill().await;
                 }
             }
             Ok(())
@@ -279,6 +885,13 @@ impl ProcessAdapter {
         }
     }
 
+    /// Removes the unit's PID file, if it has one
+    fn remove_pid_file(&self, id: &UnitId) {
+        if let Some(path) = self.units.get(id).and_then(|u| u.pid_file.as_ref()) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
     async fn install_unit(
         &self,
         id: &UnitId,
@@ -361,6 +974,501 @@ impl ProcessAdapter {
         self.emit_log(event_tx, id, "install completed".into());
         Ok(())
     }
+
+    fn add_watch(
+        &mut self,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+        id: UnitId,
+        metric: String,
+        op: CompareOp,
+        threshold: f64,
+        then: AdapterCommand,
+    ) {
+        if UnitMetrics::default().value(&metric).is_none() {
+            self.emit_log(
+                event_tx,
+                &id,
+                format!(
+                    "[error] watch: unknown metric '{}' (expected {} or {})",
+                    metric, METRIC_CPU_PERCENT, METRIC_MEMORY_BYTES
+                ),
+            );
+            return;
+        }
+        if then.try_clone().is_none() {
+            self.emit_log(
+                event_tx,
+                &id,
+                format!("[error] watch: '{}' cannot be triggered", then.describe()),
+            );
+            return;
+        }
+        let Some(fired_tx) = self.watch_fired_tx.clone() else {
+            return;
+        };
+
+        let watch_id = format!("w{}", self.next_watch_id);
+        self.next_watch_id += 1;
+
+        let info = WatchInfo {
+            metric: metric.clone(),
+            op,
+            threshold,
+            action: then.describe(),
+            fired: 0,
+        };
+        let task = tokio::spawn(run_watch(
+            watch_id.clone(),
+            id.clone(),
+            metric,
+            op,
+            threshold,
+            event_tx.subscribe(),
+            fired_tx,
+        ));
+
+        self.emit_log(
+            event_tx,
+            &id,
+            format!(
+                "[watch {}] {} {} {} -> {}",
+                watch_id, info.metric, info.op, info.threshold, info.action
+            ),
+        );
+        self.emit(
+            event_tx,
+            AdapterEvent::WatchChanged {
+                id: id.clone(),
+                watch_id: watch_id.clone(),
+                watch: Some(info.clone()),
+            },
+        );
+        self.watches.insert(
+            watch_id,
+            WatchHandle {
+                unit_id: id,
+                info,
+                then,
+                task,
+            },
+        );
+    }
+
+    async fn fire_watch(&mut self, event_tx: &broadcast::Sender<AdapterEvent>, watch_id: &str) {
+        let Some(watch) = self.watches.get_mut(watch_id) else {
+            return;
+        };
+        let Some(then) = watch.then.try_clone() else {
+            return;
+        };
+        watch.info.fired += 1;
+        let unit_id = watch.unit_id.clone();
+        let info = watch.info.clone();
+
+        let value = self
+            .last_metrics
+            .get(&unit_id)
+            .and_then(|m| m.value(&info.metric))
+            .map(|v| format!(" ({})", v))
+            .unwrap_or_default();
+        self.emit_log(
+            event_tx,
+            &unit_id,
+            format!(
+                "[watch {}] {} {} {}{}, running {}",
+                watch_id, info.metric, info.op, info.threshold, value, info.action
+            ),
+        );
+        self.emit(
+            event_tx,
+            AdapterEvent::WatchChanged {
+                id: unit_id,
+                watch_id: watch_id.to_string(),
+                watch: Some(info),
+            },
+        );
+        self.handle_command(then, event_tx).await;
+    }
+
+    fn fail_start(&self, event_tx: &broadcast::Sender<AdapterEvent>, id: &str, message: String) {
+        self.emit_log(event_tx, id, format!("[error] not started: {}", message));
+        self.emit_status(event_tx, id, UnitStatus::Errored { message });
+    }
+
+    /// Settles the pending starts waiting on `dep`: with a `failure` they
+    /// fail, otherwise those with nothing left to wait for start. Units
+    /// started here may in turn release their own dependents.
+    async fn release_pending(
+        &mut self,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+        dep: UnitId,
+        failure: Option<String>,
+    ) {
+        let mut settled = vec![(dep, failure)];
+        while let Some((dep, failure)) = settled.pop() {
+            let mut released = Vec::new();
+            self.pending.retain_mut(|p| {
+                if !p.waiting.remove(&dep) {
+                    return true;
+                }
+                if failure.is_some() || p.waiting.is_empty() {
+                    released.push(p.id.clone());
+                    return false;
+                }
+                true
+            });
+
+            for id in released {
+                if let Some(message) = &failure {
+                    self.fail_start(event_tx, &id, message.clone());
+                    let message = format!("dependency '{}' is not running", id);
+                    settled.push((id, Some(message)));
+                    continue;
+                }
+                self.start(&id, event_tx).await;
+                if self.readiness.contains_key(&id) {
+                    // Dependents wait for its own readiness report
+                } else if self.processes.contains_key(&id) {
+                    settled.push((id, None));
+                } else {
+                    let message = format!("dependency '{}' is not running", id);
+                    settled.push((id, Some(message)));
+                }
+            }
+        }
+    }
+
+    /// Drops `id` from the pending starts and fails the units waiting on it
+    async fn cancel_pending(&mut self, event_tx: &broadcast::Sender<AdapterEvent>, id: &UnitId) {
+        let was_pending = self.pending.iter().any(|p| p.id == *id);
+        if !was_pending && !self.readiness.contains_key(id) {
+            return;
+        }
+        self.pending.retain(|p| p.id != *id);
+        let message = format!("dependency '{}' was stopped", id);
+        self.release_pending(event_tx, id.clone(), Some(message))
+            .await;
+    }
+
+    /// Reports the unit running and starts polling its `ready_when` check
+    fn mark_running(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        self.emit_status(event_tx, id, UnitStatus::Running);
+
+        let Some(unit) = self.units.get(id) else {
+            return;
+        };
+        let Some(check) = unit.ready_when.clone() else {
+            return;
+        };
+        let timeout = Duration::from_millis(unit.ready_timeout_ms);
+        let host = unit.host().to_string();
+        let ready_tx = self.ready_tx.clone();
+        let unit_id = id.clone();
+        let event_tx = event_tx.clone();
+        let task = tokio::spawn(async move {
+            let ready = wait_ready(unit_id.clone(), check, host, timeout, event_tx).await;
+            if let Some(ready_tx) = ready_tx {
+                let _ = ready_tx.send((unit_id, tokio::task::id(), ready)).await;
+            }
+        });
+        self.not_ready.remove(id);
+        if let Some(previous) = self.readiness.insert(id.clone(), task) {
+            previous.abort();
+        }
+    }
+
+    async fn start(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        if self.processes.contains_key(id) {
+            self.emit_log(event_tx, id, "[warn] already running".into());
+            return;
+        }
+
+        self.emit_status(event_tx, id, UnitStatus::Starting);
+
+        match self.spawn_unit(id, event_tx).await {
+            Ok(()) => {
+                self.mark_running(id, event_tx);
+            }
+            Err(e) => {
+                self.emit_status(event_tx, id, UnitStatus::Errored { message: e.clone() });
+                self.emit_log(event_tx, id, format!("[error] {}", e));
+            }
+        }
+    }
+
+    /// Returns `false` once the adapter should shut down
+    async fn handle_command(
+        &mut self,
+        cmd: AdapterCommand,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+    ) -> bool {
+        if self.dry_run
+            && let Some((id, text)) = self.describe_dry_run(&cmd)
+        {
+            self.emit_log(event_tx, &id, format!("[dry-run] {}", text));
+            return true;
+        }
+
+        match cmd {
+            AdapterCommand::Shutdown => {
+                for (_, watch) in std::mem::take(&mut self.watches) {
+                    watch.task.abort();
+                }
+                self.pending.clear();
+                // Dependents first, so nothing loses a dependency while running
+                let ids: Vec<_> = self.processes.keys().cloned().collect();
+                for id in topological_order(&ids, &self.edges).into_iter().rev() {
+                    let _ = self.stop_unit(&id, false).await;
+                }
+                return false;
+            }
+
+            AdapterCommand::Start { id } => self.start(&id, event_tx).await,
+
+            AdapterCommand::StartMany { ids } => {
+                for id in topological_order(&ids, &self.edges) {
+                    if self.dry_run {
+                        if let Some((id, text)) =
+                            self.describe_dry_run(&AdapterCommand::Start { id })
+                        {
+                            self.emit_log(event_tx, &id, format!("[dry-run] {}", text));
+                        }
+                        continue;
+                    }
+
+                    let deps: Vec<UnitId> = self
+                        .edges
+                        .iter()
+                        .filter(|e| {
+                            e.kind == EdgeKind::DependsOn
+                                && e.from == id
+                                && e.to != id
+                                && ids.contains(&e.to)
+                        })
+                        .map(|e| e.to.clone())
+                        .collect();
+                    let mut waiting = BTreeSet::new();
+                    let mut blocked = None;
+                    for dep in deps {
+                        if self.pending.iter().any(|p| p.id == dep)
+                            || self.readiness.contains_key(&dep)
+                        {
+                            waiting.insert(dep);
+                        } else if !self.processes.contains_key(&dep) {
+                            blocked = Some(format!("dependency '{}' is not running", dep));
+                            break;
+                        } else if self.not_ready.contains(&dep) {
+                            blocked = Some(format!("dependency '{}' is not ready", dep));
+                            break;
+                        }
+                    }
+                    match blocked {
+                        Some(message) => self.fail_start(event_tx, &id, message),
+                        None if waiting.is_empty() => self.start(&id, event_tx).await,
+                        None => {
+                            let names: Vec<&str> = waiting.iter().map(String::as_str).collect();
+                            self.emit_log(
+                                event_tx,
+                                &id,
+                                format!("waiting for {} to be ready...", names.join(", ")),
+                            );
+                            self.pending.push(PendingStart { id, waiting });
+                        }
+                    }
+                }
+            }
+
+            AdapterCommand::Stop { id } => {
+                self.cancel_pending(event_tx, &id).await;
+                self.emit_log(event_tx, &id, "stopping...".into());
+                self.emit_status(event_tx, &id, UnitStatus::Stopping);
+
+                match self.stop_unit(&id, false).await {
+                    Ok(()) => {
+                        self.emit_status(event_tx, &id, UnitStatus::Stopped);
+                    }
+                    Err(e) => {
+                        self.emit_log(event_tx, &id, format!("[warn] {}", e));
+                    }
+                }
+            }
+
+            AdapterCommand::Restart { id } => {
+                self.emit_log(event_tx, &id, "restarting...".into());
+
+                let _ = self.stop_unit(&id, false).await;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+
+                self.emit_status(event_tx, &id, UnitStatus::Starting);
+                match self.spawn_unit(&id, event_tx).await {
+                    Ok(()) => {
+                        self.mark_running(&id, event_tx);
+                        self.emit_log(event_tx, &id, "restarted".into());
+                    }
+                    Err(e) => {
+                        self.emit_status(event_tx, &id, UnitStatus::Errored { message: e.clone() });
+                        self.emit_log(event_tx, &id, format!("[error] restart failed: {}", e));
+                    }
+                }
+            }
+
+            AdapterCommand::Kill { id } => {
+                self.cancel_pending(event_tx, &id).await;
+                match self.stop_unit(&id, true).await {
+                    Ok(()) => {
+                        self.emit_status(event_tx, &id, UnitStatus::Stopped);
+                        self.emit_log(event_tx, &id, "killed".into());
+                    }
+                    Err(e) => {
+                        self.emit_log(event_tx, &id, format!("[warn] {}", e));
+                    }
+                }
+            }
+
+            AdapterCommand::Toggle { id } => {
+                if self.processes.contains_key(&id) {
+                    self.cancel_pending(event_tx, &id).await;
+                    self.emit_log(event_tx, &id, "stopping...".into());
+                    self.emit_status(event_tx, &id, UnitStatus::Stopping);
+                    let _ = self.stop_unit(&id, false).await;
+                    self.emit_status(event_tx, &id, UnitStatus::Stopped);
+                } else {
+                    self.start(&id, event_tx).await;
+                }
+            }
+
+            AdapterCommand::ClearLogs { id } => {
+                self.logs.clear(&id);
+                self.emit_log(event_tx, &id, "logs cleared".into());
+            }
+
+            AdapterCommand::AddNote { id, text } => {
+                self.emit(
+                    event_tx,
+                    AdapterEvent::UserNote {
+                        id,
+                        text,
+                        at: SystemTime::now(),
+                    },
+                );
+            }
+
+            AdapterCommand::GetLogs {
+                id,
+                last_n,
+                reply_tx,
+            } => {
+                let _ = reply_tx.send(self.logs.tail(&id, last_n));
+            }
+
+            AdapterCommand::Watch {
+                id,
+                metric,
+                op,
+                threshold,
+                then,
+            } => {
+                self.add_watch(event_tx, id, metric, op, threshold, *then);
+            }
+
+            AdapterCommand::CancelWatch { watch_id } => {
+                if let Some(watch) = self.watches.remove(&watch_id) {
+                    watch.task.abort();
+                    self.emit_log(
+                        event_tx,
+                        &watch.unit_id,
+                        format!("[watch {}] cancelled", watch_id),
+                    );
+                    self.emit(
+                        event_tx,
+                        AdapterEvent::WatchChanged {
+                            id: watch.unit_id,
+                            watch_id,
+                            watch: None,
+                        },
+                    );
+                }
+            }
+
+            AdapterCommand::Install { id } => {
+                self.emit_log(event_tx, &id, "installing dependencies...".into());
+                match self.install_unit(&id, event_tx).await {
+                    Ok(()) => {}
+                    Err(e) => {
+                        self.emit_log(event_tx, &id, format!("[error] install failed: {}", e));
+                    }
+                }
+            }
+
+            AdapterCommand::Exec { id, cmd } => {
+                let shown = cmd.join(" ");
+                self.emit_log(event_tx, &id, format!("$ {}", shown));
+
+                if cmd.is_empty() {
+                    self.emit_log(event_tx, &id, "[error] empty command".into());
+                    return true;
+                }
+
+                let unit = self.units.get(&id);
+                let mut command = Command::new(&cmd[0]);
+                command.args(&cmd[1..]);
+
+                if let Some(u) = unit {
+                    if let Some(cwd) = &u.cwd {
+                        command.current_dir(cwd);
+                    }
+                    for (k, v) in &u.env {
+                        command.env(k, v);
+                    }
+                }
+
+                let output = command.output().await;
+
+                match output {
+                    Ok(out) => {
+                        if !out.stdout.is_empty() {
+                            if let Ok(s) = String::from_utf8(out.stdout) {
+                                for line in s.lines() {
+                                    self.emit(
+                                        event_tx,
+                                        AdapterEvent::LogLine {
+                                            id: id.clone(),
+                                            stream: LogStream::Stdout,
+                                            text: line.to_string(),
+                                        },
+                                    );
+                                }
+                            }
+                        }
+                        if !out.stderr.is_empty() {
+                            if let Ok(s) = String::from_utf8(out.stderr) {
+                                for line in s.lines() {
+                                    self.emit(
+                                        event_tx,
+                                        AdapterEvent::LogLine {
+                                            id: id.clone(),
+                                            stream: LogStream::Stderr,
+                                            text: line.to_string(),
+                                        },
+                                    );
+                                }
+                            }
+                        }
+                        let status = if out.status.success() {
+                            "ok".to_string()
+                        } else {
+                            format!("exit code: {:?}", out.status.code())
+                        };
+                        self.emit_log(event_tx, &id, status);
+                    }
+                    Err(e) => {
+                        self.emit_log(event_tx, &id, format!("[error] {}", e));
+                    }
+                }
+            }
+        }
+        true
+    }
 }
 
 impl Default for ProcessAdapter {
@@ -398,6 +1506,16 @@ impl Adapter for ProcessAdapter {
         units: Vec<Unit>,
     ) {
         for unit in units {
+            self.maskers
+                .insert(unit.id.clone(), Arc::new(SecretMasker::from_env(&unit.env)));
+            if let Some(config) = &unit.log_sink {
+                match LogSink::open(config) {
+                    Ok(sink) => {
+                        self.sinks.insert(unit.id.clone(), Arc::new(sink));
+                    }
+                    Err(e) => self.emit_log(&event_tx, &unit.id, format!("[log-sink] {}", e)),
+                }
+            }
             self.units.insert(unit.id.clone(), unit);
         }
 
@@ -407,215 +1525,93 @@ impl Adapter for ProcessAdapter {
         let mut metrics_interval = tokio::time::interval(Duration::from_secs(2));
         metrics_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
+        let (watch_fired_tx, mut watch_fired_rx) = mpsc::channel(16);
+        self.watch_fired_tx = Some(watch_fired_tx);
+        let (ready_tx, mut ready_rx) = mpsc::channel(16);
+        self.ready_tx = Some(ready_tx);
+
+        // Buffer our own log output so GetLogs can answer without subscribers
+        let mut log_rx = event_tx.subscribe();
+
         loop {
             tokio::select! {
+                event = log_rx.recv() => {
+                    if let Ok(AdapterEvent::LogLine { id, stream, text }) = event {
+                        let line = LogLine {
+                            at: std::time::SystemTime::now(),
+                            service_id: id.clone(),
+                            stream,
+                            text,
+                            repeat_count: 1,
+                        };
+                        self.logs.push(&id, line);
+                    }
+                }
+
                 _ = check_interval.tick() => {
                     let mut exited = vec![];
                     for (id, handle) in &mut self.processes {
-                        if let Ok(Some(status)) = handle.child.try_wait() {
-                            exited.push((id.clone(), status.code()));
+                        if let Some(code) = handle.try_exit() {
+                            exited.push((id.clone(), code));
                         }
                     }
 
                     for (id, code) in exited {
                         self.processes.remove(&id);
+                        if let Some(task) = self.readiness.remove(&id) {
+                            task.abort();
+                        }
+                        self.remove_pid_file(&id);
                         self.last_metrics.remove(&id);
+                        self.alerts.retain(|(unit_id, _), _| *unit_id != id);
                         self.emit_status(&event_tx, &id, UnitStatus::Exited { code });
                         self.emit_log(&event_tx, &id, format!("process exited with code: {:?}", code));
+                        let message = format!("dependency '{}' exited", id);
+                        self.release_pending(&event_tx, id, Some(message)).await;
                     }
                 }
 
                 _ = metrics_interval.tick() => {
-                    for (id, handle) in &self.processes {
-                        if let Some(pid) = handle.child.id() {
-                            let uptime = handle.started_at.elapsed().as_secs();
-                            let metrics = self.collect_metrics(pid, uptime).await;
-                            self.last_metrics.insert(id.clone(), metrics.clone());
-                            self.emit(&event_tx, AdapterEvent::MetricsUpdated {
-                                id: id.clone(),
-                                metrics,
-                            });
-                        }
+                    let running: Vec<(UnitId, u32, u64)> = self
+                        .processes
+                        .iter()
+                        .filter_map(|(id, handle)| {
+                            let pid = handle.pid?;
+                            Some((id.clone(), pid, handle.started_at.elapsed().as_secs()))
+                        })
+                        .collect();
+                    for (id, pid, uptime) in running {
+                        let metrics = self.collect_metrics(pid, uptime).await;
+                        self.check_alerts(&event_tx, &id, &metrics);
+                        self.last_metrics.insert(id.clone(), metrics.clone());
+                        self.emit(&event_tx, AdapterEvent::MetricsUpdated { id, metrics });
                     }
                 }
 
                 cmd = command_rx.recv() => {
                     let Some(cmd) = cmd else { break };
+                    if !self.handle_command(cmd, &event_tx).await {
+                        break;
+                    }
+                }
 
-                    match cmd {
-                        AdapterCommand::Shutdown => {
-                            let ids: Vec<_> = self.processes.keys().cloned().collect();
-                            for id in ids {
-                                let _ = self.stop_unit(&id, false).await;
-                            }
-                            break;
-                        }
-
-                        AdapterCommand::Start { id } => {
-                            if self.processes.contains_key(&id) {
-                                self.emit_log(&event_tx, &id, "[warn] already running".into());
-                                continue;
-                            }
-
-                            self.emit_status(&event_tx, &id, UnitStatus::Starting);
-
-                            match self.spawn_unit(&id, &event_tx).await {
-                                Ok(()) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Running);
-                                }
-                                Err(e) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Errored { message: e.clone() });
-                                    self.emit_log(&event_tx, &id, format!("[error] {}", e));
-                                }
-                            }
-                        }
-
-                        AdapterCommand::Stop { id } => {
-                            self.emit_log(&event_tx, &id, "stopping...".into());
-                            self.emit_status(&event_tx, &id, UnitStatus::Stopping);
-
-                            match self.stop_unit(&id, false).await {
-                                Ok(()) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Stopped);
-                                }
-                                Err(e) => {
-                                    self.emit_log(&event_tx, &id, format!("[warn] {}", e));
-                                }
-                            }
-                        }
-
-                        AdapterCommand::Restart { id } => {
-                            self.emit_log(&event_tx, &id, "restarting...".into());
-
-                            let _ = self.stop_unit(&id, false).await;
-                            tokio::time::sleep(Duration::from_millis(100)).await;
-
-                            self.emit_status(&event_tx, &id, UnitStatus::Starting);
-                            match self.spawn_unit(&id, &event_tx).await {
-                                Ok(()) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Running);
-                                    self.emit_log(&event_tx, &id, "restarted".into());
-                                }
-                                Err(e) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Errored { message: e.clone() });
-                                    self.emit_log(&event_tx, &id, format!("[error] restart failed: {}", e));
-                                }
-                            }
-                        }
-
-                        AdapterCommand::Kill { id } => {
-                            match self.stop_unit(&id, true).await {
-                                Ok(()) => {
-                                    self.emit_status(&event_tx, &id, UnitStatus::Stopped);
-                                    self.emit_log(&event_tx, &id, "killed".into());
-                                }
-                                Err(e) => {
-                                    self.emit_log(&event_tx, &id, format!("[warn] {}", e));
-                                }
-                            }
-                        }
-
-                        AdapterCommand::Toggle { id } => {
-                            if self.processes.contains_key(&id) {
-                                self.emit_log(&event_tx, &id, "stopping...".into());
-                                self.emit_status(&event_tx, &id, UnitStatus::Stopping);
-                                let _ = self.stop_unit(&id, false).await;
-                                self.emit_status(&event_tx, &id, UnitStatus::Stopped);
-                            } else {
-                                self.emit_status(&event_tx, &id, UnitStatus::Starting);
-                                match self.spawn_unit(&id, &event_tx).await {
-                                    Ok(()) => {
-                                        self.emit_status(&event_tx, &id, UnitStatus::Running);
-                                    }
-                                    Err(e) => {
-                                        self.emit_status(&event_tx, &id, UnitStatus::Errored { message: e.clone() });
-                                        self.emit_log(&event_tx, &id, format!("[error] {}", e));
-                                    }
-                                }
-                            }
-                        }
-
-                        AdapterCommand::ClearLogs { id } => {
-                            self.emit_log(&event_tx, &id, "logs cleared".into());
-                        }
-
-                        AdapterCommand::Install { id } => {
-                            self.emit_log(&event_tx, &id, "installing dependencies...".into());
-                            match self.install_unit(&id, &event_tx).await {
-                                Ok(()) => {}
-                                Err(e) => {
-                                    self.emit_log(&event_tx, &id, format!("[error] install failed: {}", e));
-                                }
-                            }
-                        }
-
-                        AdapterCommand::Exec { id, cmd } => {
-                            let shown = cmd.join(" ");
-                            self.emit_log(&event_tx, &id, format!("$ {}", shown));
-
-                            if cmd.is_empty() {
-                                self.emit_log(&event_tx, &id, "[error] empty command".into());
-                                continue;
-                            }
-
-                            let unit = self.units.get(&id);
-                            let mut command = Command::new(&cmd[0]);
-                            command.args(&cmd[1..]);
-
-                            if let Some(u) = unit {
-                                if let Some(cwd) = &u.cwd {
-                                    command.current_dir(cwd);
-                                }
-                                for (k, v) in &u.env {
-                                    command.env(k, v);
-                                }
-                            }
+                Some(watch_id) = watch_fired_rx.recv() => {
+                    self.fire_watch(&event_tx, &watch_id).await;
+                }
 
-                            let output = command.output().await;
-
-                            match output {
-                                Ok(out) => {
-                                    if !out.stdout.is_empty() {
-                                        if let Ok(s) = String::from_utf8(out.stdout) {
-                                            for line in s.lines() {
-                                                self.emit(
-                                                    &event_tx,
-                                                    AdapterEvent::LogLine {
-                                                        id: id.clone(),
-                                                        stream: LogStream::Stdout,
-                                                        text: line.to_string(),
-                                                    },
-                                                );
-                                            }
-                                        }
-                                    }
-                                    if !out.stderr.is_empty() {
-                                        if let Ok(s) = String::from_utf8(out.stderr) {
-                                            for line in s.lines() {
-                                                self.emit(
-                                                    &event_tx,
-                                                    AdapterEvent::LogLine {
-                                                        id: id.clone(),
-                                                        stream: LogStream::Stderr,
-                                                        text: line.to_string(),
-                                                    },
-                                                );
-                                            }
-                                        }
-                                    }
-                                    let status = if out.status.success() {
-                                        "ok".to_string()
-                                    } else {
-                                        format!("exit code: {:?}", out.status.code())
-                                    };
-                                    self.emit_log(&event_tx, &id, status);
-                                }
-                                Err(e) => {
-                                    self.emit_log(&event_tx, &id, format!("[error] {}", e));
-                                }
-                            }
-                        }
+                Some((id, task_id, ready)) = ready_rx.recv() => {
+                    // Ignore reports from tasks replaced by a restart
+                    if self.readiness.get(&id).is_none_or(|t| t.id() != task_id) {
+                        continue;
                     }
+                    self.readiness.remove(&id);
+                    let failure = if ready {
+                        None
+                    } else {
+                        self.not_ready.insert(id.clone());
+                        Some(format!("dependency '{}' is not ready", id))
+                    };
+                    self.release_pending(&event_tx, id, failure).await;
                 }
             }
         }
@@ -639,7 +1635,8 @@ impl Adapter for ProcessAdapter {
             cpu_percent: 0.0,
             memory_bytes: 0,
             uptime_secs: handle.started_at.elapsed().as_secs(),
-            pid: handle.child.id(),
+            pid: handle.pid,
+            ..Default::default()
         })
     }
 }
@@ -0,0 +1,631 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, Command};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+
+use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, LogStream};
+use orkesy_core::env_file;
+use orkesy_core::log_filter::SecretMasker;
+use orkesy_core::state::truncate_log_line;
+use orkesy_core::unit::{StopBehavior, StopSignal, Unit, UnitId, UnitKind, UnitStatus};
+
+use super::process::wait_ready;
+use crate::commands::shell_quote;
+
+/// Runs the unit's script (`$1`) in the background, in its own process
+/// group, and sends each signal name read from the session's stdin to the
+/// whole group, so the shell running the script and everything it started
+/// get it. When the connection drops, stdin closes and the group gets
+/// SIGHUP, so nothing is left running on the remote host. The group comes
+/// from `setsid`, or job control where there's no `setsid` (macOS); a shell
+/// without either only signals the script's shell.
+const REMOTE_WRAPPER: &str = r#"exec 3<&0
+if command -v setsid >/dev/null 2>&1; then
+    setsid sh -c "$1" </dev/null &
+else
+    set -m 2>/dev/null
+    sh -c "$1" </dev/null &
+fi
+pid=$!
+signal() { kill -s "$1" -- -"$pid" 2>/dev/null || kill -s "$1" "$pid" 2>/dev/null; }
+(while read -r sig <&3; do signal "$sig"; done; signal HUP) &
+watcher=$!
+wait "$pid"
+code=$?
+kill "$watcher" 2>/dev/null
+exit "$code""#;
+
+/// How long the local `ssh` gets to exit after a remote SIGKILL
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// A running `ssh` session for a unit
+struct Session {
+    /// Distinguishes this session's exit from an earlier one of the same unit
+    generation: u64,
+    /// Signal names for the remote wrapper
+    signals: ChildStdin,
+    /// Exit code once the session has ended (`Some(None)` for a signal)
+    exited: watch::Receiver<Option<Option<i32>>>,
+    /// Kills the local `ssh` process
+    kill_tx: Option<oneshot::Sender<()>>,
+    readiness: Option<JoinHandle<bool>>,
+}
+
+/// A session that ended
+struct SessionExit {
+    id: UnitId,
+    generation: u64,
+    code: Option<i32>,
+}
+
+/// Runs `kind: ssh` units on their remote host through the system `ssh`
+/// client, so `~/.ssh/config`, agents and known hosts apply as usual.
+/// Connections never prompt: hosts must be reachable with keys.
+pub struct SshAdapter {
+    units: BTreeMap<UnitId, Unit>,
+    sessions: BTreeMap<UnitId, Session>,
+    next_generation: u64,
+    /// Set while `run` is active
+    exit_tx: Option<mpsc::Sender<SessionExit>>,
+    global_env_file: Option<std::path::PathBuf>,
+}
+
+impl SshAdapter {
+    pub fn new() -> Self {
+        Self {
+            units: BTreeMap::new(),
+            sessions: BTreeMap::new(),
+            next_generation: 0,
+            exit_tx: None,
+            global_env_file: None,
+        }
+    }
+
+    /// `.env` file loaded for every unit before its own `env_file`
+    pub fn with_global_env_file(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.global_env_file = path;
+        self
+    }
+
+    fn emit_log(&self, event_tx: &broadcast::Sender<AdapterEvent>, id: &str, text: String) {
+        let _ = event_tx.send(AdapterEvent::LogLine {
+            id: id.to_string(),
+            stream: LogStream::System,
+            text,
+        });
+    }
+
+    fn emit_status(
+        &self,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+        id: &str,
+        status: UnitStatus,
+    ) {
+        let _ = event_tx.send(AdapterEvent::StatusChanged {
+            id: id.to_string(),
+            status,
+        });
+    }
+
+    /// The unit's `env` over its env files (`global_env_file` first); the
+    /// files are read locally and the values sent to the remote host
+    fn unit_env(
+        &self,
+        unit: &Unit,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+    ) -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        for path in self.global_env_file.iter().chain(&unit.env_file) {
+            match env_file::load(path) {
+                Ok(vars) => env.extend(vars),
+                Err(e) => self.emit_log(
+                    event_tx,
+                    &unit.id,
+                    format!("[env_file] skipped {}: {}", path.display(), e),
+                ),
+            }
+        }
+        env.extend(unit.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env
+    }
+
+    async fn start(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        let Some(unit) = self.units.get(id).cloned() else {
+            return; // Not an ssh unit
+        };
+        if self.sessions.contains_key(id) {
+            self.emit_log(event_tx, id, "[warn] already running".into());
+            return;
+        }
+
+        self.emit_status(event_tx, id, UnitStatus::Starting);
+        let env = self.unit_env(&unit, event_tx);
+        let remote = format!(
+            "sh -c {} orkesy {}",
+            shell_quote(REMOTE_WRAPPER),
+            shell_quote(&remote_script(&unit, &env))
+        );
+        let Some(mut cmd) = ssh_command(&unit, &remote) else {
+            return;
+        };
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                let message = format!("failed to run ssh: {}", e);
+                self.emit_log(event_tx, id, format!("[error] {}", message));
+                self.emit_status(event_tx, id, UnitStatus::Errored { message });
+                return;
+            }
+        };
+
+        let masker = Arc::new(SecretMasker::from_env(&env));
+        let max_line = unit.log_line_limit();
+        for (stream, reader) in [
+            (
+                LogStream::Stdout,
+                child
+                    .stdout
+                    .take()
+                    .map(|r| Box::new(r) as Box<dyn AsyncRead + Unpin + Send>),
+            ),
+            (
+                LogStream::Stderr,
+                child
+                    .stderr
+                    .take()
+                    .map(|r| Box::new(r) as Box<dyn AsyncRead + Unpin + Send>),
+            ),
+        ] {
+            if let Some(reader) = reader {
+                forward_logs(id, stream, reader, max_line, masker.clone(), event_tx);
+            }
+        }
+
+        let Some(signals) = child.stdin.take() else {
+            return;
+        };
+        self.next_generation += 1;
+        let generation = self.next_generation;
+        let (exited_tx, exited) = watch::channel(None);
+        let (kill_tx, kill_rx) = oneshot::channel();
+        let exit_tx = self.exit_tx.clone();
+        let unit_id = id.clone();
+        tokio::spawn(async move {
+            let code = tokio::select! {
+                status = child.wait() => status.ok().and_then(|s| s.code()),
+                _ = kill_rx => {
+                    let _ = child.kill().await;
+                    None
+                }
+            };
+            let _ = exited_tx.send(Some(code));
+            if let Some(exit_tx) = exit_tx {
+                let _ = exit_tx
+                    .send(SessionExit {
+                        id: unit_id,
+                        generation,
+                        code,
+                    })
+                    .await;
+            }
+        });
+
+        let readiness = unit.ready_when.clone().map(|check| {
+            tokio::spawn(wait_ready(
+                id.clone(),
+                check,
+                unit.host().to_string(),
+                Duration::from_millis(unit.ready_timeout_ms),
+                event_tx.clone(),
+            ))
+        });
+        self.sessions.insert(
+            id.clone(),
+            Session {
+                generation,
+                signals,
+                exited,
+                kill_tx: Some(kill_tx),
+                readiness,
+            },
+        );
+        self.emit_log(event_tx, id, format!("started on {}", unit.host()));
+        self.emit_status(event_tx, id, UnitStatus::Running);
+    }
+
+    /// Sends the unit's stop signal (or runs its stop command) and waits up
+    /// to `stop_timeout_ms` before killing it
+    async fn stop(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        let Some(unit) = self.units.get(id).cloned() else {
+            return;
+        };
+        let Some(mut session) = self.sessions.remove(id) else {
+            return;
+        };
+        if let Some(task) = session.readiness.take() {
+            task.abort();
+        }
+
+        self.emit_log(event_tx, id, "stopping...".into());
+        self.emit_status(event_tx, id, UnitStatus::Stopping);
+
+        match &unit.stop {
+            StopBehavior::Signal(signal) => {
+                send_signal(&mut session, signal_name(signal)).await;
+            }
+            StopBehavior::Command(stop_cmd) => {
+                let remote = format!("sh -c {}", shell_quote(stop_cmd));
+                if let Some(mut cmd) = ssh_command(&unit, &remote) {
+                    let _ = cmd.stdin(Stdio::null()).status().await;
+                }
+            }
+        }
+
+        let timeout = Duration::from_millis(unit.stop_timeout_ms);
+        if !wait_exit(&mut session, timeout).await {
+            self.emit_log(
+                event_tx,
+                id,
+                format!("[warn] still running after {}s, killing", timeout.as_secs()),
+            );
+            send_signal(&mut session, "KILL").await;
+            if !wait_exit(&mut session, KILL_GRACE).await
+                && let Some(kill_tx) = session.kill_tx.take()
+            {
+                let _ = kill_tx.send(());
+            }
+        }
+        self.emit_status(event_tx, id, UnitStatus::Stopped);
+    }
+
+    async fn kill(&mut self, id: &UnitId, event_tx: &broadcast::Sender<AdapterEvent>) {
+        let Some(mut session) = self.sessions.remove(id) else {
+            return;
+        };
+        if let Some(task) = session.readiness.take() {
+            task.abort();
+        }
+        send_signal(&mut session, "KILL").await;
+        if !wait_exit(&mut session, KILL_GRACE).await
+            && let Some(kill_tx) = session.kill_tx.take()
+        {
+            let _ = kill_tx.send(());
+        }
+        self.emit_status(event_tx, id, UnitStatus::Stopped);
+        self.emit_log(event_tx, id, "killed".into());
+    }
+
+    /// A session ended on its own; exits of stopped sessions are ignored
+    fn session_exited(&mut self, event_tx: &broadcast::Sender<AdapterEvent>, exit: SessionExit) {
+        if self
+            .sessions
+            .get(&exit.id)
+            .is_none_or(|s| s.generation != exit.generation)
+        {
+            return;
+        }
+        if let Some(task) = self
+            .sessions
+            .remove(&exit.id)
+            .and_then(|mut s| s.readiness.take())
+        {
+            task.abort();
+        }
+        // ssh exits with 255 when it can't connect or authenticate
+        let text = match exit.code {
+            Some(255) => "ssh connection failed or was lost (exit code 255)".to_string(),
+            code => format!("exited with code: {:?}", code),
+        };
+        self.emit_log(event_tx, &exit.id, text);
+        self.emit_status(event_tx, &exit.id, UnitStatus::Exited { code: exit.code });
+    }
+
+    /// Runs `cmd` on the unit's host in a separate connection, streaming its
+    /// output into the unit's logs
+    fn exec(&self, id: &UnitId, cmd: &[String], event_tx: &broadcast::Sender<AdapterEvent>) {
+        let Some(unit) = self.units.get(id) else {
+            return;
+        };
+        let shown = cmd.join(" ");
+        self.emit_log(event_tx, id, format!("$ {}", shown));
+
+        let remote = format!("sh -c {}", shell_quote(&shown));
+        let Some(mut command) = ssh_command(unit, &remote) else {
+            return;
+        };
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let tx = event_tx.clone();
+        let unit_id = id.clone();
+        tokio::spawn(async move {
+            let text = match command.output().await {
+                Ok(output) => {
+                    for (stream, bytes) in [
+                        (LogStream::Stdout, &output.stdout),
+                        (LogStream::Stderr, &output.stderr),
+                    ] {
+                        for line in String::from_utf8_lossy(bytes).lines() {
+                            let _ = tx.send(AdapterEvent::LogLine {
+                                id: unit_id.clone(),
+                                stream,
+                                text: line.to_string(),
+                            });
+                        }
+                    }
+                    format!("exec exited with code: {:?}", output.status.code())
+                }
+                Err(e) => format!("[error] failed to run ssh: {}", e),
+            };
+            let _ = tx.send(AdapterEvent::LogLine {
+                id: unit_id,
+                stream: LogStream::System,
+                text,
+            });
+        });
+    }
+
+    async fn handle_command(
+        &mut self,
+        cmd: AdapterCommand,
+        event_tx: &broadcast::Sender<AdapterEvent>,
+    ) {
+        match cmd {
+            AdapterCommand::Start { id } => self.start(&id, event_tx).await,
+
+            // Started in the order given, like docker units
+            AdapterCommand::StartMany { ids } => {
+                for id in ids {
+                    self.start(&id, event_tx).await;
+                }
+            }
+
+            AdapterCommand::Stop { id } => self.stop(&id, event_tx).await,
+
+            AdapterCommand::Restart { id } => {
+                if !self.units.contains_key(&id) {
+                    return;
+                }
+                self.emit_log(event_tx, &id, "restarting...".into());
+                self.stop(&id, event_tx).await;
+                self.start(&id, event_tx).await;
+            }
+
+            AdapterCommand::Kill { id } => self.kill(&id, event_tx).await,
+
+            AdapterCommand::Toggle { id } => {
+                if self.sessions.contains_key(&id) {
+                    self.stop(&id, event_tx).await;
+                } else {
+                    self.start(&id, event_tx).await;
+                }
+            }
+
+            AdapterCommand::Exec { id, cmd } => self.exec(&id, &cmd, event_tx),
+
+            AdapterCommand::ClearLogs { id } => {
+                if self.units.contains_key(&id) {
+                    self.emit_log(event_tx, &id, "logs cleared".into());
+                }
+            }
+
+            AdapterCommand::AddNote { id, text } => {
+                let _ = event_tx.send(AdapterEvent::UserNote {
+                    id,
+                    text,
+                    at: SystemTime::now(),
+                });
+            }
+
+            AdapterCommand::Install { id } => {
+                if self.units.contains_key(&id) {
+                    self.emit_log(event_tx, &id, "ssh units have no install step".into());
+                }
+            }
+
+            AdapterCommand::GetLogs { reply_tx, .. } => {
+                // Lines are streamed as they arrive, not buffered here
+                let _ = reply_tx.send(Vec::new());
+            }
+
+            AdapterCommand::Watch { id, .. } => {
+                if self.units.contains_key(&id) {
+                    self.emit_log(
+                        event_tx,
+                        &id,
+                        "[warn] watches are not supported for ssh units".into(),
+                    );
+                }
+            }
+
+            AdapterCommand::CancelWatch { .. } => {}
+
+            AdapterCommand::Shutdown => {
+                let ids: Vec<UnitId> = self.sessions.keys().cloned().collect();
+                for id in ids {
+                    self.stop(&id, event_tx).await;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Adapter for SshAdapter {
+    fn name(&self) -> &'static str {
+        "ssh"
+    }
+
+    async fn run(
+        &mut self,
+        mut command_rx: mpsc::Receiver<AdapterCommand>,
+        event_tx: broadcast::Sender<AdapterEvent>,
+        units: Vec<Unit>,
+    ) {
+        for unit in units {
+            if matches!(unit.kind, UnitKind::Ssh { .. }) {
+                self.units.insert(unit.id.clone(), unit);
+            }
+        }
+
+        let (exit_tx, mut exit_rx) = mpsc::channel(16);
+        self.exit_tx = Some(exit_tx);
+
+        loop {
+            let cmd = tokio::select! {
+                cmd = command_rx.recv() => match cmd {
+                    Some(cmd) => cmd,
+                    None => break,
+                },
+                Some(exit) = exit_rx.recv() => {
+                    self.session_exited(&event_tx, exit);
+                    continue;
+                }
+            };
+
+            let shutdown = matches!(cmd, AdapterCommand::Shutdown);
+            self.handle_command(cmd, &event_tx).await;
+            if shutdown {
+                break;
+            }
+        }
+        self.exit_tx = None;
+    }
+
+    fn status(&self, id: &str) -> Option<UnitStatus> {
+        if self.sessions.contains_key(id) {
+            Some(UnitStatus::Running)
+        } else if self.units.contains_key(id) {
+            Some(UnitStatus::Stopped)
+        } else {
+            None
+        }
+    }
+}
+
+/// Runs an `SshAdapter` for the ssh units among `units` next to another
+/// adapter, which should read the returned receiver (see
+/// [`super::route_commands`])
+pub fn split_ssh_commands(
+    command_rx: mpsc::Receiver<AdapterCommand>,
+    units: &[Unit],
+    event_tx: broadcast::Sender<AdapterEvent>,
+    global_env_file: Option<std::path::PathBuf>,
+) -> mpsc::Receiver<AdapterCommand> {
+    let ssh_ids: BTreeSet<UnitId> = units
+        .iter()
+        .filter(|u| matches!(u.kind, UnitKind::Ssh { .. }))
+        .map(|u| u.id.clone())
+        .collect();
+    if ssh_ids.is_empty() {
+        return command_rx;
+    }
+
+    let (ssh_tx, ssh_rx) = mpsc::channel(100);
+    let ssh_units = units.to_vec();
+    tokio::spawn(async move {
+        SshAdapter::new()
+            .with_global_env_file(global_env_file)
+            .run(ssh_rx, event_tx, ssh_units)
+            .await;
+    });
+    super::route_commands(command_rx, ssh_ids, ssh_tx)
+}
+
+/// `ssh` to the unit's host running `remote`; `None` for other units.
+/// `-T` keeps stdout and stderr apart, and `BatchMode` fails instead of
+/// prompting for a password the TUI couldn't show.
+fn ssh_command(unit: &Unit, remote: &str) -> Option<Command> {
+    let UnitKind::Ssh {
+        host,
+        user,
+        identity_file,
+    } = &unit.kind
+    else {
+        return None;
+    };
+    let mut cmd = Command::new("ssh");
+    cmd.args(["-T", "-o", "BatchMode=yes"]);
+    if let Some(user) = user {
+        cmd.arg("-l").arg(user);
+    }
+    if let Some(identity_file) = identity_file {
+        cmd.arg("-i").arg(identity_file);
+    }
+    cmd.arg("--").arg(host).arg(remote);
+    Some(cmd)
+}
+
+/// The unit's env exports, `cd` to its `cwd` on the remote host, and its
+/// start command; always run through a shell
+fn remote_script(unit: &Unit, env: &BTreeMap<String, String>) -> String {
+    let mut script = String::new();
+    for (key, value) in env {
+        script.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+    }
+    if let Some(cwd) = &unit.cwd {
+        script.push_str(&format!(
+            "cd {} || exit 1\n",
+            shell_quote(&cwd.display().to_string())
+        ));
+    }
+    script.push_str(&unit.start);
+    script
+}
+
+fn signal_name(signal: &StopSignal) -> &'static str {
+    match signal {
+        StopSignal::SigInt => "INT",
+        StopSignal::SigTerm => "TERM",
+        StopSignal::SigKill => "KILL",
+    }
+}
+
+/// Asks the remote wrapper to send `signal` to the unit's process
+async fn send_signal(session: &mut Session, signal: &str) {
+    let _ = session
+        .signals
+        .write_all(format!("{}\n", signal).as_bytes())
+        .await;
+    let _ = session.signals.flush().await;
+}
+
+/// Whether the session ended within `timeout`
+async fn wait_exit(session: &mut Session, timeout: Duration) -> bool {
+    let exited = session.exited.wait_for(|code| code.is_some());
+    matches!(tokio::time::timeout(timeout, exited).await, Ok(Ok(_)))
+}
+
+fn forward_logs(
+    id: &UnitId,
+    stream: LogStream,
+    reader: Box<dyn AsyncRead + Unpin + Send>,
+    max_line: usize,
+    masker: Arc<SecretMasker>,
+    event_tx: &broadcast::Sender<AdapterEvent>,
+) {
+    let tx = event_tx.clone();
+    let unit_id = id.clone();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = tx.send(AdapterEvent::LogLine {
+                id: unit_id.clone(),
+                stream,
+                text: truncate_log_line(masker.mask(&line), max_line),
+            });
+        }
+    });
+}
@@ -1,6 +1,12 @@
-use std::net::TcpListener;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
 use std::path::Path;
 use std::process::Command;
+use std::time::Duration;
+
+use orkesy_core::unit::{HealthCheck, Unit};
+
+use crate::profile::load_config;
 
 #[derive(Debug)]
 pub struct Check {
@@ -35,7 +41,7 @@ impl Check {
     }
 }
 
-pub fn run_doctor() -> Result<(), String> {
+pub fn run_doctor(timeout_secs: u64) -> Result<(), String> {
     println!("Orkesy Doctor\n");
     println!("Checking environment...\n");
 
@@ -84,6 +90,18 @@ pub fn run_doctor() -> Result<(), String> {
             }
             println!();
         }
+
+        let network_checks = check_network(path, Duration::from_secs(timeout_secs.max(1)));
+        if !network_checks.is_empty() {
+            println!("Network:");
+            for check in network_checks {
+                print_check(&check);
+                if !check.passed {
+                    warnings.push(check);
+                }
+            }
+            println!();
+        }
     } else {
         println!("Configuration: not found");
         println!("  Run `orkesy init` to create one");
@@ -313,7 +331,14 @@ fn check_go() -> Check {
 fn find_config() -> Option<std::path::PathBuf> {
     let cwd = std::env::current_dir().ok()?;
 
-    for name in &["orkesy.yml", "orkesy.yaml", ".orkesy.yml", ".orkesy.yaml"] {
+    for name in &[
+        "orkesy.yml",
+        "orkesy.yaml",
+        "orkesy.toml",
+        ".orkesy.yml",
+        ".orkesy.yaml",
+        ".orkesy.toml",
+    ] {
         let path = cwd.join(name);
         if path.exists() {
             return Some(path);
@@ -323,9 +348,19 @@ fn find_config() -> Option<std::path::PathBuf> {
 }
 
 fn check_config(path: &Path) -> Option<Vec<Check>> {
-    use orkesy_core::config::OrkesyConfig;
-
-    let config = OrkesyConfig::load(path).ok()?;
+    use orkesy_core::config::ConfigError;
+    use orkesy_core::unit::check_acyclic;
+
+    let config = match load_config(path) {
+        Ok(config) => config,
+        Err(e @ ConfigError::CyclicDependency { .. }) => {
+            return Some(vec![
+                Check::fail("depends_on", e.to_string())
+                    .with_hint("Remove one of the depends_on entries in the cycle"),
+            ]);
+        }
+        Err(e) => return Some(vec![Check::fail("config", e.to_string())]),
+    };
     let units = config.to_units();
 
     if units.is_empty() {
@@ -334,6 +369,14 @@ fn check_config(path: &Path) -> Option<Vec<Check>> {
 
     let mut checks = Vec::new();
 
+    match check_acyclic(config.to_edges()) {
+        Ok(edges) => checks.push(Check::ok(
+            "depends_on",
+            format!("{} dependency edge(s), no cycles", edges.len()),
+        )),
+        Err(e) => checks.push(Check::fail("depends_on", e.to_string())),
+    }
+
     for unit in &units {
         let mut issues = Vec::new();
 
@@ -376,6 +419,200 @@ fn check_config(path: &Path) -> Option<Vec<Check>> {
     Some(checks)
 }
 
-fn is_port_available(port: u16) -> bool {
+/// An external endpoint referenced by a unit, e.g. `DATABASE_URL`.
+struct Endpoint {
+    label: String,
+    display: String,
+    host: String,
+    port: u16,
+    http_path: Option<String>,
+}
+
+fn check_network(path: &Path, timeout: Duration) -> Vec<Check> {
+    let Ok(config) = load_config(path) else {
+        return Vec::new();
+    };
+
+    config
+        .to_units()
+        .iter()
+        .flat_map(unit_endpoints)
+        .map(|endpoint| check_endpoint(&endpoint, timeout))
+        .collect()
+}
+
+fn unit_endpoints(unit: &Unit) -> Vec<Endpoint> {
+    let mut endpoints = Vec::new();
+
+    for (key, value) in &unit.env {
+        if !is_endpoint_var(key) {
+            continue;
+        }
+        // KAFKA_BROKERS=host1:9092,host2:9092
+        for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if let Some(endpoint) = parse_endpoint(part) {
+                endpoints.push(Endpoint {
+                    label: format!("{}.{}", unit.id, key),
+                    ..endpoint
+                });
+            }
+        }
+    }
+
+    if let Some(HealthCheck::Http { url, .. }) = &unit.health {
+        if let Some(endpoint) = parse_endpoint(url) {
+            endpoints.push(Endpoint {
+                label: format!("{}.health", unit.id),
+                ..endpoint
+            });
+        }
+    }
+
+    endpoints
+}
+
+fn is_endpoint_var(key: &str) -> bool {
+    const SUFFIXES: [&str; 5] = ["_URL", "_URI", "_DSN", "_BROKERS", "_ADDR"];
+    SUFFIXES.iter().any(|s| key.ends_with(s))
+}
+
+/// Parses `scheme://[user[:pass]@]host[:port][/path]` or a bare `host:port`.
+/// Credentials are dropped from the displayed form.
+fn parse_endpoint(raw: &str) -> Option<Endpoint> {
+    let (scheme, rest) = match raw.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme.to_lowercase()), rest),
+        None => (None, raw),
+    };
+
+    let (authority, path) = match rest.find(['/', '?', '#']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() => (host, port.parse().ok()?),
+        _ => (authority, default_port(scheme.as_deref()?)?),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return None;
+    }
+
+    let display = match &scheme {
+        Some(scheme) => format!("{}://{}:{}", scheme, host, port),
+        None => format!("{}:{}", host, port),
+    };
+    let http_path = (scheme.as_deref() == Some("http")).then(|| {
+        if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{}", path)
+        }
+    });
+
+    Some(Endpoint {
+        label: String::new(),
+        display,
+        host: host.to_string(),
+        port,
+        http_path,
+    })
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "postgres" | "postgresql" => Some(5432),
+        "mysql" | "mariadb" => Some(3306),
+        "redis" => Some(6379),
+        "rediss" => Some(6380),
+        "mongodb" => Some(27017),
+        "amqp" => Some(5672),
+        "amqps" => Some(5671),
+        "nats" => Some(4222),
+        "kafka" => Some(9092),
+        _ => None,
+    }
+}
+
+fn check_endpoint(endpoint: &Endpoint, timeout: Duration) -> Check {
+    let result = connect(&endpoint.host, endpoint.port, timeout).and_then(|stream| match &endpoint
+        .http_path
+    {
+        Some(path) => http_status(stream, &endpoint.host, path, timeout),
+        None => Ok("reachable".to_string()),
+    });
+
+    match result {
+        Ok(status) => Check::ok(
+            &endpoint.label,
+            format!("{} ({})", endpoint.display, status),
+        ),
+        Err(reason) => Check::fail(
+            &endpoint.label,
+            format!("{} ({})", endpoint.display, reason),
+        ),
+    }
+}
+
+pub(super) fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, String> {
+    let addrs: Vec<_> = (host, port)
+        .to_socket_addrs()
+        .map_err(|_| "host not found".to_string())?
+        .collect();
+
+    let mut last_err = "host not found".to_string();
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => {
+                last_err = match e.kind() {
+                    ErrorKind::ConnectionRefused => "connection refused".to_string(),
+                    ErrorKind::TimedOut | ErrorKind::WouldBlock => "timed out".to_string(),
+                    _ => e.to_string(),
+                }
+            }
+        }
+    }
+    Err(last_err)
+}
+
+/// Sends a bare HTTP/1.0 GET and reports the response status.
+fn http_status(
+    mut stream: TcpStream,
+    host: &str,
+    path: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: orkesy-doctor\r\n\r\n",
+        path, host
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).map_err(|e| e.to_string())?;
+    let head = String::from_utf8_lossy(&buf[..n]);
+    let code: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .ok_or_else(|| "invalid HTTP response".to_string())?;
+
+    if (200..400).contains(&code) {
+        Ok(format!("HTTP {}", code))
+    } else {
+        Err(format!("HTTP {}", code))
+    }
+}
+
+pub(super) fn is_port_available(port: u16) -> bool {
     TcpListener::bind(("127.0.0.1", port)).is_ok()
 }
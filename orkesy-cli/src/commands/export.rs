@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value};
+
+use orkesy_core::unit::{EdgeKind, HealthCheck, Unit, UnitEdge};
+
+use super::env::shell_quote;
+use crate::profile::discover_config;
+
+/// Writes the config's `docker_image` units as a docker-compose file, to
+/// `output` or stdout. Other units are skipped with a warning.
+pub fn run_export(output: Option<&Path>) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (_, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+
+    let units = config.to_units();
+    for unit in units.iter().filter(|u| u.docker_image.is_none()) {
+        eprintln!(
+            "warning: skipping '{}': only units with a docker_image can be exported",
+            unit.id
+        );
+    }
+    let yaml = compose_file(&units, &config.to_edges());
+    let yaml = serde_yaml::to_string(&yaml).map_err(|e| e.to_string())?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, yaml)
+                .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
+
+/// A compose v3 document with a service per `docker_image` unit
+fn compose_file(units: &[Unit], edges: &[UnitEdge]) -> Value {
+    let exported: Vec<&Unit> = units.iter().filter(|u| u.docker_image.is_some()).collect();
+    let is_exported = |id: &str| exported.iter().any(|u| u.id == id);
+
+    let mut services = Mapping::new();
+    for unit in &exported {
+        let mut service = Mapping::new();
+        let image = unit.docker_image.as_deref().unwrap_or_default();
+        service.insert("image".into(), image.into());
+        if !unit.start.trim().is_empty() {
+            service.insert("command".into(), unit.start.clone().into());
+        }
+
+        let depends_on: Vec<Value> = edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::DependsOn && e.from == unit.id)
+            .filter(|e| {
+                let exported = is_exported(&e.to);
+                if !exported {
+                    eprintln!(
+                        "warning: dropping '{}' depends_on '{}': it is not exported",
+                        e.from, e.to
+                    );
+                }
+                exported
+            })
+            .map(|e| e.to.clone().into())
+            .collect();
+        if !depends_on.is_empty() {
+            service.insert("depends_on".into(), Value::Sequence(depends_on));
+        }
+
+        if !unit.env.is_empty() {
+            let environment: Mapping = unit
+                .env
+                .iter()
+                .map(|(k, v)| (k.clone().into(), v.clone().into()))
+                .collect();
+            service.insert("environment".into(), Value::Mapping(environment));
+        }
+
+        let ports: Vec<Value> = if !unit.docker_ports.is_empty() {
+            unit.docker_ports.iter().map(|p| p.clone().into()).collect()
+        } else {
+            unit.port
+                .map(|p| format!("{}:{}", p, p).into())
+                .into_iter()
+                .collect()
+        };
+        if !ports.is_empty() {
+            service.insert("ports".into(), Value::Sequence(ports));
+        }
+
+        if let Some(health) = &unit.health {
+            service.insert("healthcheck".into(), healthcheck(health));
+        }
+
+        services.insert(unit.id.clone().into(), Value::Mapping(service));
+    }
+
+    let mut root = Mapping::new();
+    root.insert("version".into(), "3.8".into());
+    root.insert("services".into(), Value::Mapping(services));
+    Value::Mapping(root)
+}
+
+/// HTTP checks use `curl` (and `grep` for `body_contains`), TCP checks `nc`
+/// and gRPC checks `grpc_health_probe` inside the container, so the image
+/// needs them. `expected_status` isn't carried over; curl fails on >= 400.
+fn healthcheck(check: &HealthCheck) -> Value {
+    let (test, timeout_ms) = match check {
+        HealthCheck::Http {
+            url,
+            method,
+            headers,
+            body_contains,
+            timeout_ms,
+            ..
+        } => {
+            let mut test = "curl -fsS".to_string();
+            if let Some(method) = method {
+                test.push_str(&format!(" -X {}", shell_quote(method)));
+            }
+            for (name, value) in headers {
+                test.push_str(&format!(
+                    " -H {}",
+                    shell_quote(&format!("{}: {}", name, value))
+                ));
+            }
+            test.push_str(&format!(" {}", shell_quote(url)));
+            if let Some(needle) = body_contains {
+                test.push_str(&format!(" | grep -qF {}", shell_quote(needle)));
+            }
+            test.push_str(" || exit 1");
+            (test, Some(*timeout_ms))
+        }
+        HealthCheck::Tcp { port, .. } => (format!("nc -z localhost {} || exit 1", port), None),
+        HealthCheck::Exec { command, .. } => (command.clone(), None),
+        HealthCheck::Grpc {
+            port,
+            service,
+            timeout_ms,
+            ..
+        } => {
+            let mut test = format!("grpc_health_probe -addr=localhost:{}", port);
+            if !service.is_empty() {
+                test.push_str(&format!(" -service={}", service));
+            }
+            test.push_str(" || exit 1");
+            (test, Some(*timeout_ms))
+        }
+    };
+
+    let mut block = Mapping::new();
+    block.insert(
+        "test".into(),
+        Value::Sequence(vec!["CMD-SHELL".into(), test.into()]),
+    );
+    block.insert(
+        "interval".into(),
+        format!("{}ms", check.interval_ms()).into(),
+    );
+    if let Some(timeout_ms) = timeout_ms {
+        block.insert("timeout".into(), format!("{}ms", timeout_ms).into());
+    }
+    block.insert("retries".into(), check.failure_threshold().into());
+    Value::Mapping(block)
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde_yaml::{Mapping, Value};
+
+use orkesy_core::config::OrkesyConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImportSource {
+    /// `name: command` lines from ./Procfile
+    Procfile,
+    /// Services with an `image` from ./docker-compose.yml or ./compose.yml
+    DockerCompose,
+}
+
+impl ImportSource {
+    /// Files looked for in the current directory, in order
+    fn file_names(self) -> &'static [&'static str] {
+        match self {
+            ImportSource::Procfile => &["Procfile"],
+            ImportSource::DockerCompose => &[
+                "docker-compose.yml",
+                "docker-compose.yaml",
+                "compose.yml",
+                "compose.yaml",
+            ],
+        }
+    }
+
+    fn load(self, path: &Path) -> Result<OrkesyConfig, String> {
+        match self {
+            ImportSource::Procfile => OrkesyConfig::from_procfile(path),
+            ImportSource::DockerCompose => OrkesyConfig::from_docker_compose(path),
+        }
+        .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Converts `source` (`file`, or its usual file in the current directory) to
+/// an orkesy config written to `output`, which is only overwritten with `yes`
+pub fn run_import(
+    source: ImportSource,
+    file: Option<PathBuf>,
+    output: &Path,
+    yes: bool,
+) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let input = match file {
+        Some(file) if file.exists() => file,
+        Some(file) => return Err(format!("{} not found", file.display())),
+        None => source
+            .file_names()
+            .iter()
+            .map(|name| cwd.join(name))
+            .find(|path| path.exists())
+            .ok_or_else(|| format!("{} not found", source.file_names().join(" or ")))?,
+    };
+    if output.exists() && !yes {
+        return Err(format!(
+            "{} already exists. Use --yes to overwrite.",
+            output.display()
+        ));
+    }
+
+    let config = source.load(&input)?;
+    let input_name = input
+        .file_name()
+        .unwrap_or(input.as_os_str())
+        .to_string_lossy();
+    if source == ImportSource::DockerCompose {
+        for id in compose_service_ids(&input) {
+            if !config.services.contains_key(&id) {
+                eprintln!("warning: skipping '{}': it has no image", id);
+            }
+        }
+    }
+
+    let yaml = serde_yaml::to_string(&config_yaml(&config)).map_err(|e| e.to_string())?;
+    let yaml = format!(
+        "# Imported from {} by `orkesy import`\n{}",
+        input_name, yaml
+    );
+    std::fs::write(output, yaml)
+        .map_err(|e| format!("could not write {}: {}", output.display(), e))?;
+
+    println!(
+        "Imported {} service(s) from {} into {}",
+        config.services.len(),
+        input_name,
+        output.display()
+    );
+    Ok(())
+}
+
+fn compose_service_ids(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<Value>(&content).ok())
+        .and_then(|compose| {
+            let services = compose.get("services")?.as_mapping()?;
+            Some(
+                services
+                    .keys()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Only the fields an import sets, so the written file stays readable
+fn config_yaml(config: &OrkesyConfig) -> Value {
+    let strings =
+        |values: &[String]| Value::Sequence(values.iter().map(|v| v.as_str().into()).collect());
+
+    let mut services = Mapping::new();
+    for (id, svc) in &config.services {
+        let mut service = Mapping::new();
+        if let Some(image) = &svc.docker_image {
+            service.insert("docker_image".into(), image.as_str().into());
+        }
+        if !svc.command.is_empty() {
+            service.insert("command".into(), strings(&svc.command));
+        }
+        if let Some(shell) = svc.shell {
+            service.insert("shell".into(), shell.into());
+        }
+        if let Some(port) = svc.port {
+            service.insert("port".into(), port.into());
+        }
+        if !svc.docker_ports.is_empty() {
+            service.insert("docker_ports".into(), strings(&svc.docker_ports));
+        }
+        if !svc.env.is_empty() {
+            let env = svc
+                .env
+                .iter()
+                .map(|(k, v)| (k.as_str().into(), v.as_str().into()))
+                .collect();
+            service.insert("env".into(), Value::Mapping(env));
+        }
+        if !svc.depends_on.is_empty() {
+            service.insert("depends_on".into(), strings(&svc.depends_on));
+        }
+        if !svc.autostart {
+            service.insert("autostart".into(), false.into());
+        }
+        services.insert(id.as_str().into(), Value::Mapping(service));
+    }
+
+    let mut root = Mapping::new();
+    if let Some(name) = &config.name {
+        root.insert("name".into(), name.as_str().into());
+    }
+    root.insert("services".into(), Value::Mapping(services));
+    Value::Mapping(root)
+}
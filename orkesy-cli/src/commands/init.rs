@@ -1,5 +1,14 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Build and cache directories added to `.gitignore`, with the files that
+/// show they apply to this project. `.orkesy/` is always added.
+const GITIGNORE_ENTRIES: [(&str, &[&str]); 3] = [
+    ("node_modules/", &["package.json"]),
+    ("target/", &["Cargo.toml"]),
+    (".venv/", &["pyproject.toml", "requirements.txt"]),
+];
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -28,7 +37,14 @@ pub fn run_init(yes: bool) -> Result<(), String> {
         std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
 
     // Check if config already exists
-    let config_names = ["orkesy.yml", "orkesy.yaml", ".orkesy.yml", ".orkesy.yaml"];
+    let config_names = [
+        "orkesy.yml",
+        "orkesy.yaml",
+        "orkesy.toml",
+        ".orkesy.yml",
+        ".orkesy.yaml",
+        ".orkesy.toml",
+    ];
     for name in &config_names {
         let path = cwd.join(name);
         if path.exists() {
@@ -70,10 +86,23 @@ pub fn run_init(yes: bool) -> Result<(), String> {
     let output_path = cwd.join("orkesy.yml");
     fs::write(&output_path, &yaml).map_err(|e| format!("Failed to write config: {}", e))?;
 
-    println!("Created: {}\n", output_path.display());
-    println!("Next steps:");
+    println!("Created: {}", output_path.display());
+
+    let in_git = git_dir(&cwd).is_some();
+    if in_git {
+        match update_gitignore(&cwd) {
+            Ok(added) if added.is_empty() => {}
+            Ok(added) => println!("Updated .gitignore: {}", added.join(", ")),
+            Err(e) => eprintln!("Warning: could not update .gitignore: {}", e),
+        }
+    }
+
+    println!("\nNext steps:");
     println!("  1. Review and customize orkesy.yml");
     println!("  2. Run `orkesy` to start the TUI");
+    if in_git {
+        println!("  3. Track the config: git add orkesy.yml .gitignore");
+    }
 
     Ok(())
 }
@@ -143,6 +172,11 @@ fn detect_project_name(dir: &Path) -> Option<String> {
         }
     }
 
+    // Try the git remote, then the repository root
+    if let Some(name) = git_project_name(dir) {
+        return Some(name);
+    }
+
     // Fall back to directory name
     dir.file_name()
         .and_then(|n| n.to_str())
@@ -607,6 +641,95 @@ fn detect_go(dir: &Path) -> Option<Vec<DetectedUnit>> {
     }])
 }
 
+// Git integration
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+/// `None` outside a git repository or when git isn't installed
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    git(dir, &["rev-parse", "--git-dir"]).map(PathBuf::from)
+}
+
+/// Repository name from `origin` (`git@github.com:acme/shop.git` -> `shop`),
+/// or the repository root's directory name once there is a commit
+fn git_project_name(dir: &Path) -> Option<String> {
+    git_dir(dir)?;
+
+    let from_remote = git(dir, &["remote", "get-url", "origin"]).and_then(|url| {
+        let name = url
+            .trim_end_matches('/')
+            .rsplit(['/', ':'])
+            .next()?
+            .trim_end_matches(".git")
+            .to_string();
+        (!name.is_empty()).then_some(name)
+    });
+    if from_remote.is_some() {
+        return from_remote;
+    }
+
+    git(dir, &["log", "--oneline", "-1"])?;
+    let root = git(dir, &["rev-parse", "--show-toplevel"])?;
+    Path::new(&root)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string())
+}
+
+/// Appends `.orkesy/` and the build directories that apply to this project
+/// to `.gitignore`, creating it if needed. Returns the entries added.
+fn update_gitignore(dir: &Path) -> Result<Vec<String>, String> {
+    let path = dir.join(".gitignore");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let ignored = |entry: &str| {
+        let bare = entry.trim_end_matches('/');
+        existing.lines().map(str::trim).any(|line| {
+            let line = line.trim_start_matches('/');
+            line == entry || line == bare || line == format!("{}/**", bare)
+        })
+    };
+
+    let mut added: Vec<String> = Vec::new();
+    if !ignored(".orkesy/") {
+        added.push(".orkesy/".to_string());
+    }
+    for (entry, markers) in GITIGNORE_ENTRIES {
+        let applies = dir.join(entry).is_dir() || markers.iter().any(|m| dir.join(m).exists());
+        if applies && !ignored(entry) {
+            added.push(entry.to_string());
+        }
+    }
+    if added.is_empty() {
+        return Ok(added);
+    }
+
+    let mut content = existing.clone();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content.push_str("# Added by `orkesy init`\n");
+    for entry in &added {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(added)
+}
+
 // Helper functions for parsing
 
 fn extract_json_string(content: &str, key: &str) -> Option<String> {
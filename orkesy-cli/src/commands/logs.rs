@@ -0,0 +1,182 @@
+use std::time::UNIX_EPOCH;
+
+use orkesy_core::log_filter::detect_level;
+use orkesy_core::state::{LogLine, LogStream};
+
+use crate::ui::ansi::strip_ansi;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Timestamp,
+    TimestampMs,
+    Unit,
+    Stream,
+    Level,
+    Text,
+    TextStripped,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "timestamp" => Some(Self::Timestamp),
+            "timestamp_ms" => Some(Self::TimestampMs),
+            "unit" => Some(Self::Unit),
+            "stream" => Some(Self::Stream),
+            "level" => Some(Self::Level),
+            "text" => Some(Self::Text),
+            "text_stripped" => Some(Self::TextStripped),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+#[derive(Clone, Debug)]
+enum Output {
+    Template(Vec<Segment>),
+    JsonLines,
+}
+
+/// Output template for `orkesy logs --format`, e.g. `{timestamp} [{unit}] {text}`.
+/// Unknown `{placeholders}` are printed as written. The template `jsonl`
+/// prints one JSON object per line instead.
+#[derive(Clone, Debug)]
+pub struct LogFormatter {
+    output: Output,
+}
+
+impl LogFormatter {
+    pub fn new(template: &str) -> Self {
+        if template == "jsonl" {
+            return Self {
+                output: Output::JsonLines,
+            };
+        }
+
+        let template = unescape(template);
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template.as_str();
+
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let field = after
+                .find('}')
+                .and_then(|close| Field::parse(&after[..close]).map(|f| (f, close)));
+            match field {
+                Some((field, close)) => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field));
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    literal.push('{');
+                    rest = after;
+                }
+            }
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Self {
+            output: Output::Template(segments),
+        }
+    }
+
+    /// Whether lines are printed as JSON, so other output belongs on stderr
+    pub fn is_json_lines(&self) -> bool {
+        matches!(self.output, Output::JsonLines)
+    }
+
+    /// Renders `line`; `timestamp` is the configured display form of `line.at`
+    pub fn format(&self, line: &LogLine, timestamp: &str) -> String {
+        let segments = match &self.output {
+            Output::Template(segments) => segments,
+            Output::JsonLines => return json_line(line),
+        };
+
+        let mut out = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field(Field::Timestamp) => out.push_str(timestamp),
+                Segment::Field(Field::TimestampMs) => {
+                    let ms = line
+                        .at
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_millis())
+                        .unwrap_or(0);
+                    out.push_str(&ms.to_string());
+                }
+                Segment::Field(Field::Unit) => out.push_str(&line.service_id),
+                Segment::Field(Field::Stream) => out.push_str(stream_name(&line.stream)),
+                Segment::Field(Field::Level) => {
+                    out.push_str(&detect_level(&strip_ansi(&line.text)).to_string())
+                }
+                Segment::Field(Field::Text) => out.push_str(&line.text),
+                Segment::Field(Field::TextStripped) => out.push_str(&strip_ansi(&line.text)),
+            }
+        }
+        out
+    }
+}
+
+pub fn stream_name(stream: &LogStream) -> &'static str {
+    match stream {
+        LogStream::Stdout => "stdout",
+        LogStream::Stderr => "stderr",
+        LogStream::System => "system",
+    }
+}
+
+/// `{"ts", "unit", "stream", "text"}` with an ISO-8601 UTC timestamp
+fn json_line(line: &LogLine) -> String {
+    let ts = chrono::DateTime::<chrono::Utc>::from(line.at)
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let value = serde_json::json!({
+        "ts": ts,
+        "unit": line.service_id,
+        "stream": stream_name(&line.stream),
+        "text": line.text,
+    });
+    serde_json::to_string(&value).unwrap_or_default()
+}
+
+/// Expands `\x1b`, `\e`, `\033`, `\n`, `\t` and `\\` so color codes can be
+/// passed from a shell without `$'...'` quoting.
+fn unescape(template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let escape = &rest[pos + 1..];
+        let (replacement, len) = if ["x1b", "x1B", "033"].iter().any(|p| escape.starts_with(p)) {
+            ("\x1b", 3)
+        } else if escape.starts_with('e') {
+            ("\x1b", 1)
+        } else if escape.starts_with('n') {
+            ("\n", 1)
+        } else if escape.starts_with('t') {
+            ("\t", 1)
+        } else if escape.starts_with('\\') {
+            ("\\", 1)
+        } else {
+            ("\\", 0)
+        };
+        out.push_str(replacement);
+        rest = &escape[len..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,413 @@
+use std::collections::BTreeSet;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use comfy_table::{CellAlignment, Table, presets};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+
+use orkesy_core::unit::{StopBehavior, StopSignal, Unit};
+
+use crate::profile::discover_config;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MetricsFormat {
+    Prometheus,
+    Json,
+    Table,
+}
+
+pub(super) struct UnitSample {
+    pub(super) id: String,
+    pub(super) pids: Vec<u32>,
+    pub(super) cpu_percent: f32,
+    pub(super) memory_bytes: u64,
+    pub(super) uptime_secs: u64,
+    pub(super) port: Option<u16>,
+}
+
+pub(super) struct Snapshot {
+    timestamp_ms: u128,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    pub(super) units: Vec<UnitSample>,
+}
+
+/// Prints metrics for the configured units. Processes are found by matching
+/// their command line against each unit's `start` command, so this works
+/// for units started by any orkesy instance.
+pub fn run_metrics_export(
+    format: MetricsFormat,
+    once: bool,
+    interval_ms: u64,
+) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (_, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+    let units = config.to_units();
+
+    let mut sys = System::new();
+    sys.refresh_cpu_usage();
+    refresh_processes(&mut sys);
+    // CPU usage is a delta between two refreshes
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let interactive = !once && std::io::stdout().is_terminal();
+    let interval = Duration::from_millis(interval_ms.max(100));
+
+    loop {
+        sys.refresh_cpu_usage();
+        sys.refresh_memory();
+        refresh_processes(&mut sys);
+
+        let snapshot = take_snapshot(&sys, &units);
+        let output = match format {
+            MetricsFormat::Prometheus => format_prometheus(&snapshot),
+            MetricsFormat::Json => format_json(&snapshot, interactive),
+            MetricsFormat::Table => format_table(&snapshot),
+        };
+
+        let mut stdout = std::io::stdout().lock();
+        if interactive {
+            // Redraw in place
+            let _ = write!(stdout, "\x1b[2J\x1b[H");
+        }
+        if write!(stdout, "{}", output)
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            // Downstream pipe closed
+            return Ok(());
+        }
+        drop(stdout);
+
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+pub(super) fn refresh_processes(sys: &mut System) {
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new()
+            .with_memory()
+            .with_cpu()
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+}
+
+pub(super) fn take_snapshot(sys: &System, units: &[Unit]) -> Snapshot {
+    let units = units
+        .iter()
+        .map(|unit| {
+            let pids = unit_pids(sys, unit);
+            let processes: Vec<_> = pids
+                .iter()
+                .filter_map(|pid| sys.process(Pid::from_u32(*pid)))
+                .collect();
+            UnitSample {
+                id: unit.id.clone(),
+                cpu_percent: processes.iter().fold(0.0, |acc, p| acc + p.cpu_usage()),
+                memory_bytes: processes.iter().map(|p| p.memory()).sum(),
+                uptime_secs: processes.iter().map(|p| p.run_time()).max().unwrap_or(0),
+                port: unit.port,
+                pids,
+            }
+        })
+        .collect();
+
+    Snapshot {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0),
+        cpu_percent: sys.global_cpu_usage(),
+        memory_bytes: sys.used_memory(),
+        units,
+    }
+}
+
+/// Ids of units that currently have a matching process
+pub fn running_units(units: &[Unit]) -> BTreeSet<String> {
+    let mut sys = System::new();
+    refresh_processes(&mut sys);
+    units
+        .iter()
+        .filter(|u| !unit_pids(&sys, u).is_empty())
+        .map(|u| u.id.clone())
+        .collect()
+}
+
+/// Sends the unit's stop signal (SIGTERM for a stop command) to its
+/// processes, then SIGKILL to any left after `stop_timeout_ms`. Returns
+/// whether none are left.
+pub async fn stop_unit_processes(unit: &Unit) -> bool {
+    let signal = match &unit.stop {
+        StopBehavior::Signal(StopSignal::SigInt) => sysinfo::Signal::Interrupt,
+        StopBehavior::Signal(StopSignal::SigKill) => sysinfo::Signal::Kill,
+        _ => sysinfo::Signal::Term,
+    };
+    let mut sys = System::new();
+    // SIGKILL gets a second to take effect
+    let rounds = [
+        (signal, Duration::from_millis(unit.stop_timeout_ms)),
+        (sysinfo::Signal::Kill, Duration::from_secs(1)),
+    ];
+    for (signal, timeout) in rounds {
+        refresh_processes(&mut sys);
+        for pid in unit_pids(&sys, unit) {
+            if let Some(process) = sys.process(Pid::from_u32(pid)) {
+                let _ = process.kill_with(signal);
+            }
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            refresh_processes(&mut sys);
+            if unit_pids(&sys, unit).is_empty() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Root processes whose command line is the unit's start command (directly
+/// or via `sh -c`), plus all of their descendants. Zombies are left out.
+pub(super) fn unit_pids(sys: &System, unit: &Unit) -> Vec<u32> {
+    let start = unit.start.trim();
+    if start.is_empty() {
+        return Vec::new();
+    }
+
+    let mut pids: BTreeSet<Pid> = sys
+        .processes()
+        .iter()
+        .filter(|(_, p)| p.status() != ProcessStatus::Zombie)
+        .filter(|(_, p)| {
+            let cmd: Vec<String> = p
+                .cmd()
+                .iter()
+                .map(|s| s.to_string_lossy().into_owned())
+                .collect();
+            let joined = cmd.join(" ");
+            joined == start || cmd.last().is_some_and(|last| last == start)
+        })
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    loop {
+        let children: Vec<Pid> = sys
+            .processes()
+            .iter()
+            .filter(|(pid, p)| {
+                !pids.contains(pid)
+                    && p.status() != ProcessStatus::Zombie
+                    && p.parent().is_some_and(|pp| pids.contains(&pp))
+            })
+            .map(|(pid, _)| *pid)
+            .collect();
+        if children.is_empty() {
+            break;
+        }
+        pids.extend(children);
+    }
+
+    pids.into_iter().map(|p| p.as_u32()).collect()
+}
+
+fn format_prometheus(snapshot: &Snapshot) -> String {
+    let ts = snapshot.timestamp_ms;
+    let mut out = String::new();
+
+    let mut metric = |name: &str, help: &str, kind: &str, samples: Vec<(Option<&str>, String)>| {
+        out.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, kind
+        ));
+        for (unit, value) in samples {
+            match unit {
+                Some(unit) => out.push_str(&format!(
+                    "{}{{unit=\"{}\"}} {} {}\n",
+                    name,
+                    escape_label(unit),
+                    value,
+                    ts
+                )),
+                None => out.push_str(&format!("{} {} {}\n", name, value, ts)),
+            }
+        }
+    };
+
+    metric(
+        "orkesy_unit_up",
+        "Whether a process for the unit is running.",
+        "gauge",
+        snapshot
+            .units
+            .iter()
+            .map(|u| {
+                (
+                    Some(u.id.as_str()),
+                    u8::from(!u.pids.is_empty()).to_string(),
+                )
+            })
+            .collect(),
+    );
+    metric(
+        "orkesy_unit_cpu_percent",
+        "CPU usage of the unit's processes in percent.",
+        "gauge",
+        snapshot
+            .units
+            .iter()
+            .map(|u| (Some(u.id.as_str()), format!("{:.1}", u.cpu_percent)))
+            .collect(),
+    );
+    metric(
+        "orkesy_unit_memory_bytes",
+        "Resident memory of the unit's processes.",
+        "gauge",
+        snapshot
+            .units
+            .iter()
+            .map(|u| (Some(u.id.as_str()), u.memory_bytes.to_string()))
+            .collect(),
+    );
+    metric(
+        "orkesy_unit_uptime_seconds",
+        "Seconds since the unit's oldest process started.",
+        "gauge",
+        snapshot
+            .units
+            .iter()
+            .map(|u| (Some(u.id.as_str()), u.uptime_secs.to_string()))
+            .collect(),
+    );
+    metric(
+        "orkesy_system_cpu_percent",
+        "Host CPU usage in percent.",
+        "gauge",
+        vec![(None, format!("{:.1}", snapshot.cpu_percent))],
+    );
+    metric(
+        "orkesy_system_memory_bytes",
+        "Host memory in use.",
+        "gauge",
+        vec![(None, snapshot.memory_bytes.to_string())],
+    );
+
+    out
+}
+
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_json(snapshot: &Snapshot, pretty: bool) -> String {
+    let json = serde_json::json!({
+        "timestamp_ms": snapshot.timestamp_ms as u64,
+        "system": {
+            "cpu_percent": round1(snapshot.cpu_percent),
+            "memory_bytes": snapshot.memory_bytes,
+        },
+        "units": snapshot.units.iter().map(|u| serde_json::json!({
+            "id": u.id,
+            "running": !u.pids.is_empty(),
+            "pids": u.pids,
+            "cpu_percent": round1(u.cpu_percent),
+            "memory_bytes": u.memory_bytes,
+            "uptime_secs": u.uptime_secs,
+        })).collect::<Vec<_>>(),
+    });
+
+    // One object per line when piped, so successive snapshots stay parseable
+    let text = if pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    };
+    format!("{}\n", text.unwrap_or_default())
+}
+
+fn round1(v: f32) -> f64 {
+    (v as f64 * 10.0).round() / 10.0
+}
+
+fn format_table(snapshot: &Snapshot) -> String {
+    let id_width = snapshot
+        .units
+        .iter()
+        .map(|u| u.id.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    let mut out = format!(
+        "{:<id_width$}  {:<7}  {:>6}  {:>10}  {:>8}  PIDS\n",
+        "UNIT", "STATUS", "CPU%", "MEM", "UPTIME"
+    );
+    for u in &snapshot.units {
+        let (status, pids) = if u.pids.is_empty() {
+            ("stopped", "-".to_string())
+        } else {
+            let pids: Vec<String> = u.pids.iter().map(|p| p.to_string()).collect();
+            ("running", pids.join(","))
+        };
+        out.push_str(&format!(
+            "{:<id_width$}  {:<7}  {:>6.1}  {:>10}  {:>8}  {}\n",
+            u.id,
+            status,
+            u.cpu_percent,
+            format_bytes(u.memory_bytes),
+            format_uptime(u.uptime_secs),
+            pids
+        ));
+    }
+    out.push_str(&format!(
+        "\nsystem  cpu {:.1}%  mem {}\n",
+        snapshot.cpu_percent,
+        format_bytes(snapshot.memory_bytes)
+    ));
+    out
+}
+
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.1}GB", bytes as f64 / (1024.0 * MB))
+    } else {
+        format!("{:.1}MB", bytes as f64 / MB)
+    }
+}
+
+pub(super) fn format_uptime(secs: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// A borderless table with two spaces between columns; the columns at
+/// `right_aligned` are aligned right
+pub(super) fn plain_table(header: &[&str], right_aligned: &[usize]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_style(presets::NOTHING)
+        .set_header(header.to_vec());
+    let last = header.len().saturating_sub(1);
+    for (i, column) in table.column_iter_mut().enumerate() {
+        column.set_padding((u16::from(i > 0), u16::from(i < last)));
+        if right_aligned.contains(&i) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+    table
+}
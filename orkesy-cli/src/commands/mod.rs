@@ -1,5 +1,29 @@
 mod doctor;
+mod env;
+mod export;
+mod import;
 mod init;
+mod logs;
+mod metrics;
+mod plan;
+mod ps;
+mod status;
+mod validate;
+mod version;
+mod wait;
 
 pub use doctor::run_doctor;
+pub use env::{EnvFormat, run_env, shell_quote};
+pub use export::run_export;
+pub use import::{ImportSource, run_import};
 pub use init::run_init;
+pub use logs::{LogFormatter, stream_name};
+pub use metrics::{
+    MetricsFormat, escape_label, run_metrics_export, running_units, stop_unit_processes,
+};
+pub use plan::{PlanAction, print_plan};
+pub use ps::run_ps;
+pub use status::{StatusFormat, run_status};
+pub use validate::run_config_validate;
+pub use version::{cached_update_notice, run_version};
+pub use wait::run_wait;
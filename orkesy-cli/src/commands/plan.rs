@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use orkesy_core::config::{HealthCheck, OrkesyConfig};
+
+use super::doctor::{connect, is_port_available};
+
+/// How long a readiness gate outside the plan gets to accept a connection
+const GATE_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+/// Prints the order `action` would run `targets` in, their readiness gates
+/// and port availability. Returns the number of ports already in use.
+pub fn print_plan(action: PlanAction, config: &OrkesyConfig, targets: &[String]) -> usize {
+    let levels = config.start_levels(targets);
+    let mut stop_levels = levels.clone();
+    stop_levels.reverse();
+
+    match action {
+        PlanAction::Start => println!("Would start: {}", format_levels(&levels)),
+        PlanAction::Stop => println!("Would stop: {}", format_levels(&stop_levels)),
+        PlanAction::Restart => {
+            println!("Would stop: {}", format_levels(&stop_levels));
+            println!("Then start: {}", format_levels(&levels));
+        }
+    }
+    if action == PlanAction::Stop {
+        println!();
+        return 0;
+    }
+
+    let mut gates = Vec::new();
+    for id in targets {
+        let Some(svc) = config.services.get(id) else {
+            continue;
+        };
+        for dep in &svc.depends_on {
+            gates.push(format!(
+                "{} waits for {}: {}",
+                id,
+                dep,
+                gate(config, targets, dep)
+            ));
+        }
+    }
+    if !gates.is_empty() {
+        println!("\nReadiness gates:");
+        for line in gates {
+            println!("  {}", line);
+        }
+    }
+
+    let mut conflicts = 0;
+    let ports: Vec<_> = targets
+        .iter()
+        .filter_map(|id| Some((id, config.services.get(id)?.port?)))
+        .collect();
+    if !ports.is_empty() {
+        println!("\nPorts:");
+        for (id, port) in ports {
+            if is_port_available(port) {
+                println!("  ✓ {} :{} available", id, port);
+            } else {
+                // A restart frees the port first, so only `up` conflicts
+                let note = if action == PlanAction::Start {
+                    conflicts += 1;
+                    "in use"
+                } else {
+                    "in use (freed by the stop)"
+                };
+                println!("  ✗ {} :{} {}", id, port, note);
+            }
+        }
+    }
+
+    println!();
+    conflicts
+}
+
+/// `postgres → redis → api, worker (parallel)`
+fn format_levels(levels: &[Vec<String>]) -> String {
+    levels
+        .iter()
+        .map(|level| {
+            if level.len() > 1 {
+                format!("{} (parallel)", level.join(", "))
+            } else {
+                level.join(", ")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// What a dependent waits on before `dep` counts as ready. Dependencies that
+/// are not part of the plan must already be reachable.
+fn gate(config: &OrkesyConfig, targets: &[String], dep: &str) -> String {
+    let Some(svc) = config.services.get(dep) else {
+        return "unknown service".into();
+    };
+    // Health checks without a port probe 8000, as in `OrkesyConfig::to_units`
+    let health_port = svc.port.unwrap_or(8000);
+    let (check, port) = match &svc.health_check {
+        Some(HealthCheck::Http { path, .. }) => (
+            format!("HTTP health check on :{}{}", health_port, path),
+            Some(health_port),
+        ),
+        Some(HealthCheck::Tcp { .. }) => (
+            format!("TCP health check on :{}", health_port),
+            Some(health_port),
+        ),
+        Some(HealthCheck::Grpc { .. }) => (
+            format!("gRPC health check on :{}", health_port),
+            Some(health_port),
+        ),
+        Some(HealthCheck::Exec { command, .. }) => {
+            (format!("health check `{}`", command.join(" ")), svc.port)
+        }
+        None => match svc.port {
+            Some(port) => (format!("port :{}", port), Some(port)),
+            None => ("process start (no port or health check)".to_string(), None),
+        },
+    };
+
+    if targets.iter().any(|t| t == dep) {
+        return check;
+    }
+    match port {
+        Some(port) => match connect("127.0.0.1", port, GATE_TIMEOUT) {
+            Ok(_) => format!("{}, not in this run, ✓ reachable", check),
+            Err(e) => format!("{}, not in this run, ✗ {}", check, e),
+        },
+        None => format!("{}, not in this run", check),
+    }
+}
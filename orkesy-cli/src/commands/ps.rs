@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use sysinfo::System;
+
+use super::metrics::{
+    Snapshot, UnitSample, format_bytes, format_uptime, plain_table, refresh_processes,
+    take_snapshot,
+};
+use crate::profile::discover_config;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// How a unit changed since the previous refresh
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Change {
+    None,
+    Started,
+    Stopped,
+    /// Still running but with a different process (restarted)
+    Restarted,
+}
+
+impl Change {
+    fn color(self) -> Option<&'static str> {
+        match self {
+            Change::None => None,
+            Change::Started => Some(GREEN),
+            Change::Stopped => Some(RED),
+            Change::Restarted => Some(YELLOW),
+        }
+    }
+}
+
+/// Prints the status of the configured units. Like `metrics export`, units
+/// are matched to processes by command line, so no running TUI is needed.
+/// With `watch`, the table is redrawn every `interval_ms` until Ctrl+C.
+pub fn run_ps(watch: bool, interval_ms: u64, no_color: bool) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (_, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+    let units = config.to_units();
+
+    let mut sys = System::new();
+    refresh_processes(&mut sys);
+    // CPU usage is a delta between two refreshes
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let terminal = std::io::stdout().is_terminal();
+    let color = !no_color && terminal;
+    let interval = Duration::from_millis(interval_ms.max(100));
+    let mut previous: Option<BTreeMap<String, Vec<u32>>> = None;
+
+    loop {
+        refresh_processes(&mut sys);
+        let snapshot = take_snapshot(&sys, &units);
+        let output = format_ps(&snapshot, previous.as_ref(), color);
+
+        let mut stdout = std::io::stdout().lock();
+        if watch && color {
+            // Redraw in place: cursor home, clear to end of screen
+            let _ = write!(stdout, "\x1b[H\x1b[J");
+        } else if previous.is_some() {
+            let _ = writeln!(stdout);
+        }
+        if write!(stdout, "{}", output)
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            // Downstream pipe closed
+            return Ok(());
+        }
+        drop(stdout);
+
+        if !watch {
+            return Ok(());
+        }
+        previous = Some(
+            snapshot
+                .units
+                .iter()
+                .map(|u| (u.id.clone(), u.pids.clone()))
+                .collect(),
+        );
+        std::thread::sleep(interval);
+    }
+}
+
+fn change_for(unit: &UnitSample, previous: Option<&BTreeMap<String, Vec<u32>>>) -> Change {
+    let Some(prev_pids) = previous.and_then(|p| p.get(&unit.id)) else {
+        return Change::None;
+    };
+    match (prev_pids.is_empty(), unit.pids.is_empty()) {
+        (true, false) => Change::Started,
+        (false, true) => Change::Stopped,
+        (false, false) if prev_pids.first() != unit.pids.first() => Change::Restarted,
+        _ => Change::None,
+    }
+}
+
+fn format_ps(
+    snapshot: &Snapshot,
+    previous: Option<&BTreeMap<String, Vec<u32>>>,
+    color: bool,
+) -> String {
+    let mut table = plain_table(
+        &["UNIT", "STATUS", "PID", "CPU%", "MEM", "UPTIME", "PORT"],
+        &[2, 3, 4, 5, 6],
+    );
+    let mut changes = Vec::with_capacity(snapshot.units.len());
+    for u in &snapshot.units {
+        let change = change_for(u, previous);
+        let status = match (change, u.pids.is_empty()) {
+            (Change::Restarted, _) => "restarted",
+            (_, true) => "stopped",
+            (_, false) => "running",
+        };
+        let pid = u
+            .pids
+            .first()
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "-".into());
+        table.add_row(vec![
+            u.id.clone(),
+            status.to_string(),
+            pid,
+            format!("{:.1}", u.cpu_percent),
+            format_bytes(u.memory_bytes),
+            format_uptime(u.uptime_secs),
+            u.port.map(|p| p.to_string()).unwrap_or_else(|| "-".into()),
+        ]);
+        changes.push(change);
+    }
+
+    // Colors go around whole lines, so comfy-table measures plain text
+    let mut out = String::new();
+    for (i, line) in table.lines().enumerate() {
+        let code = match i.checked_sub(1) {
+            None => Some(DIM),
+            Some(row) => changes[row].color(),
+        };
+        match code.filter(|_| color) {
+            Some(code) => out.push_str(&format!("{}{}{}\n", code, line, RESET)),
+            None => out.push_str(&format!("{}\n", line)),
+        }
+    }
+
+    out
+}
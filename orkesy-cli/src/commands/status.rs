@@ -0,0 +1,119 @@
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use sysinfo::{Pid, System};
+
+use orkesy_core::unit::{Unit, UnitStatus};
+
+use super::metrics::{format_uptime, plain_table, refresh_processes, unit_pids};
+use crate::profile::discover_config;
+
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StatusFormat {
+    #[default]
+    Json,
+    Table,
+}
+
+pub(super) struct UnitState {
+    pub(super) id: String,
+    /// `Running`, `Starting`, `Stopped` or `Errored`
+    pub(super) status: UnitStatus,
+    pub(super) pid: Option<u32>,
+    pub(super) uptime_secs: Option<u64>,
+}
+
+/// Prints the state of every configured unit and returns whether any is
+/// errored. Like `ps`, this inspects the system rather than a running TUI: a
+/// unit runs if its `pid_file` names a live process or a process matches its
+/// command; a unit with a port counts as starting until the port accepts
+/// connections, and as running if only the port answers. A `pid_file` left
+/// behind by a process that is gone means the unit is errored.
+pub fn run_status(format: StatusFormat) -> Result<bool, String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (_, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+
+    let mut sys = System::new();
+    refresh_processes(&mut sys);
+    let states: Vec<UnitState> = config
+        .to_units()
+        .iter()
+        .map(|unit| unit_state(&sys, unit))
+        .collect();
+
+    match format {
+        StatusFormat::Json => {
+            let json: Vec<_> = states
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "id": s.id,
+                        "status": s.status.name(),
+                        "pid": s.pid,
+                        "uptime_secs": s.uptime_secs,
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+            println!("{}", json);
+        }
+        StatusFormat::Table => print!("{}", format_table(&states)),
+    }
+
+    Ok(states
+        .iter()
+        .any(|s| matches!(s.status, UnitStatus::Errored { .. })))
+}
+
+pub(super) fn unit_state(sys: &System, unit: &Unit) -> UnitState {
+    let state = |status, pid: Option<u32>| UnitState {
+        id: unit.id.clone(),
+        status,
+        pid,
+        uptime_secs: pid
+            .and_then(|pid| sys.process(Pid::from_u32(pid)))
+            .map(|p| p.run_time()),
+    };
+
+    let pid_file = unit.pid_file.as_deref().and_then(|path| {
+        let pid: u32 = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+        Some(pid)
+    });
+    let pid = match pid_file {
+        Some(pid) if sys.process(Pid::from_u32(pid)).is_none() => {
+            let message = "pid file names a process that is gone".to_string();
+            return state(UnitStatus::Errored { message }, None);
+        }
+        Some(pid) => Some(pid),
+        None => unit_pids(sys, unit).first().copied(),
+    };
+
+    let port_open = unit.port.map(|port| {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        TcpStream::connect_timeout(&addr, PORT_PROBE_TIMEOUT).is_ok()
+    });
+    match (pid, port_open) {
+        (Some(pid), Some(false)) => state(UnitStatus::Starting, Some(pid)),
+        (Some(pid), _) => state(UnitStatus::Running, Some(pid)),
+        (None, Some(true)) => state(UnitStatus::Running, None),
+        (None, _) => state(UnitStatus::Stopped, None),
+    }
+}
+
+fn format_table(states: &[UnitState]) -> String {
+    let mut table = plain_table(&["UNIT", "STATUS", "PID", "UPTIME"], &[2, 3]);
+    for s in states {
+        table.add_row(vec![
+            s.id.clone(),
+            s.status.name().to_string(),
+            s.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".into()),
+            s.uptime_secs
+                .map(format_uptime)
+                .unwrap_or_else(|| "-".into()),
+        ]);
+    }
+    format!("{}\n", table)
+}
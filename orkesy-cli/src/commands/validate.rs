@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use orkesy_core::config::{ConfigError, OrkesyConfig};
+use orkesy_core::env_file;
+use orkesy_core::unit::{Unit, interpolate};
+
+use crate::platform::{find_on_path, is_executable};
+use crate::profile::load_config;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Shell builtins and keywords that aren't looked up on `$PATH`
+const SHELL_BUILTINS: [&str; 14] = [
+    "cd", "export", "exec", "source", ".", "set", "test", "[", "echo", "true", "false", "if",
+    "for", "while",
+];
+
+/// One problem found in the config
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// Config path of the offending field, e.g. `services.api.cwd`
+    pub field: String,
+    pub found: String,
+    pub message: String,
+    pub fix: String,
+}
+
+impl Diagnostic {
+    fn new(
+        field: impl Into<String>,
+        found: impl Into<String>,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            found: found.into(),
+            message: message.into(),
+            fix: fix.into(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "field": self.field,
+            "found": self.found,
+            "message": self.message,
+            "fix": self.fix,
+        })
+    }
+}
+
+/// Validates the config found from the current directory and prints every
+/// issue. Returns the number of issues.
+pub fn run_config_validate(json: bool) -> Result<usize, String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let path = OrkesyConfig::find(&cwd).map_err(|e| e.to_string())?;
+    let diagnostics = match load_config(&path) {
+        Ok(config) => validate(&config),
+        Err(e) => vec![load_diagnostic(&path, e)],
+    };
+
+    if json {
+        let out = serde_json::json!({
+            "config": path.display().to_string(),
+            "valid": diagnostics.is_empty(),
+            "issues": diagnostics.iter().map(Diagnostic::to_json).collect::<Vec<_>>(),
+        });
+        let out = serde_json::to_string_pretty(&out).map_err(|e| e.to_string())?;
+        println!("{}", out);
+    } else {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        print_human(&path, &diagnostics, color);
+    }
+
+    Ok(diagnostics.len())
+}
+
+fn print_human(path: &Path, diagnostics: &[Diagnostic], color: bool) {
+    let paint = |code: &'static str| if color { code } else { "" };
+    let (red, green, dim, reset) = (paint(RED), paint(GREEN), paint(DIM), paint(RESET));
+
+    println!("Validating {}\n", path.display());
+    for d in diagnostics {
+        println!("  {}✗{} {}: {}", red, reset, d.field, d.message);
+        println!("    {}found:{} {}", dim, reset, d.found);
+        println!("    {}fix:{}   {}", dim, reset, d.fix);
+    }
+
+    match diagnostics.len() {
+        0 => println!("{}✓{} No issues found", green, reset),
+        1 => println!("\n{}1 issue found{}", red, reset),
+        n => println!("\n{}{} issues found{}", red, n, reset),
+    }
+}
+
+/// A config that fails to load yields a single diagnostic for the error
+fn load_diagnostic(path: &Path, error: ConfigError) -> Diagnostic {
+    let message = error.to_string();
+    match error {
+        ConfigError::CyclicDependency { cycle } => Diagnostic::new(
+            "depends_on",
+            cycle.join(" -> "),
+            message,
+            "remove one of the depends_on entries in the cycle",
+        ),
+        ConfigError::InvalidDependency {
+            service,
+            dependency,
+        } => Diagnostic::new(
+            format!("services.{}.depends_on", service),
+            dependency.clone(),
+            message,
+            format!(
+                "define a service '{}' or remove it from the list",
+                dependency
+            ),
+        ),
+        ConfigError::MissingCommand { service } => Diagnostic::new(
+            format!("services.{}.command", service),
+            "(none)",
+            message,
+            "add a command, or a docker_image for docker services",
+        ),
+        ConfigError::InvalidEnvFrom { service, source } => Diagnostic::new(
+            format!("services.{}.env_from", service),
+            source.clone(),
+            message,
+            format!("define a service '{}' or remove it from env_from", source),
+        ),
+        ConfigError::UnknownTemplate { service, template } => Diagnostic::new(
+            format!("services.{}.extends", service),
+            template,
+            message,
+            "add the template under `templates` or fix the name",
+        ),
+        ConfigError::InvalidGroup { group, .. } => Diagnostic::new(
+            format!("groups.{}", group),
+            group,
+            message,
+            "list only defined services, under a name no service uses",
+        ),
+        _ => Diagnostic::new(
+            "config",
+            path.display().to_string(),
+            message,
+            "fix the error reported for the config file",
+        ),
+    }
+}
+
+/// Checks a loaded config against the filesystem and `$PATH`
+fn validate(config: &OrkesyConfig) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let global_env = match &config.global_env_file {
+        Some(path) => check_env_file("global_env_file", path, &mut diagnostics),
+        None => BTreeMap::new(),
+    };
+
+    for unit in config.to_units() {
+        let field = |name: &str| format!("services.{}.{}", unit.id, name);
+
+        if let Some(cwd) = &unit.cwd
+            && !cwd.is_dir()
+        {
+            diagnostics.push(Diagnostic::new(
+                field("cwd"),
+                cwd.display().to_string(),
+                "directory does not exist",
+                "create the directory or correct the path",
+            ));
+        }
+
+        let mut env: BTreeMap<String, String> = std::env::vars().collect();
+        env.extend(global_env.clone());
+        if let Some(path) = &unit.env_file {
+            env.extend(check_env_file(&field("env_file"), path, &mut diagnostics));
+        }
+        env.extend(unit.env.clone());
+
+        if let Some(d) = check_executable(&unit, &env) {
+            diagnostics.push(Diagnostic {
+                field: field("command"),
+                ..d
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// Loads an env file, recording a diagnostic if it is missing or malformed
+fn check_env_file(
+    field: &str,
+    path: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> BTreeMap<String, String> {
+    match env_file::load(path) {
+        Ok(vars) => vars,
+        Err(env_file::EnvFileError::Io(e)) => {
+            diagnostics.push(Diagnostic::new(
+                field,
+                path.display().to_string(),
+                format!("env file cannot be read: {}", e),
+                "create the file or correct the path",
+            ));
+            BTreeMap::new()
+        }
+        Err(e) => {
+            diagnostics.push(Diagnostic::new(
+                field,
+                path.display().to_string(),
+                format!("env file is malformed: {}", e),
+                "use KEY=VALUE lines, with # for comments",
+            ));
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Checks that the program `start` runs exists; the `field` is left empty.
+/// Commands of `docker_image` units run in the container and are skipped.
+fn check_executable(unit: &Unit, env: &BTreeMap<String, String>) -> Option<Diagnostic> {
+    if unit.start.trim().is_empty() || unit.docker_image.is_some() {
+        return None;
+    }
+
+    let start = if unit.uses_shell() {
+        unit.start.clone()
+    } else {
+        match interpolate(&unit.start, env) {
+            Ok(start) => start,
+            Err(e) => {
+                return Some(Diagnostic::new(
+                    "",
+                    unit.start.clone(),
+                    e.to_string(),
+                    "set the variable in env or env_file, or give it a default with ${VAR:-default}",
+                ));
+            }
+        }
+    };
+
+    let program = start.split_whitespace().next()?;
+    if unit.uses_shell() && (SHELL_BUILTINS.contains(&program) || program.contains(['=', '$'])) {
+        return None;
+    }
+
+    let found = if program.contains('/') {
+        let path = Path::new(program);
+        let path = match &unit.cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+        is_executable(&path)
+    } else {
+        find_on_path(program).is_some()
+    };
+
+    (!found).then(|| {
+        Diagnostic::new(
+            "",
+            program,
+            if program.contains('/') {
+                "executable does not exist".to_string()
+            } else {
+                format!("'{}' was not found on $PATH", program)
+            },
+            "install it, add its directory to $PATH, or use an absolute path",
+        )
+    })
+}
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const RELEASES_URL: &str = "https://api.github.com/repos/uzairali19/orkesy/releases/latest";
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn run_version(check_update: bool) -> Result<(), String> {
+    println!("orkesy {}", CURRENT_VERSION);
+
+    if !check_update {
+        return Ok(());
+    }
+
+    let latest = match read_cache().filter(|c| c.is_fresh()) {
+        Some(cache) => cache.latest,
+        None => {
+            let latest = fetch_latest_version()?;
+            write_cache(&latest);
+            latest
+        }
+    };
+
+    if is_newer(&latest, CURRENT_VERSION) {
+        println!(
+            "Update available: v{} → v{}. Run: cargo install orkesy",
+            CURRENT_VERSION, latest
+        );
+    } else {
+        println!("orkesy is up to date");
+    }
+
+    Ok(())
+}
+
+/// Returns a short notice if the cached release is newer than this build.
+/// Never touches the network.
+pub fn cached_update_notice() -> Option<String> {
+    let cache = read_cache()?;
+    is_newer(&cache.latest, CURRENT_VERSION).then(|| format!("v{} available", cache.latest))
+}
+
+struct UpdateCache {
+    checked_at: u64,
+    latest: String,
+}
+
+impl UpdateCache {
+    fn is_fresh(&self) -> bool {
+        now_secs().saturating_sub(self.checked_at) < CACHE_TTL.as_secs()
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    Some(base.join("orkesy").join("update-check.json"))
+}
+
+fn read_cache() -> Option<UpdateCache> {
+    let content = std::fs::read_to_string(cache_path()?).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    Some(UpdateCache {
+        checked_at: json.get("checked_at")?.as_u64()?,
+        latest: json.get("latest")?.as_str()?.to_string(),
+    })
+}
+
+fn write_cache(latest: &str) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let json = serde_json::json!({
+        "checked_at": now_secs(),
+        "latest": latest,
+    });
+    let _ = std::fs::write(path, json.to_string());
+}
+
+fn fetch_latest_version() -> Result<String, String> {
+    let output = Command::new("curl")
+        .args([
+            "-fsSL",
+            "--max-time",
+            "5",
+            "-H",
+            "Accept: application/vnd.github+json",
+            "-H",
+            concat!("User-Agent: orkesy/", env!("CARGO_PKG_VERSION")),
+            RELEASES_URL,
+        ])
+        .output()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "update check failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("invalid release response: {}", e))?;
+    json.get("tag_name")
+        .and_then(|t| t.as_str())
+        .map(|t| t.trim_start_matches('v').to_string())
+        .ok_or_else(|| "release response has no tag_name".to_string())
+}
+
+fn parse_version(v: &str) -> Vec<u64> {
+    v.trim_start_matches('v')
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    parse_version(latest) > parse_version(current)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+
+use sysinfo::System;
+
+use orkesy_core::unit::UnitStatus;
+
+use super::metrics::refresh_processes;
+use super::status::unit_state;
+use crate::profile::discover_config;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Blocks until `unit` reaches `status` (a case-insensitive `UnitStatus`
+/// name) or `timeout_secs` pass. The unit's state is polled the same way as
+/// `orkesy status`, so units started by any orkesy process are seen.
+pub fn run_wait(unit_id: &str, status: &str, timeout_secs: u64) -> Result<(), String> {
+    let target = UnitStatus::parse(status).ok_or_else(|| {
+        format!(
+            "unknown status '{}'. Expected one of: unknown, starting, running, stopping, stopped, exited, errored",
+            status
+        )
+    })?;
+
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (_, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+    let units = config.to_units();
+    let unit = units.iter().find(|u| u.id == unit_id).ok_or_else(|| {
+        let ids: Vec<_> = units.iter().map(|u| u.id.as_str()).collect();
+        format!("unknown unit '{}'. Available: {}", unit_id, ids.join(", "))
+    })?;
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(timeout_secs);
+    let mut sys = System::new();
+    loop {
+        refresh_processes(&mut sys);
+        let state = unit_state(&sys, unit);
+        if state.status.matches(&target) {
+            println!(
+                "{} is {} (after {:.1}s)",
+                unit_id,
+                target.name(),
+                started.elapsed().as_secs_f64()
+            );
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            return Err(format!(
+                "timed out after {}s waiting for {} to be {} (it is {})",
+                timeout_secs,
+                unit_id,
+                target.name(),
+                state.status.name()
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,689 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{RwLock, broadcast, mpsc, oneshot};
+
+use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent};
+use orkesy_core::config::OrkesyConfig;
+use orkesy_core::reducer::{EventEnvelope, reduce};
+use orkesy_core::state::RuntimeState;
+use orkesy_core::unit::{UnitHealth, UnitId, UnitStatus};
+
+use crate::adapters::{self, ProcessAdapter};
+use crate::commands::stream_name;
+use crate::metrics_server;
+use crate::profile::discover_config;
+
+/// Lines returned by `logs` when the request doesn't set `lines`
+const DEFAULT_LOG_LINES: usize = 100;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Shared by every connection
+struct Daemon {
+    config: OrkesyConfig,
+    unit_ids: Vec<UnitId>,
+    cmd_tx: mpsc::Sender<AdapterCommand>,
+    event_tx: broadcast::Sender<AdapterEvent>,
+    /// Latest status per unit, kept up to date from adapter events
+    statuses: RwLock<BTreeMap<UnitId, UnitStatus>>,
+}
+
+/// `~/.run/orkesy-<project>.sock`, where the project is the config's `name`
+/// or the current directory's
+pub fn default_socket_path(config: &OrkesyConfig, cwd: &Path) -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    let project = config
+        .project_name()
+        .map(str::to_string)
+        .or_else(|| Some(cwd.file_name()?.to_string_lossy().into_owned()))?;
+    let project: String = project
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    Some(
+        PathBuf::from(home)
+            .join(".run")
+            .join(format!("orkesy-{}.sock", project)),
+    )
+}
+
+/// The default socket of the daemon running `config`'s project, if one is
+/// listening on it
+pub async fn running_daemon(config: &OrkesyConfig, cwd: &Path) -> Option<PathBuf> {
+    let socket = default_socket_path(config, cwd)?;
+    UnixStream::connect(&socket).await.ok()?;
+    Some(socket)
+}
+
+/// Sends one request to the daemon on `socket` and returns its result
+pub async fn call(socket: &Path, method: &str, params: Value) -> Result<Value, String> {
+    let stream = UnixStream::connect(socket)
+        .await
+        .map_err(|e| format!("could not connect to {}: {}", socket.display(), e))?;
+    let (reader, mut writer) = stream.into_split();
+    let mut request =
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+    request.push('\n');
+    writer
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
+        let response: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+        // Skip notifications
+        if response.get("id") != Some(&json!(1)) {
+            continue;
+        }
+        if let Some(message) = response.pointer("/error/message").and_then(Value::as_str) {
+            return Err(message.to_string());
+        }
+        return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+    }
+    Err("the daemon closed the connection".to_string())
+}
+
+/// `orkesy daemon`: runs the configured units without the TUI and serves
+/// newline-delimited JSON-RPC 2.0 on a Unix socket, and Prometheus metrics on
+/// `metrics_port`, until Ctrl+C or SIGTERM, then stops the units and removes
+/// the socket.
+pub async fn run_daemon(socket: Option<PathBuf>, metrics_port: u16) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (path, config) = discover_config(&cwd).map_err(|e| e.to_string())?;
+    let socket = match socket {
+        Some(socket) => socket,
+        None => {
+            default_socket_path(&config, &cwd).ok_or("could not determine the home directory")?
+        }
+    };
+    let listener = bind(&socket).await?;
+
+    let units = config.to_units();
+    let edges = config.to_edges();
+    let (cmd_tx, cmd_rx) = mpsc::channel::<AdapterCommand>(100);
+    let (event_tx, _) = broadcast::channel::<AdapterEvent>(1_000);
+
+    let cmd_rx = adapters::split_unit_commands(
+        cmd_rx,
+        &units,
+        event_tx.clone(),
+        config.global_env_file.clone(),
+    );
+    let mut adapter = ProcessAdapter::new()
+        .with_edges(edges.clone())
+        .with_global_env_file(config.global_env_file.clone());
+    let adapter_units = units.clone();
+    let adapter_event_tx = event_tx.clone();
+    let (adapter_done_tx, adapter_done_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        adapter.run(cmd_rx, adapter_event_tx, adapter_units).await;
+        let _ = adapter_done_tx.send(());
+    });
+
+    let daemon = Arc::new(Daemon {
+        unit_ids: units.iter().map(|u| u.id.clone()).collect(),
+        statuses: RwLock::new(
+            units
+                .iter()
+                .map(|u| (u.id.clone(), UnitStatus::Stopped))
+                .collect(),
+        ),
+        config,
+        cmd_tx: cmd_tx.clone(),
+        event_tx: event_tx.clone(),
+    });
+    tokio::spawn(track_statuses(daemon.clone(), event_tx.subscribe()));
+
+    // Same reducer as the TUI, for the metrics endpoint
+    let state = Arc::new(RwLock::new(RuntimeState::new(crate::units_to_graph(
+        &units, &edges,
+    ))));
+    tokio::spawn(reduce_events(state.clone(), event_tx.subscribe()));
+    let metrics_addr = metrics_server::spawn_metrics_server(metrics_port, state)
+        .await
+        .map_err(|e| format!("could not serve metrics on port {}: {}", metrics_port, e));
+
+    let autostart: Vec<UnitId> = units
+        .iter()
+        .filter(|u| u.autostart)
+        .map(|u| u.id.clone())
+        .collect();
+    if !autostart.is_empty() {
+        let _ = cmd_tx
+            .send(AdapterCommand::StartMany { ids: autostart })
+            .await;
+    }
+
+    println!("Loaded config from: {}", path.display());
+    match metrics_addr {
+        Ok(addr) => println!("Serving metrics on http://{}/metrics", addr),
+        Err(e) => eprintln!("Warning: {}", e),
+    }
+    println!("Listening on {} (Ctrl+C to stop)", socket.display());
+
+    let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .map_err(|e| e.to_string())?;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve(stream, daemon.clone()));
+                }
+                Err(e) => eprintln!("accept failed: {}", e),
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+        }
+    }
+
+    println!("Stopping units...");
+    let _ = cmd_tx.send(AdapterCommand::Shutdown).await;
+    let _ = adapter_done_rx.await;
+    let _ = std::fs::remove_file(&socket);
+    Ok(())
+}
+
+/// Binds `socket`, replacing a stale socket file but not a live daemon.
+/// Anyone who can connect can run the units' commands, so the socket is
+/// bound inside a private (0700) directory, made 0600, and only then moved
+/// into place.
+async fn bind(socket: &Path) -> Result<UnixListener, String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let dir = socket.parent().unwrap_or(Path::new("."));
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+    if socket.exists() {
+        if UnixStream::connect(socket).await.is_ok() {
+            return Err(format!(
+                "a daemon is already listening on {}",
+                socket.display()
+            ));
+        }
+        let _ = std::fs::remove_file(socket);
+    }
+
+    let private = dir.join(format!(".orkesy-bind-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&private);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private)
+        .map_err(|e| format!("could not create {}: {}", private.display(), e))?;
+    let staged = private.join("sock");
+    let bound = UnixListener::bind(&staged)
+        .map_err(|e| format!("could not bind {}: {}", socket.display(), e))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .and_then(|()| std::fs::rename(&staged, socket))
+                .map_err(|e| format!("could not bind {}: {}", socket.display(), e))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&private);
+    bound
+}
+
+async fn track_statuses(daemon: Arc<Daemon>, mut event_rx: broadcast::Receiver<AdapterEvent>) {
+    loop {
+        match event_rx.recv().await {
+            Ok(AdapterEvent::StatusChanged { id, status }) => {
+                daemon.statuses.write().await.insert(id, status);
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+async fn reduce_events(
+    state: Arc<RwLock<RuntimeState>>,
+    mut event_rx: broadcast::Receiver<AdapterEvent>,
+) {
+    let mut event_id = 1u64;
+    loop {
+        let event = match event_rx.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let env = EventEnvelope {
+            id: event_id,
+            at: std::time::SystemTime::now(),
+            event: crate::adapter_event_to_runtime(event),
+        };
+        reduce(&mut *state.write().await, &env);
+        event_id += 1;
+    }
+}
+
+/// Handles one connection: a request per line, a response per request (none
+/// for notifications). `logs` and `events` subscriptions keep sending
+/// notifications on the connection until it closes.
+async fn serve(stream: UnixStream, daemon: Arc<Daemon>) {
+    let (reader, mut writer) = stream.into_split();
+    let (out_tx, mut out_rx) = mpsc::channel::<Value>(256);
+    tokio::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = out_tx
+                    .send(error(Value::Null, PARSE_ERROR, e.to_string()))
+                    .await;
+                continue;
+            }
+        };
+        if let Some(response) = handle(&daemon, request, &out_tx).await
+            && out_tx.send(response).await.is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Runs one request and returns its response; `None` for notifications
+async fn handle(daemon: &Daemon, request: Value, out_tx: &mpsc::Sender<Value>) -> Option<Value> {
+    let id = request.get("id").cloned();
+    let reply_id = id.clone().unwrap_or(Value::Null);
+    let (Some("2.0"), Some(method)) = (
+        request.get("jsonrpc").and_then(Value::as_str),
+        request.get("method").and_then(Value::as_str),
+    ) else {
+        return Some(error(
+            reply_id,
+            INVALID_REQUEST,
+            "expected a JSON-RPC 2.0 request".into(),
+        ));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "start" | "stop" | "restart" | "kill" => unit_action(daemon, method, &params).await,
+        "status" => status(daemon, &params).await,
+        "logs" => logs(daemon, &params, out_tx).await,
+        "events" => events(daemon, &params, out_tx),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+    };
+
+    id.as_ref()?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": reply_id, "result": result }),
+        Err((code, message)) => error(reply_id, code, message),
+    })
+}
+
+type RpcResult = Result<Value, (i64, String)>;
+
+fn error(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// The units named by `params.unit`: a unit id, a group, or `all`
+fn target_units(daemon: &Daemon, params: &Value) -> Result<Vec<UnitId>, (i64, String)> {
+    let Some(name) = params.get("unit").and_then(Value::as_str) else {
+        return Err((INVALID_PARAMS, "missing string param 'unit'".into()));
+    };
+    if name == "all" {
+        Ok(daemon.unit_ids.clone())
+    } else if daemon.unit_ids.iter().any(|id| id == name) {
+        Ok(vec![name.to_string()])
+    } else if let Some(members) = daemon.config.groups.get(name) {
+        Ok(members.clone())
+    } else {
+        Err((
+            INVALID_PARAMS,
+            format!(
+                "unknown unit '{}', available: {}",
+                name,
+                daemon.unit_ids.join(", ")
+            ),
+        ))
+    }
+}
+
+/// `start`, `stop`, `restart` or `kill` `{"unit"}`; replies once the
+/// commands are queued, with the unit ids they apply to
+async fn unit_action(daemon: &Daemon, method: &str, params: &Value) -> RpcResult {
+    let ids = target_units(daemon, params)?;
+    let cmds = match method {
+        "start" => vec![AdapterCommand::StartMany { ids: ids.clone() }],
+        _ => ids
+            .iter()
+            .map(|id| {
+                let id = id.clone();
+                match method {
+                    "stop" => AdapterCommand::Stop { id },
+                    "restart" => AdapterCommand::Restart { id },
+                    _ => AdapterCommand::Kill { id },
+                }
+            })
+            .collect(),
+    };
+    for cmd in cmds {
+        daemon
+            .cmd_tx
+            .send(cmd)
+            .await
+            .map_err(|_| (INVALID_REQUEST, "the daemon is shutting down".to_string()))?;
+    }
+    Ok(json!({ "units": ids }))
+}
+
+/// `status` `{"unit"?}`: `[{"id", "status", "code"?, "message"?}]`
+async fn status(daemon: &Daemon, params: &Value) -> RpcResult {
+    let ids = match params.get("unit") {
+        Some(_) => target_units(daemon, params)?,
+        None => daemon.unit_ids.clone(),
+    };
+    let statuses = daemon.statuses.read().await;
+    let units: Vec<Value> = ids
+        .iter()
+        .map(|id| {
+            let status = statuses.get(id).cloned().unwrap_or_default();
+            let mut unit = json!({ "id": id, "status": status.name() });
+            match status {
+                UnitStatus::Exited { code } => unit["code"] = json!(code),
+                UnitStatus::Errored { message } => unit["message"] = json!(message),
+                _ => {}
+            }
+            unit
+        })
+        .collect();
+    Ok(Value::Array(units))
+}
+
+/// `logs` `{"unit", "lines"?, "follow"?}`: the last `lines` (default 100)
+/// buffered lines as `[{"stream", "text"}]`; unless `follow` is false, new
+/// lines follow as `log` notifications
+async fn logs(daemon: &Daemon, params: &Value, out_tx: &mpsc::Sender<Value>) -> RpcResult {
+    let ids = target_units(daemon, params)?;
+    let [id] = &ids[..] else {
+        return Err((INVALID_PARAMS, "'unit' must name a single unit".into()));
+    };
+    let last_n = params
+        .get("lines")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_LOG_LINES, |n| n as usize);
+    let follow = params
+        .get("follow")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+
+    // Subscribe first so no line falls between the backlog and the stream
+    let event_rx = follow.then(|| daemon.event_tx.subscribe());
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = daemon
+        .cmd_tx
+        .send(AdapterCommand::GetLogs {
+            id: id.clone(),
+            last_n,
+            reply_tx,
+        })
+        .await;
+    let backlog: Vec<Value> = reply_rx
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|line| json!({ "stream": stream_name(&line.stream), "text": line.text }))
+        .collect();
+
+    if let Some(event_rx) = event_rx {
+        let unit = id.clone();
+        tokio::spawn(notify(event_rx, out_tx.clone(), move |event| match event {
+            AdapterEvent::LogLine { id, stream, text } if *id == unit => Some((
+                "log",
+                json!({ "unit": id, "stream": stream_name(stream), "text": text }),
+            )),
+            _ => None,
+        }));
+    }
+    Ok(Value::Array(backlog))
+}
+
+/// `events` `{"unit"?}`: replies `{"subscribed": true}`, then sends each
+/// status, health, log, metrics, alert and readiness event as an `event`
+/// notification
+fn events(daemon: &Daemon, params: &Value, out_tx: &mpsc::Sender<Value>) -> RpcResult {
+    let filter = match params.get("unit") {
+        Some(_) => Some(target_units(daemon, params)?),
+        None => None,
+    };
+    tokio::spawn(notify(
+        daemon.event_tx.subscribe(),
+        out_tx.clone(),
+        move |event| {
+            let event = event_json(event)?;
+            let unit = event["unit"].as_str()?;
+            let wanted = filter
+                .as_ref()
+                .is_none_or(|ids| ids.iter().any(|id| id == unit));
+            wanted.then_some(("event", event))
+        },
+    ));
+    Ok(json!({ "subscribed": true }))
+}
+
+/// Sends the events `select` maps to `(method, params)` as notifications
+/// until the connection or the daemon goes away
+async fn notify(
+    mut event_rx: broadcast::Receiver<AdapterEvent>,
+    out_tx: mpsc::Sender<Value>,
+    select: impl Fn(&AdapterEvent) -> Option<(&'static str, Value)>,
+) {
+    loop {
+        let event = match event_rx.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let Some((method, params)) = select(&event) else {
+            continue;
+        };
+        let notification = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        if out_tx.send(notification).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// `{"type", "unit", ...}` for the events `events` streams
+fn event_json(event: &AdapterEvent) -> Option<Value> {
+    Some(match event {
+        AdapterEvent::StatusChanged { id, status } => {
+            let mut event = json!({ "type": "status", "unit": id, "status": status.name() });
+            match status {
+                UnitStatus::Exited { code } => event["code"] = json!(code),
+                UnitStatus::Errored { message } => event["message"] = json!(message),
+                _ => {}
+            }
+            event
+        }
+        AdapterEvent::HealthChanged { id, health } => {
+            let (health, reason) = match health {
+                UnitHealth::Unknown => ("unknown", None),
+                UnitHealth::Healthy => ("healthy", None),
+                UnitHealth::Degraded { reason } => ("degraded", Some(reason)),
+                UnitHealth::Unhealthy { reason } => ("unhealthy", Some(reason)),
+            };
+            json!({ "type": "health", "unit": id, "health": health, "reason": reason })
+        }
+        AdapterEvent::LogLine { id, stream, text } => {
+            json!({ "type": "log", "unit": id, "stream": stream_name(stream), "text": text })
+        }
+        AdapterEvent::MetricsUpdated { id, metrics } => json!({
+            "type": "metrics",
+            "unit": id,
+            "cpu_percent": metrics.cpu_percent,
+            "memory_bytes": metrics.memory_bytes,
+            "io_read_bytes": metrics.io_read_bytes,
+            "io_write_bytes": metrics.io_write_bytes,
+        }),
+        AdapterEvent::ResourceWarning {
+            id,
+            metric,
+            value,
+            threshold,
+        } => json!({
+            "type": "resource_warning",
+            "unit": id,
+            "metric": metric,
+            "value": value,
+            "threshold": threshold,
+        }),
+        AdapterEvent::ReadinessReached { id } => json!({ "type": "ready", "unit": id }),
+        AdapterEvent::UserNote { .. } | AdapterEvent::WatchChanged { .. } => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_daemon() -> (Daemon, mpsc::Receiver<AdapterCommand>) {
+        let config = OrkesyConfig::parse(
+            r#"
+services:
+  api:
+    command: ["node", "server.js"]
+  db:
+    command: ["postgres"]
+groups:
+  backend: [api, db]
+"#,
+        )
+        .unwrap();
+        let (cmd_tx, cmd_rx) = mpsc::channel(8);
+        let (event_tx, _) = broadcast::channel(8);
+        let daemon = Daemon {
+            unit_ids: config.to_units().into_iter().map(|u| u.id).collect(),
+            config,
+            cmd_tx,
+            event_tx,
+            statuses: RwLock::new(BTreeMap::new()),
+        };
+        (daemon, cmd_rx)
+    }
+
+    async fn request(daemon: &Daemon, line: &str) -> Option<Value> {
+        let (out_tx, _out_rx) = mpsc::channel(8);
+        handle(daemon, serde_json::from_str(line).unwrap(), &out_tx).await
+    }
+
+    #[tokio::test]
+    async fn test_handle_rejects_malformed_requests() {
+        let (daemon, _cmd_rx) = make_daemon();
+
+        let response = request(&daemon, r#"{"id": 1, "method": "status"}"#).await;
+        assert_eq!(response.unwrap()["error"]["code"], INVALID_REQUEST);
+
+        let response = request(
+            &daemon,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "reboot"}"#,
+        )
+        .await;
+        let response = response.unwrap();
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = request(
+            &daemon,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "stop", "params": {"unit": "web"}}"#,
+        )
+        .await;
+        let response = response.unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert_eq!(
+            response["error"]["message"],
+            "unknown unit 'web', available: api, db"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_unit_actions() {
+        let (daemon, mut cmd_rx) = make_daemon();
+
+        let response = request(
+            &daemon,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "start", "params": {"unit": "backend"}}"#,
+        )
+        .await;
+        assert_eq!(
+            response.unwrap()["result"],
+            json!({ "units": ["api", "db"] })
+        );
+        assert!(matches!(
+            cmd_rx.recv().await,
+            Some(AdapterCommand::StartMany { ids }) if ids == ["api", "db"]
+        ));
+
+        // Notifications run but get no response
+        let response = request(
+            &daemon,
+            r#"{"jsonrpc": "2.0", "method": "kill", "params": {"unit": "api"}}"#,
+        )
+        .await;
+        assert!(response.is_none());
+        assert!(matches!(
+            cmd_rx.recv().await,
+            Some(AdapterCommand::Kill { id }) if id == "api"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_handle_status() {
+        let (daemon, _cmd_rx) = make_daemon();
+        daemon
+            .statuses
+            .write()
+            .await
+            .insert("db".to_string(), UnitStatus::Exited { code: Some(1) });
+
+        let response = request(
+            &daemon,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "status"}"#,
+        )
+        .await;
+        assert_eq!(
+            response.unwrap()["result"],
+            json!([
+                { "id": "api", "status": "unknown" },
+                { "id": "db", "status": "exited", "code": 1 },
+            ])
+        );
+    }
+}
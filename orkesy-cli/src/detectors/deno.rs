@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use orkesy_core::command::{CommandCategory, CommandSpec, DetectedTool, Heuristics};
+
+use super::Detector;
+
+const CONFIG_NAMES: [&str; 2] = ["deno.json", "deno.jsonc"];
+const ENTRY_POINTS: [&str; 4] = ["main.ts", "main.tsx", "main.js", "src/main.ts"];
+
+pub struct DenoDetector;
+
+/// Drops `//` and `/* */` comments and trailing commas so a `deno.jsonc`
+/// parses as plain JSON. String contents are left untouched.
+fn strip_jsonc(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            ('}' | ']', _) => {
+                let end = out.trim_end().len();
+                if out[..end].ends_with(',') {
+                    out.truncate(end - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl DenoDetector {
+    fn config_file(root: &Path) -> Option<PathBuf> {
+        CONFIG_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.exists())
+    }
+
+    async fn read_config(file: &Path) -> Option<serde_json::Value> {
+        let content = tokio::fs::read_to_string(file).await.ok()?;
+        serde_json::from_str(&strip_jsonc(&content)).ok()
+    }
+
+    fn entry_point(root: &Path) -> Option<&'static str> {
+        ENTRY_POINTS
+            .into_iter()
+            .find(|entry| root.join(entry).exists())
+    }
+
+    /// Packages published to JSR declare `exports`; otherwise an import map
+    /// or a `main` entry point marks an application
+    fn is_library(config: &serde_json::Value, root: &Path) -> bool {
+        config.get("exports").is_some()
+            || (config.get("imports").is_none() && Self::entry_point(root).is_none())
+    }
+}
+
+#[async_trait]
+impl Detector for DenoDetector {
+    fn name(&self) -> &'static str {
+        "deno"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &CONFIG_NAMES
+    }
+
+    async fn detect(&self, root: &Path) -> Option<DetectedTool> {
+        let file = Self::config_file(root)?;
+        let library = Self::read_config(&file)
+            .await
+            .is_some_and(|config| Self::is_library(&config, root));
+        Some(DetectedTool::Deno { file, library })
+    }
+
+    async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec> {
+        let DetectedTool::Deno { file, library } = tool else {
+            return vec![];
+        };
+        let Some(config) = Self::read_config(file).await else {
+            return vec![];
+        };
+
+        let spec =
+            |name: &str, command: String, description: Option<String>, category| CommandSpec {
+                id: format!("deno:{}", name),
+                tool: tool.clone(),
+                name: name.to_string(),
+                display_name: command.clone(),
+                command,
+                cwd: Some(root.to_path_buf()),
+                description,
+                category,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
+            };
+
+        let mut commands = Vec::new();
+        let tasks = config.get("tasks").and_then(|t| t.as_object());
+        let has_task = |name: &str| tasks.is_some_and(|tasks| tasks.contains_key(name));
+
+        if let Some(tasks) = tasks {
+            for (name, task) in tasks {
+                // Tasks are either a command string or `{ command, description }`
+                let (script, description) = match task {
+                    serde_json::Value::Object(task) => (
+                        task.get("command").and_then(|c| c.as_str()),
+                        task.get("description").and_then(|d| d.as_str()),
+                    ),
+                    task => (task.as_str(), None),
+                };
+                commands.push(spec(
+                    name,
+                    format!("deno task {}", name),
+                    description.map(str::to_string),
+                    Heuristics::categorize(name, script.unwrap_or_default()),
+                ));
+            }
+        }
+
+        if !has_task("test") {
+            commands.push(spec(
+                "test",
+                "deno test".into(),
+                Some("Run tests".into()),
+                CommandCategory::Test,
+            ));
+        }
+        if *library {
+            if !has_task("publish") {
+                commands.push(spec(
+                    "publish",
+                    "deno publish --dry-run".into(),
+                    Some("Check the package can be published to JSR".into()),
+                    CommandCategory::Build,
+                ));
+            }
+        } else if let Some(entry) = Self::entry_point(root)
+            && !has_task("run")
+        {
+            commands.push(spec(
+                "run",
+                format!("deno run -A {}", entry),
+                Some(format!("Run {}", entry)),
+                CommandCategory::Dev,
+            ));
+        }
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let source = r#"{
+  // Tasks run with `deno task`
+  "tasks": {
+    "dev": "deno run --watch main.ts", /* inline */
+    "url": "http://localhost:8000/*not a comment*/",
+  },
+  "exclude": ["dist",],
+}"#;
+        let json: serde_json::Value = serde_json::from_str(&strip_jsonc(source)).unwrap();
+        assert_eq!(json["tasks"]["dev"], "deno run --watch main.ts");
+        assert_eq!(
+            json["tasks"]["url"],
+            "http://localhost:8000/*not a comment*/"
+        );
+        assert_eq!(json["exclude"], serde_json::json!(["dist"]));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
@@ -8,15 +9,15 @@ use super::Detector;
 pub struct DockerComposeDetector;
 
 impl DockerComposeDetector {
+    const COMPOSE_FILES: [&'static str; 4] = [
+        "docker-compose.yml",
+        "docker-compose.yaml",
+        "compose.yml",
+        "compose.yaml",
+    ];
+
     fn find_compose_file(root: &Path) -> Option<PathBuf> {
-        let candidates = [
-            "docker-compose.yml",
-            "docker-compose.yaml",
-            "compose.yml",
-            "compose.yaml",
-        ];
-
-        for name in candidates {
+        for name in Self::COMPOSE_FILES {
             let path = root.join(name);
             if path.exists() {
                 return Some(path);
@@ -32,6 +33,10 @@ impl Detector for DockerComposeDetector {
         "docker-compose"
     }
 
+    fn inputs(&self) -> &'static [&'static str] {
+        &Self::COMPOSE_FILES
+    }
+
     async fn detect(&self, root: &Path) -> Option<DetectedTool> {
         Self::find_compose_file(root).map(|file| DetectedTool::DockerCompose { file })
     }
@@ -69,6 +74,9 @@ impl Detector for DockerComposeDetector {
                         cwd: cwd.clone(),
                         description: Some(format!("Start {} service", service_name)),
                         category: CommandCategory::Dev,
+                        stdin_input: None,
+                        clean_env: None,
+                        env: BTreeMap::new(),
                     });
 
                     commands.push(CommandSpec {
@@ -80,6 +88,9 @@ impl Detector for DockerComposeDetector {
                         cwd: cwd.clone(),
                         description: Some(format!("Follow logs for {}", service_name)),
                         category: CommandCategory::Dev,
+                        stdin_input: None,
+                        clean_env: None,
+                        env: BTreeMap::new(),
                     });
                 }
             }
@@ -103,6 +114,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Start all services".into()),
                 category: CommandCategory::Dev,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:up-d".into(),
@@ -113,6 +127,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Start all services in background".into()),
                 category: CommandCategory::Dev,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:up-build".into(),
@@ -123,6 +140,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Build and start all services".into()),
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:down".into(),
@@ -133,6 +153,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Stop all services".into()),
                 category: CommandCategory::Script,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:logs".into(),
@@ -143,6 +166,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Follow all logs".into()),
                 category: CommandCategory::Dev,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:ps".into(),
@@ -153,6 +179,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("List running containers".into()),
                 category: CommandCategory::Script,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "compose:pull".into(),
@@ -163,6 +192,9 @@ impl DockerComposeDetector {
                 cwd: cwd.clone(),
                 description: Some("Pull latest images".into()),
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
         ]
     }
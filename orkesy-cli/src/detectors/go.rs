@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use async_trait::async_trait;
+use orkesy_core::command::{CommandCategory, CommandSpec, DetectedTool};
+
+use super::Detector;
+
+pub struct GoDetector;
+
+impl GoDetector {
+    /// The `module` path declared in `go.mod`
+    fn module_name(go_mod: &str) -> Option<&str> {
+        go_mod.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix("module")?;
+            let name = rest.trim().trim_matches('"');
+            (!name.is_empty() && rest.starts_with(char::is_whitespace)).then_some(name)
+        })
+    }
+
+    fn spec(
+        tool: &DetectedTool,
+        root: &Path,
+        id: String,
+        name: &str,
+        command: String,
+        description: Option<String>,
+        category: CommandCategory,
+    ) -> CommandSpec {
+        CommandSpec {
+            id,
+            tool: tool.clone(),
+            name: name.to_string(),
+            display_name: command.clone(),
+            command,
+            cwd: Some(root.to_path_buf()),
+            description,
+            category,
+            stdin_input: None,
+            clean_env: None,
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Detector for GoDetector {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &["go.mod"]
+    }
+
+    async fn detect(&self, root: &Path) -> Option<DetectedTool> {
+        root.join("go.mod").exists().then_some(DetectedTool::Go)
+    }
+
+    async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec> {
+        let DetectedTool::Go = tool else {
+            return vec![];
+        };
+
+        let go_mod = tokio::fs::read_to_string(root.join("go.mod"))
+            .await
+            .unwrap_or_default();
+        let module = Self::module_name(&go_mod).unwrap_or("the module");
+
+        let fixed = [
+            (
+                "run",
+                "go run .",
+                format!("Run {}", module),
+                CommandCategory::Dev,
+            ),
+            (
+                "build",
+                "go build ./...",
+                "Build all packages".to_string(),
+                CommandCategory::Build,
+            ),
+            (
+                "test",
+                "go test ./...",
+                "Run tests".to_string(),
+                CommandCategory::Test,
+            ),
+            (
+                "vet",
+                "go vet ./...",
+                "Report suspicious code".to_string(),
+                CommandCategory::Lint,
+            ),
+        ];
+        let mut commands: Vec<CommandSpec> = fixed
+            .into_iter()
+            .map(|(name, command, description, category)| {
+                Self::spec(
+                    tool,
+                    root,
+                    format!("go:{}", name),
+                    name,
+                    command.to_string(),
+                    Some(description),
+                    category,
+                )
+            })
+            .collect();
+
+        // One `go run` per binary under cmd/
+        if let Ok(mut entries) = tokio::fs::read_dir(root.join("cmd")).await {
+            let mut binaries = Vec::new();
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                    binaries.push(entry.file_name().to_string_lossy().into_owned());
+                }
+            }
+            binaries.sort();
+            for binary in binaries {
+                commands.push(Self::spec(
+                    tool,
+                    root,
+                    format!("go:run-{}", binary),
+                    &format!("run {}", binary),
+                    format!("go run ./cmd/{}", binary),
+                    Some(format!("Run the {} binary", binary)),
+                    CommandCategory::Dev,
+                ));
+            }
+        }
+
+        commands
+    }
+}
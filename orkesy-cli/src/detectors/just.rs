@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use orkesy_core::command::{CommandSpec, DetectedTool};
+
+use super::Detector;
+use super::make::categorize_task;
+
+const JUSTFILE_NAMES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+
+pub struct JustDetector;
+
+/// A recipe and its doc comment
+#[derive(Debug, PartialEq, Eq)]
+struct Recipe<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+}
+
+/// Public recipes in file order. A `# comment` or `[doc("...")]` attribute
+/// right above a recipe becomes its description; `[private]` recipes and
+/// names starting with `_` are skipped, as `just --list` does.
+fn parse_recipes(justfile: &str) -> Vec<Recipe<'_>> {
+    let mut recipes = Vec::new();
+    let mut doc = None;
+    let mut private = false;
+    for line in justfile.lines() {
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            // `#!` only appears in recipe bodies, which are indented
+            doc = Some(comment.trim());
+            continue;
+        }
+        if let Some(attributes) = line.strip_prefix('[') {
+            if let Some(text) = attributes
+                .strip_prefix("doc(\"")
+                .and_then(|rest| rest.split_once("\")"))
+                .map(|(text, _)| text)
+            {
+                doc = Some(text);
+            }
+            private |= attributes.contains("private");
+            continue;
+        }
+
+        let header = line.strip_prefix('@').unwrap_or(line);
+        let recipe = header.split_once(':').and_then(|(signature, rest)| {
+            let name = signature.split_whitespace().next()?;
+            let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            // `name := value` is an assignment; `set`, `alias` and friends
+            // take one as well
+            (valid && !rest.starts_with('=')).then_some(name)
+        });
+        if let Some(name) = recipe
+            && !private
+            && !name.starts_with('_')
+        {
+            recipes.push(Recipe {
+                name,
+                description: doc.filter(|d| !d.is_empty()),
+            });
+        }
+        doc = None;
+        private = false;
+    }
+    recipes
+}
+
+impl JustDetector {
+    fn justfile(root: &Path) -> Option<PathBuf> {
+        JUSTFILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.exists())
+    }
+}
+
+#[async_trait]
+impl Detector for JustDetector {
+    fn name(&self) -> &'static str {
+        "just"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &JUSTFILE_NAMES
+    }
+
+    async fn detect(&self, root: &Path) -> Option<DetectedTool> {
+        Self::justfile(root).map(|file| DetectedTool::Just { file })
+    }
+
+    async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec> {
+        let DetectedTool::Just { file } = tool else {
+            return vec![];
+        };
+        let Ok(content) = tokio::fs::read_to_string(file).await else {
+            return vec![];
+        };
+
+        parse_recipes(&content)
+            .into_iter()
+            .map(|recipe| CommandSpec {
+                id: format!("just:{}", recipe.name),
+                tool: tool.clone(),
+                name: recipe.name.to_string(),
+                display_name: format!("just {}", recipe.name),
+                command: format!("just {}", recipe.name),
+                cwd: Some(root.to_path_buf()),
+                description: recipe.description.map(str::to_string),
+                category: categorize_task(recipe.name),
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recipes() {
+        let justfile = r#"
+set dotenv-load
+port := "8080"
+
+# Start the dev server
+dev:
+    cargo run -- --port {{port}}
+
+[doc("Run every test")]
+@test filter="":
+    cargo test {{filter}}
+
+[private]
+setup:
+    ./setup.sh
+
+_helper:
+    echo hidden
+
+release: test
+"#;
+        assert_eq!(
+            parse_recipes(justfile),
+            [
+                Recipe {
+                    name: "dev",
+                    description: Some("Start the dev server"),
+                },
+                Recipe {
+                    name: "test",
+                    description: Some("Run every test"),
+                },
+                Recipe {
+                    name: "release",
+                    description: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use orkesy_core::command::{CommandCategory, CommandSpec, DetectedTool};
+
+use super::Detector;
+
+/// Generated Makefiles can have hundreds of targets; only the first are listed
+const MAX_TARGETS: usize = 50;
+
+const MAKEFILE_NAMES: [&str; 2] = ["GNUmakefile", "Makefile"];
+
+pub struct MakeDetector;
+
+/// A rule target and its `##` doc comment
+#[derive(Debug, PartialEq, Eq)]
+struct Target<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+}
+
+/// Rule targets in file order. A `## text` comment on the line above, or
+/// after the target (`build: ## text`), becomes its description.
+fn parse_targets(makefile: &str) -> Vec<Target<'_>> {
+    let mut targets: Vec<Target> = Vec::new();
+    let mut doc = None;
+    for line in makefile.lines() {
+        if let Some(comment) = line.strip_prefix("##") {
+            doc = Some(comment.trim());
+            continue;
+        }
+        let target = line.split_once(':').and_then(|(name, rest)| {
+            let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            // `NAME := value` and `NAME ::= value` are assignments
+            (valid && !rest.starts_with([':', '='])).then_some((name, rest))
+        });
+        if let Some((name, rest)) = target
+            && !targets.iter().any(|t| t.name == name)
+        {
+            let inline = rest.split_once("##").map(|(_, text)| text.trim());
+            targets.push(Target {
+                name,
+                description: inline.or(doc).filter(|d| !d.is_empty()),
+            });
+        }
+        doc = None;
+    }
+    targets
+}
+
+/// Category of a Make target or Just recipe from the words of its name
+pub(super) fn categorize_task(name: &str) -> CommandCategory {
+    let lower = name.to_lowercase();
+    let has = |words: &[&str]| lower.split(['-', '_']).any(|w| words.contains(&w));
+    if has(&["test", "tests"]) {
+        CommandCategory::Test
+    } else if has(&["build"]) {
+        CommandCategory::Build
+    } else if has(&["lint", "fmt", "format"]) {
+        CommandCategory::Lint
+    } else if has(&["dev", "run"]) {
+        CommandCategory::Dev
+    } else {
+        CommandCategory::Task
+    }
+}
+
+impl MakeDetector {
+    fn makefile(root: &Path) -> Option<PathBuf> {
+        MAKEFILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.exists())
+    }
+}
+
+#[async_trait]
+impl Detector for MakeDetector {
+    fn name(&self) -> &'static str {
+        "make"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &MAKEFILE_NAMES
+    }
+
+    async fn detect(&self, root: &Path) -> Option<DetectedTool> {
+        Self::makefile(root).map(|file| DetectedTool::Make { file })
+    }
+
+    async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec> {
+        let DetectedTool::Make { file } = tool else {
+            return vec![];
+        };
+        let Ok(content) = tokio::fs::read_to_string(file).await else {
+            return vec![];
+        };
+
+        parse_targets(&content)
+            .into_iter()
+            .take(MAX_TARGETS)
+            .map(|target| CommandSpec {
+                id: format!("make:{}", target.name),
+                tool: tool.clone(),
+                name: target.name.to_string(),
+                display_name: format!("make {}", target.name),
+                command: format!("make {}", target.name),
+                cwd: Some(root.to_path_buf()),
+                description: target.description.map(str::to_string),
+                category: categorize_task(target.name),
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        let makefile = "\
+CC := gcc
+PREFIX ::= /usr/local
+
+## Build the binary
+build: main.o
+\t$(CC) -o app main.o
+
+test: build ## Run the tests
+\t./app --test
+
+.PHONY: build test
+build: extra
+lint-all:
+";
+        assert_eq!(
+            parse_targets(makefile),
+            [
+                Target {
+                    name: "build",
+                    description: Some("Build the binary"),
+                },
+                Target {
+                    name: "test",
+                    description: Some("Run the tests"),
+                },
+                Target {
+                    name: "lint-all",
+                    description: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_categorize_task() {
+        assert!(matches!(
+            categorize_task("unit-tests"),
+            CommandCategory::Test
+        ));
+        assert!(matches!(
+            categorize_task("build_docs"),
+            CommandCategory::Build
+        ));
+        assert!(matches!(categorize_task("fmt"), CommandCategory::Lint));
+        assert!(matches!(categorize_task("dev"), CommandCategory::Dev));
+        // Whole words only
+        assert!(matches!(categorize_task("rebuild"), CommandCategory::Task));
+    }
+}
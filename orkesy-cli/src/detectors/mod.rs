@@ -1,34 +1,75 @@
+mod deno;
 mod docker;
+mod go;
+mod just;
+mod make;
 mod node;
+mod python;
 mod rust;
+mod watch;
 
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use async_trait::async_trait;
 use orkesy_core::command::{CommandSpec, DetectedTool, ProjectIndex};
+use sha2::{Digest, Sha256};
 
+pub use deno::DenoDetector;
 pub use docker::DockerComposeDetector;
+pub use go::GoDetector;
+pub use just::JustDetector;
+pub use make::MakeDetector;
 pub use node::NodeDetector;
+pub use python::PythonDetector;
 pub use rust::RustDetector;
+pub use watch::spawn_project_watcher;
 
 #[async_trait]
 pub trait Detector: Send + Sync {
     #[allow(dead_code)]
     fn name(&self) -> &'static str;
 
+    /// Files (relative to the project root) this detector looks at. Their
+    /// hashes decide when the project needs reindexing.
+    fn inputs(&self) -> &'static [&'static str];
+
     async fn detect(&self, root: &Path) -> Option<DetectedTool>;
 
     async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec>;
 }
 
-pub async fn index_project(root: &Path) -> ProjectIndex {
-    let detectors: Vec<Box<dyn Detector>> = vec![
+fn detectors() -> Vec<Box<dyn Detector>> {
+    vec![
         Box::new(NodeDetector),
+        Box::new(PythonDetector),
+        Box::new(DenoDetector),
         Box::new(RustDetector),
+        Box::new(GoDetector),
         Box::new(DockerComposeDetector),
-    ];
+        Box::new(MakeDetector),
+        Box::new(JustDetector),
+    ]
+}
+
+/// SHA-256 of every detector input that exists under `root`.
+pub async fn hash_inputs(root: &Path) -> BTreeMap<PathBuf, [u8; 32]> {
+    let mut hashes = BTreeMap::new();
+    for detector in detectors() {
+        for name in detector.inputs() {
+            let path = root.join(name);
+            if let Ok(bytes) = tokio::fs::read(&path).await {
+                hashes.insert(path, Sha256::digest(&bytes).into());
+            }
+        }
+    }
+    hashes
+}
+
+pub async fn index_project(root: &Path) -> ProjectIndex {
+    let detectors = detectors();
+    let file_hashes = hash_inputs(root).await;
 
     let mut tools = Vec::new();
     let mut commands = BTreeMap::new();
@@ -48,5 +89,6 @@ pub async fn index_project(root: &Path) -> ProjectIndex {
         tools,
         commands,
         indexed_at: SystemTime::now(),
+        file_hashes,
     }
 }
@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use async_trait::async_trait;
-use orkesy_core::command::{CommandCategory, CommandSpec, DetectedTool, PackageManager};
+use orkesy_core::command::{
+    CommandCategory, CommandSpec, DetectedTool, Heuristics, PackageManager,
+};
 
 use super::Detector;
 
@@ -19,32 +22,6 @@ impl NodeDetector {
             PackageManager::Npm
         }
     }
-
-    fn categorize_script(name: &str) -> CommandCategory {
-        let lower = name.to_lowercase();
-        if lower == "dev"
-            || lower == "start"
-            || lower == "serve"
-            || lower.starts_with("dev:")
-            || lower.contains("watch")
-        {
-            CommandCategory::Dev
-        } else if lower == "build" || lower.starts_with("build:") || lower == "compile" {
-            CommandCategory::Build
-        } else if lower == "test" || lower.starts_with("test:") || lower == "coverage" {
-            CommandCategory::Test
-        } else if lower == "lint"
-            || lower == "format"
-            || lower == "check"
-            || lower.starts_with("lint:")
-            || lower == "prettier"
-            || lower == "eslint"
-        {
-            CommandCategory::Lint
-        } else {
-            CommandCategory::Script
-        }
-    }
 }
 
 #[async_trait]
@@ -53,6 +30,10 @@ impl Detector for NodeDetector {
         "node"
     }
 
+    fn inputs(&self) -> &'static [&'static str] {
+        &["package.json", "pnpm-lock.yaml", "yarn.lock", "bun.lockb"]
+    }
+
     async fn detect(&self, root: &Path) -> Option<DetectedTool> {
         let pkg_json = root.join("package.json");
         if pkg_json.exists() {
@@ -92,14 +73,17 @@ impl Detector for NodeDetector {
             cwd: Some(root.to_path_buf()),
             description: Some("Install dependencies".into()),
             category: CommandCategory::Build,
+            stdin_input: None,
+            clean_env: None,
+            env: BTreeMap::new(),
         });
 
         // Extract scripts from package.json
         if let Some(scripts) = pkg.get("scripts").and_then(|s| s.as_object()) {
             let prefix = pm.run_prefix();
 
-            for (name, _cmd) in scripts {
-                let category = Self::categorize_script(name);
+            for (name, script) in scripts {
+                let category = Heuristics::categorize(name, script.as_str().unwrap_or_default());
                 let display = format!("{} {}", prefix, name);
                 let cmd_str = format!("{} {}", prefix, name);
 
@@ -112,6 +96,9 @@ impl Detector for NodeDetector {
                     cwd: Some(root.to_path_buf()),
                     description: None,
                     category,
+                    stdin_input: None,
+                    clean_env: None,
+                    env: BTreeMap::new(),
                 });
             }
         }
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use async_trait::async_trait;
+use orkesy_core::command::{
+    CommandCategory, CommandSpec, DetectedTool, Heuristics, PythonPackageManager,
+};
+use serde_yaml::Value;
+
+use super::Detector;
+use crate::platform::find_on_path;
+
+pub struct PythonDetector;
+
+impl PythonDetector {
+    /// Poetry for Poetry projects when it is installed, otherwise uv when
+    /// installed, otherwise plain pip
+    fn detect_package_manager(root: &Path, pyproject: Option<&Value>) -> PythonPackageManager {
+        let poetry_project = pyproject.is_some_and(|doc| !doc["tool"]["poetry"].is_null());
+        if poetry_project && find_on_path("poetry").is_some() {
+            PythonPackageManager::Poetry
+        } else if find_on_path("uv").is_some()
+            && (root.join("uv.lock").exists() || pyproject.is_some())
+        {
+            PythonPackageManager::Uv
+        } else {
+            PythonPackageManager::Pip
+        }
+    }
+
+    /// `pyproject.toml` as a document, if present and readable
+    fn read_pyproject(root: &Path) -> Option<Value> {
+        let content = std::fs::read_to_string(root.join("pyproject.toml")).ok()?;
+        orkesy_core::config::toml_value(&content).ok()
+    }
+
+    fn install_cmd(root: &Path, pm: &PythonPackageManager) -> &'static str {
+        match pm {
+            PythonPackageManager::Uv => "uv sync",
+            PythonPackageManager::Poetry => "poetry install",
+            PythonPackageManager::Pip if root.join("requirements.txt").exists() => {
+                "pip install -r requirements.txt"
+            }
+            PythonPackageManager::Pip => "pip install -e .",
+        }
+    }
+}
+
+#[async_trait]
+impl Detector for PythonDetector {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn inputs(&self) -> &'static [&'static str] {
+        &[
+            "pyproject.toml",
+            "setup.py",
+            "requirements.txt",
+            "uv.lock",
+            "poetry.lock",
+        ]
+    }
+
+    async fn detect(&self, root: &Path) -> Option<DetectedTool> {
+        let found = ["pyproject.toml", "setup.py", "requirements.txt"]
+            .iter()
+            .any(|name| root.join(name).exists());
+        found.then(|| DetectedTool::Python {
+            pm: Self::detect_package_manager(root, Self::read_pyproject(root).as_ref()),
+        })
+    }
+
+    async fn commands(&self, root: &Path, tool: &DetectedTool) -> Vec<CommandSpec> {
+        let DetectedTool::Python { pm } = tool else {
+            return vec![];
+        };
+
+        let command = |id: String, name: String, command: String, category| CommandSpec {
+            id,
+            tool: tool.clone(),
+            display_name: command.clone(),
+            name,
+            command,
+            cwd: Some(root.to_path_buf()),
+            description: None,
+            category,
+            stdin_input: None,
+            clean_env: None,
+            env: BTreeMap::new(),
+        };
+
+        let install = Self::install_cmd(root, pm);
+        let mut commands = vec![CommandSpec {
+            description: Some("Install dependencies".into()),
+            ..command(
+                "python:install".into(),
+                "install".into(),
+                install.into(),
+                CommandCategory::Build,
+            )
+        }];
+
+        let Some(pyproject) = Self::read_pyproject(root) else {
+            return commands;
+        };
+
+        // Entry points run as programs; taskipy tasks through `task`
+        let scripts = [
+            (&pyproject["project"]["scripts"], false),
+            (&pyproject["tool"]["poetry"]["scripts"], false),
+            (&pyproject["tool"]["taskipy"]["tasks"], true),
+        ];
+        for (table, is_task) in scripts {
+            let Value::Mapping(entries) = table else {
+                continue;
+            };
+            for (name, target) in entries {
+                let Some(name) = name.as_str() else {
+                    continue;
+                };
+                // Tasks are either `"cmd"` or `{ cmd = "...", help = "..." }`
+                let target = target
+                    .as_str()
+                    .or_else(|| target["cmd"].as_str())
+                    .unwrap_or_default();
+                let program = if is_task {
+                    format!("task {}", name)
+                } else {
+                    name.to_string()
+                };
+                commands.push(command(
+                    format!("python:{}", name),
+                    name.to_string(),
+                    pm.run(&program),
+                    Heuristics::categorize(name, target),
+                ));
+            }
+        }
+
+        commands
+    }
+}
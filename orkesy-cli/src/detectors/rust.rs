@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use async_trait::async_trait;
@@ -13,6 +14,10 @@ impl Detector for RustDetector {
         "rust"
     }
 
+    fn inputs(&self) -> &'static [&'static str] {
+        &["Cargo.toml"]
+    }
+
     async fn detect(&self, root: &Path) -> Option<DetectedTool> {
         if root.join("Cargo.toml").exists() {
             Some(DetectedTool::Rust)
@@ -38,6 +43,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Build the project".into()),
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:build-release".into(),
@@ -48,6 +56,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Build in release mode".into()),
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:run".into(),
@@ -58,6 +69,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Run the project".into()),
                 category: CommandCategory::Dev,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:test".into(),
@@ -68,6 +82,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Run tests".into()),
                 category: CommandCategory::Test,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:check".into(),
@@ -78,6 +95,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Check for errors without building".into()),
                 category: CommandCategory::Lint,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:clippy".into(),
@@ -88,6 +108,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Run Clippy lints".into()),
                 category: CommandCategory::Lint,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:fmt".into(),
@@ -98,6 +121,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Format code".into()),
                 category: CommandCategory::Lint,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
             CommandSpec {
                 id: "cargo:doc".into(),
@@ -108,6 +134,9 @@ impl Detector for RustDetector {
                 cwd: cwd.clone(),
                 description: Some("Build and open documentation".into()),
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
         ]
     }
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::{broadcast, mpsc};
+
+use orkesy_core::reducer::{EventEnvelope, RuntimeEvent};
+
+use super::{hash_inputs, index_project};
+
+/// Editors write files in several steps; wait for them to settle
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the project root and re-runs `index_project` when the content of
+/// a detector input changes, emitting `ProjectIndexing` then `ProjectIndexed`.
+pub fn spawn_project_watcher(
+    root: &Path,
+    file_hashes: BTreeMap<PathBuf, [u8; 32]>,
+    event_tx: broadcast::Sender<EventEnvelope>,
+) -> Result<(), String> {
+    let (change_tx, mut change_rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok() {
+            let _ = change_tx.send(());
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(root, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+
+    let root = root.to_path_buf();
+    tokio::spawn(async move {
+        // Dropping the watcher stops it, so it lives as long as the task
+        let _watcher = watcher;
+        let mut hashes = file_hashes;
+        let mut next_id = 2_000_000u64;
+        let mut emit = |event: RuntimeEvent| {
+            next_id += 1;
+            let _ = event_tx.send(EventEnvelope {
+                id: next_id,
+                at: SystemTime::now(),
+                event,
+            });
+        };
+
+        while change_rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while change_rx.try_recv().is_ok() {}
+
+            if hash_inputs(&root).await == hashes {
+                continue;
+            }
+
+            emit(RuntimeEvent::ProjectIndexing);
+            let project = index_project(&root).await;
+            hashes = project.file_hashes.clone();
+            emit(RuntimeEvent::ProjectIndexed { project });
+        }
+    });
+
+    Ok(())
+}
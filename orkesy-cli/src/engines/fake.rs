@@ -1,27 +1,35 @@
 use std::collections::BTreeMap;
-use std::time::{Duration, SystemTime};
+use std::f64::consts::TAU;
+use std::time::{Duration, Instant, SystemTime};
 
 use async_trait::async_trait;
 use tokio::sync::{broadcast, mpsc};
 
 use orkesy_core::engine::{Engine, EngineCommand};
-use orkesy_core::model::{RuntimeGraph, ServiceId, ServiceStatus};
+use orkesy_core::model::{
+    DesiredState, HealthStatus, RuntimeGraph, ServiceId, ServiceKind, ServiceStatus,
+};
 use orkesy_core::reducer::{EventEnvelope, RuntimeEvent};
 use orkesy_core::state::LogStream;
 use orkesy_core::unit::UnitMetrics;
 
+/// How often pending starts/restarts are checked
+const SCHEDULE_INTERVAL: Duration = Duration::from_millis(100);
+const CRASH_PROBABILITY_PER_MINUTE: f64 = 0.05;
+const CRASH_RESTART_DELAY: Duration = Duration::from_millis(1500);
+
 pub struct FakeEngine {
     tick_interval: Duration,
-    tick_counter: u64,
     job_counter: u64,
+    rng: Rng,
 }
 
 impl FakeEngine {
     pub fn new() -> Self {
         Self {
             tick_interval: Duration::from_millis(600),
-            tick_counter: 0,
             job_counter: 0,
+            rng: Rng::seeded(),
         }
     }
 
@@ -30,6 +38,16 @@ impl FakeEngine {
         self.tick_interval = interval;
         self
     }
+
+    fn start_delay(&mut self) -> Duration {
+        Duration::from_millis(self.rng.range(500, 2000))
+    }
+
+    /// Per-tick crash chance that adds up to 5% per minute
+    fn crash_chance(&self) -> f64 {
+        let ticks_per_minute = 60.0 / self.tick_interval.as_secs_f64();
+        1.0 - (1.0 - CRASH_PROBABILITY_PER_MINUTE).powf(1.0 / ticks_per_minute)
+    }
 }
 
 impl Default for FakeEngine {
@@ -38,6 +56,323 @@ impl Default for FakeEngine {
     }
 }
 
+/// Small xorshift generator; good enough for demo noise.
+struct Rng(u64);
+
+impl Rng {
+    fn seeded() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x2545_F491_4F6C_DD1D);
+        Self(nanos | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `[0, 1)`
+    fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[lo, hi)`
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo).max(1)
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.float() < p
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as u64) as usize]
+    }
+}
+
+/// Simulated state of one demo service
+struct SimService {
+    kind: ServiceKind,
+    status: ServiceStatus,
+    health: HealthStatus,
+    /// Ticks until a degraded/unhealthy service recovers
+    recover_in: u32,
+    cpu: f64,
+    burst: f64,
+    phase: f64,
+    mem: u64,
+    started_at: Option<Instant>,
+    /// Pending transition to Running
+    ready_at: Option<Instant>,
+    /// Pending restart after a simulated crash
+    restart_at: Option<Instant>,
+}
+
+impl SimService {
+    fn new(kind: ServiceKind, rng: &mut Rng) -> Self {
+        let profile = Profile::for_kind(&kind);
+        Self {
+            kind,
+            status: ServiceStatus::Stopped,
+            health: HealthStatus::Unknown,
+            recover_in: 0,
+            cpu: profile.base_cpu,
+            burst: 0.0,
+            phase: rng.float() * TAU,
+            mem: profile.base_mem,
+            started_at: None,
+            ready_at: None,
+            restart_at: None,
+        }
+    }
+
+    fn is_up(&self) -> bool {
+        matches!(
+            self.status,
+            ServiceStatus::Running | ServiceStatus::Starting | ServiceStatus::Restarting
+        )
+    }
+
+    /// CPU follows a slow wave per service plus decaying load bursts,
+    /// memory grows steadily and drops back on "GC"
+    fn step_metrics(&mut self, rng: &mut Rng) {
+        let profile = Profile::for_kind(&self.kind);
+
+        self.phase = (self.phase + TAU / profile.period_ticks) % TAU;
+        if rng.chance(0.04) {
+            self.burst += 15.0 + rng.float() * 20.0;
+        }
+        self.burst *= 0.7;
+
+        let target = profile.base_cpu + profile.swing * (0.5 + 0.5 * self.phase.sin()) + self.burst;
+        let noise = (rng.float() - 0.5) * 2.0;
+        self.cpu = (self.cpu + (target - self.cpu) * 0.35 + noise).clamp(5.0, 80.0);
+
+        self.mem += rng.range(0, 2_000_000);
+        if self.mem > profile.base_mem + profile.base_mem / 3 {
+            self.mem = profile.base_mem + rng.range(0, 10_000_000);
+        }
+    }
+}
+
+/// Resource shape of a service kind
+struct Profile {
+    base_cpu: f64,
+    swing: f64,
+    period_ticks: f64,
+    base_mem: u64,
+}
+
+impl Profile {
+    fn for_kind(kind: &ServiceKind) -> Self {
+        let (base_cpu, swing, period_ticks, base_mem) = match kind {
+            ServiceKind::HttpApi => (12.0, 25.0, 60.0, 150_000_000),
+            ServiceKind::Worker => (25.0, 40.0, 90.0, 280_000_000),
+            ServiceKind::Database => (8.0, 15.0, 120.0, 512_000_000),
+            ServiceKind::Cache => (5.0, 6.0, 80.0, 64_000_000),
+            ServiceKind::Queue => (6.0, 12.0, 70.0, 128_000_000),
+            ServiceKind::Frontend => (8.0, 20.0, 50.0, 200_000_000),
+            ServiceKind::Generic => (5.0, 10.0, 100.0, 50_000_000),
+        };
+        Self {
+            base_cpu,
+            swing,
+            period_ticks,
+            base_mem,
+        }
+    }
+}
+
+// ---------------- Log template library ----------------
+
+const HTTP_METHODS: [&str; 5] = ["GET", "GET", "GET", "POST", "PUT"];
+const HTTP_ROUTES: [&str; 8] = [
+    "/health",
+    "/api/users",
+    "/api/users/42",
+    "/api/orders",
+    "/api/orders?page=2",
+    "/api/session",
+    "/api/search?q=orkesy",
+    "/api/metrics",
+];
+const DB_STATEMENTS: [&str; 6] = [
+    "SELECT * FROM users WHERE id = $1",
+    "SELECT id, status FROM orders WHERE user_id = $1 ORDER BY created_at DESC LIMIT 20",
+    "INSERT INTO events (kind, payload) VALUES ($1, $2)",
+    "UPDATE jobs SET status = 'done' WHERE id = $1",
+    "SELECT count(*) FROM sessions WHERE expires_at > now()",
+    "DELETE FROM sessions WHERE expires_at < now()",
+];
+const DB_MAINTENANCE: [&str; 3] = [
+    "checkpoint complete: wrote 214 buffers (1.3%)",
+    "automatic vacuum of table \"app.public.jobs\": index scans: 1",
+    "connection authorized: user=app database=app",
+];
+const JOB_KINDS: [&str; 5] = [
+    "send_email",
+    "resize_image",
+    "sync_inventory",
+    "generate_report",
+    "charge_card",
+];
+const QUEUES: [&str; 3] = ["orders", "emails", "webhooks"];
+const FRONTEND_FILES: [&str; 4] = [
+    "src/App.tsx",
+    "src/pages/Dashboard.tsx",
+    "src/components/Chart.tsx",
+    "src/styles.css",
+];
+const DEGRADED_REASONS: [&str; 3] = [
+    "p99 latency 850ms",
+    "connection pool 90% used",
+    "queue backlog growing",
+];
+const UNHEALTHY_REASONS: [&str; 2] = ["health check timed out", "upstream unavailable"];
+const CRASH_MESSAGES: [&str; 3] = [
+    "panic: runtime error: invalid memory address or nil pointer dereference",
+    "FATAL: out of memory",
+    "Error: connect ECONNRESET 10.0.0.12:5432",
+];
+
+fn fake_log_line(kind: &ServiceKind, rng: &mut Rng, job_counter: &mut u64) -> String {
+    match kind {
+        ServiceKind::HttpApi => {
+            let method = rng.pick(&HTTP_METHODS);
+            let route = rng.pick(&HTTP_ROUTES);
+            let ms = if rng.chance(0.08) {
+                rng.range(400, 1200)
+            } else {
+                rng.range(2, 80)
+            };
+            if rng.chance(0.03) {
+                format!(
+                    "[ERROR] {} {} 500 {}ms - upstream connection refused",
+                    method, route, ms
+                )
+            } else if ms >= 400 {
+                format!("[WARN] {} {} 200 {}ms - slow request", method, route, ms)
+            } else {
+                let status = match *method {
+                    "POST" => 201,
+                    _ if rng.chance(0.05) => 404,
+                    _ => 200,
+                };
+                format!("{} {} {} {}ms", method, route, status, ms)
+            }
+        }
+        ServiceKind::Worker => {
+            *job_counter += 1;
+            let job = rng.pick(&JOB_KINDS);
+            if rng.chance(0.04) {
+                format!(
+                    "[ERROR] job {} ({}) failed: timeout after 30s",
+                    job_counter, job
+                )
+            } else if rng.chance(0.06) {
+                format!("[WARN] retrying job {} ({}) attempt 2/3", job_counter, job)
+            } else {
+                format!(
+                    "processed job {} ({}) in {}ms",
+                    job_counter,
+                    job,
+                    rng.range(20, 900)
+                )
+            }
+        }
+        ServiceKind::Database => {
+            if rng.chance(0.2) {
+                rng.pick(&DB_MAINTENANCE).to_string()
+            } else {
+                let ms = if rng.chance(0.05) {
+                    rng.range(1000, 2500)
+                } else {
+                    rng.range(1, 40)
+                };
+                let statement = rng.pick(&DB_STATEMENTS);
+                if ms >= 1000 {
+                    format!(
+                        "[WARN] slow query: duration: {} ms  statement: {}",
+                        ms, statement
+                    )
+                } else {
+                    format!("duration: {} ms  statement: {}", ms, statement)
+                }
+            }
+        }
+        ServiceKind::Cache => {
+            if rng.chance(0.1) {
+                "Background saving started".to_string()
+            } else {
+                format!(
+                    "{} keys, {:.1}MB used, hit rate {}%",
+                    rng.range(1_000, 1_500),
+                    2.0 + rng.float() * 1.5,
+                    rng.range(88, 99)
+                )
+            }
+        }
+        ServiceKind::Queue => {
+            let queue = rng.pick(&QUEUES);
+            format!("published {} messages to '{}'", rng.range(1, 50), queue)
+        }
+        ServiceKind::Frontend => {
+            if rng.chance(0.3) {
+                format!("compiled successfully in {}ms", rng.range(80, 600))
+            } else {
+                format!("hmr update /{}", rng.pick(&FRONTEND_FILES))
+            }
+        }
+        ServiceKind::Generic => format!("heartbeat ok ({}ms)", rng.range(1, 20)),
+    }
+}
+
+/// Sends events with increasing ids
+struct Emitter {
+    tx: broadcast::Sender<EventEnvelope>,
+    next_id: u64,
+}
+
+impl Emitter {
+    fn emit(&mut self, event: RuntimeEvent) {
+        let _ = self.tx.send(EventEnvelope {
+            id: self.next_id,
+            at: SystemTime::now(),
+            event,
+        });
+        self.next_id += 1;
+    }
+
+    fn status(&mut self, id: &str, svc: &mut SimService, status: ServiceStatus) {
+        svc.status = status.clone();
+        self.emit(RuntimeEvent::StatusChanged {
+            id: id.to_string(),
+            status,
+        });
+    }
+
+    fn health(&mut self, id: &str, svc: &mut SimService, health: HealthStatus) {
+        svc.health = health.clone();
+        self.emit(RuntimeEvent::HealthChanged {
+            id: id.to_string(),
+            health,
+            latency_ms: None,
+        });
+    }
+
+    fn log(&mut self, id: &str, stream: LogStream, text: impl Into<String>) {
+        self.emit(RuntimeEvent::LogLine {
+            id: id.to_string(),
+            stream,
+            text: text.into(),
+        });
+    }
+}
+
 #[async_trait]
 impl Engine for FakeEngine {
     fn name(&self) -> &'static str {
@@ -50,150 +385,117 @@ impl Engine for FakeEngine {
         event_tx: broadcast::Sender<EventEnvelope>,
         graph: RuntimeGraph,
     ) {
-        let mut next_id: u64 = 1;
-
-        let mut emit = |event: RuntimeEvent| {
-            let _ = event_tx.send(EventEnvelope {
-                id: next_id,
-                at: SystemTime::now(),
-                event,
-            });
-            next_id += 1;
+        let mut out = Emitter {
+            tx: event_tx,
+            next_id: 1,
         };
 
-        let mut statuses: BTreeMap<ServiceId, ServiceStatus> = graph
+        let mut services: BTreeMap<ServiceId, SimService> = graph
             .nodes
-            .keys()
-            .map(|id| (id.clone(), ServiceStatus::Stopped))
+            .iter()
+            .map(|(id, node)| {
+                (
+                    id.clone(),
+                    SimService::new(node.kind.clone(), &mut self.rng),
+                )
+            })
             .collect();
 
-        emit(RuntimeEvent::TopologyLoaded {
+        out.emit(RuntimeEvent::TopologyLoaded {
             graph: graph.clone(),
+            log_options: BTreeMap::new(),
         });
 
         for (id, node) in &graph.nodes {
-            if matches!(node.desired, orkesy_core::model::DesiredState::Running) {
-                emit(RuntimeEvent::StatusChanged {
-                    id: id.clone(),
-                    status: ServiceStatus::Starting,
-                });
-                statuses.insert(id.clone(), ServiceStatus::Starting);
-            }
-        }
-
-        tokio::time::sleep(Duration::from_millis(250)).await;
-
-        for (id, status) in statuses.iter_mut() {
-            if matches!(status, ServiceStatus::Starting) {
-                let _ = event_tx.send(EventEnvelope {
-                    id: next_id,
-                    at: SystemTime::now(),
-                    event: RuntimeEvent::StatusChanged {
-                        id: id.clone(),
-                        status: ServiceStatus::Running,
-                    },
-                });
-                next_id += 1;
-                *status = ServiceStatus::Running;
-
-                let _ = event_tx.send(EventEnvelope {
-                    id: next_id,
-                    at: SystemTime::now(),
-                    event: RuntimeEvent::LogLine {
-                        id: id.clone(),
-                        stream: LogStream::System,
-                        text: format!("{} started", id),
-                    },
-                });
-                next_id += 1;
+            if matches!(node.desired, DesiredState::Running) {
+                let delay = self.start_delay();
+                let svc = services.get_mut(id).unwrap();
+                out.status(id, svc, ServiceStatus::Starting);
+                svc.ready_at = Some(Instant::now() + delay);
             }
         }
 
         let mut tick = tokio::time::interval(self.tick_interval);
         tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut schedule = tokio::time::interval(SCHEDULE_INTERVAL);
+        schedule.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
         loop {
             tokio::select! {
+                _ = schedule.tick() => {
+                    let now = Instant::now();
+                    for (id, svc) in services.iter_mut() {
+                        if svc.restart_at.is_some_and(|at| at <= now) {
+                            svc.restart_at = None;
+                            out.status(id, svc, ServiceStatus::Restarting);
+                            out.log(id, LogStream::System, "restarting after crash...");
+                            svc.ready_at = Some(now + self.start_delay());
+                        }
+                        if svc.ready_at.is_some_and(|at| at <= now) {
+                            svc.ready_at = None;
+                            svc.started_at = Some(now);
+                            out.status(id, svc, ServiceStatus::Running);
+                            out.health(id, svc, HealthStatus::Healthy);
+                            out.log(id, LogStream::System, format!("{} started", id));
+                        }
+                    }
+                }
+
                 _ = tick.tick() => {
-                    self.tick_counter += 1;
-                    let tick_num = self.tick_counter;
-
-                    for (id, st) in statuses.iter() {
-                        if matches!(st, ServiceStatus::Running) {
-                            let text: String = match id.as_str() {
-                                "api" => {
-                                    if tick_num % 12 == 7 {
-                                        "[ERROR] Connection refused to upstream service".into()
-                                    } else if tick_num % 8 == 3 {
-                                        "[WARN] High latency detected: 450ms".into()
-                                    } else {
-                                        let routes = ["GET /health 200", "GET /api/users 200", "POST /api/data 201", "GET /api/status 200"];
-                                        routes[(tick_num as usize) % routes.len()].into()
-                                    }
-                                }
-                                "worker" => {
-                                    self.job_counter += 1;
-                                    if tick_num % 10 == 5 {
-                                        format!("[ERROR] Job {} failed: timeout after 30s", self.job_counter)
-                                    } else if tick_num % 7 == 2 {
-                                        format!("[WARN] Queue depth high: {} pending", 50 + (tick_num % 30))
-                                    } else {
-                                        format!("processed job id={}", self.job_counter)
-                                    }
-                                }
-                                "postgres" | "db" => {
-                                    if tick_num % 15 == 10 {
-                                        "[WARN] Slow query detected: 1250ms".into()
-                                    } else {
-                                        let msgs = ["checkpoint complete", "autovacuum: processing", "connection accepted"];
-                                        msgs[(tick_num as usize) % msgs.len()].into()
-                                    }
-                                }
-                                "redis" | "cache" => {
-                                    let keys = 1000 + (tick_num % 500);
-                                    let mem = 2.0 + (tick_num % 10) as f32 * 0.1;
-                                    format!("keys: {}, memory: {:.1}MB", keys, mem)
-                                }
-                                _ => format!("tick {}", tick_num),
-                            };
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine {
-                                    id: id.clone(),
-                                    stream: LogStream::Stdout,
-                                    text,
-                                },
-                            });
-                            next_id += 1;
-
-                            let (base_cpu, base_mem) = match id.as_str() {
-                                "api" => (12.0, 150_000_000u64),
-                                "worker" => (35.0, 280_000_000u64),
-                                "postgres" | "db" => (8.0, 512_000_000u64),
-                                "redis" | "cache" => (3.0, 64_000_000u64),
-                                _ => (5.0, 50_000_000u64),
-                            };
-
-                            let cpu = base_cpu + ((tick_num % 10) as f32 * 1.5) - 5.0;
-                            let memory = base_mem + ((tick_num % 20) * 1_000_000);
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::MetricsUpdated {
-                                    id: id.clone(),
-                                    metrics: UnitMetrics {
-                                        cpu_percent: cpu.max(0.1),
-                                        memory_bytes: memory,
-                                        uptime_secs: tick_num * self.tick_interval.as_secs().max(1),
-                                        pid: Some(10000 + (id.len() as u32 * 100)),
-                                    },
-                                },
-                            });
-                            next_id += 1;
+                    let crash_chance = self.crash_chance();
+
+                    for (id, svc) in services.iter_mut() {
+                        if !matches!(svc.status, ServiceStatus::Running) {
+                            continue;
+                        }
+
+                        // Simulated crash followed by an automatic restart
+                        if self.rng.chance(crash_chance) {
+                            let message = *self.rng.pick(&CRASH_MESSAGES);
+                            out.log(id, LogStream::Stderr, format!("[ERROR] {}", message));
+                            out.status(id, svc, ServiceStatus::Exited { code: Some(1) });
+                            out.health(id, svc, HealthStatus::Unhealthy { reason: "process crashed".into() });
+                            svc.started_at = None;
+                            svc.restart_at = Some(Instant::now() + CRASH_RESTART_DELAY);
+                            continue;
+                        }
+
+                        // 0-2 log lines per tick, drawn from the template library
+                        for _ in 0..self.rng.range(0, 3) {
+                            let text = fake_log_line(&svc.kind, &mut self.rng, &mut self.job_counter);
+                            out.log(id, LogStream::Stdout, text);
+                        }
+
+                        // Health wanders away from Healthy and recovers after a while
+                        if matches!(svc.health, HealthStatus::Healthy) {
+                            if self.rng.chance(0.01) {
+                                let reason = self.rng.pick(&UNHEALTHY_REASONS).to_string();
+                                svc.recover_in = self.rng.range(3, 8) as u32;
+                                out.health(id, svc, HealthStatus::Unhealthy { reason });
+                            } else if self.rng.chance(0.03) {
+                                let reason = self.rng.pick(&DEGRADED_REASONS).to_string();
+                                svc.recover_in = self.rng.range(5, 15) as u32;
+                                out.health(id, svc, HealthStatus::Degraded { reason });
+                            }
+                        } else {
+                            svc.recover_in = svc.recover_in.saturating_sub(1);
+                            if svc.recover_in == 0 {
+                                out.health(id, svc, HealthStatus::Healthy);
+                            }
                         }
+
+                        svc.step_metrics(&mut self.rng);
+                        let uptime_secs = svc.started_at.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+                        out.emit(RuntimeEvent::MetricsUpdated {
+                            id: id.clone(),
+                            metrics: UnitMetrics {
+                                cpu_percent: svc.cpu as f32,
+                                memory_bytes: svc.mem,
+                                uptime_secs,
+                                pid: Some(10000 + (id.len() as u32 * 100)),
+                                ..Default::default()
+                            },
+                        });
                     }
                 }
 
@@ -204,193 +506,71 @@ impl Engine for FakeEngine {
                         EngineCommand::Shutdown => break,
 
                         EngineCommand::Start { id } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Starting,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id.clone(), ServiceStatus::Starting);
-
-                            tokio::time::sleep(Duration::from_millis(300)).await;
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Running,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id, ServiceStatus::Running);
+                            let delay = self.start_delay();
+                            if let Some(svc) = services.get_mut(&id) {
+                                if svc.is_up() {
+                                    out.log(&id, LogStream::System, "[warn] already running");
+                                    continue;
+                                }
+                                svc.restart_at = None;
+                                out.status(&id, svc, ServiceStatus::Starting);
+                                svc.ready_at = Some(Instant::now() + delay);
+                            }
                         }
 
                         EngineCommand::Stop { id } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Stopped,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id, ServiceStatus::Stopped);
+                            if let Some(svc) = services.get_mut(&id) {
+                                stop(&mut out, &id, svc);
+                            }
                         }
 
                         EngineCommand::Restart { id } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Restarting,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id.clone(), ServiceStatus::Restarting);
-
-                            tokio::time::sleep(Duration::from_millis(200)).await;
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Running,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id.clone(), ServiceStatus::Running);
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine {
-                                    id,
-                                    stream: LogStream::System,
-                                    text: "restarted".into(),
-                                },
-                            });
-                            next_id += 1;
+                            let delay = self.start_delay();
+                            if let Some(svc) = services.get_mut(&id) {
+                                svc.restart_at = None;
+                                out.status(&id, svc, ServiceStatus::Restarting);
+                                out.log(&id, LogStream::System, "restarting...");
+                                svc.ready_at = Some(Instant::now() + delay);
+                            }
                         }
 
                         EngineCommand::Kill { id } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::StatusChanged {
-                                    id: id.clone(),
-                                    status: ServiceStatus::Stopped,
-                                },
-                            });
-                            next_id += 1;
-                            statuses.insert(id.clone(), ServiceStatus::Stopped);
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine {
-                                    id,
-                                    stream: LogStream::System,
-                                    text: "process killed".into(),
-                                },
-                            });
-                            next_id += 1;
+                            if let Some(svc) = services.get_mut(&id) {
+                                stop(&mut out, &id, svc);
+                                out.log(&id, LogStream::System, "process killed");
+                            }
                         }
 
                         EngineCommand::Toggle { id } => {
-                            let st = statuses.get(&id).cloned().unwrap_or(ServiceStatus::Unknown);
-                            if matches!(
-                                st,
-                                ServiceStatus::Running
-                                    | ServiceStatus::Starting
-                                    | ServiceStatus::Restarting
-                            ) {
-                                let _ = event_tx.send(EventEnvelope {
-                                    id: next_id,
-                                    at: SystemTime::now(),
-                                    event: RuntimeEvent::StatusChanged {
-                                        id: id.clone(),
-                                        status: ServiceStatus::Stopped,
-                                    },
-                                });
-                                next_id += 1;
-                                statuses.insert(id, ServiceStatus::Stopped);
-                            } else {
-                                let _ = event_tx.send(EventEnvelope {
-                                    id: next_id,
-                                    at: SystemTime::now(),
-                                    event: RuntimeEvent::StatusChanged {
-                                        id: id.clone(),
-                                        status: ServiceStatus::Starting,
-                                    },
-                                });
-                                next_id += 1;
-                                statuses.insert(id.clone(), ServiceStatus::Starting);
-
-                                tokio::time::sleep(Duration::from_millis(250)).await;
-
-                                let _ = event_tx.send(EventEnvelope {
-                                    id: next_id,
-                                    at: SystemTime::now(),
-                                    event: RuntimeEvent::StatusChanged {
-                                        id: id.clone(),
-                                        status: ServiceStatus::Running,
-                                    },
-                                });
-                                next_id += 1;
-                                statuses.insert(id, ServiceStatus::Running);
+                            let delay = self.start_delay();
+                            if let Some(svc) = services.get_mut(&id) {
+                                if svc.is_up() {
+                                    stop(&mut out, &id, svc);
+                                } else {
+                                    svc.restart_at = None;
+                                    out.status(&id, svc, ServiceStatus::Starting);
+                                    svc.ready_at = Some(Instant::now() + delay);
+                                }
                             }
                         }
 
                         EngineCommand::ClearLogs { id } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::ClearLogs { id },
-                            });
-                            next_id += 1;
+                            out.emit(RuntimeEvent::ClearLogs { id });
                         }
 
                         EngineCommand::Exec { id, cmd } => {
                             let shown = cmd.join(" ");
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine {
-                                    id: id.clone(),
-                                    stream: LogStream::System,
-                                    text: format!("$ {shown}"),
-                                },
-                            });
-                            next_id += 1;
-
+                            out.log(&id, LogStream::System, format!("$ {shown}"));
                             tokio::time::sleep(Duration::from_millis(120)).await;
-
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine {
-                                    id,
-                                    stream: LogStream::System,
-                                    text: "ok".into(),
-                                },
-                            });
-                            next_id += 1;
+                            out.log(&id, LogStream::System, "ok");
                         }
 
                         EngineCommand::EmitLog { id, text } => {
-                            let _ = event_tx.send(EventEnvelope {
-                                id: next_id,
-                                at: SystemTime::now(),
-                                event: RuntimeEvent::LogLine { id, stream: LogStream::System, text },
-                            });
-                            next_id += 1;
+                            out.log(&id, LogStream::System, text);
+                        }
+
+                        EngineCommand::AddNote { id, text } => {
+                            out.emit(RuntimeEvent::UserNote { id, text, at: SystemTime::now() });
                         }
                     }
                 }
@@ -398,3 +578,11 @@ impl Engine for FakeEngine {
         }
     }
 }
+
+fn stop(out: &mut Emitter, id: &str, svc: &mut SimService) {
+    svc.ready_at = None;
+    svc.restart_at = None;
+    svc.started_at = None;
+    out.status(id, svc, ServiceStatus::Stopped);
+    out.health(id, svc, HealthStatus::Unknown);
+}
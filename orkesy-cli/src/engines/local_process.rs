@@ -236,6 +236,7 @@ impl Engine for LocalProcessEngine {
             &event_tx,
             RuntimeEvent::TopologyLoaded {
                 graph: graph.clone(),
+                log_options: BTreeMap::new(),
             },
         );
 
@@ -713,6 +714,10 @@ impl Engine for LocalProcessEngine {
                         EngineCommand::EmitLog { id, text } => {
                             self.emit(&event_tx, RuntimeEvent::LogLine { id, stream: LogStream::System, text });
                         }
+
+                        EngineCommand::AddNote { id, text } => {
+                            self.emit(&event_tx, RuntimeEvent::UserNote { id, text, at: SystemTime::now() });
+                        }
                     }
                 }
             }
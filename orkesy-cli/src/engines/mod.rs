@@ -2,10 +2,4 @@ mod fake;
 #[allow(dead_code)]
 mod local_process;
 
-#[cfg(feature = "docker")]
-mod docker;
-
 pub use fake::FakeEngine;
-
-#[cfg(feature = "docker")]
-pub use docker::DockerEngine;
@@ -1,18 +1,103 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use tokio::net::TcpStream;
 use tokio::sync::broadcast;
 
-use orkesy_core::model::{HealthStatus, ServiceId};
+use orkesy_core::model::{HealthStatus, ServiceId, ServiceStatus};
 use orkesy_core::reducer::{EventEnvelope, RuntimeEvent};
+use orkesy_core::state::LogStream;
 use orkesy_core::unit::{HealthCheck, Unit};
 
+pub const PORT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Consecutive check results, so a single failed probe only degrades a unit
+/// and a single success doesn't immediately clear a failure. Only
+/// `Unhealthy` probe results count toward `failure_threshold`; a `Degraded`
+/// result (an unreachable gRPC server, an HTTP status or body mismatch) is
+/// reported as degraded however often it repeats. The probe interval
+/// doubles with each consecutive failure of either kind, up to
+/// `max_interval_ms`, and is back to `interval_ms` after a success.
+#[derive(Debug)]
+struct HealthCheckState {
+    failure_threshold: u32,
+    success_threshold: u32,
+    consecutive_failures: u32,
+    consecutive_unhealthy: u32,
+    consecutive_successes: u32,
+    current: HealthStatus,
+    base_interval_ms: u64,
+    max_interval_ms: u64,
+    current_interval_ms: u64,
+}
+
+impl HealthCheckState {
+    fn new(check: &HealthCheck) -> Self {
+        Self {
+            failure_threshold: check.failure_threshold(),
+            success_threshold: check.success_threshold(),
+            consecutive_failures: 0,
+            consecutive_unhealthy: 0,
+            consecutive_successes: 0,
+            current: HealthStatus::Unknown,
+            base_interval_ms: check.interval_ms(),
+            max_interval_ms: check.max_interval_ms(),
+            current_interval_ms: check.interval_ms(),
+        }
+    }
+
+    /// Folds one probe result in and returns the status to report
+    fn observe(&mut self, result: HealthStatus) -> HealthStatus {
+        match result {
+            HealthStatus::Unknown => return self.current.clone(),
+            HealthStatus::Healthy => {
+                self.consecutive_failures = 0;
+                self.consecutive_unhealthy = 0;
+                self.consecutive_successes += 1;
+                self.current_interval_ms = self.base_interval_ms;
+                if matches!(self.current, HealthStatus::Healthy)
+                    || self.consecutive_successes >= self.success_threshold
+                {
+                    self.current = HealthStatus::Healthy;
+                }
+                return self.current.clone();
+            }
+            HealthStatus::Degraded { .. } | HealthStatus::Unhealthy { .. } => {}
+        }
+
+        self.consecutive_successes = 0;
+        self.consecutive_failures += 1;
+        self.current_interval_ms = self
+            .current_interval_ms
+            .saturating_mul(2)
+            .min(self.max_interval_ms);
+        self.current = match result {
+            HealthStatus::Unhealthy { reason } => {
+                self.consecutive_unhealthy += 1;
+                if self.consecutive_unhealthy >= self.failure_threshold {
+                    HealthStatus::Unhealthy { reason }
+                } else {
+                    HealthStatus::Degraded {
+                        reason: format!(
+                            "{} ({}/{} failures)",
+                            reason, self.consecutive_unhealthy, self.failure_threshold
+                        ),
+                    }
+                }
+            }
+            degraded => degraded,
+        };
+        self.current.clone()
+    }
+}
+
 #[allow(dead_code)]
 pub struct HealthChecker {
     service_id: ServiceId,
     check: HealthCheck,
+    host: String,
     port: Option<u16>,
     next_id: Arc<AtomicU64>,
 }
@@ -21,12 +106,14 @@ impl HealthChecker {
     pub fn new(
         service_id: ServiceId,
         check: HealthCheck,
+        host: String,
         port: Option<u16>,
         next_id: Arc<AtomicU64>,
     ) -> Self {
         Self {
             service_id,
             check,
+            host,
             port,
             next_id,
         }
@@ -34,113 +121,347 @@ impl HealthChecker {
 
     /// Run the health checker in a loop
     pub async fn run(self, event_tx: broadcast::Sender<EventEnvelope>) {
-        let interval = match &self.check {
-            HealthCheck::Http { interval_ms, .. } => Duration::from_millis(*interval_ms),
-            HealthCheck::Tcp { interval_ms, .. } => Duration::from_millis(*interval_ms),
-            HealthCheck::Exec { interval_ms, .. } => Duration::from_millis(*interval_ms),
-        };
+        let mut state = HealthCheckState::new(&self.check);
 
-        let mut ticker = tokio::time::interval(interval);
+        #[cfg(not(feature = "health-http"))]
+        {
+            let ignored = http_options_needing_client(&self.check);
+            if !ignored.is_empty() {
+                self.emit(
+                    &event_tx,
+                    RuntimeEvent::LogLine {
+                        id: self.service_id.clone(),
+                        stream: LogStream::System,
+                        text: format!(
+                            "[warn] health check {}: {} need the health-http feature; only checking a TCP connect",
+                            self.service_id,
+                            ignored.join(", ")
+                        ),
+                    },
+                );
+            }
+        }
 
         loop {
-            ticker.tick().await;
-
-            let health = match &self.check {
-                HealthCheck::Http {
-                    url, timeout_ms, ..
-                } => {
-                    self.check_http_url(url, Duration::from_millis(*timeout_ms))
-                        .await
-                }
-                HealthCheck::Tcp { port, .. } => self.check_tcp_port(*port).await,
-                HealthCheck::Exec { command, .. } => self.check_exec_str(command).await,
-            };
+            let started = Instant::now();
+            let result = probe(&self.check, &self.host).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let previous_interval_ms = state.current_interval_ms;
+            let health = state.observe(result);
 
-            let _ = event_tx.send(EventEnvelope {
-                id: self.next_id.fetch_add(1, Ordering::SeqCst),
-                at: SystemTime::now(),
-                event: RuntimeEvent::HealthChanged {
+            self.emit(
+                &event_tx,
+                RuntimeEvent::HealthChanged {
                     id: self.service_id.clone(),
                     health,
+                    latency_ms: Some(latency_ms),
                 },
-            });
+            );
+            if state.current_interval_ms > previous_interval_ms {
+                self.emit(
+                    &event_tx,
+                    RuntimeEvent::LogLine {
+                        id: self.service_id.clone(),
+                        stream: LogStream::System,
+                        text: format!(
+                            "[health] {}: backing off to {} after {} consecutive failures",
+                            self.service_id,
+                            format_interval(state.current_interval_ms),
+                            state.consecutive_failures
+                        ),
+                    },
+                );
+            }
+
+            tokio::time::sleep(Duration::from_millis(state.current_interval_ms)).await;
         }
     }
 
-    /// Check health via TCP connection to specific port
-    async fn check_tcp_port(&self, port: u16) -> HealthStatus {
-        let addr = format!("127.0.0.1:{}", port);
+    fn emit(&self, event_tx: &broadcast::Sender<EventEnvelope>, event: RuntimeEvent) {
+        let _ = event_tx.send(EventEnvelope {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            at: SystemTime::now(),
+            event,
+        });
+    }
+}
 
-        match tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(&addr)).await {
-            Ok(Ok(_)) => HealthStatus::Healthy,
-            Ok(Err(e)) => HealthStatus::Unhealthy {
-                reason: e.to_string(),
-            },
-            Err(_) => HealthStatus::Unhealthy {
-                reason: "connection timeout".into(),
-            },
-        }
+/// `8s`, `1.5s` or `500ms`
+fn format_interval(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
     }
+}
 
-    /// Check health via HTTP GET request to URL (requires reqwest feature)
-    #[cfg(feature = "health-http")]
-    async fn check_http_url(&self, url: &str, timeout: Duration) -> HealthStatus {
-        match tokio::time::timeout(timeout, reqwest::get(url)).await {
-            Ok(Ok(resp)) if resp.status().is_success() => HealthStatus::Healthy,
-            Ok(Ok(resp)) => HealthStatus::Degraded {
-                reason: format!("HTTP {}", resp.status()),
-            },
-            Ok(Err(e)) => HealthStatus::Unhealthy {
-                reason: e.to_string(),
-            },
-            Err(_) => HealthStatus::Unhealthy {
-                reason: "timeout".into(),
-            },
+/// Runs `check` once; TCP checks connect to `host`
+pub async fn probe(check: &HealthCheck, host: &str) -> HealthStatus {
+    match check {
+        HealthCheck::Http {
+            url,
+            method,
+            headers,
+            expected_status,
+            body_contains,
+            timeout_ms,
+            ..
+        } => {
+            let request = HttpRequest {
+                url,
+                method: method.as_deref().unwrap_or("GET"),
+                headers,
+            };
+            let expect = HttpExpect {
+                status: *expected_status,
+                body_contains: body_contains.as_deref(),
+            };
+            check_http_url(request, expect, Duration::from_millis(*timeout_ms)).await
         }
+        HealthCheck::Tcp { port, .. } => check_tcp_port(host, *port).await,
+        HealthCheck::Exec { command, .. } => check_exec_str(command).await,
+        HealthCheck::Grpc {
+            host,
+            port,
+            service,
+            timeout_ms,
+            ..
+        } => check_grpc(host, *port, service, Duration::from_millis(*timeout_ms)).await,
     }
+}
 
-    /// Fallback HTTP check when reqwest is not available - just try TCP connect
-    #[cfg(not(feature = "health-http"))]
-    async fn check_http_url(&self, url: &str, _timeout: Duration) -> HealthStatus {
-        // Parse URL to get host and port, then do TCP check
-        // Simple parsing for http://host:port/...
-        let url = url
-            .trim_start_matches("http://")
-            .trim_start_matches("https://");
-        let host_port = url.split('/').next().unwrap_or("127.0.0.1:80");
+/// Check health via TCP connection to specific port
+async fn check_tcp_port(host: &str, port: u16) -> HealthStatus {
+    let addr = format!("{}:{}", host, port);
 
-        match tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(host_port)).await {
-            Ok(Ok(_)) => HealthStatus::Healthy,
-            Ok(Err(e)) => HealthStatus::Unhealthy {
-                reason: e.to_string(),
-            },
-            Err(_) => HealthStatus::Unhealthy {
-                reason: "connection timeout".into(),
-            },
+    match tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(&addr)).await {
+        Ok(Ok(_)) => HealthStatus::Healthy,
+        Ok(Err(e)) => HealthStatus::Unhealthy {
+            reason: e.to_string(),
+        },
+        Err(_) => HealthStatus::Unhealthy {
+            reason: "connection timeout".into(),
+        },
+    }
+}
+
+// The TCP fallback only looks at the URL
+#[cfg_attr(not(feature = "health-http"), allow(dead_code))]
+struct HttpRequest<'a> {
+    url: &'a str,
+    method: &'a str,
+    headers: &'a BTreeMap<String, String>,
+}
+
+/// What a healthy response looks like
+#[cfg_attr(not(feature = "health-http"), allow(dead_code))]
+struct HttpExpect<'a> {
+    /// Any 2xx when unset
+    status: Option<u16>,
+    body_contains: Option<&'a str>,
+}
+
+/// Check health via HTTP request to URL (requires reqwest feature)
+#[cfg(feature = "health-http")]
+async fn check_http_url(
+    request: HttpRequest<'_>,
+    expect: HttpExpect<'_>,
+    timeout: Duration,
+) -> HealthStatus {
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let method = match reqwest::Method::from_bytes(request.method.as_bytes()) {
+        Ok(method) => method,
+        Err(_) => {
+            return HealthStatus::Unhealthy {
+                reason: format!("invalid HTTP method '{}'", request.method),
+            };
+        }
+    };
+    let mut headers = HeaderMap::new();
+    for (name, value) in request.headers {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => {
+                return HealthStatus::Unhealthy {
+                    reason: format!("invalid header '{}'", name),
+                };
+            }
         }
     }
 
-    /// Check health via command execution (shell string)
-    async fn check_exec_str(&self, command: &str) -> HealthStatus {
-        if command.is_empty() {
-            return HealthStatus::Unknown;
+    let send = async {
+        let resp = reqwest::Client::new()
+            .request(method, request.url)
+            .headers(headers)
+            .send()
+            .await?;
+        let status = resp.status();
+        // Only read the body when something is looking for it
+        let body = match expect.body_contains {
+            Some(_) => resp.text().await?,
+            None => String::new(),
+        };
+        Ok::<_, reqwest::Error>((status, body))
+    };
+
+    match tokio::time::timeout(timeout, send).await {
+        Ok(Ok((status, body))) => {
+            match expect.status {
+                Some(expected) if status.as_u16() != expected => {
+                    return HealthStatus::Degraded {
+                        reason: format!("HTTP {}, expected {}", status, expected),
+                    };
+                }
+                None if !status.is_success() => {
+                    return HealthStatus::Degraded {
+                        reason: format!("HTTP {}", status),
+                    };
+                }
+                _ => {}
+            }
+            match expect.body_contains {
+                Some(needle) if !body.contains(needle) => HealthStatus::Degraded {
+                    reason: format!("body did not contain '{}'", needle),
+                },
+                _ => HealthStatus::Healthy,
+            }
         }
+        Ok(Err(e)) => HealthStatus::Unhealthy {
+            reason: e.to_string(),
+        },
+        Err(_) => HealthStatus::Unhealthy {
+            reason: "timeout".into(),
+        },
+    }
+}
 
-        let output = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()
-            .await;
+/// HTTP check options set on `check` that the TCP fallback can't honour
+#[cfg(not(feature = "health-http"))]
+fn http_options_needing_client(check: &HealthCheck) -> Vec<&'static str> {
+    let HealthCheck::Http {
+        method,
+        headers,
+        expected_status,
+        body_contains,
+        ..
+    } = check
+    else {
+        return Vec::new();
+    };
+    let set = [
+        ("method", method.is_some()),
+        ("headers", !headers.is_empty()),
+        ("expected_status", expected_status.is_some()),
+        ("body_contains", body_contains.is_some()),
+    ];
+    set.into_iter()
+        .filter(|(_, is_set)| *is_set)
+        .map(|(name, _)| name)
+        .collect()
+}
 
-        match output {
-            Ok(o) if o.status.success() => HealthStatus::Healthy,
-            Ok(o) => HealthStatus::Unhealthy {
-                reason: format!("exit code: {:?}", o.status.code()),
-            },
-            Err(e) => HealthStatus::Unhealthy {
+/// Fallback HTTP check when reqwest is not available - just try TCP connect
+#[cfg(not(feature = "health-http"))]
+async fn check_http_url(
+    request: HttpRequest<'_>,
+    _expect: HttpExpect<'_>,
+    _timeout: Duration,
+) -> HealthStatus {
+    // Parse URL to get host and port, then do TCP check
+    // Simple parsing for http://host:port/...
+    let url = request
+        .url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://");
+    let host_port = url.split('/').next().unwrap_or("127.0.0.1:80");
+
+    match tokio::time::timeout(Duration::from_secs(2), TcpStream::connect(host_port)).await {
+        Ok(Ok(_)) => HealthStatus::Healthy,
+        Ok(Err(e)) => HealthStatus::Unhealthy {
+            reason: e.to_string(),
+        },
+        Err(_) => HealthStatus::Unhealthy {
+            reason: "connection timeout".into(),
+        },
+    }
+}
+
+/// Check health via `grpc.health.v1.Health/Check` (requires tonic feature).
+/// A server that can't be reached only degrades the unit; one that answers
+/// anything but SERVING is unhealthy.
+#[cfg(feature = "health-grpc")]
+async fn check_grpc(host: &str, port: u16, service: &str, timeout: Duration) -> HealthStatus {
+    use tonic::transport::Endpoint;
+    use tonic_health::pb::health_check_response::ServingStatus;
+    use tonic_health::pb::{HealthCheckRequest, health_client::HealthClient};
+
+    let endpoint = match Endpoint::from_shared(format!("http://{}:{}", host, port)) {
+        Ok(endpoint) => endpoint.connect_timeout(timeout).timeout(timeout),
+        Err(e) => {
+            return HealthStatus::Unhealthy {
                 reason: e.to_string(),
-            },
+            };
+        }
+    };
+    let channel = match endpoint.connect().await {
+        Ok(channel) => channel,
+        Err(e) => {
+            return HealthStatus::Degraded {
+                reason: format!("gRPC connect failed: {}", e),
+            };
         }
+    };
+
+    let request = HealthCheckRequest {
+        service: service.to_string(),
+    };
+    match HealthClient::new(channel).check(request).await {
+        Ok(resp) => match resp.into_inner().status() {
+            ServingStatus::Serving => HealthStatus::Healthy,
+            status => HealthStatus::Unhealthy {
+                reason: status.as_str_name().to_string(),
+            },
+        },
+        Err(status) => HealthStatus::Unhealthy {
+            reason: format!("gRPC {:?}: {}", status.code(), status.message()),
+        },
+    }
+}
+
+/// Fallback gRPC check when tonic is not available - just try TCP connect
+#[cfg(not(feature = "health-grpc"))]
+async fn check_grpc(host: &str, port: u16, _service: &str, _timeout: Duration) -> HealthStatus {
+    check_tcp_port(host, port).await
+}
+
+/// Check health via command execution (shell string)
+async fn check_exec_str(command: &str) -> HealthStatus {
+    if command.is_empty() {
+        return HealthStatus::Unknown;
+    }
+
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .await;
+
+    match output {
+        Ok(o) if o.status.success() => HealthStatus::Healthy,
+        Ok(o) => HealthStatus::Unhealthy {
+            reason: format!("exit code: {:?}", o.status.code()),
+        },
+        Err(e) => HealthStatus::Unhealthy {
+            reason: e.to_string(),
+        },
     }
 }
 
@@ -151,8 +472,13 @@ pub fn spawn_health_checkers(
 ) {
     for unit in units {
         if let Some(check) = &unit.health {
-            let checker =
-                HealthChecker::new(unit.id.clone(), check.clone(), unit.port, next_id.clone());
+            let checker = HealthChecker::new(
+                unit.id.clone(),
+                check.clone(),
+                unit.host().to_string(),
+                unit.port,
+                next_id.clone(),
+            );
             let tx = event_tx.clone();
             tokio::spawn(async move {
                 checker.run(tx).await;
@@ -160,3 +486,311 @@ pub fn spawn_health_checkers(
         }
     }
 }
+
+/// Polls a unit's declared port while it is starting and emits
+/// `PortReady` on the first successful connection.
+pub struct PortPoller {
+    service_id: ServiceId,
+    host: String,
+    port: u16,
+    next_id: Arc<AtomicU64>,
+}
+
+impl PortPoller {
+    pub fn new(service_id: ServiceId, host: String, port: u16, next_id: Arc<AtomicU64>) -> Self {
+        Self {
+            service_id,
+            host,
+            port,
+            next_id,
+        }
+    }
+
+    pub async fn run(self, event_tx: broadcast::Sender<EventEnvelope>) {
+        let mut rx = event_tx.subscribe();
+        let mut starting = false;
+        let addr = format!("{}:{}", self.host, self.port);
+
+        loop {
+            if !starting {
+                match rx.recv().await {
+                    Ok(env) => starting = self.is_starting(&env).unwrap_or(false),
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+                continue;
+            }
+
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(env) => {
+                        if let Some(still_starting) = self.is_starting(&env) {
+                            starting = still_starting;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                _ = tokio::time::sleep(PORT_POLL_INTERVAL) => {
+                    let connect = TcpStream::connect(&addr);
+                    if let Ok(Ok(_)) = tokio::time::timeout(PORT_POLL_INTERVAL, connect).await {
+                        let _ = event_tx.send(EventEnvelope {
+                            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+                            at: SystemTime::now(),
+                            event: RuntimeEvent::PortReady {
+                                id: self.service_id.clone(),
+                                port: self.port,
+                            },
+                        });
+                        starting = false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// `Some(true)` when the event moves this unit into `Starting`,
+    /// `Some(false)` for any other status change of this unit.
+    fn is_starting(&self, env: &EventEnvelope) -> Option<bool> {
+        match &env.event {
+            RuntimeEvent::StatusChanged { id, status } if *id == self.service_id => {
+                Some(*status == ServiceStatus::Starting)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub fn spawn_port_pollers(
+    units: &[Unit],
+    event_tx: broadcast::Sender<EventEnvelope>,
+    next_id: Arc<AtomicU64>,
+) {
+    for unit in units.iter().filter(|u| u.waits_for_port()) {
+        let Some(port) = unit.port else {
+            continue;
+        };
+        let poller = PortPoller::new(
+            unit.id.clone(),
+            unit.host().to_string(),
+            port,
+            next_id.clone(),
+        );
+        let tx = event_tx.clone();
+        tokio::spawn(async move {
+            poller.run(tx).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_state(yaml: &str) -> HealthCheckState {
+        let check: HealthCheck = serde_yaml::from_str(yaml).unwrap();
+        HealthCheckState::new(&check)
+    }
+
+    fn degraded(reason: &str) -> HealthStatus {
+        HealthStatus::Degraded {
+            reason: reason.to_string(),
+        }
+    }
+
+    fn unhealthy(reason: &str) -> HealthStatus {
+        HealthStatus::Unhealthy {
+            reason: reason.to_string(),
+        }
+    }
+
+    fn is_degraded(status: &HealthStatus, expected: &str) -> bool {
+        matches!(status, HealthStatus::Degraded { reason } if reason == expected)
+    }
+
+    fn is_unhealthy(status: &HealthStatus, expected: &str) -> bool {
+        matches!(status, HealthStatus::Unhealthy { reason } if reason == expected)
+    }
+
+    #[test]
+    fn test_degraded_results_never_escalate() {
+        let mut state = make_state("type: tcp\nfailure_threshold: 3");
+
+        for _ in 0..10 {
+            let health = state.observe(degraded("gRPC connect failed"));
+            assert!(is_degraded(&health, "gRPC connect failed"), "{:?}", health);
+        }
+        assert_eq!(state.consecutive_failures, 10);
+    }
+
+    #[test]
+    fn test_unhealthy_results_escalate_at_threshold() {
+        let mut state = make_state("type: tcp\nfailure_threshold: 3");
+
+        let health = state.observe(unhealthy("NOT_SERVING"));
+        assert!(
+            is_degraded(&health, "NOT_SERVING (1/3 failures)"),
+            "{:?}",
+            health
+        );
+        let health = state.observe(unhealthy("NOT_SERVING"));
+        assert!(
+            is_degraded(&health, "NOT_SERVING (2/3 failures)"),
+            "{:?}",
+            health
+        );
+        let health = state.observe(unhealthy("NOT_SERVING"));
+        assert!(is_unhealthy(&health, "NOT_SERVING"), "{:?}", health);
+    }
+
+    #[test]
+    fn test_degraded_results_dont_count_toward_threshold() {
+        let mut state = make_state("type: tcp\nfailure_threshold: 2");
+
+        state.observe(unhealthy("refused"));
+        let health = state.observe(degraded("HTTP 503"));
+        assert!(is_degraded(&health, "HTTP 503"), "{:?}", health);
+        let health = state.observe(unhealthy("refused"));
+        assert!(is_unhealthy(&health, "refused"), "{:?}", health);
+    }
+
+    #[test]
+    fn test_success_threshold() {
+        let mut state = make_state("type: tcp\nsuccess_threshold: 2");
+
+        state.observe(unhealthy("refused"));
+        let health = state.observe(HealthStatus::Healthy);
+        assert!(
+            is_degraded(&health, "refused (1/3 failures)"),
+            "{:?}",
+            health
+        );
+        let health = state.observe(HealthStatus::Healthy);
+        assert!(matches!(health, HealthStatus::Healthy), "{:?}", health);
+        // Once healthy, a probe that can't tell keeps the last status
+        let health = state.observe(HealthStatus::Unknown);
+        assert!(matches!(health, HealthStatus::Healthy), "{:?}", health);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut state = make_state("type: tcp\ninterval_ms: 1000\nmax_interval_ms: 5000");
+
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            state.observe(unhealthy("refused"));
+            intervals.push(state.current_interval_ms);
+        }
+        assert_eq!(intervals, [2000, 4000, 5000, 5000]);
+
+        // Degraded results back off too
+        state.observe(HealthStatus::Healthy);
+        assert_eq!(state.current_interval_ms, 1000);
+        state.observe(degraded("HTTP 503"));
+        assert_eq!(state.current_interval_ms, 2000);
+    }
+
+    #[test]
+    fn test_format_interval() {
+        assert_eq!(format_interval(500), "500ms");
+        assert_eq!(format_interval(8000), "8s");
+        assert_eq!(format_interval(1500), "1.5s");
+    }
+
+    /// Answers every connection with `response` and returns its URL
+    #[cfg(feature = "health-http")]
+    async fn serve_http(response: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[cfg(feature = "health-http")]
+    #[tokio::test]
+    async fn test_http_mismatch_is_degraded() {
+        let url =
+            serve_http("HTTP/1.1 200 OK\r\ncontent-length: 8\r\nconnection: close\r\n\r\nstarting")
+                .await;
+        let check: HealthCheck = serde_yaml::from_str(&format!(
+            "type: http\nurl: {}\nbody_contains: ok\nfailure_threshold: 2",
+            url
+        ))
+        .unwrap();
+        let mut state = HealthCheckState::new(&check);
+
+        for _ in 0..3 {
+            let health = state.observe(probe(&check, "127.0.0.1").await);
+            assert!(
+                is_degraded(&health, "body did not contain 'ok'"),
+                "{:?}",
+                health
+            );
+        }
+
+        let check: HealthCheck = serde_yaml::from_str(&format!(
+            "type: http\nurl: {}\nexpected_status: 204\nfailure_threshold: 2",
+            url
+        ))
+        .unwrap();
+        let health = probe(&check, "127.0.0.1").await;
+        assert!(
+            is_degraded(&health, "HTTP 200 OK, expected 204"),
+            "{:?}",
+            health
+        );
+    }
+
+    #[cfg(feature = "health-grpc")]
+    #[tokio::test]
+    async fn test_grpc_connect_failure_is_degraded() {
+        // Bind and drop a listener to get a port nothing listens on
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let health = check_grpc("127.0.0.1", port, "", Duration::from_secs(2)).await;
+        assert!(
+            matches!(&health, HealthStatus::Degraded { reason } if reason.starts_with("gRPC connect failed")),
+            "{:?}",
+            health
+        );
+    }
+
+    #[cfg(feature = "health-grpc")]
+    #[tokio::test]
+    async fn test_grpc_not_serving_is_unhealthy() {
+        use tonic::transport::Server;
+        use tonic::transport::server::TcpIncoming;
+        use tonic_health::ServingStatus;
+
+        let (reporter, service) = tonic_health::server::health_reporter();
+        reporter
+            .set_service_status("api", ServingStatus::NotServing)
+            .await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpIncoming::from(listener)),
+        );
+
+        let timeout = Duration::from_secs(2);
+        let health = check_grpc("127.0.0.1", port, "api", timeout).await;
+        assert!(is_unhealthy(&health, "NOT_SERVING"), "{:?}", health);
+        reporter
+            .set_service_status("api", ServingStatus::Serving)
+            .await;
+        let health = check_grpc("127.0.0.1", port, "api", timeout).await;
+        assert!(matches!(health, HealthStatus::Healthy), "{:?}", health);
+    }
+}
@@ -1,5 +1,6 @@
 // Suppress clippy warnings that require extensive refactoring
 #![allow(clippy::collapsible_if)]
+#![allow(clippy::collapsible_match)]
 #![allow(clippy::unnecessary_unwrap)]
 #![allow(clippy::manual_clamp)]
 #![allow(clippy::match_single_binding)]
@@ -8,29 +9,38 @@
 
 mod adapters;
 mod commands;
+#[cfg(unix)]
+mod daemon;
 mod detectors;
 mod engines;
 mod health;
+mod metrics_server;
+mod platform;
+mod profile;
 mod runner;
 mod sampler;
 mod ui;
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, Event as CEvent, KeyCode, KeyEvent, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode, KeyEvent,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
-    Terminal,
+    Frame, Terminal,
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span, Text},
@@ -40,31 +50,73 @@ use ratatui::{
     },
 };
 
-use tokio::sync::{RwLock, broadcast, mpsc};
+use tokio::sync::{RwLock, broadcast, mpsc, watch};
 
-use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, LogStream};
-use orkesy_core::config::OrkesyConfig;
-use orkesy_core::log_filter::{LogFilterMode, detect_level};
+use orkesy_core::adapter::{Adapter, AdapterCommand, AdapterEvent, CompareOp, LogStream};
+use orkesy_core::command::{CommandRun, RunId, RunStatus};
+use orkesy_core::config::{DEFAULT_TIMESTAMP_FORMAT, OrkesyConfig, TimestampTz, remove_service};
+use orkesy_core::log_filter::{LogFilterMode, LogLevel, detect_level};
+use orkesy_core::metrics::{MetricsState, Series};
 use orkesy_core::model::*;
 use orkesy_core::reducer::*;
 use orkesy_core::state::*;
-use orkesy_core::unit::{Unit, UnitStatus as AdapterUnitStatus};
+use orkesy_core::unit::{METRIC_MEMORY_BYTES, Unit, UnitStatus as AdapterUnitStatus};
 
 use adapters::ProcessAdapter;
 use engines::FakeEngine;
+use sampler::SamplerConfig;
 use ui::styles;
+use ui::toast::{Toast, ToastLevel};
 
 /// Format a SystemTime as HH:MM:SS for log display
-fn format_timestamp(time: SystemTime) -> String {
-    match time.duration_since(SystemTime::UNIX_EPOCH) {
-        Ok(duration) => {
-            let secs = duration.as_secs();
-            let hours = (secs / 3600) % 24;
-            let minutes = (secs / 60) % 60;
-            let seconds = secs % 60;
-            format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+fn format_timestamp_tz(time: SystemTime, tz: &TimestampTz, format: &str) -> String {
+    use chrono::{DateTime, FixedOffset, Local, Utc};
+    use std::fmt::Write;
+
+    let utc: DateTime<Utc> = time.into();
+    let mut out = String::new();
+    // An invalid format string makes chrono return fmt::Error; fall back
+    let written = match tz {
+        TimestampTz::Local => write!(out, "{}", utc.with_timezone(&Local).format(format)),
+        TimestampTz::Utc => write!(out, "{}", utc.format(format)),
+        TimestampTz::Fixed(secs) => match FixedOffset::east_opt(*secs) {
+            Some(offset) => write!(out, "{}", utc.with_timezone(&offset).format(format)),
+            None => write!(out, "{}", utc.format(format)),
+        },
+    };
+    if written.is_err() && format != DEFAULT_TIMESTAMP_FORMAT {
+        return format_timestamp_tz(time, tz, DEFAULT_TIMESTAMP_FORMAT);
+    }
+    out
+}
+
+/// Timezone and format for log timestamps, from `timestamp_tz` and
+/// `timestamp_format` in the config
+#[derive(Clone, Debug)]
+struct LogTimestamps {
+    tz: TimestampTz,
+    format: String,
+}
+
+impl Default for LogTimestamps {
+    fn default() -> Self {
+        Self {
+            tz: TimestampTz::default(),
+            format: DEFAULT_TIMESTAMP_FORMAT.to_string(),
+        }
+    }
+}
+
+impl LogTimestamps {
+    fn from_config(config: &OrkesyConfig) -> Self {
+        Self {
+            tz: config.timestamp_tz.clone(),
+            format: config.timestamp_format().to_string(),
         }
-        Err(_) => "??:??:??".to_string(),
+    }
+
+    fn format(&self, time: SystemTime) -> String {
+        format_timestamp_tz(time, &self.tz, &self.format)
     }
 }
 
@@ -73,12 +125,196 @@ fn format_timestamp(time: SystemTime) -> String {
 struct DisplayLogLine {
     timestamp: Option<SystemTime>,
     text: String,
+    /// Render ANSI color codes as styles (stripped otherwise)
+    render_ansi: bool,
+    /// Times the line arrived in a row, shown as `(×N)` when more than once
+    repeat_count: u32,
+}
+
+impl DisplayLogLine {
+    /// Text with escape sequences removed, used for search and highlighting
+    fn plain_text(&self) -> String {
+        ui::ansi::strip_ansi(&self.text)
+    }
+
+    /// Spans for the line on top of `base`; the line's own ANSI colors win
+    fn spans(&self, base: Style) -> Vec<Span<'static>> {
+        let (body, truncated) = match self.text.strip_suffix(TRUNCATED_MARKER) {
+            Some(body) => (body, true),
+            None => (self.text.as_str(), false),
+        };
+        let mut spans = if self.render_ansi {
+            ui::ansi::ansi_to_spans(body, base)
+        } else {
+            vec![Span::styled(ui::ansi::strip_ansi(body), base)]
+        };
+        if truncated {
+            spans.push(Span::styled(TRUNCATED_MARKER, styles::text_muted()));
+        }
+        if let Some(repeats) = self.repeat_suffix() {
+            spans.push(repeats);
+        }
+        spans
+    }
+
+    /// ` (×N)` for a line that arrived N times in a row
+    fn repeat_suffix(&self) -> Option<Span<'static>> {
+        (self.repeat_count > 1)
+            .then(|| Span::styled(format!(" (×{})", self.repeat_count), styles::text_muted()))
+    }
+
+    /// Spans for a structured JSON log line (`J` in the Logs view): time,
+    /// level and message first, then the remaining fields as `key=value`.
+    /// `None` for lines that aren't a JSON object, or are too long to parse
+    /// on every frame.
+    fn json_spans(&self, timestamps: &LogTimestamps) -> Option<Vec<Span<'static>>> {
+        let plain = self.plain_text();
+        let start = plain.find('{')?;
+        if plain.len() > MAX_JSON_LOG_LINE || !plain.trim_end().ends_with('}') {
+            return None;
+        }
+        let (prefix, json) = plain.split_at(start);
+        let serde_json::Value::Object(mut fields) = serde_json::from_str(json).ok()? else {
+            return None;
+        };
+
+        let mut take = |keys: &[&str]| keys.iter().find_map(|k| fields.remove(*k));
+        let ts = take(&["ts", "time", "timestamp"]);
+        let level = take(&["level", "lvl", "severity"]);
+        let msg = take(&["msg", "message"]);
+
+        let as_text = |v: &serde_json::Value| match v {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        let mut spans = Vec::new();
+        if !prefix.is_empty() {
+            spans.push(Span::raw(prefix.to_string()));
+        }
+        if let Some(ts) = ts {
+            // Numeric times are epoch seconds, or milliseconds when that large
+            let text = match ts.as_f64() {
+                Some(n) => {
+                    let secs = if n > 1e11 { n / 1000.0 } else { n };
+                    let at = SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs.max(0.0));
+                    timestamps.format(at)
+                }
+                None => as_text(&ts),
+            };
+            spans.push(Span::styled(format!("{} ", text), styles::text_muted()));
+        }
+        if let Some(level) = level {
+            let level = as_text(&level).to_uppercase();
+            let style = match detect_level(&level) {
+                LogLevel::Error => Style::default().fg(Color::Red),
+                LogLevel::Warn => Style::default().fg(Color::Yellow),
+                LogLevel::Debug => Style::default().fg(Color::DarkGray),
+                LogLevel::Info => Style::default().fg(Color::Green),
+            };
+            spans.push(Span::styled(
+                format!("{:5} ", level),
+                style.add_modifier(Modifier::BOLD),
+            ));
+        }
+        if let Some(msg) = msg {
+            spans.push(Span::styled(as_text(&msg), styles::text()));
+        }
+        for (key, value) in fields {
+            spans.push(Span::styled(
+                format!(" {}=", key),
+                Style::default().fg(Color::Cyan),
+            ));
+            spans.push(Span::styled(as_text(&value), styles::text_dim()));
+        }
+        spans.extend(self.repeat_suffix());
+        Some(spans)
+    }
+}
+
+/// `render_ansi` defaults to auto-detect, which only differs from plain
+/// text for lines that actually contain escape sequences.
+fn renders_ansi(units: &BTreeMap<String, Unit>, id: &str) -> bool {
+    units.get(id).and_then(|u| u.render_ansi).unwrap_or(true)
+}
+
+/// The lines shown in the Logs view as plain text, each prefixed with an
+/// ISO-8601 timestamp, for writing to a file with `W`
+fn export_log_lines(
+    snapshot: &RuntimeState,
+    sid: Option<&str>,
+    left_mode: LeftMode,
+    selected_run: usize,
+    logs: &LogsUiState,
+) -> Vec<String> {
+    let stream_prefix = |stream: &LogStream| match stream {
+        LogStream::Stderr => "[stderr] ",
+        LogStream::System => "[system] ",
+        LogStream::Stdout => "",
+    };
+
+    let lines: Vec<(SystemTime, String, u32)> = if logs.paused {
+        logs.frozen_logs
+            .iter()
+            .filter_map(|l| Some((l.timestamp?, l.text.clone(), l.repeat_count)))
+            .collect()
+    } else if left_mode == LeftMode::Runs {
+        snapshot
+            .run_order
+            .get(selected_run)
+            .and_then(|run_id| snapshot.logs.per_run.get(run_id))
+            .map(|l| {
+                l.iter()
+                    .map(|x| {
+                        let text = format!("{}{}", stream_prefix(&x.stream), x.text);
+                        (x.at, text, x.repeat_count)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else if sid == Some("all") {
+        snapshot
+            .logs
+            .merged
+            .iter()
+            .map(|x| {
+                let text = format!("{:8}| {}{}", x.service_id, stream_prefix(&x.stream), x.text);
+                (x.at, text, x.repeat_count)
+            })
+            .collect()
+    } else {
+        sid.and_then(|id| snapshot.logs.per_service.get(id))
+            .map(|l| {
+                l.iter()
+                    .map(|x| (x.at, x.text.clone(), x.repeat_count))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    lines
+        .into_iter()
+        .map(|(at, text, repeats)| (at, ui::ansi::strip_ansi(&text), repeats))
+        .filter(|(_, text, _)| logs.log_filter.matches(detect_level(text)))
+        .map(|(at, text, repeats)| {
+            let at = chrono::DateTime::<chrono::Local>::from(at)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+            match repeats {
+                1 => format!("{} {}", at, text),
+                n => format!("{} {} (×{})", at, text, n),
+            }
+        })
+        .collect()
 }
 
 #[derive(Parser)]
 #[command(name = "orkesy")]
 #[command(about = "Manage and orchestrate local services", long_about = None)]
 struct Cli {
+    /// Config profile whose overrides are applied (default: $ORKESY_PROFILE)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -89,24 +325,71 @@ enum Commands {
         #[arg(short, long)]
         yes: bool,
     },
-    Doctor,
-    Tui,
+    Doctor {
+        /// Timeout for network reachability checks
+        #[arg(long, default_value_t = 5)]
+        timeout_secs: u64,
+    },
+    Tui {
+        /// Serve unit metrics for Prometheus at http://127.0.0.1:<port>/metrics
+        #[arg(long)]
+        metrics_port: Option<u16>,
+    },
+    /// Run the units without the TUI, controlled over a JSON-RPC Unix socket
+    #[cfg(unix)]
+    Daemon {
+        /// Socket path (default `~/.run/orkesy-<project>.sock`)
+        #[arg(long)]
+        socket: Option<PathBuf>,
+        /// Port for the Prometheus `/metrics` endpoint
+        #[arg(long, default_value_t = metrics_server::DEFAULT_METRICS_PORT)]
+        metrics_port: u16,
+    },
     Up {
         #[arg(required = true)]
         units: Vec<String>,
+        /// Print the start order, readiness gates and port checks without starting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// With --dry-run, exit non-zero if a unit's port is already in use
+        #[arg(long, requires = "dry_run")]
+        fail_on_port_conflict: bool,
     },
     Down {
         #[arg(required = true)]
         units: Vec<String>,
+        /// Print the shutdown order without stopping anything
+        #[arg(long)]
+        dry_run: bool,
     },
     Restart {
         #[arg(required = true)]
         units: Vec<String>,
+        /// Print the stop-then-start sequence without restarting anything
+        #[arg(long)]
+        dry_run: bool,
     },
     Logs {
         unit: String,
         #[arg(short, long, default_value = "true")]
         follow: bool,
+        /// Only show lines matching this regex (repeatable, OR by default)
+        #[arg(long, value_name = "PATTERN")]
+        grep: Vec<String>,
+        /// Require every --grep pattern to match
+        #[arg(long)]
+        and: bool,
+        /// Show only lines that do NOT match
+        #[arg(long)]
+        invert: bool,
+        /// Only show lines from this stream
+        #[arg(long, value_parser = ["stdout", "stderr", "system"])]
+        stream: Option<String>,
+        /// Output template, e.g. `{timestamp} [{unit}] {stream}: {text}`
+        /// (fields: timestamp, timestamp_ms, unit, stream, level, text, text_stripped),
+        /// or `jsonl` for one JSON object per line
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
     },
     Install {
         units: Vec<String>,
@@ -116,6 +399,139 @@ enum Commands {
         #[arg(last = true, required = true)]
         cmd: Vec<String>,
     },
+    /// Start a temporary copy of a unit with env overrides (not saved to the config)
+    Fork {
+        from: String,
+        to: String,
+        /// `KEY=VALUE` env overrides
+        args: Vec<String>,
+    },
+    /// Run a detected project command (e.g. `cargo:test`)
+    Run {
+        #[arg(required_unless_present = "list")]
+        command_id: Option<String>,
+        /// Extra arguments appended to the command, after `--`
+        #[arg(last = true)]
+        args: Vec<String>,
+        /// List the detected commands instead of running one
+        #[arg(long, conflicts_with = "command_id")]
+        list: bool,
+        /// Make sure the command's `depends_on_ready` services are running first
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        check_deps: bool,
+    },
+    Env {
+        unit: String,
+        /// Prefix lines with `export ` for shell sourcing
+        #[arg(long)]
+        export: bool,
+        #[arg(long, value_enum, default_value = "env")]
+        format: commands::EnvFormat,
+        #[arg(long, alias = "show-secrets")]
+        reveal_secrets: bool,
+    },
+    /// Write the docker_image units as a docker-compose.yml
+    Export {
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Convert another process manager's config to orkesy.yml
+    Import {
+        #[arg(value_enum)]
+        source: commands::ImportSource,
+        /// File to read instead of the source's usual one
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+        #[arg(short, long, default_value = "orkesy.yml")]
+        output: PathBuf,
+        /// Overwrite the output file if it exists
+        #[arg(short, long)]
+        yes: bool,
+    },
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommand,
+    },
+    /// Check the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Edit the units defined in the config
+    Service {
+        #[command(subcommand)]
+        command: ServiceCommand,
+    },
+    /// Show the status of configured units
+    Ps {
+        /// Keep refreshing the table in place (Ctrl+C to exit)
+        #[arg(long)]
+        watch: bool,
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+        /// Disable ANSI colors and cursor movement
+        #[arg(long)]
+        no_color: bool,
+    },
+    /// Print each unit's status, pid and uptime (exits 1 if any unit is errored)
+    Status {
+        #[arg(long, value_enum, default_value = "json")]
+        format: commands::StatusFormat,
+    },
+    /// Block until a unit reaches a status, e.g. `orkesy wait postgres running`
+    Wait {
+        unit: String,
+        /// running, starting, stopped, errored, ... (case-insensitive)
+        status: String,
+        #[arg(long, default_value_t = 60)]
+        timeout_secs: u64,
+    },
+    Version {
+        #[arg(long)]
+        check_update: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetricsCommand {
+    /// Print unit metrics to stdout
+    Export {
+        #[arg(long, value_enum, default_value = "table")]
+        format: commands::MetricsFormat,
+        /// Print a single snapshot and exit
+        #[arg(long)]
+        once: bool,
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Report dependency cycles, missing cwd directories, executables not on
+    /// $PATH and missing env files; exits 1 if any are found
+    Validate {
+        /// Print the issues as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServiceCommand {
+    /// Remove a unit and every dependency edge referencing it from the config
+    #[command(name = "rm")]
+    Remove {
+        unit: String,
+        /// Stop the unit first if it is running (through the daemon if one runs
+        /// it); the config is left unchanged if it can't be stopped
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        stop_first: bool,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 fn demo_graph() -> RuntimeGraph {
@@ -161,12 +577,19 @@ fn demo_graph() -> RuntimeGraph {
 
 fn try_load_config() -> Option<(PathBuf, OrkesyConfig)> {
     let cwd = std::env::current_dir().ok()?;
-    let names = ["orkesy.yml", "orkesy.yaml", ".orkesy.yml", ".orkesy.yaml"];
+    let names = [
+        "orkesy.yml",
+        "orkesy.yaml",
+        "orkesy.toml",
+        ".orkesy.yml",
+        ".orkesy.yaml",
+        ".orkesy.toml",
+    ];
 
     for name in &names {
         let path = cwd.join(name);
         if path.exists() {
-            match OrkesyConfig::load(&path) {
+            match profile::load_config(&path) {
                 Ok(config) => return Some((path, config)),
                 Err(e) => {
                     eprintln!("Error loading {}: {}", path.display(), e);
@@ -175,15 +598,300 @@ fn try_load_config() -> Option<(PathBuf, OrkesyConfig)> {
             }
         }
     }
+
+    let procfile = cwd.join("Procfile");
+    if procfile.exists() {
+        match OrkesyConfig::from_procfile(&procfile) {
+            Ok(config) => return Some((procfile, config)),
+            Err(e) => eprintln!("Error loading {}: {}", procfile.display(), e),
+        }
+    }
     None
 }
 
+/// A temporary copy of a configured unit started by `orkesy fork`; it is
+/// never written to the config and leaves the graph when it exits
+struct UnitFork {
+    from: String,
+    to: String,
+    env: BTreeMap<String, String>,
+}
+
+impl UnitFork {
+    /// `overrides` are `KEY=VALUE` env overrides
+    fn new(from: String, to: String, overrides: &[String]) -> Result<Self, String> {
+        let env = overrides
+            .iter()
+            .map(|arg| match arg.split_once('=') {
+                Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+                _ => Err(format!("expected KEY=VALUE, got '{}'", arg)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { from, to, env })
+    }
+
+    /// Adds the fork to `units`, with copies of `from`'s outgoing edges. A
+    /// `PORT` override also becomes the fork's port.
+    fn apply(
+        &self,
+        units: &mut Vec<Unit>,
+        edges: &mut Vec<orkesy_core::unit::UnitEdge>,
+    ) -> Result<(), String> {
+        if units.iter().any(|u| u.id == self.to) {
+            return Err(format!("unit '{}' already exists", self.to));
+        }
+        let mut unit = units
+            .iter()
+            .find(|u| u.id == self.from)
+            .cloned()
+            .ok_or_else(|| format!("unknown unit: {}", self.from))?;
+
+        unit.id = self.to.clone();
+        unit.name = None;
+        for (key, value) in &self.env {
+            unit.inherited_env.remove(key);
+            unit.env.insert(key.clone(), value.clone());
+        }
+        if let Some(port) = self.env.get("PORT").and_then(|p| p.parse().ok()) {
+            unit.port = Some(port);
+        }
+        // The PID file belongs to the original
+        unit.pid_file = None;
+        unit.autostart = true;
+
+        let copied: Vec<_> = edges
+            .iter()
+            .filter(|e| e.from == self.from)
+            .map(|e| orkesy_core::unit::UnitEdge {
+                from: self.to.clone(),
+                ..e.clone()
+            })
+            .collect();
+        edges.extend(copied);
+        units.push(unit);
+        Ok(())
+    }
+}
+
+/// Spike points from `Series::anomaly_points`, drawn in red over a chart's line
+fn anomaly_dataset(points: &[(f64, f64)]) -> Dataset<'_> {
+    Dataset::default()
+        .marker(symbols::Marker::Dot)
+        .graph_type(GraphType::Scatter)
+        .style(Style::default().fg(Color::Red))
+        .data(points)
+}
+
+/// `Marker::Dot` draws `•`; swap the red ones from `anomaly_dataset` for `●`
+fn fill_anomaly_markers(buf: &mut Buffer, area: Rect) {
+    for pos in area.positions() {
+        let cell = &mut buf[pos];
+        if cell.symbol() == "•" && cell.fg == Color::Red {
+            cell.set_symbol("●");
+        }
+    }
+}
+
+/// Line chart over `x_bounds` with `anomalies` as red dots, from 0 to `y_max`
+fn line_chart<'a>(
+    title: String,
+    data: &'a [(f64, f64)],
+    anomalies: &'a [(f64, f64)],
+    color: Color,
+    x_bounds: [f64; 2],
+    y_max: f64,
+) -> Chart<'a> {
+    let dim = styles::text_dim();
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data);
+    Chart::new(vec![dataset, anomaly_dataset(anomalies)])
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(styles::border_subtle()),
+        )
+        .x_axis(Axis::default().bounds(x_bounds).labels(vec![
+            Span::styled(format!("-{:.0}s", x_bounds[1] - x_bounds[0]), dim),
+            Span::styled("now", dim),
+        ]))
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, y_max])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{:.0}", y_max))]),
+        )
+}
+
+/// Disk read and write rates in KB/s as two lines against `x_axis`
+fn io_chart<'a>(
+    title: String,
+    read: &'a [(f64, f64)],
+    write: &'a [(f64, f64)],
+    border: Style,
+    x_axis: Axis<'a>,
+) -> Chart<'a> {
+    let io_max = read
+        .iter()
+        .chain(write)
+        .map(|(_, v)| *v)
+        .fold(10.0_f64, f64::max);
+    let line = |name: &'static str, color: Color, data: &'a [(f64, f64)]| {
+        Dataset::default()
+            .name(name)
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(data)
+    };
+    Chart::new(vec![
+        line("read", Color::Blue, read),
+        line("write", Color::LightRed, write),
+    ])
+    .block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border),
+    )
+    .x_axis(x_axis)
+    .y_axis(
+        Axis::default()
+            .bounds([0.0, io_max])
+            .labels(vec![Span::raw("0"), Span::raw(format!("{:.0}", io_max))]),
+    )
+}
+
+/// Full-screen charts for one unit over the chart window: CPU, memory, log
+/// rate and disk I/O
+fn draw_metrics_drill(
+    f: &mut Frame,
+    area: Rect,
+    id: &str,
+    metrics: &MetricsState,
+    window: ChartWindow,
+) {
+    let windowed = |series: &BTreeMap<ServiceId, Series>| {
+        series
+            .get(id)
+            .map(|s| s.window(window.secs()))
+            .unwrap_or_default()
+    };
+    let cpu_data = windowed(&metrics.svc_cpu);
+    let mem_data = windowed(&metrics.svc_mem);
+    let log_data = windowed(&metrics.logs_rate);
+    let io_read_data = windowed(&metrics.svc_io_read);
+    let io_write_data = windowed(&metrics.svc_io_write);
+    let t_max = [&cpu_data, &mem_data, &log_data, &io_read_data]
+        .iter()
+        .filter_map(|data| data.last().map(|(t, _)| *t))
+        .fold(window.secs(), f64::max);
+    let x_bounds = [t_max - window.secs(), t_max];
+    let anomalies = |series: &BTreeMap<ServiceId, Series>| {
+        let mut points = series
+            .get(id)
+            .map(|s| s.anomaly_points())
+            .unwrap_or_default();
+        points.retain(|(t, _)| *t >= x_bounds[0]);
+        points
+    };
+    let cpu_anomalies = anomalies(&metrics.svc_cpu);
+    let mem_anomalies = anomalies(&metrics.svc_mem);
+    let max = |data: &[(f64, f64)], floor: f64| data.iter().map(|(_, v)| *v).fold(floor, f64::max);
+
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Metrics: {} [{}] ", id, window.label()),
+            styles::title(),
+        ))
+        .title_bottom(Line::from(vec![
+            Span::styled(" Esc/q", styles::key_hint()),
+            Span::styled(" close  ", styles::text_dim()),
+            Span::styled("[ ]", styles::key_hint()),
+            Span::styled(" window ", styles::text_dim()),
+        ]))
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    let cells: Vec<Rect> = rows
+        .iter()
+        .flat_map(|row| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(*row)
+                .to_vec()
+        })
+        .collect();
+
+    let cpu_max = max(&cpu_data, 100.0);
+    f.render_widget(
+        line_chart(
+            " CPU % ".to_string(),
+            &cpu_data,
+            &cpu_anomalies,
+            Color::Cyan,
+            x_bounds,
+            cpu_max,
+        ),
+        cells[0],
+    );
+    let mem_max = max(&mem_data, 100.0);
+    f.render_widget(
+        line_chart(
+            " Memory MB ".to_string(),
+            &mem_data,
+            &mem_anomalies,
+            Color::Green,
+            x_bounds,
+            mem_max,
+        ),
+        cells[1],
+    );
+    let log_max = max(&log_data, 1.0);
+    f.render_widget(
+        line_chart(
+            " Logs/s ".to_string(),
+            &log_data,
+            &[],
+            Color::Magenta,
+            x_bounds,
+            log_max,
+        ),
+        cells[2],
+    );
+    let dim = styles::text_dim();
+    f.render_widget(
+        io_chart(
+            " I/O KB/s ".to_string(),
+            &io_read_data,
+            &io_write_data,
+            styles::border_subtle(),
+            Axis::default().bounds(x_bounds).labels(vec![
+                Span::styled(format!("-{:.0}s", x_bounds[1] - x_bounds[0]), dim),
+                Span::styled("now", dim),
+            ]),
+        ),
+        cells[3],
+    );
+    fill_anomaly_markers(f.buffer_mut(), inner);
+}
+
 fn units_to_graph(units: &[Unit], edges: &[orkesy_core::unit::UnitEdge]) -> RuntimeGraph {
     let mut nodes = BTreeMap::new();
 
     for unit in units {
         let kind = match unit.kind {
-            orkesy_core::unit::UnitKind::Process => {
+            orkesy_core::unit::UnitKind::Process | orkesy_core::unit::UnitKind::Ssh { .. } => {
                 // Infer from name/description
                 let name_lower = unit.id.to_lowercase();
                 if name_lower.contains("api") || name_lower.contains("server") {
@@ -290,12 +998,35 @@ fn adapter_event_to_runtime(event: AdapterEvent) -> RuntimeEvent {
             RuntimeEvent::HealthChanged {
                 id,
                 health: health_status,
+                latency_ms: None,
             }
         }
         AdapterEvent::LogLine { id, stream, text } => RuntimeEvent::LogLine { id, stream, text },
         AdapterEvent::MetricsUpdated { id, metrics } => {
             RuntimeEvent::MetricsUpdated { id, metrics }
         }
+        AdapterEvent::ResourceWarning {
+            id,
+            metric,
+            value,
+            threshold,
+        } => RuntimeEvent::ResourceWarning {
+            id,
+            metric,
+            value,
+            threshold,
+        },
+        AdapterEvent::ReadinessReached { id } => RuntimeEvent::ReadinessReached { id },
+        AdapterEvent::UserNote { id, text, at } => RuntimeEvent::UserNote { id, text, at },
+        AdapterEvent::WatchChanged {
+            id,
+            watch_id,
+            watch,
+        } => RuntimeEvent::WatchChanged {
+            id,
+            watch_id,
+            watch,
+        },
     }
 }
 
@@ -303,18 +1034,67 @@ fn adapter_event_to_runtime(event: AdapterEvent) -> RuntimeEvent {
 fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     Terminal::new(backend)
 }
 
 fn restore_terminal(mut terminal: Terminal<CrosstermBackend<io::Stdout>>) -> io::Result<()> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Suspends the TUI, opens `path` at `line` in `$EDITOR` (then `$VISUAL`,
+/// then `vi`) and takes the screen back once the editor exits.
+fn open_in_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    path: &Path,
+    line: u32,
+) -> io::Result<()> {
+    let editor = ["EDITOR", "VISUAL"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // EDITOR may carry arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let mut cmd = std::process::Command::new(program);
+    cmd.args(parts);
+    match Path::new(program).file_name().and_then(|n| n.to_str()) {
+        Some("code" | "code-insiders" | "codium") => {
+            cmd.arg("--goto")
+                .arg(format!("{}:{}", path.display(), line));
+        }
+        _ => {
+            cmd.arg(format!("+{}", line)).arg(path);
+        }
+    }
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    let status = cmd.status();
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+    status.map(|_| ())
+}
+
 fn status_label(s: &ServiceStatus) -> &'static str {
     match s {
         ServiceStatus::Unknown => "unknown",
@@ -340,6 +1120,18 @@ fn status_icon(s: &ServiceStatus) -> &'static str {
     }
 }
 
+const SPINNER_FRAMES: [&str; 4] = ["◐", "◓", "◑", "◒"];
+
+/// `status_icon`, with a spinner for units that are starting or restarting
+fn animated_status_icon(s: &ServiceStatus, frame: u64) -> &'static str {
+    match s {
+        ServiceStatus::Starting | ServiceStatus::Restarting => {
+            SPINNER_FRAMES[(frame % SPINNER_FRAMES.len() as u64) as usize]
+        }
+        _ => status_icon(s),
+    }
+}
+
 fn health_icon(h: &HealthStatus) -> &'static str {
     match h {
         HealthStatus::Unknown => " ",
@@ -400,7 +1192,38 @@ fn fit_title(s: &str, width: u16) -> String {
     out
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+/// Terminals wider than this get a third column
+const THREE_COLUMN_MIN_WIDTH: u16 = 200;
+
+/// `[units | view]`, or `[units | logs | view]` on wide terminals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LayoutMode {
+    TwoColumn,
+    ThreeColumn,
+}
+
+impl LayoutMode {
+    fn for_width(width: u16) -> Self {
+        if width > THREE_COLUMN_MIN_WIDTH {
+            LayoutMode::ThreeColumn
+        } else {
+            LayoutMode::TwoColumn
+        }
+    }
+
+    fn constraints(self) -> Vec<Constraint> {
+        match self {
+            LayoutMode::TwoColumn => vec![Constraint::Percentage(20), Constraint::Percentage(80)],
+            LayoutMode::ThreeColumn => vec![
+                Constraint::Percentage(20),
+                Constraint::Percentage(50),
+                Constraint::Percentage(30),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 enum View {
     #[default]
     Logs,
@@ -408,6 +1231,10 @@ enum View {
     Exec, // Commands explorer
     Deps,
     Metrics,
+    /// Full-screen charts for one unit, opened with Enter from Metrics
+    MetricsDrill {
+        id: String,
+    },
 }
 
 #[allow(dead_code)]
@@ -418,7 +1245,7 @@ impl View {
             View::Inspect => "Inspect",
             View::Exec => "Exec",
             View::Deps => "Deps",
-            View::Metrics => "Metrics",
+            View::Metrics | View::MetricsDrill { .. } => "Metrics",
         }
     }
 
@@ -428,11 +1255,46 @@ impl View {
             View::Inspect => 'i',
             View::Exec => 'e',
             View::Deps => 'd',
-            View::Metrics => 'm',
+            View::Metrics | View::MetricsDrill { .. } => 'm',
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        [
+            View::Logs,
+            View::Inspect,
+            View::Exec,
+            View::Deps,
+            View::Metrics,
+        ]
+        .into_iter()
+        .find(|v| v.label() == label)
+    }
+
+    /// How often the sampler should run while this view is on screen
+    fn sample_interval(&self) -> Duration {
+        match self {
+            View::Inspect | View::Metrics | View::MetricsDrill { .. } => {
+                sampler::FAST_SAMPLE_INTERVAL
+            }
+            View::Logs => sampler::SLOW_SAMPLE_INTERVAL,
+            View::Exec | View::Deps => sampler::DEFAULT_SAMPLE_INTERVAL,
         }
     }
 }
 
+/// Lines above the bottom of the Logs view searched for a file location by `o`
+const EDITOR_SCAN_LINES: usize = 50;
+
+/// Notes listed in the Inspect view; older ones stay in the log stream
+const MAX_INSPECT_NOTES: usize = 4;
+
+/// Longer lines are shown raw in JSON mode rather than parsed every frame
+const MAX_JSON_LOG_LINE: usize = 4 * 1024;
+
+/// Most lines `+` shows around each search match
+const MAX_SEARCH_CONTEXT: usize = 20;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 enum InspectSection {
     #[default]
@@ -487,8 +1349,10 @@ impl Focus {
 #[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum PickerCategory {
+    RunCommand,
     ServiceAction,
     ProjectAction,
+    Group,
     DetectedCommand,
     Navigation,
 }
@@ -497,8 +1361,10 @@ enum PickerCategory {
 impl PickerCategory {
     fn label(&self) -> &'static str {
         match self {
+            PickerCategory::RunCommand => "Recent Runs",
             PickerCategory::ServiceAction => "Service Actions",
             PickerCategory::ProjectAction => "Project Actions",
+            PickerCategory::Group => "Groups",
             PickerCategory::DetectedCommand => "Commands",
             PickerCategory::Navigation => "Navigation",
         }
@@ -506,8 +1372,10 @@ impl PickerCategory {
 
     fn icon(&self) -> &'static str {
         match self {
+            PickerCategory::RunCommand => "↻",
             PickerCategory::ServiceAction => "●",
             PickerCategory::ProjectAction => "◉",
+            PickerCategory::Group => "◈",
             PickerCategory::DetectedCommand => "▶",
             PickerCategory::Navigation => "◇",
         }
@@ -554,6 +1422,17 @@ impl PickerItem {
         }
     }
 
+    fn new_group_action(label: &str, detail: &str, command: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            detail: Some(detail.to_string()),
+            category: PickerCategory::Group,
+            command: Some(command.to_string()),
+            target_view: None,
+            service_id: None,
+        }
+    }
+
     fn new_detected_command(label: &str, command: &str, detail: Option<&str>) -> Self {
         Self {
             label: label.to_string(),
@@ -565,6 +1444,22 @@ impl PickerItem {
         }
     }
 
+    /// `command` holds the command id to re-run rather than a palette command
+    fn new_run_command(run: &CommandRun) -> Self {
+        Self {
+            label: run.display_name.clone(),
+            detail: Some(format!(
+                "{} {}",
+                format_ago(run.started_at),
+                run.status.icon()
+            )),
+            category: PickerCategory::RunCommand,
+            command: Some(run.command_id.clone()),
+            target_view: None,
+            service_id: None,
+        }
+    }
+
     fn new_navigation(label: &str, view: View) -> Self {
         Self {
             label: label.to_string(),
@@ -577,19 +1472,25 @@ impl PickerItem {
     }
 
     fn fuzzy_matches(&self, pattern: &str) -> bool {
-        if pattern.is_empty() {
-            return true;
-        }
-        let label_lower = self.label.to_lowercase();
-        let pattern_lower = pattern.to_lowercase();
+        self.fuzzy_match_indices(pattern).is_some()
+    }
 
+    /// Char indices of `label` that the pattern's chars matched, taking the
+    /// first match for each in turn; `None` if the pattern doesn't match
+    fn fuzzy_match_indices(&self, pattern: &str) -> Option<Vec<usize>> {
+        let pattern_lower = pattern.to_lowercase();
         let mut pattern_chars = pattern_lower.chars().peekable();
-        for c in label_lower.chars() {
-            if pattern_chars.peek() == Some(&c) {
+        let mut indices = Vec::new();
+        for (i, c) in self.label.chars().enumerate() {
+            let Some(&p) = pattern_chars.peek() else {
+                break;
+            };
+            if c.to_lowercase().eq(std::iter::once(p)) {
+                indices.push(i);
                 pattern_chars.next();
             }
         }
-        pattern_chars.peek().is_none()
+        pattern_chars.peek().is_none().then_some(indices)
     }
 
     fn fuzzy_score(&self, pattern: &str) -> i32 {
@@ -617,6 +1518,7 @@ impl PickerItem {
 
 fn build_picker_items(
     service_ids: &[String],
+    groups: &BTreeMap<String, Vec<String>>,
     selected_service: Option<&str>,
     _detected_commands: &[String], // For future: parsed from package.json etc.
 ) -> Vec<PickerItem> {
@@ -708,6 +1610,18 @@ fn build_picker_items(
         "clear all",
     ));
 
+    // Group actions
+    for (name, members) in groups {
+        let detail = members.join(", ");
+        for action in ["Start", "Stop", "Restart"] {
+            items.push(PickerItem::new_group_action(
+                &format!("{} {}", action, name),
+                &detail,
+                &format!("{} {}", action.to_lowercase(), name),
+            ));
+        }
+    }
+
     // Navigation actions
     items.push(PickerItem::new_navigation("Open Logs view", View::Logs));
     items.push(PickerItem::new_navigation(
@@ -727,21 +1641,122 @@ fn build_picker_items(
     items
 }
 
+/// Number of recently run commands offered at the top of the palette
+const RECENT_RUN_LIMIT: usize = 5;
+
+/// The last `RECENT_RUN_LIMIT` distinct commands that were run, most
+/// recent first
+fn recent_run_items(state: &RuntimeState) -> Vec<PickerItem> {
+    let mut runs: Vec<&CommandRun> = state.runs.values().collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.started_at));
+
+    let mut seen = HashSet::new();
+    runs.into_iter()
+        .filter(|run| seen.insert(run.command_id.as_str()))
+        .take(RECENT_RUN_LIMIT)
+        .map(PickerItem::new_run_command)
+        .collect()
+}
+
+/// "just now", "42s ago", "5m ago", "3h ago" or "2d ago"
+fn format_ago(time: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or_default()
+        .as_secs();
+    match secs {
+        0..5 => "just now".to_string(),
+        5..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// What the command palette lists
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum PaletteMode {
+    #[default]
+    Commands,
+    /// `Ctrl+R` history search; `from_commands` means Esc goes back to the
+    /// normal palette instead of closing
+    History { from_commands: bool },
+}
+
+/// Palette items for the current mode and input. History mode lists
+/// previously executed commands, most recent first.
+fn palette_items(
+    ui: &UiState,
+    service_ids: &[String],
+    groups: &BTreeMap<String, Vec<String>>,
+    selected_id: Option<&str>,
+    recent_runs: &[PickerItem],
+) -> Vec<PickerItem> {
+    let mut all_items = recent_runs.to_vec();
+    all_items.extend(build_picker_items(service_ids, groups, selected_id, &[]));
+    match ui.palette_mode {
+        PaletteMode::Commands => {
+            let mut items = filter_picker_items(&all_items, &ui.palette_input);
+            // `watch ...` / `unwatch ...` take free-form arguments, so the
+            // typed input itself becomes the first item
+            let input = ui.palette_input.trim();
+            if let Some(keyword @ ("watch" | "unwatch")) = input.split_whitespace().next()
+                && input.len() > keyword.len()
+            {
+                items.insert(
+                    0,
+                    PickerItem {
+                        label: input.to_string(),
+                        detail: Some(match keyword {
+                            "watch" => "Run an action when a metric crosses a threshold".into(),
+                            _ => "Cancel a watch".into(),
+                        }),
+                        category: PickerCategory::ServiceAction,
+                        command: Some(input.to_string()),
+                        target_view: None,
+                        service_id: None,
+                    },
+                );
+            }
+            items
+        }
+        PaletteMode::History { .. } => {
+            let history: Vec<PickerItem> = ui
+                .history
+                .iter()
+                .rev()
+                .filter_map(|label| all_items.iter().find(|i| &i.label == label).cloned())
+                .collect();
+            if ui.palette_input.is_empty() {
+                history
+            } else {
+                filter_picker_items(&history, &ui.palette_input)
+            }
+        }
+    }
+}
+
 fn filter_picker_items(items: &[PickerItem], query: &str) -> Vec<PickerItem> {
     if query.is_empty() {
         // Return all items, grouped by category
         let mut result = items.to_vec();
         result.sort_by(|a, b| {
-            // Sort by category order, then by label
+            // Sort by category order, then by label; recent runs keep their
+            // most-recent-first order
             let cat_order = |c: &PickerCategory| match c {
-                PickerCategory::ServiceAction => 0,
-                PickerCategory::ProjectAction => 1,
-                PickerCategory::DetectedCommand => 2,
-                PickerCategory::Navigation => 3,
+                PickerCategory::RunCommand => 0,
+                PickerCategory::ServiceAction => 1,
+                PickerCategory::ProjectAction => 2,
+                PickerCategory::Group => 3,
+                PickerCategory::DetectedCommand => 4,
+                PickerCategory::Navigation => 5,
             };
             cat_order(&a.category)
                 .cmp(&cat_order(&b.category))
-                .then_with(|| a.label.cmp(&b.label))
+                .then_with(|| match a.category {
+                    PickerCategory::RunCommand => std::cmp::Ordering::Equal,
+                    _ => a.label.cmp(&b.label),
+                })
         });
         return result;
     }
@@ -753,7 +1768,7 @@ fn filter_picker_items(items: &[PickerItem], query: &str) -> Vec<PickerItem> {
         .map(|item| (item.clone(), item.fuzzy_score(query)))
         .collect();
 
-    filtered.sort_by(|a, b| b.1.cmp(&a.1));
+    filtered.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
     filtered.into_iter().map(|(item, _)| item).collect()
 }
 
@@ -763,6 +1778,7 @@ enum LeftMode {
     Services,
     Commands,
     Runs,
+    Groups,
 }
 
 impl LeftMode {
@@ -771,6 +1787,7 @@ impl LeftMode {
             LeftMode::Services => "Units",
             LeftMode::Commands => "Commands",
             LeftMode::Runs => "Runs",
+            LeftMode::Groups => "Groups",
         }
     }
 
@@ -779,6 +1796,225 @@ impl LeftMode {
             LeftMode::Services => '1',
             LeftMode::Commands => '2',
             LeftMode::Runs => '3',
+            LeftMode::Groups => '4',
+        }
+    }
+
+    /// Stable name for `ui-state.json` (`label` shows "Units" for Services)
+    fn name(&self) -> &'static str {
+        match self {
+            LeftMode::Services => "Services",
+            LeftMode::Commands => "Commands",
+            LeftMode::Runs => "Runs",
+            LeftMode::Groups => "Groups",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            LeftMode::Services,
+            LeftMode::Commands,
+            LeftMode::Runs,
+            LeftMode::Groups,
+        ]
+        .into_iter()
+        .find(|m| m.name() == name)
+    }
+}
+
+/// Whether Logs view lines are colored by their detected level (`C` toggles)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum LogColorMode {
+    #[default]
+    Auto,
+    Off,
+}
+
+impl LogColorMode {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Auto => Self::Off,
+            Self::Off => Self::Auto,
+        }
+    }
+
+    /// Style for a whole line: errors red, warnings yellow, debug dimmed
+    fn line_style(self, plain: &str) -> Style {
+        if self == Self::Off {
+            return Style::default();
+        }
+        match detect_level(plain) {
+            LogLevel::Error => Style::default().fg(Color::Red),
+            LogLevel::Warn => Style::default().fg(Color::Yellow),
+            LogLevel::Debug => Style::default().fg(Color::DarkGray),
+            LogLevel::Info => Style::default(),
+        }
+    }
+}
+
+/// Span of time the Inspect charts cover (`[`/`]` cycle with Metrics focused)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ChartWindow {
+    Thirty,
+    #[default]
+    Sixty,
+    FiveMinutes,
+}
+
+impl ChartWindow {
+    fn next(self) -> Self {
+        match self {
+            ChartWindow::Thirty => ChartWindow::Sixty,
+            ChartWindow::Sixty => ChartWindow::FiveMinutes,
+            ChartWindow::FiveMinutes => ChartWindow::Thirty,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            ChartWindow::Thirty => ChartWindow::FiveMinutes,
+            ChartWindow::Sixty => ChartWindow::Thirty,
+            ChartWindow::FiveMinutes => ChartWindow::Sixty,
+        }
+    }
+
+    fn secs(self) -> f64 {
+        match self {
+            ChartWindow::Thirty => 30.0,
+            ChartWindow::Sixty => 60.0,
+            ChartWindow::FiveMinutes => 300.0,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ChartWindow::Thirty => "30s",
+            ChartWindow::Sixty => "60s",
+            ChartWindow::FiveMinutes => "5m",
+        }
+    }
+}
+
+/// One row of the Units pane in Services mode
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum UnitRow {
+    /// A unit, or the merged "all" entry; `nested` ones sit under a group
+    Unit { id: String, nested: bool },
+    /// A config group; Enter expands or collapses it
+    Group {
+        name: String,
+        members: usize,
+        expanded: bool,
+    },
+}
+
+impl UnitRow {
+    /// The unit the row selects; group headers select none
+    fn unit_id(&self) -> Option<&str> {
+        match self {
+            UnitRow::Unit { id, .. } => Some(id),
+            UnitRow::Group { .. } => None,
+        }
+    }
+}
+
+/// First row showing unit `id`
+fn unit_row_position(rows: &[UnitRow], id: &str) -> Option<usize> {
+    rows.iter().position(|row| row.unit_id() == Some(id))
+}
+
+/// Order of the units in the Units pane (`O` cycles); "all" stays first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortOrder {
+    #[default]
+    Name,
+    Status,
+    Cpu,
+    Memory,
+}
+
+impl SortOrder {
+    fn next(self) -> Self {
+        match self {
+            SortOrder::Name => SortOrder::Status,
+            SortOrder::Status => SortOrder::Cpu,
+            SortOrder::Cpu => SortOrder::Memory,
+            SortOrder::Memory => SortOrder::Name,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortOrder::Name => "name ↑",
+            SortOrder::Status => "status ↓",
+            SortOrder::Cpu => "cpu ↓",
+            SortOrder::Memory => "memory ↓",
+        }
+    }
+
+    /// Sorts `ids` (already by name) so that errored units, then the
+    /// busiest ones, come first; ties keep name order
+    fn sort(self, ids: &mut [String], state: &RuntimeState) {
+        let status_rank = |id: &String| match state.graph.nodes.get(id).map(|n| &n.observed.status)
+        {
+            Some(ServiceStatus::Errored { .. }) => 0,
+            Some(ServiceStatus::Exited { .. }) => 1,
+            Some(ServiceStatus::Starting | ServiceStatus::Restarting) => 2,
+            Some(ServiceStatus::Running) => 3,
+            Some(ServiceStatus::Stopped) => 4,
+            Some(ServiceStatus::Unknown) | None => 5,
+        };
+        let metrics = |id: &String| state.metrics.get(id);
+        match self {
+            SortOrder::Name => {}
+            SortOrder::Status => ids.sort_by_key(status_rank),
+            SortOrder::Cpu => ids.sort_by(|a, b| {
+                let cpu = |id| metrics(id).map_or(0.0, |m| m.cpu_percent);
+                cpu(b).total_cmp(&cpu(a))
+            }),
+            SortOrder::Memory => {
+                ids.sort_by_key(|id| std::cmp::Reverse(metrics(id).map_or(0, |m| m.memory_bytes)))
+            }
+        }
+    }
+}
+
+/// Which units the Units pane lists (`F` cycles)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum StatusFilter {
+    #[default]
+    All,
+    RunningOnly,
+    ErroredOnly,
+}
+
+impl StatusFilter {
+    fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::RunningOnly,
+            StatusFilter::RunningOnly => StatusFilter::ErroredOnly,
+            StatusFilter::ErroredOnly => StatusFilter::All,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "all",
+            StatusFilter::RunningOnly => "running",
+            StatusFilter::ErroredOnly => "errored",
+        }
+    }
+
+    /// Errored covers units that failed to start and ones that exited non-zero
+    fn matches(self, status: &ServiceStatus) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::RunningOnly => *status == ServiceStatus::Running,
+            StatusFilter::ErroredOnly => match status {
+                ServiceStatus::Errored { .. } => true,
+                ServiceStatus::Exited { code } => code.is_some_and(|c| c != 0),
+                _ => false,
+            },
         }
     }
 }
@@ -789,10 +2025,24 @@ struct LogsUiState {
     paused: bool,
     scroll: usize,
     search: Option<String>,
+    /// Compiled form of a `/regex/` search, rebuilt when the query changes
+    search_regex: Option<regex::Regex>,
+    /// Why the `/regex/` search doesn't compile
+    search_error: Option<String>,
+    /// Lines shown around each search match, like `grep -C` (`+`/`-`)
+    context_lines: usize,
     matches: Vec<usize>,
     match_idx: usize,
     frozen_logs: Vec<DisplayLogLine>,
     log_filter: LogFilterMode,
+    color_mode: LogColorMode,
+    /// Render JSON log lines as time, level, message and fields (`J`)
+    json_mode: bool,
+    /// Horizontal scroll offset in columns (disables wrapping when > 0)
+    scroll_x: usize,
+    /// Prefix each line with its number in the log buffer (`N`)
+    show_line_numbers: bool,
+    timestamps: LogTimestamps,
 }
 
 impl LogsUiState {
@@ -809,20 +2059,86 @@ impl LogsUiState {
 
     fn enter_search(&mut self) {
         self.search = Some(String::new());
+        self.search_regex = None;
+        self.search_error = None;
         self.matches.clear();
         self.match_idx = 0;
     }
 
     fn exit_search(&mut self) {
         self.search = None;
+        self.search_regex = None;
+        self.search_error = None;
         self.matches.clear();
         self.match_idx = 0;
     }
 
+    /// Recompiles the search after the query changed. A query starting with
+    /// `/` (e.g. `/error \d+/`) is a case-insensitive regex; anything else
+    /// is a case-insensitive substring.
+    fn compile_search(&mut self) {
+        self.search_regex = None;
+        self.search_error = None;
+        let Some(pattern) = self.search.as_deref().and_then(|q| q.strip_prefix('/')) else {
+            return;
+        };
+        let pattern = pattern.strip_suffix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return;
+        }
+        match regex::RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+        {
+            Ok(regex) => self.search_regex = Some(regex),
+            // Syntax errors span several lines; the last one says what's wrong
+            Err(e) => {
+                let message = e.to_string();
+                let reason = message.lines().last().unwrap_or_default();
+                self.search_error = Some(reason.trim_start_matches("error: ").to_string());
+            }
+        }
+    }
+
+    /// Rows of `lines` within `context_lines` of a search match that don't
+    /// match themselves
+    fn search_context(&self, lines: &[DisplayLogLine]) -> HashSet<usize> {
+        if self.context_lines == 0 || self.search.is_none() {
+            return HashSet::new();
+        }
+        let hits: Vec<bool> = lines
+            .iter()
+            .map(|l| self.search_matches(&l.plain_text()))
+            .collect();
+        let mut context = HashSet::new();
+        for row in hits
+            .iter()
+            .enumerate()
+            .filter(|(_, hit)| **hit)
+            .map(|(i, _)| i)
+        {
+            let end = (row + self.context_lines).min(lines.len() - 1);
+            context.extend((row.saturating_sub(self.context_lines)..=end).filter(|i| !hits[*i]));
+        }
+        context
+    }
+
+    /// Whether `plain` (a line without escape sequences) matches the search
+    fn search_matches(&self, plain: &str) -> bool {
+        match (&self.search_regex, self.search.as_deref()) {
+            (Some(regex), _) => regex.is_match(plain),
+            (None, Some(query)) if !query.is_empty() && !query.starts_with('/') => {
+                plain.to_lowercase().contains(&query.to_lowercase())
+            }
+            _ => false,
+        }
+    }
+
     fn toggle_follow(&mut self) {
         self.follow = !self.follow;
         if self.follow {
             self.scroll = 0;
+            self.scroll_x = 0;
         }
     }
 
@@ -863,6 +2179,14 @@ struct UiState {
     left_mode: LeftMode,
     selected_command: usize,
     selected_run: usize,
+    /// Last view other than Logs; the third column shows it while Logs is
+    /// the current view
+    last_detail_view: View,
+    /// Index into the config's groups, in name order
+    selected_group: usize,
+    unit_sort: SortOrder,
+    unit_filter: StatusFilter,
+    chart_window: ChartWindow,
     logs: LogsUiState,
     inspect_scroll: usize,
     deps_scroll: usize,
@@ -872,10 +2196,36 @@ struct UiState {
     palette_pick: usize,
     palette_scroll: usize,
     palette_sugg_offset: usize,
+    palette_mode: PaletteMode,
     help_open: bool,
     metrics_paused: bool,
     history: Vec<String>,
     history_cursor: Option<usize>,
+    /// Inline stdin input for the selected run (Some = input bar open)
+    run_input: Option<String>,
+    /// Note being typed for a unit: (unit id, text)
+    note_input: Option<(String, String)>,
+    /// File name being typed to export the visible logs to (Some = input bar open)
+    log_export_input: Option<String>,
+    /// Notifications in the bottom-right corner, oldest first
+    toasts: Vec<Toast>,
+    /// Mass action waiting for `y` in the confirmation dialog
+    pending_confirm: Option<PendingConfirm>,
+    /// Spinner frame for starting/restarting units, advanced every idle tick
+    spin_frame: u64,
+    /// Unit selected when the state was saved, restored on the next start
+    selected_unit: Option<String>,
+    /// Services marked with Space; `r`/`s`/`t`/`x` act on all of them
+    batch_selection: BTreeSet<String>,
+    /// Services pinned with `P`, listed first whatever the sort order
+    pinned: BTreeSet<String>,
+    /// Groups listed with their members in the Units pane; the others show
+    /// only their header
+    expanded_groups: BTreeSet<String>,
+    runs_sort: RunsSortKey,
+    runs_sort_desc: bool,
+    /// Only list runs matching this status (see `RunStatus::matches_filter`)
+    runs_filter: Option<RunStatus>,
 }
 
 impl Default for UiState {
@@ -886,6 +2236,11 @@ impl Default for UiState {
             left_mode: LeftMode::Services,
             selected_command: 0,
             selected_run: 0,
+            last_detail_view: View::Inspect,
+            selected_group: 0,
+            unit_sort: SortOrder::default(),
+            unit_filter: StatusFilter::default(),
+            chart_window: ChartWindow::default(),
             logs: LogsUiState::new(),
             inspect_scroll: 0,
             deps_scroll: 0,
@@ -895,10 +2250,24 @@ impl Default for UiState {
             palette_pick: 0,
             palette_scroll: 0,
             palette_sugg_offset: 0,
+            palette_mode: PaletteMode::Commands,
             help_open: false,
             metrics_paused: false,
             history: Vec::new(),
             history_cursor: None,
+            run_input: None,
+            note_input: None,
+            log_export_input: None,
+            toasts: Vec::new(),
+            pending_confirm: None,
+            spin_frame: 0,
+            selected_unit: None,
+            batch_selection: BTreeSet::new(),
+            pinned: BTreeSet::new(),
+            expanded_groups: BTreeSet::new(),
+            runs_sort: RunsSortKey::default(),
+            runs_sort_desc: RunsSortKey::default().default_desc(),
+            runs_filter: None,
         }
     }
 }
@@ -915,11 +2284,190 @@ impl UiState {
     fn enter_follow(&mut self) {
         self.logs.follow = true;
         self.logs.scroll = 0;
+        self.logs.scroll_x = 0;
+    }
+
+    /// View for the third column of the wide layout
+    fn detail_view(&self) -> View {
+        match &self.view {
+            View::Logs => self.last_detail_view.clone(),
+            view => view.clone(),
+        }
     }
 
     fn search_query(&self) -> Option<&str> {
         self.logs.search.as_deref()
     }
+
+    /// Runs as listed in the Runs pane, with the current sort and filter
+    fn visible_runs<'a>(&self, state: &'a RuntimeState) -> Vec<&'a CommandRun> {
+        state
+            .runs_ordered_by(self.runs_sort, self.runs_sort_desc)
+            .into_iter()
+            .filter(|run| {
+                self.runs_filter
+                    .as_ref()
+                    .is_none_or(|filter| run.status.matches_filter(filter))
+            })
+            .collect()
+    }
+
+    /// Moves the run selection to the previous/next visible run.
+    /// `selected_run` stays an index into `run_order`.
+    fn step_run_selection(&mut self, state: &RuntimeState, down: bool) -> bool {
+        let visible: Vec<RunId> = self
+            .visible_runs(state)
+            .iter()
+            .map(|r| r.id.clone())
+            .collect();
+        let pos = state
+            .run_order
+            .get(self.selected_run)
+            .and_then(|id| visible.iter().position(|v| v == id));
+        let target = match (pos, down) {
+            (Some(pos), true) => pos + 1,
+            (Some(pos), false) => match pos.checked_sub(1) {
+                Some(target) => target,
+                None => return false,
+            },
+            (None, _) => 0,
+        };
+        let Some(index) = visible
+            .get(target)
+            .and_then(|id| state.run_order.iter().position(|r| r == id))
+        else {
+            return false;
+        };
+        self.selected_run = index;
+        true
+    }
+
+    /// "all" followed by the units as listed in the Units pane, with the
+    /// current sort and filter
+    fn unit_display_ids(&self, state: &RuntimeState) -> Vec<String> {
+        let mut ids: Vec<String> = state
+            .graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| self.unit_filter.matches(&node.observed.status))
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.unit_sort.sort(&mut ids, state);
+        ids.sort_by_key(|id| !self.pinned.contains(id));
+        std::iter::once("all".to_string()).chain(ids).collect()
+    }
+
+    /// Rows of the Units pane: "all", each group's header followed by its
+    /// members when expanded, then the units in no group. Units keep the
+    /// order and filter of `unit_display_ids`.
+    fn unit_rows(&self, state: &RuntimeState) -> Vec<UnitRow> {
+        let ids = self.unit_display_ids(state);
+        let (all, ids) = ids.split_at(1);
+        let unit = |id: &String, nested| UnitRow::Unit {
+            id: id.clone(),
+            nested,
+        };
+
+        let mut rows: Vec<UnitRow> = all.iter().map(|id| unit(id, false)).collect();
+        for (name, members) in &state.groups {
+            let expanded = self.expanded_groups.contains(name);
+            rows.push(UnitRow::Group {
+                name: name.clone(),
+                members: members.len(),
+                expanded,
+            });
+            if expanded {
+                rows.extend(
+                    ids.iter()
+                        .filter(|id| members.contains(id))
+                        .map(|id| unit(id, true)),
+                );
+            }
+        }
+        rows.extend(
+            ids.iter()
+                .filter(|id| !state.groups.values().any(|members| members.contains(id)))
+                .map(|id| unit(id, false)),
+        );
+        rows
+    }
+
+    /// Units pane title, e.g. `Units [status ↓, running] [2 selected]`;
+    /// the default name order without a filter is left out
+    fn units_title(&self) -> String {
+        let mut title = "Units".to_string();
+        let mut parts = Vec::new();
+        if self.unit_sort != SortOrder::Name {
+            parts.push(self.unit_sort.label());
+        }
+        if self.unit_filter != StatusFilter::All {
+            parts.push(self.unit_filter.label());
+        }
+        if !parts.is_empty() {
+            title.push_str(&format!(" [{}]", parts.join(", ")));
+        }
+        if !self.batch_selection.is_empty() {
+            title.push_str(&format!(" [{} selected]", self.batch_selection.len()));
+        }
+        title
+    }
+
+    /// Runs pane title, e.g. `Runs [by: duration↓, failed]`
+    fn runs_title(&self) -> String {
+        let arrow = if self.runs_sort_desc { "↓" } else { "↑" };
+        let filter = self
+            .runs_filter
+            .as_ref()
+            .map(|f| format!(", {}", f.filter_label()))
+            .unwrap_or_default();
+        format!("Runs [by: {}{}{}]", self.runs_sort.label(), arrow, filter)
+    }
+
+    /// Writes the view, left pane mode, selected unit and pinned units;
+    /// nothing else is kept
+    fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::json!({
+            "view": self.view.label(),
+            "selected_unit": self.selected_unit,
+            "left_mode": self.left_mode.name(),
+            "pinned": self.pinned,
+        });
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(&json).map_err(io::Error::other)?;
+        std::fs::write(path, content)
+    }
+
+    fn load(path: &Path) -> io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut ui = Self::default();
+        if let Some(view) = json["view"].as_str().and_then(View::from_label) {
+            ui.view = view;
+        }
+        if let Some(mode) = json["left_mode"].as_str().and_then(LeftMode::from_name) {
+            ui.left_mode = mode;
+        }
+        ui.selected_unit = json["selected_unit"].as_str().map(str::to_string);
+        if let Some(pinned) = json["pinned"].as_array() {
+            ui.pinned = pinned
+                .iter()
+                .filter_map(|id| id.as_str().map(str::to_string))
+                .collect();
+        }
+        Ok(ui)
+    }
+}
+
+/// `$XDG_DATA_HOME/orkesy/<project>/ui-state.json` (`~/.local/share` by default)
+fn ui_state_path(project_name: &str) -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))?;
+    let project = project_name.replace(['/', '\\'], "_");
+    Some(base.join("orkesy").join(project).join("ui-state.json"))
 }
 
 enum RuntimeBackend {
@@ -945,6 +2493,20 @@ impl RuntimeBackend {
         }
     }
 
+    /// Starts `ids` in dependency order where the backend supports it
+    async fn send_start_many(&self, ids: Vec<String>) {
+        match self {
+            RuntimeBackend::Adapter { cmd_tx } => {
+                let _ = cmd_tx.send(AdapterCommand::StartMany { ids }).await;
+            }
+            RuntimeBackend::LegacyEngine { .. } => {
+                for id in ids {
+                    self.send_start(id).await;
+                }
+            }
+        }
+    }
+
     async fn send_stop(&self, id: String) {
         match self {
             RuntimeBackend::Adapter { cmd_tx } => {
@@ -1010,6 +2572,50 @@ impl RuntimeBackend {
         }
     }
 
+    async fn send_note(&self, id: String, text: String) {
+        match self {
+            RuntimeBackend::Adapter { cmd_tx } => {
+                let _ = cmd_tx.send(AdapterCommand::AddNote { id, text }).await;
+            }
+            RuntimeBackend::LegacyEngine { cmd_tx } => {
+                let _ = cmd_tx
+                    .send(orkesy_core::engine::EngineCommand::AddNote { id, text })
+                    .await;
+            }
+        }
+    }
+
+    async fn send_watch(
+        &self,
+        id: String,
+        metric: String,
+        op: CompareOp,
+        threshold: f64,
+        then: AdapterCommand,
+    ) {
+        match self {
+            RuntimeBackend::Adapter { cmd_tx } => {
+                let _ = cmd_tx
+                    .send(AdapterCommand::Watch {
+                        id,
+                        metric,
+                        op,
+                        threshold,
+                        then: Box::new(then),
+                    })
+                    .await;
+            }
+            // Legacy engines have no watch support
+            RuntimeBackend::LegacyEngine { .. } => {}
+        }
+    }
+
+    async fn send_cancel_watch(&self, watch_id: String) {
+        if let RuntimeBackend::Adapter { cmd_tx } = self {
+            let _ = cmd_tx.send(AdapterCommand::CancelWatch { watch_id }).await;
+        }
+    }
+
     async fn send_exec(&self, id: String, cmd: Vec<String>) {
         match self {
             RuntimeBackend::Adapter { cmd_tx } => {
@@ -1032,33 +2638,97 @@ enum CliAction {
     Install,
 }
 
-async fn run_cli_command(action: CliAction, unit_args: Vec<String>) -> io::Result<()> {
+/// Expands "all" and exits with an error on unknown unit IDs
+fn resolve_target_ids(units: &[Unit], unit_args: Vec<String>) -> Vec<String> {
+    let unit_ids: Vec<String> = units.iter().map(|u| u.id.clone()).collect();
+
+    if unit_args.iter().any(|u| u == "all") {
+        return unit_ids;
+    }
+    // Validate unit IDs exist
+    for id in &unit_args {
+        if !unit_ids.contains(id) {
+            eprintln!(
+                "Error: Unknown unit '{}'. Available: {}",
+                id,
+                unit_ids.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+    unit_args
+}
+
+/// `up`/`down`/`restart --dry-run`: prints the plan, then runs the commands
+/// through a dry-run `ProcessAdapter` to show what each unit would execute.
+async fn run_cli_dry_run(
+    action: CliAction,
+    unit_args: Vec<String>,
+    fail_on_port_conflict: bool,
+) -> io::Result<()> {
     let Some((path, config)) = try_load_config() else {
         eprintln!("Error: No orkesy.yml found. Run `orkesy init` first.");
         std::process::exit(1);
     };
 
     let units = config.to_units();
-    let unit_ids: Vec<String> = units.iter().map(|u| u.id.clone()).collect();
+    let target_ids = resolve_target_ids(&units, unit_args);
 
-    // Expand "all" to all unit IDs
-    let target_ids: Vec<String> = if unit_args.iter().any(|u| u == "all") {
-        unit_ids.clone()
-    } else {
-        // Validate unit IDs exist
-        for id in &unit_args {
-            if !unit_ids.contains(id) {
-                eprintln!(
-                    "Error: Unknown unit '{}'. Available: {}",
-                    id,
-                    unit_ids.join(", ")
-                );
-                std::process::exit(1);
-            }
+    let plan_action = match action {
+        CliAction::Start | CliAction::Install => commands::PlanAction::Start,
+        CliAction::Stop => commands::PlanAction::Stop,
+        CliAction::Restart => commands::PlanAction::Restart,
+    };
+
+    println!("Loaded config from: {}", path.display());
+    println!("Dry run, nothing will be started or stopped.\n");
+    let conflicts = commands::print_plan(plan_action, &config, &target_ids);
+
+    let (cmd_tx, cmd_rx) = mpsc::channel::<AdapterCommand>(100);
+    let (event_tx, mut event_rx) = broadcast::channel::<AdapterEvent>(1_000);
+    let mut adapter = ProcessAdapter::new().with_dry_run(true);
+    tokio::spawn(async move {
+        adapter.run(cmd_rx, event_tx, units).await;
+    });
+
+    let mut order: Vec<String> = config.start_levels(&target_ids).concat();
+    if matches!(action, CliAction::Stop) {
+        order.reverse();
+    }
+    for id in order {
+        let cmd = match action {
+            CliAction::Start => AdapterCommand::Start { id },
+            CliAction::Stop => AdapterCommand::Stop { id },
+            CliAction::Restart => AdapterCommand::Restart { id },
+            CliAction::Install => AdapterCommand::Install { id },
+        };
+        let _ = cmd_tx.send(cmd).await;
+    }
+    let _ = cmd_tx.send(AdapterCommand::Shutdown).await;
+
+    // The channel closes once the adapter has handled Shutdown
+    while let Ok(event) = event_rx.recv().await {
+        if let AdapterEvent::LogLine { id, text, .. } = event {
+            println!("[{}] {}", id, text);
         }
-        unit_args
+    }
+
+    if fail_on_port_conflict && conflicts > 0 {
+        eprintln!("\n{} port conflict(s)", conflicts);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_cli_command(action: CliAction, unit_args: Vec<String>) -> io::Result<()> {
+    let Some((path, config)) = try_load_config() else {
+        eprintln!("Error: No orkesy.yml found. Run `orkesy init` first.");
+        std::process::exit(1);
     };
 
+    let units = config.to_units();
+    let target_ids = resolve_target_ids(&units, unit_args);
+
     if target_ids.is_empty() {
         eprintln!("No units to process.");
         return Ok(());
@@ -1078,20 +2748,35 @@ async fn run_cli_command(action: CliAction, unit_args: Vec<String>) -> io::Resul
     let (cmd_tx, cmd_rx) = mpsc::channel::<AdapterCommand>(100);
     let (event_tx, mut event_rx) = broadcast::channel::<AdapterEvent>(1_000);
 
-    let mut adapter = ProcessAdapter::new();
+    let cmd_rx = adapters::split_unit_commands(
+        cmd_rx,
+        &units,
+        event_tx.clone(),
+        config.global_env_file.clone(),
+    );
+    let mut adapter = ProcessAdapter::new()
+        .with_edges(config.to_edges())
+        .with_global_env_file(config.global_env_file.clone());
     let units_clone = units.clone();
     tokio::spawn(async move {
         adapter.run(cmd_rx, event_tx, units_clone).await;
     });
 
-    // Send commands
-    for id in &target_ids {
-        let cmd = match action {
-            CliAction::Start => AdapterCommand::Start { id: id.clone() },
-            CliAction::Stop => AdapterCommand::Stop { id: id.clone() },
-            CliAction::Restart => AdapterCommand::Restart { id: id.clone() },
-            CliAction::Install => AdapterCommand::Install { id: id.clone() },
-        };
+    // Send commands; starts wait for the dependencies among the targets
+    let cmds: Vec<AdapterCommand> = match action {
+        CliAction::Start => vec![AdapterCommand::StartMany {
+            ids: target_ids.clone(),
+        }],
+        CliAction::Stop | CliAction::Restart | CliAction::Install => target_ids
+            .iter()
+            .map(|id| match action {
+                CliAction::Stop => AdapterCommand::Stop { id: id.clone() },
+                CliAction::Restart => AdapterCommand::Restart { id: id.clone() },
+                _ => AdapterCommand::Install { id: id.clone() },
+            })
+            .collect(),
+    };
+    for cmd in cmds {
         let _ = cmd_tx.send(cmd).await;
     }
 
@@ -1167,7 +2852,62 @@ async fn run_cli_command(action: CliAction, unit_args: Vec<String>) -> io::Resul
     Ok(())
 }
 
-async fn run_cli_logs(unit_id: &str, follow: bool) -> io::Result<()> {
+/// Line filter for `orkesy logs`, applied before printing
+struct CliLogFilter {
+    patterns: Vec<regex::Regex>,
+    require_all: bool,
+    invert: bool,
+    stream: Option<LogStream>,
+}
+
+impl CliLogFilter {
+    fn new(
+        grep: &[String],
+        require_all: bool,
+        invert: bool,
+        stream: Option<&str>,
+    ) -> Result<Self, regex::Error> {
+        let patterns = grep
+            .iter()
+            .map(|p| regex::Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let stream = stream.map(|s| match s {
+            "stderr" => LogStream::Stderr,
+            "system" => LogStream::System,
+            _ => LogStream::Stdout,
+        });
+        Ok(Self {
+            patterns,
+            require_all,
+            invert,
+            stream,
+        })
+    }
+
+    fn matches(&self, stream: &LogStream, text: &str) -> bool {
+        if self.stream.as_ref().is_some_and(|s| s != stream) {
+            return false;
+        }
+        if self.patterns.is_empty() {
+            return true;
+        }
+        let plain = ui::ansi::strip_ansi(text);
+        let hit = if self.require_all {
+            self.patterns.iter().all(|re| re.is_match(&plain))
+        } else {
+            self.patterns.iter().any(|re| re.is_match(&plain))
+        };
+        hit != self.invert
+    }
+}
+
+/// Without a `formatter`, lines print as `{text}` with stderr/system lines tagged
+async fn run_cli_logs(
+    unit_id: &str,
+    follow: bool,
+    filter: CliLogFilter,
+    formatter: Option<commands::LogFormatter>,
+) -> io::Result<()> {
     let Some((path, config)) = try_load_config() else {
         eprintln!("Error: No orkesy.yml found. Run `orkesy init` first.");
         std::process::exit(1);
@@ -1175,6 +2915,7 @@ async fn run_cli_logs(unit_id: &str, follow: bool) -> io::Result<()> {
 
     let units = config.to_units();
     let unit_ids: Vec<String> = units.iter().map(|u| u.id.clone()).collect();
+    let timestamps = LogTimestamps::from_config(&config);
 
     if !unit_ids.contains(&unit_id.to_string()) {
         eprintln!(
@@ -1185,14 +2926,26 @@ async fn run_cli_logs(unit_id: &str, follow: bool) -> io::Result<()> {
         std::process::exit(1);
     }
 
-    println!("Loaded config from: {}", path.display());
-    println!("Streaming logs for '{}'... (Ctrl+C to stop)\n", unit_id);
+    // Keep stdout to one JSON object per line for `--format jsonl`
+    if formatter.as_ref().is_some_and(|f| f.is_json_lines()) {
+        eprintln!("Loaded config from: {}", path.display());
+        eprintln!("Streaming logs for '{}'... (Ctrl+C to stop)\n", unit_id);
+    } else {
+        println!("Loaded config from: {}", path.display());
+        println!("Streaming logs for '{}'... (Ctrl+C to stop)\n", unit_id);
+    }
 
     // Set up adapter
     let (cmd_tx, cmd_rx) = mpsc::channel::<AdapterCommand>(100);
     let (event_tx, mut event_rx) = broadcast::channel::<AdapterEvent>(1_000);
 
-    let mut adapter = ProcessAdapter::new();
+    let cmd_rx = adapters::split_unit_commands(
+        cmd_rx,
+        &units,
+        event_tx.clone(),
+        config.global_env_file.clone(),
+    );
+    let mut adapter = ProcessAdapter::new().with_global_env_file(config.global_env_file.clone());
     let units_clone = units.clone();
     tokio::spawn(async move {
         adapter.run(cmd_rx, event_tx, units_clone).await;
@@ -1218,7 +2971,20 @@ async fn run_cli_logs(unit_id: &str, follow: bool) -> io::Result<()> {
             event = event_rx.recv() => {
                 if let Ok(event) = event {
                     match event {
-                        AdapterEvent::LogLine { id, stream, text } if id == unit_id_owned => {
+                        AdapterEvent::LogLine { id, stream, text }
+                            if id == unit_id_owned && filter.matches(&stream, &text) =>
+                        {
+                            if let Some(formatter) = &formatter {
+                                let line = LogLine {
+                                    at: SystemTime::now(),
+                                    service_id: id,
+                                    stream,
+                                    text,
+                                    repeat_count: 1,
+                                };
+                                println!("{}", formatter.format(&line, &timestamps.format(line.at)));
+                                continue;
+                            }
                             let prefix = match stream {
                                 LogStream::Stdout => "",
                                 LogStream::Stderr => "\x1b[33m[stderr]\x1b[0m ",
@@ -1229,7 +2995,12 @@ async fn run_cli_logs(unit_id: &str, follow: bool) -> io::Result<()> {
                         AdapterEvent::StatusChanged { id, status } if id == unit_id_owned => {
                             match status {
                                 orkesy_core::unit::UnitStatus::Exited { code } => {
-                                    println!("\n\x1b[33mProcess exited with code: {:?}\x1b[0m", code);
+                                    let message = format!("\n\x1b[33mProcess exited with code: {:?}\x1b[0m", code);
+                                    if formatter.as_ref().is_some_and(|f| f.is_json_lines()) {
+                                        eprintln!("{}", message);
+                                    } else {
+                                        println!("{}", message);
+                                    }
                                     if !follow {
                                         break;
                                     }
@@ -1283,18 +3054,451 @@ async fn run_cli_exec(unit_id: &str, cmd: Vec<String>) -> io::Result<()> {
         command.env(k, v);
     }
 
-    let status = command.status().await?;
+    let status = command.status().await?;
+
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
+
+/// How long `orkesy run` waits for a dependency it started to become ready
+const DEPENDENCY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Prints each detected command's id and what it runs, by id
+async fn run_cli_run_list() -> io::Result<()> {
+    let cwd = std::env::current_dir()?;
+    let project = detectors::index_project(&cwd).await;
+    if project.commands.is_empty() {
+        println!("No commands detected in {}", cwd.display());
+        return Ok(());
+    }
+
+    let width = project
+        .commands
+        .keys()
+        .map(|id| id.len())
+        .max()
+        .unwrap_or(0);
+    for (id, spec) in &project.commands {
+        println!("{:width$}  {}", id, spec.command, width = width);
+    }
+    Ok(())
+}
+
+async fn run_cli_run(command_id: &str, args: &[String], check_deps: bool) -> io::Result<()> {
+    let cwd = std::env::current_dir()?;
+    let project = detectors::index_project(&cwd).await;
+
+    let Some(spec) = project.commands.get(command_id) else {
+        let ids: Vec<&str> = project.commands.keys().map(|id| id.as_str()).collect();
+        eprintln!(
+            "Error: Unknown command '{}'. Available: {}",
+            command_id,
+            ids.join(", ")
+        );
+        std::process::exit(1);
+    };
+
+    let config = try_load_config().map(|(_, config)| config);
+    let mut spec = spec.clone();
+    if let Some(config) = &config {
+        config.configure_command(&mut spec);
+    }
+    for arg in args {
+        spec.command.push(' ');
+        spec.command.push_str(&commands::shell_quote(arg));
+    }
+
+    let mut started = None;
+    if check_deps && let Some(config) = &config {
+        let deps = config
+            .commands
+            .get(command_id)
+            .map(|c| c.depends_on_ready.clone())
+            .unwrap_or_default();
+        if !deps.is_empty() {
+            match ensure_dependencies_running(config, &cwd, &deps).await {
+                Ok(deps) => started = deps,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    let (command_line, env_file) =
+        runner::prepare_command(&spec, "cli").map_err(io::Error::other)?;
+    println!("$ {}", command_line);
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut c = tokio::process::Command::new("sh");
+        c.arg("-c").arg(&command_line);
+        c
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut c = tokio::process::Command::new("cmd");
+        c.args(["/C", &command_line]);
+        c
+    };
+    if let Some(cwd) = &spec.cwd {
+        command.current_dir(cwd);
+    }
+    runner::apply_env(&mut command, &spec);
+
+    // Ctrl+C reaches the command; orkesy stays up to stop the dependencies
+    if started.is_some() {
+        tokio::spawn(async { while tokio::signal::ctrl_c().await.is_ok() {} });
+    }
+    let status = command.status().await;
+    if let Some(path) = env_file {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Some(started) = started {
+        started.stop().await;
+    }
+    let status = status?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => eprintln!("{} exited with code {}", command_id, code),
+            None => eprintln!("{} was terminated by a signal", command_id),
+        }
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
+
+/// Dependencies `orkesy run` started in its own adapter; they are stopped
+/// once the command exits
+struct StartedDependencies {
+    ids: Vec<String>,
+    cmd_tx: mpsc::Sender<AdapterCommand>,
+    adapter: tokio::task::JoinHandle<()>,
+}
+
+impl StartedDependencies {
+    async fn stop(self) {
+        println!("Stopping {}...", self.ids.join(", "));
+        let _ = self.cmd_tx.send(AdapterCommand::Shutdown).await;
+        let _ = self.adapter.await;
+    }
+}
+
+/// Offers to start dependencies that have no running process, then waits
+/// until they are running (and their port accepts connections, if set).
+/// They are started by the project's daemon when one is running; otherwise
+/// they are returned to be stopped when the command is done.
+async fn ensure_dependencies_running(
+    config: &OrkesyConfig,
+    cwd: &Path,
+    deps: &[String],
+) -> Result<Option<StartedDependencies>, String> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let units = config.to_units();
+    let running = commands::running_units(&units);
+
+    let mut to_start = Vec::new();
+    for dep in deps.iter().filter(|d| !running.contains(*d)) {
+        if !io::stdin().is_terminal() {
+            return Err(format!(
+                "{} is not running (start it with `orkesy up {}` or pass --check-deps false)",
+                dep, dep
+            ));
+        }
+        print!("{} is not running. Start it first? [Y/n] ", dep);
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .map_err(|e| e.to_string())?;
+        match answer.trim().to_lowercase().as_str() {
+            "" | "y" | "yes" => to_start.push(dep.clone()),
+            _ => eprintln!("Continuing without {}", dep),
+        }
+    }
+
+    if to_start.is_empty() {
+        return Ok(None);
+    }
+
+    let deadline = tokio::time::Instant::now() + DEPENDENCY_READY_TIMEOUT;
+    #[cfg(unix)]
+    if let Some(socket) = daemon::running_daemon(config, cwd).await {
+        start_dependencies_in_daemon(&socket, &to_start, deadline).await?;
+        wait_for_dependency_ports(&units, &to_start, deadline).await?;
+        return Ok(None);
+    }
+    let _ = cwd;
+
+    let (cmd_tx, cmd_rx) = mpsc::channel::<AdapterCommand>(100);
+    let (event_tx, mut event_rx) = broadcast::channel::<AdapterEvent>(1_000);
+    let cmd_rx = adapters::split_unit_commands(
+        cmd_rx,
+        &units,
+        event_tx.clone(),
+        config.global_env_file.clone(),
+    );
+    let mut adapter = ProcessAdapter::new().with_global_env_file(config.global_env_file.clone());
+    let units_for_adapter = units.clone();
+    let adapter = tokio::spawn(async move {
+        adapter.run(cmd_rx, event_tx, units_for_adapter).await;
+    });
+    let started = StartedDependencies {
+        ids: to_start.clone(),
+        cmd_tx,
+        adapter,
+    };
+
+    for id in &to_start {
+        println!("Starting {} (stopped again when the command exits)...", id);
+        let _ = started
+            .cmd_tx
+            .send(AdapterCommand::Start { id: id.clone() })
+            .await;
+    }
+
+    let mut pending: BTreeSet<String> = to_start.iter().cloned().collect();
+    let mut result = Ok(());
+    while !pending.is_empty() && result.is_ok() {
+        let event = match tokio::time::timeout_at(deadline, event_rx.recv()).await {
+            Ok(event) => event,
+            Err(_) => {
+                let ids: Vec<_> = pending.iter().cloned().collect();
+                result = Err(format!("timed out waiting for {} to start", ids.join(", ")));
+                break;
+            }
+        };
+        let Ok(AdapterEvent::StatusChanged { id, status }) = event else {
+            continue;
+        };
+        if !pending.contains(&id) {
+            continue;
+        }
+        match status {
+            AdapterUnitStatus::Running => {
+                pending.remove(&id);
+            }
+            AdapterUnitStatus::Exited { code } => {
+                result = Err(format!("{} exited during startup (code {:?})", id, code));
+            }
+            AdapterUnitStatus::Errored { message } => {
+                result = Err(format!("{} failed to start: {}", id, message));
+            }
+            _ => {}
+        }
+    }
+    if result.is_ok() {
+        result = wait_for_dependency_ports(&units, &to_start, deadline).await;
+    }
+
+    match result {
+        Ok(()) => Ok(Some(started)),
+        Err(e) => {
+            started.stop().await;
+            Err(e)
+        }
+    }
+}
+
+/// Asks the daemon on `socket` to start `ids` and waits until it reports
+/// them running
+#[cfg(unix)]
+async fn start_dependencies_in_daemon(
+    socket: &Path,
+    ids: &[String],
+    deadline: tokio::time::Instant,
+) -> Result<(), String> {
+    for id in ids {
+        println!("Starting {} in the running daemon...", id);
+        daemon::call(socket, "start", serde_json::json!({ "unit": id })).await?;
+    }
+    let mut pending: BTreeSet<String> = ids.iter().cloned().collect();
+    while !pending.is_empty() {
+        if tokio::time::Instant::now() >= deadline {
+            let ids: Vec<_> = pending.into_iter().collect();
+            return Err(format!("timed out waiting for {} to start", ids.join(", ")));
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let statuses = daemon::call(socket, "status", serde_json::Value::Null).await?;
+        for unit in statuses.as_array().into_iter().flatten() {
+            let Some(id) = unit["id"].as_str().filter(|id| pending.contains(*id)) else {
+                continue;
+            };
+            match unit["status"].as_str() {
+                Some("running") => {
+                    pending.remove(id);
+                }
+                Some("exited") => {
+                    return Err(format!(
+                        "{} exited during startup (code {})",
+                        id, unit["code"]
+                    ));
+                }
+                Some("errored") => {
+                    return Err(format!(
+                        "{} failed to start: {}",
+                        id,
+                        unit["message"].as_str().unwrap_or_default()
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Waits until the `port` of each of `ids` that sets one accepts
+/// connections; a running process is not necessarily listening yet
+async fn wait_for_dependency_ports(
+    units: &[Unit],
+    ids: &[String],
+    deadline: tokio::time::Instant,
+) -> Result<(), String> {
+    for unit in units.iter().filter(|u| ids.contains(&u.id)) {
+        let Some(port) = unit.port else {
+            continue;
+        };
+        let addr = format!("{}:{}", unit.host(), port);
+        loop {
+            if tokio::net::TcpStream::connect(&addr).await.is_ok() {
+                break;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(format!(
+                    "timed out waiting for {} on port {}",
+                    unit.id, port
+                ));
+            }
+            tokio::time::sleep(health::PORT_POLL_INTERVAL).await;
+        }
+        println!("{} is ready", unit.id);
+    }
+    Ok(())
+}
+
+/// Stops `unit` from a CLI command: through the daemon when one runs the
+/// project, otherwise by signalling its processes. Returns whether it no
+/// longer runs.
+async fn stop_unit_outside_tui(
+    config: &OrkesyConfig,
+    cwd: &Path,
+    unit: &Unit,
+) -> Result<bool, String> {
+    #[cfg(unix)]
+    if let Some(socket) = daemon::running_daemon(config, cwd).await {
+        let params = serde_json::json!({ "unit": unit.id });
+        daemon::call(&socket, "stop", params.clone()).await?;
+        let deadline = tokio::time::Instant::now()
+            + Duration::from_millis(unit.stop_timeout_ms)
+            + Duration::from_secs(1);
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let status = daemon::call(&socket, "status", params.clone()).await?;
+            let running = status[0]["status"]
+                .as_str()
+                .is_some_and(|s| matches!(s, "running" | "stopping" | "starting"));
+            if !running {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+    let _ = (config, cwd);
+    Ok(commands::stop_unit_processes(unit).await)
+}
+
+/// `orkesy service rm`: confirms, stops the unit if it is running, then
+/// writes the config without it and its edges.
+async fn run_cli_service_rm(unit: &str, stop_first: bool, yes: bool) -> Result<(), String> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (path, config) = profile::discover_config(&cwd).map_err(|e| e.to_string())?;
+    if OrkesyConfig::is_toml(&path) {
+        return Err(format!(
+            "{} is a TOML config; only YAML configs can be edited",
+            path.display()
+        ));
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let removal = remove_service(&content, unit).map_err(|e| e.to_string())?;
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            return Err("refusing to remove without confirmation (pass --yes)".to_string());
+        }
+        print!(
+            "Remove unit '{}' and all its edges? This cannot be undone. [y/N] ",
+            unit
+        );
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .map_err(|e| e.to_string())?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Aborted");
+            return Ok(());
+        }
+    }
+
+    let units = config.to_units();
+    if stop_first && commands::running_units(&units).contains(unit) {
+        println!("Stopping {}...", unit);
+        let target = units.iter().find(|u| u.id == unit);
+        let stopped = match target {
+            Some(target) => stop_unit_outside_tui(&config, &cwd, target).await?,
+            None => true,
+        };
+        if !stopped {
+            return Err(format!(
+                "{} is still running; stop it and run the command again (the config was not changed)",
+                unit
+            ));
+        }
+    }
+
+    std::fs::write(&path, &removal.content).map_err(|e| e.to_string())?;
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+    let edges: Vec<String> = removal
+        .edges
+        .iter()
+        .map(|(from, to)| format!("{}→{}", from, to))
+        .collect();
+    match edges.len() {
+        0 => println!("Removed unit '{}'", unit),
+        n => println!(
+            "Removed unit '{}' and {} edge{} ({})",
+            unit,
+            n,
+            if n == 1 { "" } else { "s" },
+            edges.join(", ")
+        ),
+    }
+    if !removal.preserved_formatting {
+        println!(
+            "Note: {} was re-serialized, so its comments were not kept",
+            path.display()
+        );
     }
-
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
     let cli = Cli::parse();
+    if let Some(profile) = cli.profile.clone() {
+        profile::select_profile(profile);
+    }
 
     // Handle subcommands
     match cli.command {
@@ -1305,24 +3509,53 @@ async fn main() -> io::Result<()> {
                 std::process::exit(1);
             }
         },
-        Some(Commands::Doctor) => match commands::run_doctor() {
+        Some(Commands::Doctor { timeout_secs }) => match commands::run_doctor(timeout_secs) {
             Ok(()) => return Ok(()),
             Err(e) => {
                 eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
         },
-        Some(Commands::Up { units }) => {
+        Some(Commands::Up {
+            units,
+            dry_run,
+            fail_on_port_conflict,
+        }) => {
+            if dry_run {
+                return run_cli_dry_run(CliAction::Start, units, fail_on_port_conflict).await;
+            }
             return run_cli_command(CliAction::Start, units).await;
         }
-        Some(Commands::Down { units }) => {
+        Some(Commands::Down { units, dry_run }) => {
+            if dry_run {
+                return run_cli_dry_run(CliAction::Stop, units, false).await;
+            }
             return run_cli_command(CliAction::Stop, units).await;
         }
-        Some(Commands::Restart { units }) => {
+        Some(Commands::Restart { units, dry_run }) => {
+            if dry_run {
+                return run_cli_dry_run(CliAction::Restart, units, false).await;
+            }
             return run_cli_command(CliAction::Restart, units).await;
         }
-        Some(Commands::Logs { unit, follow }) => {
-            return run_cli_logs(&unit, follow).await;
+        Some(Commands::Logs {
+            unit,
+            follow,
+            grep,
+            and,
+            invert,
+            stream,
+            format,
+        }) => {
+            let filter = match CliLogFilter::new(&grep, and, invert, stream.as_deref()) {
+                Ok(filter) => filter,
+                Err(e) => {
+                    eprintln!("Error: invalid --grep pattern: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let formatter = format.as_deref().map(commands::LogFormatter::new);
+            return run_cli_logs(&unit, follow, filter, formatter).await;
         }
         Some(Commands::Install { units }) => {
             return run_cli_command(CliAction::Install, units).await;
@@ -1330,30 +3563,178 @@ async fn main() -> io::Result<()> {
         Some(Commands::Exec { unit, cmd }) => {
             return run_cli_exec(&unit, cmd).await;
         }
-        Some(Commands::Tui) | None => {
+        Some(Commands::Fork { from, to, args }) => {
+            let fork = match UnitFork::new(from, to, &args) {
+                Ok(fork) => fork,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            return run_tui(Some(fork), None).await;
+        }
+        Some(Commands::Run {
+            command_id,
+            args,
+            list,
+            check_deps,
+        }) => {
+            return match command_id {
+                Some(command_id) if !list => run_cli_run(&command_id, &args, check_deps).await,
+                _ => run_cli_run_list().await,
+            };
+        }
+        Some(Commands::Env {
+            unit,
+            export,
+            format,
+            reveal_secrets,
+        }) => match commands::run_env(&unit, export, format, reveal_secrets) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Export { output }) => match commands::run_export(output.as_deref()) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        #[cfg(unix)]
+        Some(Commands::Daemon {
+            socket,
+            metrics_port,
+        }) => match daemon::run_daemon(socket, metrics_port).await {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Import {
+            source,
+            file,
+            output,
+            yes,
+        }) => match commands::run_import(source, file, &output, yes) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Metrics {
+            command:
+                MetricsCommand::Export {
+                    format,
+                    once,
+                    interval_ms,
+                },
+        }) => match commands::run_metrics_export(format, once, interval_ms) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Config {
+            command: ConfigCommand::Validate { json },
+        }) => match commands::run_config_validate(json) {
+            Ok(0) => return Ok(()),
+            Ok(_) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Service {
+            command:
+                ServiceCommand::Remove {
+                    unit,
+                    stop_first,
+                    yes,
+                },
+        }) => {
+            if let Err(e) = run_cli_service_rm(&unit, stop_first, yes).await {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Status { format }) => match commands::run_status(format) {
+            Ok(false) => return Ok(()),
+            Ok(true) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Wait {
+            unit,
+            status,
+            timeout_secs,
+        }) => match commands::run_wait(&unit, &status, timeout_secs) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Ps {
+            watch,
+            interval_ms,
+            no_color,
+        }) => match commands::run_ps(watch, interval_ms, no_color) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Version { check_update }) => match commands::run_version(check_update) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Tui { metrics_port }) => return run_tui(None, metrics_port).await,
+        None => {
             // Fall through to TUI
         }
     }
 
     // Run TUI
-    run_tui().await
+    run_tui(None, None).await
 }
 
-async fn run_tui() -> io::Result<()> {
+async fn run_tui(fork: Option<UnitFork>, metrics_port: Option<u16>) -> io::Result<()> {
     // Track when we started for uptime display
     let start_time = std::time::Instant::now();
 
-    // Event channel for reducer (using RuntimeEvent for TUI compatibility)
-    let (event_tx, _) = broadcast::channel::<EventEnvelope>(1_000);
+    // Event channel for reducer (using RuntimeEvent for TUI compatibility).
+    // The reducer's receiver exists from the start, so events sent while the
+    // backends spawn (first health probes, their warnings) wait for it.
+    let (event_tx, mut reducer_rx) = broadcast::channel::<EventEnvelope>(1_000);
 
     // Try to load config, fall back to demo mode
-    let (graph, backend, autostart_ids, units_map, project_name): (
+    let loaded_config = try_load_config();
+    let command_config = loaded_config.as_ref().map(|(_, config)| config.clone());
+    let groups = command_config
+        .as_ref()
+        .map(|config| config.groups.clone())
+        .unwrap_or_default();
+    let (graph, backend, autostart_ids, units_map, project_name, log_timestamps): (
         RuntimeGraph,
         RuntimeBackend,
         Vec<String>,
         BTreeMap<String, Unit>,
         String,
-    ) = match try_load_config() {
+        LogTimestamps,
+    ) = match loaded_config {
         Some((path, config)) => {
             eprintln!("Loaded config from: {}", path.display());
             let proj_name = config
@@ -1362,8 +3743,14 @@ async fn run_tui() -> io::Result<()> {
                 .unwrap_or_else(|| "orkesy".to_string());
 
             // Get units and edges from config
-            let units = config.to_units();
-            let edges = config.to_edges();
+            let mut units = config.to_units();
+            let mut edges = config.to_edges();
+            if let Some(fork) = &fork
+                && let Err(e) = fork.apply(&mut units, &mut edges)
+            {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
             let graph = units_to_graph(&units, &edges);
 
             // Store units by ID for Inspect view
@@ -1382,9 +3769,36 @@ async fn run_tui() -> io::Result<()> {
             let (adapter_event_tx, mut adapter_event_rx) =
                 broadcast::channel::<AdapterEvent>(1_000);
 
+            // Docker and ssh units go to their own adapters
+            let adapter_cmd_rx = adapters::split_unit_commands(
+                adapter_cmd_rx,
+                &units,
+                adapter_event_tx.clone(),
+                command_config
+                    .as_ref()
+                    .and_then(|c| c.global_env_file.clone()),
+            );
+
             // Spawn adapter
-            let mut adapter = ProcessAdapter::new();
+            let mut adapter = ProcessAdapter::new()
+                .with_edges(edges.clone())
+                .with_global_env_file(
+                    command_config
+                        .as_ref()
+                        .and_then(|c| c.global_env_file.clone()),
+                );
             let units_for_health = units.clone();
+            let log_options: BTreeMap<String, UnitLogOptions> = units
+                .iter()
+                .map(|u| {
+                    let options = UnitLogOptions {
+                        buffer_size: u.log_buffer_size,
+                        deduplicate: u.deduplicate_logs,
+                    };
+                    (u.id.clone(), options)
+                })
+                .filter(|(_, options)| *options != UnitLogOptions::default())
+                .collect();
             tokio::spawn(async move {
                 adapter.run(adapter_cmd_rx, adapter_event_tx, units).await;
             });
@@ -1392,20 +3806,61 @@ async fn run_tui() -> io::Result<()> {
             // Spawn health checkers for units with health config
             let health_event_tx = event_tx.clone();
             let next_health_id = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(1_000_000));
-            health::spawn_health_checkers(&units_for_health, health_event_tx, next_health_id);
+            health::spawn_health_checkers(
+                &units_for_health,
+                health_event_tx.clone(),
+                next_health_id.clone(),
+            );
+            health::spawn_port_pollers(&units_for_health, health_event_tx, next_health_id);
+
+            // Units stay Starting until their port binds or `ready_when` passes
+            let gated: BTreeSet<String> = units_for_health
+                .iter()
+                .filter(|u| u.gates_running())
+                .map(|u| u.id.clone())
+                .collect();
 
             // Bridge adapter events to runtime events
             let event_tx_clone = event_tx.clone();
+            let fork_id = fork.as_ref().map(|f| f.to.clone());
             tokio::spawn(async move {
                 let mut event_id = 1u64;
                 while let Ok(adapter_event) = adapter_event_rx.recv().await {
-                    let runtime_event = adapter_event_to_runtime(adapter_event);
+                    // A fork leaves the graph once it exits or is stopped
+                    let removed = match &adapter_event {
+                        AdapterEvent::StatusChanged {
+                            id,
+                            status:
+                                AdapterUnitStatus::Stopped
+                                | AdapterUnitStatus::Exited { .. }
+                                | AdapterUnitStatus::Errored { .. },
+                        } if fork_id.as_ref() == Some(id) => Some(id.clone()),
+                        _ => None,
+                    };
+                    let runtime_event = match adapter_event_to_runtime(adapter_event) {
+                        RuntimeEvent::StatusChanged {
+                            id,
+                            status: ServiceStatus::Running,
+                        } if gated.contains(&id) => RuntimeEvent::StatusChanged {
+                            id,
+                            status: ServiceStatus::Starting,
+                        },
+                        event => event,
+                    };
                     let _ = event_tx_clone.send(EventEnvelope {
                         id: event_id,
                         at: std::time::SystemTime::now(),
                         event: runtime_event,
                     });
                     event_id += 1;
+                    if let Some(id) = removed {
+                        let _ = event_tx_clone.send(EventEnvelope {
+                            id: event_id,
+                            at: std::time::SystemTime::now(),
+                            event: RuntimeEvent::UnitRemoved { id },
+                        });
+                        event_id += 1;
+                    }
                 }
             });
 
@@ -1415,6 +3870,7 @@ async fn run_tui() -> io::Result<()> {
                 at: std::time::SystemTime::now(),
                 event: RuntimeEvent::TopologyLoaded {
                     graph: graph.clone(),
+                    log_options,
                 },
             });
 
@@ -1426,8 +3882,13 @@ async fn run_tui() -> io::Result<()> {
                 autostart_ids,
                 units_map,
                 proj_name,
+                LogTimestamps::from_config(&config),
             )
         }
+        None if fork.is_some() => {
+            eprintln!("Error: orkesy fork needs an orkesy.yml");
+            std::process::exit(1);
+        }
         None => {
             eprintln!("No orkesy.yml found, running in demo mode with fake engine");
             let graph = demo_graph();
@@ -1455,18 +3916,11 @@ async fn run_tui() -> io::Result<()> {
                 vec![],
                 units_map,
                 "demo".to_string(),
+                LogTimestamps::default(),
             )
         }
     };
 
-    // Autostart units that have autostart: true
-    if !autostart_ids.is_empty() {
-        eprintln!("Auto-starting {} unit(s)...", autostart_ids.len());
-        for id in autostart_ids {
-            backend.send_start(id).await;
-        }
-    }
-
     // Index project for Commands + Runs feature
     let cwd = std::env::current_dir().unwrap_or_default();
     eprintln!("Indexing project at: {}", cwd.display());
@@ -1481,11 +3935,40 @@ async fn run_tui() -> io::Result<()> {
     let (runner_cmd_tx, runner_cmd_rx) = mpsc::channel::<runner::RunnerCommand>(100);
     let runner_event_tx = event_tx.clone();
     tokio::spawn(async move {
-        let mut cmd_runner = runner::CommandRunner::new();
+        let mut cmd_runner = runner::CommandRunner::new().with_config(command_config);
         cmd_runner.run(runner_cmd_rx, runner_event_tx).await;
     });
 
-    let state = Arc::new(RwLock::new(RuntimeState::new(graph.clone())));
+    let mut initial_state = RuntimeState::new(graph.clone());
+    initial_state.groups = groups;
+    initial_state.health_history_sizes = units_map
+        .values()
+        .filter_map(|u| Some((u.id.clone(), u.health_history_size?)))
+        .collect();
+    let state = Arc::new(RwLock::new(initial_state));
+
+    // Reducer task
+    let state_for_reducer = state.clone();
+    tokio::spawn(async move {
+        while let Ok(env) = reducer_rx.recv().await {
+            let mut s = state_for_reducer.write().await;
+            reduce(&mut s, &env);
+        }
+    });
+
+    // Autostart units that have autostart: true (after the reducer subscribed so
+    // their first log lines aren't missed)
+    if !autostart_ids.is_empty() {
+        eprintln!("Auto-starting {} unit(s)...", autostart_ids.len());
+        backend.send_start_many(autostart_ids).await;
+    }
+
+    // Reindex when package.json, Cargo.toml, ... change
+    if let Err(e) =
+        detectors::spawn_project_watcher(&cwd, project_index.file_hashes.clone(), event_tx.clone())
+    {
+        eprintln!("Warning: not watching project files: {}", e);
+    }
 
     // Emit ProjectIndexed event
     let _ = event_tx.send(EventEnvelope {
@@ -1496,24 +3979,24 @@ async fn run_tui() -> io::Result<()> {
         },
     });
 
-    // Reducer task
-    let state_for_reducer = state.clone();
-    let mut reducer_rx = event_tx.subscribe();
-    tokio::spawn(async move {
-        while let Ok(env) = reducer_rx.recv().await {
-            let mut s = state_for_reducer.write().await;
-            reduce(&mut s, &env);
-        }
-    });
+    // Metrics sampler task (collects system stats, log and I/O rates); its
+    // interval follows the views on screen
+    let (sampler_config, sampler_config_rx) = watch::channel(SamplerConfig::default());
+    sampler::spawn_sampler(event_tx.clone(), state.clone(), sampler_config_rx);
 
-    // Metrics sampler task (collects system stats + log rates every 500ms)
-    sampler::spawn_sampler(event_tx.clone(), state.clone());
+    if let Some(port) = metrics_port {
+        match metrics_server::spawn_metrics_server(port, state.clone()).await {
+            Ok(addr) => eprintln!("Serving metrics on http://{}/metrics", addr),
+            Err(e) => eprintln!("Warning: not serving metrics on port {}: {}", port, e),
+        }
+    }
 
     let mut terminal = setup_terminal()?;
     let mut selected = 0usize;
     let mut list_state = ListState::default();
     list_state.select(Some(selected));
 
+    let status_events = event_tx.subscribe();
     let res = tui_loop(
         &mut terminal,
         state,
@@ -1524,24 +4007,84 @@ async fn run_tui() -> io::Result<()> {
         runner_cmd_tx,
         &project_name,
         start_time,
+        log_timestamps,
+        fork.as_ref().map(|f| f.to.as_str()),
+        status_events,
+        sampler_config,
     )
     .await;
     restore_terminal(terminal)?;
     res
 }
 
+/// Commands run only once the user answers `y` to `prompt`
+#[derive(Clone, Debug)]
+struct PendingConfirm {
+    actions: Vec<TuiCommand>,
+    prompt: String,
+}
+
+impl PendingConfirm {
+    /// "Kill all 5 services? [y/N]" for `verb` "Kill"
+    fn all_services(verb: &str, service_ids: &[String], action: fn(String) -> TuiCommand) -> Self {
+        Self {
+            actions: service_ids.iter().cloned().map(action).collect(),
+            prompt: format!("{} all {} services? [y/N]", verb, service_ids.len()),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 enum TuiCommand {
-    Start { id: String },
-    Stop { id: String },
-    Restart { id: String },
-    Kill { id: String },
-    Toggle { id: String },
-    ClearLogs { id: String },
-    Exec { id: String, cmd: Vec<String> },
+    Start {
+        id: String,
+    },
+    Stop {
+        id: String,
+    },
+    Restart {
+        id: String,
+    },
+    Kill {
+        id: String,
+    },
+    Toggle {
+        id: String,
+    },
+    ClearLogs {
+        id: String,
+    },
+    Exec {
+        id: String,
+        cmd: Vec<String>,
+    },
+    Watch {
+        id: String,
+        metric: String,
+        op: CompareOp,
+        threshold: f64,
+        then: Box<TuiCommand>,
+    },
+    CancelWatch {
+        watch_id: String,
+    },
 }
 
 impl TuiCommand {
+    /// The adapter form of a unit action; `None` for watch commands
+    fn into_adapter_command(self) -> Option<AdapterCommand> {
+        Some(match self {
+            TuiCommand::Start { id } => AdapterCommand::Start { id },
+            TuiCommand::Stop { id } => AdapterCommand::Stop { id },
+            TuiCommand::Restart { id } => AdapterCommand::Restart { id },
+            TuiCommand::Kill { id } => AdapterCommand::Kill { id },
+            TuiCommand::Toggle { id } => AdapterCommand::Toggle { id },
+            TuiCommand::ClearLogs { id } => AdapterCommand::ClearLogs { id },
+            TuiCommand::Exec { id, cmd } => AdapterCommand::Exec { id, cmd },
+            TuiCommand::Watch { .. } | TuiCommand::CancelWatch { .. } => return None,
+        })
+    }
+
     async fn execute(self, backend: &RuntimeBackend) {
         match self {
             TuiCommand::Start { id } => backend.send_start(id).await,
@@ -1551,11 +4094,27 @@ impl TuiCommand {
             TuiCommand::Toggle { id } => backend.send_toggle(id).await,
             TuiCommand::ClearLogs { id } => backend.send_clear_logs(id).await,
             TuiCommand::Exec { id, cmd } => backend.send_exec(id, cmd).await,
+            TuiCommand::Watch {
+                id,
+                metric,
+                op,
+                threshold,
+                then,
+            } => {
+                if let Some(then) = then.into_adapter_command() {
+                    backend.send_watch(id, metric, op, threshold, then).await;
+                }
+            }
+            TuiCommand::CancelWatch { watch_id } => backend.send_cancel_watch(watch_id).await,
         }
     }
 }
 
-fn parse_command(input: &str, service_ids: &[String]) -> Result<Vec<TuiCommand>, String> {
+fn parse_command(
+    input: &str,
+    service_ids: &[String],
+    groups: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<TuiCommand>, String> {
     let parts: Vec<&str> = input.split_whitespace().collect();
     if parts.is_empty() {
         return Err("Empty command".into());
@@ -1570,8 +4129,9 @@ fn parse_command(input: &str, service_ids: &[String]) -> Result<Vec<TuiCommand>,
         match arg {
             Some("all") => Ok(service_ids.to_vec()),
             Some(id) if exists(id) => Ok(vec![id.to_string()]),
-            Some(id) => Err(format!("Unknown service: {id}")),
-            None => Err("Missing target (service id or 'all')".into()),
+            Some(name) if groups.contains_key(name) => Ok(groups[name].clone()),
+            Some(id) => Err(format!("Unknown service or group: {id}")),
+            None => Err("Missing target (service id, group or 'all')".into()),
         }
     };
 
@@ -1630,8 +4190,56 @@ fn parse_command(input: &str, service_ids: &[String]) -> Result<Vec<TuiCommand>,
             }])
         }
 
+        "watch" => {
+            // watch <service> <metric> <op> <threshold> <start|stop|restart|kill|exec cmd...>
+            const USAGE: &str = "Usage: watch <service> <metric> <op> <threshold> <action>";
+            let (Some(svc), Some(metric), Some(op), Some(threshold), Some(action)) =
+                (arg1, parts.get(2), parts.get(3), parts.get(4), parts.get(5))
+            else {
+                return Err(USAGE.into());
+            };
+            if !exists(svc) {
+                return Err(format!("Unknown service: {svc}"));
+            }
+            let op = CompareOp::parse(op).ok_or("Operator must be >, <, >= or <=")?;
+            let threshold: f64 = threshold
+                .replace('_', "")
+                .parse()
+                .map_err(|_| format!("Invalid threshold: {threshold}"))?;
+            let id = svc.to_string();
+            let then = match *action {
+                "start" => TuiCommand::Start { id: id.clone() },
+                "stop" => TuiCommand::Stop { id: id.clone() },
+                "restart" => TuiCommand::Restart { id: id.clone() },
+                "kill" => TuiCommand::Kill { id: id.clone() },
+                "exec" if parts.len() > 6 => TuiCommand::Exec {
+                    id: id.clone(),
+                    cmd: parts[6..].iter().map(|s| s.to_string()).collect(),
+                },
+                _ => {
+                    return Err(format!(
+                        "{USAGE}\nActions: start/stop/restart/kill/exec <cmd...>"
+                    ));
+                }
+            };
+            Ok(vec![TuiCommand::Watch {
+                id,
+                metric: metric.to_string(),
+                op,
+                threshold,
+                then: Box::new(then),
+            }])
+        }
+
+        "unwatch" => {
+            let watch_id = arg1.ok_or("Usage: unwatch <watch-id>")?;
+            Ok(vec![TuiCommand::CancelWatch {
+                watch_id: watch_id.to_string(),
+            }])
+        }
+
         _ => Err(format!(
-            "Unknown command: {cmd}\nTry: up/down/restart/toggle/kill/clear/exec"
+            "Unknown command: {cmd}\nTry: up/down/restart/toggle/kill/clear/exec/watch/unwatch"
         )),
     }
 }
@@ -1646,38 +4254,128 @@ async fn tui_loop(
     runner_cmd_tx: mpsc::Sender<runner::RunnerCommand>,
     project_name: &str,
     start_time: std::time::Instant,
+    log_timestamps: LogTimestamps,
+    fork_id: Option<&str>,
+    mut events: broadcast::Receiver<EventEnvelope>,
+    sampler_config: watch::Sender<SamplerConfig>,
 ) -> io::Result<()> {
+    let state_path = ui_state_path(project_name);
     let mut ui = UiState::default();
+    if let Some(saved) = state_path.as_deref().and_then(|p| UiState::load(p).ok()) {
+        // Only restore when the saved unit is still configured; pins are
+        // always kept
+        let snapshot = state.read().await;
+        let ids = saved.unit_display_ids(&snapshot);
+        let rows = saved.unit_rows(&snapshot);
+        drop(snapshot);
+        let selected_unit = saved.selected_unit.as_deref();
+        if let Some(id) = selected_unit.filter(|id| ids.iter().any(|s| s == id)) {
+            // A unit in a collapsed group stays hidden; select "all" then
+            let position = unit_row_position(&rows, id).unwrap_or(0);
+            *selected = position;
+            list_state.select(Some(position));
+            ui = saved;
+        } else {
+            ui.pinned = saved.pinned;
+        }
+    }
+    ui.logs.timestamps = log_timestamps;
     let mut command_list_state = ListState::default();
     let mut run_list_state = ListState::default();
+    let mut group_list_state = ListState::default();
+    // Where the last frame drew the Units list and the logs, for mouse events
+    let mut units_area = Rect::default();
+    let mut logs_area: Option<Rect> = None;
+    let update_notice = commands::cached_update_notice();
+
+    // Last status seen per unit, so repeated events don't toast twice
+    let mut toasted_status: BTreeMap<ServiceId, ServiceStatus> = BTreeMap::new();
 
     loop {
+        // Toast units that became Running or Errored since the last frame
+        loop {
+            let env = match events.try_recv() {
+                Ok(env) => env,
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            let RuntimeEvent::StatusChanged { id, status } = env.event else {
+                continue;
+            };
+            if toasted_status.get(&id) == Some(&status) {
+                continue;
+            }
+            match &status {
+                ServiceStatus::Running => {
+                    ui.toasts.push(Toast::new(
+                        format!("{} is running", id),
+                        ToastLevel::Success,
+                    ));
+                }
+                ServiceStatus::Errored { message } => {
+                    ui.toasts.push(Toast::new(
+                        format!("{} errored: {}", id, message),
+                        ToastLevel::Error,
+                    ));
+                }
+                _ => {}
+            }
+            toasted_status.insert(id, status);
+        }
+        let now = Instant::now();
+        ui.toasts.retain(|toast| toast.expires_at > now);
+
         let snapshot = state.read().await;
 
         let mut service_ids: Vec<String> = snapshot.graph.nodes.keys().cloned().collect();
         service_ids.sort();
+        let groups = snapshot.groups.clone();
+        if ui.view != View::Logs {
+            ui.last_detail_view = ui.view.clone();
+        }
+
+        ui.batch_selection
+            .retain(|id| snapshot.graph.nodes.contains_key(id));
 
-        // Prepend "all" to the list for merged view
-        let mut display_ids = vec!["all".to_string()];
-        display_ids.extend(service_ids.iter().cloned());
+        // "all" (the merged view) first, then the groups and the sorted,
+        // filtered units
+        let unit_rows = ui.unit_rows(&snapshot);
 
-        if display_ids.is_empty() {
+        if unit_rows.is_empty() {
             *selected = 0;
             list_state.select(None);
         } else {
-            if *selected >= display_ids.len() {
-                *selected = display_ids.len() - 1;
+            if *selected >= unit_rows.len() {
+                *selected = unit_rows.len() - 1;
             }
             list_state.select(Some(*selected));
         }
 
-        let selected_id: Option<&str> = display_ids.get(*selected).map(|s| s.as_str());
+        let selected_id: Option<&str> = unit_rows.get(*selected).and_then(UnitRow::unit_id);
+        let recent_runs = recent_run_items(&snapshot);
 
         // Build items for Services mode with numeric indices
-        let service_items: Vec<ListItem> = display_ids
+        let service_items: Vec<ListItem> = unit_rows
             .iter()
             .enumerate()
-            .map(|(idx, id)| {
+            .map(|(idx, row)| {
+                let (id, nested) = match row {
+                    UnitRow::Group {
+                        name,
+                        members,
+                        expanded,
+                    } => {
+                        let arrow = if *expanded { "▼" } else { "▶" };
+                        return ListItem::new(Line::from(vec![
+                            Span::raw("   "),
+                            Span::styled(
+                                format!("{} {} ({})", arrow, name, members),
+                                styles::accent_bold(),
+                            ),
+                        ]));
+                    }
+                    UnitRow::Unit { id, nested } => (id, *nested),
+                };
                 // Display index (1-based for user, 0 is "all")
                 let index_str = if id == "all" {
                     "  ".to_string() // No index for "all"
@@ -1701,7 +4399,7 @@ async fn tui_loop(
                     ]))
                 } else {
                     let node = snapshot.graph.nodes.get(id).unwrap();
-                    let status_sym = status_icon(&node.observed.status);
+                    let status_sym = animated_status_icon(&node.observed.status, ui.spin_frame);
                     let health_sym = health_icon(&node.observed.health);
                     let kind_sym = kind_icon(&node.kind);
                     let port_info = node.port.map(|p| format!(":{}", p)).unwrap_or_default();
@@ -1724,10 +4422,36 @@ async fn tui_loop(
                         String::new()
                     };
 
+                    let warning_badge = if snapshot.warnings.contains_key(id) {
+                        "⚠ "
+                    } else {
+                        ""
+                    };
+
+                    let mark = if ui.batch_selection.contains(id) {
+                        "✓"
+                    } else {
+                        " "
+                    };
+
+                    let pin = if ui.pinned.contains(id) { "★ " } else { "" };
+
                     ListItem::new(Line::from(vec![
                         Span::styled(index_str, styles::text_muted()),
-                        Span::styled(format!(" {} ", status_sym), style),
+                        Span::raw(if nested { "  " } else { "" }),
+                        Span::styled(mark, styles::success()),
+                        Span::styled(format!("{} ", status_sym), style),
+                        Span::styled(warning_badge, styles::warn()),
+                        Span::styled(pin, Style::default().fg(Color::Yellow)),
                         Span::raw(format!("{} {}{} ", kind_sym, node.display_name, port_info)),
+                        Span::styled(
+                            if fork_id == Some(id.as_str()) {
+                                "[fork] "
+                            } else {
+                                ""
+                            },
+                            Style::default().fg(Color::Magenta),
+                        ),
                         Span::styled(format!("[{}]", status_label(&node.observed.status)), style),
                         Span::styled(metrics_info, Style::default().fg(Color::DarkGray)),
                         Span::raw(" "),
@@ -1760,8 +4484,8 @@ async fn tui_loop(
         };
 
         // Build items for Runs mode
-        let run_items: Vec<ListItem> = snapshot
-            .runs_ordered()
+        let visible_runs = ui.visible_runs(&snapshot);
+        let mut run_items: Vec<ListItem> = visible_runs
             .iter()
             .map(|run| {
                 let status_icon = run.status.icon();
@@ -1789,11 +4513,45 @@ async fn tui_loop(
                 ]))
             })
             .collect();
+        if let (true, Some(filter)) = (run_items.is_empty(), &ui.runs_filter) {
+            run_items.push(ListItem::new(Line::from(Span::styled(
+                format!("No {} runs (f to change filter)", filter.filter_label()),
+                Style::default().fg(Color::DarkGray),
+            ))));
+        }
+
+        // Build items for Groups mode
+        let mut group_items: Vec<ListItem> = groups
+            .iter()
+            .map(|(name, members)| {
+                let running = members
+                    .iter()
+                    .filter(|id| {
+                        snapshot
+                            .graph
+                            .nodes
+                            .get(*id)
+                            .is_some_and(|n| n.observed.status == ServiceStatus::Running)
+                    })
+                    .count();
+                ListItem::new(Line::from(vec![
+                    Span::styled(" ◈ ", Style::default().fg(Color::Magenta)),
+                    Span::raw(format!("{} ({}/{}) ", name, running, members.len())),
+                    Span::styled(members.join(", "), styles::text_muted()),
+                ]))
+            })
+            .collect();
+        if group_items.is_empty() {
+            group_items.push(ListItem::new(Line::from(Span::styled(
+                "No groups (add them under `groups` in orkesy.yml)",
+                Style::default().fg(Color::DarkGray),
+            ))));
+        }
 
         // Update list state selection based on mode (before borrowing)
         match ui.left_mode {
             LeftMode::Services => {
-                if !display_ids.is_empty() {
+                if !unit_rows.is_empty() {
                     list_state.select(Some(*selected));
                 }
             }
@@ -1811,12 +4569,28 @@ async fn tui_loop(
                 }
             }
             LeftMode::Runs => {
-                let run_count = snapshot.run_order.len();
-                if run_count > 0 {
-                    if ui.selected_run >= run_count {
-                        ui.selected_run = run_count.saturating_sub(1);
+                // `selected_run` indexes `run_order`; the list shows sorted, filtered runs
+                let selected_id = snapshot.run_order.get(ui.selected_run);
+                match visible_runs.iter().position(|r| Some(&r.id) == selected_id) {
+                    Some(pos) => run_list_state.select(Some(pos)),
+                    None => {
+                        if let Some(index) = visible_runs.first().and_then(|first| {
+                            snapshot.run_order.iter().position(|id| *id == first.id)
+                        }) {
+                            ui.selected_run = index;
+                            run_list_state.select(Some(0));
+                        } else {
+                            run_list_state.select(None);
+                        }
                     }
-                    run_list_state.select(Some(ui.selected_run));
+                }
+            }
+            LeftMode::Groups => {
+                if groups.is_empty() {
+                    group_list_state.select(None);
+                } else {
+                    ui.selected_group = ui.selected_group.min(groups.len() - 1);
+                    group_list_state.select(Some(ui.selected_group));
                 }
             }
         }
@@ -1826,9 +4600,15 @@ async fn tui_loop(
             LeftMode::Services => service_items,
             LeftMode::Commands => command_items,
             LeftMode::Runs => run_items,
+            LeftMode::Groups => group_items,
         };
 
         let build_logs = |id: &str| -> Vec<Line> {
+            // ` (×N)` after a line that arrived N times in a row
+            let repeat_span = |count: u32| match count {
+                0 | 1 => Span::raw(""),
+                n => Span::styled(format!(" (×{})", n), styles::text_muted()),
+            };
             // Helper to get service color for merged view
             let service_color = |svc: &str| -> Style {
                 // Hash-based color assignment for consistent colors
@@ -1866,6 +4646,7 @@ async fn tui_loop(
                             Span::styled(prefix, style),
                             Span::styled(stream_prefix, Style::default().fg(Color::DarkGray)),
                             Span::raw(&l.text),
+                            repeat_span(l.repeat_count),
                         ])
                     })
                     .collect()
@@ -1885,6 +4666,7 @@ async fn tui_loop(
                         Line::from(vec![
                             Span::styled(stream_prefix, Style::default().fg(Color::DarkGray)),
                             Span::raw(&l.text),
+                            repeat_span(l.repeat_count),
                         ])
                     })
                     .collect()
@@ -2034,6 +4816,29 @@ async fn tui_loop(
                 )]));
                 out.push(Line::from(""));
 
+                // Aggregate health of the running units, as a colored banner
+                let overall = snapshot.overall_health();
+                let (label, reason, color) = match &overall {
+                    HealthStatus::Healthy => ("♥ HEALTHY", None, Color::Green),
+                    HealthStatus::Degraded { reason } => {
+                        ("♡ DEGRADED", Some(reason), Color::Yellow)
+                    }
+                    HealthStatus::Unhealthy { reason } => ("✗ UNHEALTHY", Some(reason), Color::Red),
+                    HealthStatus::Unknown => ("NO HEALTH CHECKS RUNNING", None, Color::DarkGray),
+                };
+                let mut banner = vec![Span::styled(
+                    format!(" {} ", label),
+                    bold.fg(Color::Black).bg(color),
+                )];
+                if let Some(reason) = reason {
+                    banner.push(Span::styled(
+                        format!("  {}", reason),
+                        Style::default().fg(color),
+                    ));
+                }
+                out.push(Line::from(banner));
+                out.push(Line::from(""));
+
                 // Count statuses
                 let running = snapshot
                     .graph
@@ -2303,13 +5108,25 @@ async fn tui_loop(
                     if !unit.env.is_empty() {
                         out.push(Line::from(vec![Span::styled("  Env     ", dim)]));
                         for (k, v) in &unit.env {
-                            out.push(Line::from(vec![
-                                Span::styled("    ", dim),
-                                Span::raw(format!("{}={}", k, v)),
-                            ]));
+                            let mut spans =
+                                vec![Span::styled("    ", dim), Span::raw(format!("{}={}", k, v))];
+                            if let Some(source) = unit.inherited_env.get(k) {
+                                spans.push(Span::styled(format!(" [from: {}]", source), dim));
+                            }
+                            out.push(Line::from(spans));
                         }
                     }
 
+                    // Resource limits
+                    if let Some(limits) = unit.ulimits.as_ref().filter(|l| !l.is_empty()) {
+                        let limits: Vec<String> =
+                            limits.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                        out.push(Line::from(vec![
+                            Span::styled("  Ulimits ", dim),
+                            Span::raw(limits.join(" ")),
+                        ]));
+                    }
+
                     // Description
                     if let Some(desc) = &unit.description {
                         out.push(Line::from(vec![
@@ -2321,20 +5138,43 @@ async fn tui_loop(
                     // Health check config
                     if let Some(health) = &unit.health {
                         let health_cfg = match health {
-                            orkesy_core::unit::HealthCheck::Tcp { port, interval_ms } => {
+                            orkesy_core::unit::HealthCheck::Tcp {
+                                port, interval_ms, ..
+                            } => {
                                 format!("tcp:{} every {}ms", port, interval_ms)
                             }
                             orkesy_core::unit::HealthCheck::Http {
-                                url, interval_ms, ..
+                                url,
+                                method,
+                                interval_ms,
+                                ..
                             } => {
-                                format!("http {} every {}ms", url, interval_ms)
+                                format!(
+                                    "http {} {} every {}ms",
+                                    method.as_deref().unwrap_or("GET"),
+                                    url,
+                                    interval_ms
+                                )
                             }
                             orkesy_core::unit::HealthCheck::Exec {
                                 command,
                                 interval_ms,
+                                ..
                             } => {
                                 format!("exec \"{}\" every {}ms", command, interval_ms)
                             }
+                            orkesy_core::unit::HealthCheck::Grpc {
+                                host,
+                                port,
+                                service,
+                                interval_ms,
+                                ..
+                            } => {
+                                format!(
+                                    "grpc {}:{}/{} every {}ms",
+                                    host, port, service, interval_ms
+                                )
+                            }
                         };
                         out.push(Line::from(vec![
                             Span::styled("  Health  ", dim),
@@ -2813,6 +5653,14 @@ async fn tui_loop(
                             format!("  {}", adapters::format_bytes(metrics.memory_bytes)),
                             dim,
                         ),
+                        Span::styled(
+                            format!(
+                                "  io:{}/{}",
+                                adapters::format_bytes(metrics.io_read_bytes),
+                                adapters::format_bytes(metrics.io_write_bytes)
+                            ),
+                            dim,
+                        ),
                         if let Some(pid) = metrics.pid {
                             Span::styled(format!("  pid:{}", pid), dim)
                         } else {
@@ -2844,57 +5692,63 @@ async fn tui_loop(
             out
         };
 
-        // Right content
-        let right_text: Text = match ui.view {
-            View::Deps => {
-                if let Some(id) = selected_id {
-                    Text::from(build_deps(id))
-                } else {
-                    Text::from(vec![Line::from("No service selected.")])
+        // Right content for a view
+        let right_text = |view: View| -> Text {
+            match view {
+                View::Deps => {
+                    if let Some(id) = selected_id {
+                        Text::from(build_deps(id))
+                    } else {
+                        Text::from(vec![Line::from("No service selected.")])
+                    }
                 }
-            }
-            View::Inspect => {
-                if let Some(id) = selected_id {
-                    Text::from(build_inspect(id))
-                } else {
-                    Text::from(vec![Line::from("No service selected.")])
+                View::Inspect => {
+                    if let Some(id) = selected_id {
+                        Text::from(build_inspect(id))
+                    } else {
+                        Text::from(vec![Line::from("No service selected.")])
+                    }
                 }
-            }
-            View::Exec => Text::from(build_exec()),
-            View::Metrics => Text::from(build_metrics()),
-            View::Logs => {
-                if ui.logs.paused {
-                    Text::from(
-                        ui.logs
-                            .frozen_logs
-                            .iter()
-                            .map(|log_line| {
-                                if let Some(ts) = log_line.timestamp {
-                                    Line::from(vec![
-                                        Span::raw(log_line.text.clone()),
-                                        Span::styled(
-                                            format!(" {}", format_timestamp(ts)),
+                View::Exec => Text::from(build_exec()),
+                View::Metrics | View::MetricsDrill { .. } => Text::from(build_metrics()),
+                View::Logs => {
+                    if ui.logs.paused {
+                        Text::from(
+                            ui.logs
+                                .frozen_logs
+                                .iter()
+                                .map(|log_line| {
+                                    let mut spans = ui
+                                        .logs
+                                        .json_mode
+                                        .then(|| log_line.json_spans(&ui.logs.timestamps))
+                                        .flatten()
+                                        .unwrap_or_else(|| {
+                                            let plain = log_line.plain_text();
+                                            log_line.spans(ui.logs.color_mode.line_style(&plain))
+                                        });
+                                    if let Some(ts) = log_line.timestamp {
+                                        spans.push(Span::styled(
+                                            format!(" {}", ui.logs.timestamps.format(ts)),
                                             Style::default().fg(Color::DarkGray),
-                                        ),
-                                    ])
-                                } else {
-                                    Line::from(log_line.text.clone())
-                                }
-                            })
-                            .collect::<Vec<_>>(),
-                    )
-                } else if let Some(id) = selected_id {
-                    Text::from(build_logs(id))
-                } else {
-                    Text::from(vec![Line::from("No service selected.")])
+                                        ));
+                                    }
+                                    Line::from(spans)
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    } else if let Some(id) = selected_id {
+                        Text::from(build_logs(id))
+                    } else {
+                        Text::from(vec![Line::from("No service selected.")])
+                    }
                 }
             }
         };
 
         // Build picker items for command picker modal
         let picker_items: Vec<PickerItem> = if ui.palette_open {
-            let all_items = build_picker_items(&service_ids, selected_id, &[]);
-            filter_picker_items(&all_items, &ui.palette_input)
+            palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs)
         } else {
             vec![]
         };
@@ -2906,6 +5760,29 @@ async fn tui_loop(
             ui.palette_pick = 0;
         }
 
+        // Text for each right-hand pane; Logs is rebuilt with scrolling and
+        // search highlights when drawn
+        let layout_mode = LayoutMode::for_width(terminal.size()?.width);
+        let pane_views = match layout_mode {
+            LayoutMode::TwoColumn => vec![ui.view.clone()],
+            LayoutMode::ThreeColumn => vec![View::Logs, ui.detail_view()],
+        };
+        // Sample as fast as the most demanding visible view needs
+        let interval = pane_views
+            .iter()
+            .map(View::sample_interval)
+            .min()
+            .unwrap_or(sampler::DEFAULT_SAMPLE_INTERVAL);
+        sampler_config.send_if_modified(|config| {
+            let changed = config.interval != interval;
+            config.interval = interval;
+            changed
+        });
+        let pane_texts: Vec<Text> = pane_views
+            .iter()
+            .map(|view| right_text(view.clone()))
+            .collect();
+
         terminal.draw(|f| {
             let area = f.area();
 
@@ -2924,8 +5801,9 @@ async fn tui_loop(
 
             let main = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
+                .constraints(layout_mode.constraints())
                 .split(outer[1]);
+            units_area = main[0];
 
             // ---------------- Top Status Bar ----------------
             // Calculate aggregate metrics
@@ -2973,6 +5851,15 @@ async fn tui_loop(
                 Span::raw("  "),
                 Span::styled(format!("⏱ {}", uptime_str), styles::text_muted()),
             ]);
+            let top_bar = match &update_notice {
+                Some(notice) => {
+                    let mut spans = top_bar.spans;
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(format!("↑ {}", notice), styles::accent()));
+                    Line::from(spans)
+                }
+                None => top_bar,
+            };
             f.render_widget(Paragraph::new(top_bar), outer[0]);
 
             // ---------------- Left: Mode-aware pane ----------------
@@ -2982,7 +5869,12 @@ async fn tui_loop(
             } else {
                 styles::border_subtle()
             };
-            let mode_label = ui.left_mode.label();
+            let mode_label = match ui.left_mode {
+                LeftMode::Commands if snapshot.project_stale => "Commands [stale]".to_string(),
+                LeftMode::Runs => ui.runs_title(),
+                LeftMode::Services => ui.units_title(),
+                mode => mode.label().to_string(),
+            };
             let mode_key = ui.left_mode.key();
             let left_title = if left_focused {
                 format!("▸ {} [{}]", mode_label, mode_key)
@@ -3004,531 +5896,745 @@ async fn tui_loop(
                     f.render_stateful_widget(list, main[0], &mut command_list_state)
                 }
                 LeftMode::Runs => f.render_stateful_widget(list, main[0], &mut run_list_state),
+                LeftMode::Groups => f.render_stateful_widget(list, main[0], &mut group_list_state),
             }
 
             // ---------------- Right: Workspace ----------------
-            let right_focused = ui.focus == Focus::RightPane;
-            let right_border_style = if right_focused {
-                styles::border_focused()
-            } else {
-                styles::border_subtle()
-            };
+            // Two columns show the current view; three columns always show logs
+            // in the middle and the other views (Inspect by default) on the right
+            let panes = pane_views.iter().zip(pane_texts).zip(main[1..].iter());
+            logs_area = None;
+            for ((view, right_text), &pane) in panes {
+                if *view == View::Logs {
+                    logs_area = Some(pane);
+                }
+                let right_focused = ui.focus == Focus::RightPane && *view == ui.view;
+                let right_border_style = if right_focused {
+                    styles::border_focused()
+                } else {
+                    styles::border_subtle()
+                };
 
-            // Calculate visible area for scroll
-            let right_inner_height = main[1].height.saturating_sub(2) as usize; // minus borders
-            let _total_lines = match ui.view {
-                View::Logs => {
-                    if ui.logs.paused {
-                        ui.logs.frozen_logs.len()
-                    } else if let Some(id) = selected_id {
-                        snapshot
-                            .logs
-                            .per_service
-                            .get(id)
-                            .map(|l| l.len())
-                            .unwrap_or(0)
-                    } else {
-                        1
+                // Calculate visible area for scroll
+                let right_inner_height = pane.height.saturating_sub(2) as usize; // minus borders
+                let _total_lines = match view {
+                    View::Logs => {
+                        if ui.logs.paused {
+                            ui.logs.frozen_logs.len()
+                        } else if let Some(id) = selected_id {
+                            snapshot
+                                .logs
+                                .per_service
+                                .get(id)
+                                .map(|l| l.len())
+                                .unwrap_or(0)
+                        } else {
+                            1
+                        }
                     }
-                }
-                _ => 0,
-            };
+                    _ => 0,
+                };
 
-            // Build title - clean format per spec
-            let unit_name = selected_id.unwrap_or("all");
-            let raw_title = match ui.view {
-                View::Deps => format!("Deps: {}", unit_name),
-                View::Inspect => format!("Inspect: {}", unit_name),
-                View::Exec => "Commands".to_string(),
-                View::Metrics => format!("Metrics: {}", unit_name),
-                View::Logs => {
-                    // Format: "Logs: api [LIVE]" or "Logs: all [PAUSED]"
-                    let status = if ui.logs.paused {
-                        " [PAUSED]"
-                    } else if ui.is_following() {
-                        " [LIVE]"
-                    } else {
-                        ""
-                    };
-                    // Search indicator
-                    let search_info = if let Some(query) = ui.search_query() {
-                        if !query.is_empty() {
-                            let match_count = ui.logs.matches.len();
-                            let current = if match_count > 0 {
-                                ui.logs.match_idx + 1
+                // Build title - clean format per spec
+                let unit_name = selected_id.unwrap_or("all");
+                let raw_title = match view {
+                    View::Deps => format!("Deps: {}", unit_name),
+                    View::Inspect => format!("Inspect: {}", unit_name),
+                    View::Exec if snapshot.project_stale => "Commands [stale]".to_string(),
+                    View::Exec => "Commands".to_string(),
+                    View::Metrics | View::MetricsDrill { .. } => {
+                        format!("Metrics: {}", unit_name)
+                    }
+                    View::Logs => {
+                        // Format: "Logs: api [LIVE]" or "Logs: all [PAUSED]"
+                        let status = if ui.logs.paused {
+                            " [PAUSED]"
+                        } else if ui.is_following() {
+                            " [LIVE]"
+                        } else {
+                            ""
+                        };
+                        // Search indicator
+                        let search_info = if let Some(query) = ui.search_query() {
+                            if !query.is_empty() {
+                                let match_count = ui.logs.matches.len();
+                                let current = if match_count > 0 {
+                                    ui.logs.match_idx + 1
+                                } else {
+                                    0
+                                };
+                                format!(" [/{} ({}/{})]", query, current, match_count)
                             } else {
-                                0
-                            };
-                            format!(" [/{} ({}/{})]", query, current, match_count)
+                                " [/]".to_string()
+                            }
                         } else {
-                            " [/]".to_string()
-                        }
-                    } else {
-                        String::new()
-                    };
-                    format!("Logs: {}{}{}", unit_name, status, search_info)
-                }
-            };
+                            String::new()
+                        };
+                        let hscroll = if ui.logs.scroll_x > 0 {
+                            format!(" [→{}]", ui.logs.scroll_x)
+                        } else {
+                            String::new()
+                        };
+                        let json = if ui.logs.json_mode { " [JSON]" } else { "" };
+                        format!(
+                            "Logs: {}{}{}{}{}",
+                            unit_name, status, json, search_info, hscroll
+                        )
+                    }
+                };
 
-            let title = fit_title(&raw_title, main[1].width);
+                let title = fit_title(&raw_title, pane.width);
 
-            // Color the title based on log state for logs view
-            let title_style = match ui.view {
-                View::Logs => {
-                    if ui.logs.paused {
-                        Style::default().fg(Color::Yellow)
-                    } else if ui.is_following() {
-                        Style::default().fg(Color::Green)
-                    } else {
-                        Style::default().fg(Color::Cyan)
+                // Color the title based on log state for logs view
+                let title_style = match view {
+                    View::Logs => {
+                        if ui.logs.paused {
+                            Style::default().fg(Color::Yellow)
+                        } else if ui.is_following() {
+                            Style::default().fg(Color::Green)
+                        } else {
+                            Style::default().fg(Color::Cyan)
+                        }
                     }
-                }
-                _ => selected_id
-                    .and_then(|id| snapshot.graph.nodes.get(id))
-                    .map(|node| status_style(&node.observed.status))
-                    .unwrap_or_default(),
-            };
+                    _ => selected_id
+                        .and_then(|id| snapshot.graph.nodes.get(id))
+                        .map(|node| status_style(&node.observed.status))
+                        .unwrap_or_default(),
+                };
 
-            // Apply scroll to logs - scroll from bottom, with optional search/select highlighting
-            let log_scroll = ui.scroll_offset();
-            let scrolled_text: Text = match ui.view {
-                View::Logs => {
-                    let raw_lines: Vec<DisplayLogLine> = if ui.logs.paused {
-                        ui.logs.frozen_logs.clone()
-                    } else if ui.left_mode == LeftMode::Runs {
-                        // Runs mode: show logs for selected run
-                        if let Some(run_id) = snapshot.run_order.get(ui.selected_run) {
-                            if let Some(log_lines) = snapshot.logs.per_run.get(run_id) {
-                                log_lines
-                                    .iter()
-                                    .map(|l| {
-                                        let prefix = match l.stream {
-                                            LogStream::Stderr => "[stderr] ",
-                                            LogStream::System => "[system] ",
-                                            LogStream::Stdout => "",
-                                        };
-                                        DisplayLogLine {
-                                            timestamp: Some(l.at),
-                                            text: format!("{}{}", prefix, l.text),
-                                        }
-                                    })
-                                    .collect()
+                // Apply scroll to logs - scroll from bottom, with optional search/select highlighting
+                let log_scroll = ui.scroll_offset();
+                let scrolled_text: Text = match view {
+                    View::Logs => {
+                        let raw_lines: Vec<DisplayLogLine> = if ui.logs.paused {
+                            ui.logs.frozen_logs.clone()
+                        } else if ui.left_mode == LeftMode::Runs {
+                            // Runs mode: show logs for selected run
+                            if let Some(run_id) = snapshot.run_order.get(ui.selected_run) {
+                                if let Some(log_lines) = snapshot.logs.per_run.get(run_id) {
+                                    log_lines
+                                        .iter()
+                                        .map(|l| {
+                                            let prefix = match l.stream {
+                                                LogStream::Stderr => "[stderr] ",
+                                                LogStream::System => "[system] ",
+                                                LogStream::Stdout => "",
+                                            };
+                                            DisplayLogLine {
+                                                timestamp: Some(l.at),
+                                                text: format!("{}{}", prefix, l.text),
+                                                render_ansi: true,
+                                                repeat_count: l.repeat_count,
+                                            }
+                                        })
+                                        .collect()
+                                } else {
+                                    vec![DisplayLogLine {
+                                        timestamp: None,
+                                        text: "No output yet.".to_string(),
+                                        render_ansi: false,
+                                        repeat_count: 1,
+                                    }]
+                                }
                             } else {
                                 vec![DisplayLogLine {
                                     timestamp: None,
-                                    text: "No output yet.".to_string(),
+                                    text: "No run selected.".to_string(),
+                                    render_ansi: false,
+                                    repeat_count: 1,
                                 }]
                             }
-                        } else {
-                            vec![DisplayLogLine {
-                                timestamp: None,
-                                text: "No run selected.".to_string(),
-                            }]
-                        }
-                    } else if let Some(id) = selected_id {
-                        if id == "all" {
-                            // Merged logs from all services
-                            if snapshot.logs.merged.is_empty() {
+                        } else if let Some(id) = selected_id {
+                            if id == "all" {
+                                // Merged logs from all services
+                                if snapshot.logs.merged.is_empty() {
+                                    vec![DisplayLogLine {
+                                        timestamp: None,
+                                        text: "No logs yet.".to_string(),
+                                        render_ansi: false,
+                                        repeat_count: 1,
+                                    }]
+                                } else {
+                                    snapshot
+                                        .logs
+                                        .merged
+                                        .iter()
+                                        .map(|l| {
+                                            let prefix = format!("{:8}| ", l.service_id);
+                                            let stream_prefix = match l.stream {
+                                                LogStream::Stderr => "[stderr] ",
+                                                LogStream::System => "[system] ",
+                                                LogStream::Stdout => "",
+                                            };
+                                            DisplayLogLine {
+                                                timestamp: Some(l.at),
+                                                text: format!(
+                                                    "{}{}{}",
+                                                    prefix, stream_prefix, l.text
+                                                ),
+                                                render_ansi: renders_ansi(units_map, &l.service_id),
+                                                repeat_count: l.repeat_count,
+                                            }
+                                        })
+                                        .collect()
+                                }
+                            } else if let Some(log_lines) = snapshot.logs.per_service.get(id) {
+                                log_lines
+                                    .iter()
+                                    .map(|l| DisplayLogLine {
+                                        timestamp: Some(l.at),
+                                        text: l.text.clone(),
+                                        render_ansi: renders_ansi(units_map, id),
+                                        repeat_count: l.repeat_count,
+                                    })
+                                    .collect()
+                            } else {
                                 vec![DisplayLogLine {
                                     timestamp: None,
                                     text: "No logs yet.".to_string(),
+                                    render_ansi: false,
+                                    repeat_count: 1,
                                 }]
-                            } else {
-                                snapshot
-                                    .logs
-                                    .merged
-                                    .iter()
-                                    .map(|l| {
-                                        let prefix = format!("{:8}| ", l.service_id);
-                                        let stream_prefix = match l.stream {
-                                            LogStream::Stderr => "[stderr] ",
-                                            LogStream::System => "[system] ",
-                                            LogStream::Stdout => "",
-                                        };
-                                        DisplayLogLine {
-                                            timestamp: Some(l.at),
-                                            text: format!("{}{}{}", prefix, stream_prefix, l.text),
-                                        }
-                                    })
-                                    .collect()
                             }
-                        } else if let Some(log_lines) = snapshot.logs.per_service.get(id) {
-                            log_lines
-                                .iter()
-                                .map(|l| DisplayLogLine {
-                                    timestamp: Some(l.at),
-                                    text: l.text.clone(),
-                                })
-                                .collect()
                         } else {
                             vec![DisplayLogLine {
                                 timestamp: None,
-                                text: "No logs yet.".to_string(),
+                                text: "No service selected.".to_string(),
+                                render_ansi: false,
+                                repeat_count: 1,
                             }]
-                        }
-                    } else {
-                        vec![DisplayLogLine {
-                            timestamp: None,
-                            text: "No service selected.".to_string(),
-                        }]
-                    };
+                        };
 
-                    // Apply log level filter
-                    let filtered_lines: Vec<DisplayLogLine> =
-                        if ui.logs.log_filter == LogFilterMode::All {
-                            raw_lines
-                        } else {
+                        // Apply log level filter, remembering each line's
+                        // position in the buffer for the line number gutter
+                        let total_lines = raw_lines.len();
+                        let (line_numbers, filtered_lines): (Vec<usize>, Vec<DisplayLogLine>) =
                             raw_lines
                                 .into_iter()
-                                .filter(|log_line| {
-                                    let level = detect_level(&log_line.text);
-                                    ui.logs.log_filter.matches(level)
+                                .enumerate()
+                                .filter(|(_, log_line)| {
+                                    ui.logs.log_filter == LogFilterMode::All || {
+                                        let level = detect_level(&log_line.plain_text());
+                                        ui.logs.log_filter.matches(level)
+                                    }
                                 })
-                                .collect()
-                        };
-
-                    // Build lines with search highlighting and timestamps
-                    let search_query = ui.search_query().map(|s| s.to_lowercase());
-                    let search_match_idx = ui.logs.match_idx;
+                                .map(|(idx, log_line)| (idx + 1, log_line))
+                                .unzip();
+                        let gutter_width = total_lines.to_string().len().max(4);
 
-                    let all_lines: Vec<Line> = filtered_lines
-                        .iter()
-                        .enumerate()
-                        .map(|(idx, log_line)| {
-                            // Format timestamp if available
-                            let ts_span = log_line.timestamp.map(|t| {
-                                Span::styled(
-                                    format!(" {}", format_timestamp(t)),
-                                    Style::default().fg(Color::DarkGray),
-                                )
-                            });
+                        // Build lines with search highlighting and timestamps
+                        let search_active = ui.search_query().is_some();
+                        let search_match_idx = ui.logs.match_idx;
+                        let context_rows = ui.logs.search_context(&filtered_lines);
 
-                            // Check if line matches search
-                            if let Some(ref query) = search_query {
-                                if !query.is_empty() && log_line.text.to_lowercase().contains(query)
-                                {
-                                    let is_current =
-                                        ui.logs.matches.get(search_match_idx) == Some(&idx);
-                                    let style = if is_current {
-                                        Style::default().bg(Color::Yellow).fg(Color::Black)
-                                    } else {
-                                        Style::default().bg(Color::DarkGray).fg(Color::White)
-                                    };
-                                    let mut spans =
-                                        vec![Span::styled(log_line.text.clone(), style)];
-                                    if let Some(ts) = ts_span {
-                                        spans.push(ts);
+                        let all_lines: Vec<Line> = filtered_lines
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, log_line)| {
+                                // Format timestamp if available
+                                let ts_span = log_line.timestamp.map(|t| {
+                                    Span::styled(
+                                        format!(" {}", ui.logs.timestamps.format(t)),
+                                        Style::default().fg(Color::DarkGray),
+                                    )
+                                });
+
+                                // Check if line matches search
+                                if search_active {
+                                    let plain = log_line.plain_text();
+                                    if ui.logs.search_matches(&plain) {
+                                        let is_current =
+                                            ui.logs.matches.get(search_match_idx) == Some(&idx);
+                                        let style = if is_current {
+                                            Style::default().bg(Color::Yellow).fg(Color::Black)
+                                        } else {
+                                            Style::default().bg(Color::DarkGray).fg(Color::White)
+                                        };
+                                        let mut spans = vec![Span::styled(plain, style)];
+                                        spans.extend(log_line.repeat_suffix());
+                                        if let Some(ts) = ts_span {
+                                            spans.push(ts);
+                                        }
+                                        return Line::from(spans);
+                                    }
+                                    if context_rows.contains(&idx) {
+                                        let style =
+                                            Style::default().bg(Color::Gray).fg(Color::Black);
+                                        let mut spans = vec![Span::styled(plain, style)];
+                                        spans.extend(log_line.repeat_suffix());
+                                        if let Some(ts) = ts_span {
+                                            spans.push(ts);
+                                        }
+                                        return Line::from(spans);
                                     }
-                                    return Line::from(spans);
                                 }
-                            }
-
-                            // Normal line with timestamp
-                            if let Some(ts) = ts_span {
-                                Line::from(vec![Span::raw(log_line.text.clone()), ts])
-                            } else {
-                                Line::from(log_line.text.clone())
-                            }
-                        })
-                        .collect();
 
-                    if all_lines.is_empty() {
-                        Text::from(vec![Line::from("No logs yet.")])
-                    } else if ui.is_following() && !ui.logs.is_searching() {
-                        // Follow mode: show last N lines (newest at bottom)
-                        let start = all_lines.len().saturating_sub(right_inner_height);
-                        Text::from(all_lines[start..].to_vec())
-                    } else {
-                        // Scroll mode: log_scroll = lines scrolled UP from bottom
-                        let end = all_lines.len().saturating_sub(log_scroll);
-                        let start = end.saturating_sub(right_inner_height);
-                        Text::from(all_lines[start..end].to_vec())
+                                // Normal line with timestamp
+                                let mut spans = ui
+                                    .logs
+                                    .json_mode
+                                    .then(|| log_line.json_spans(&ui.logs.timestamps))
+                                    .flatten()
+                                    .unwrap_or_else(|| {
+                                        let plain = log_line.plain_text();
+                                        log_line.spans(ui.logs.color_mode.line_style(&plain))
+                                    });
+                                if let Some(ts) = ts_span {
+                                    spans.push(ts);
+                                }
+                                Line::from(spans)
+                            })
+                            .zip(&line_numbers)
+                            .map(|(mut line, number)| {
+                                // The gutter counts towards the line's width, so
+                                // the horizontal scroll limit accounts for it
+                                if ui.logs.show_line_numbers {
+                                    line.spans.insert(
+                                        0,
+                                        Span::styled(
+                                            format!("{:>width$} │ ", number, width = gutter_width),
+                                            styles::text_dim(),
+                                        ),
+                                    );
+                                }
+                                line
+                            })
+                            .collect();
+
+                        if all_lines.is_empty() {
+                            Text::from(vec![Line::from("No logs yet.")])
+                        } else if ui.is_following() && !ui.logs.is_searching() {
+                            // Follow mode: show last N lines (newest at bottom)
+                            let start = all_lines.len().saturating_sub(right_inner_height);
+                            Text::from(all_lines[start..].to_vec())
+                        } else {
+                            // Scroll mode: log_scroll = lines scrolled UP from bottom
+                            let end = all_lines.len().saturating_sub(log_scroll);
+                            let start = end.saturating_sub(right_inner_height);
+                            Text::from(all_lines[start..end].to_vec())
+                        }
                     }
-                }
-                _ => right_text,
-            };
-
-            // Special rendering for Inspect view with charts
-            if ui.view == View::Inspect && selected_id.is_some() {
-                let id = selected_id.unwrap();
-                let right_height = main[1].height;
-
-                // Adaptive layout based on available height
-                // - Full layout (>= 30): Summary + 4 charts + Health
-                // - Medium layout (20-29): Summary + 2 charts + Health
-                // - Compact layout (< 20): Summary only
-                let layout_mode = if right_height >= 30 {
-                    "full"
-                } else if right_height >= 20 {
-                    "medium"
-                } else {
-                    "compact"
+                    _ => right_text,
                 };
 
-                // Split right pane into sections based on available height
-                let inspect_layout = match layout_mode {
-                    "full" => Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Length(8), // Summary
-                            Constraint::Min(12),   // Metrics charts
-                            Constraint::Length(5), // Health
-                        ])
-                        .split(main[1]),
-                    "medium" => Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Length(6), // Summary (compact)
-                            Constraint::Min(8),    // Single chart row
-                            Constraint::Length(4), // Health (compact)
-                        ])
-                        .split(main[1]),
-                    _ => Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Min(1), // Summary fills space
-                        ])
-                        .split(main[1]),
-                };
+                // Stop scrolling right once the widest visible line fits
+                let hidden_left = *view == View::Logs && ui.logs.scroll_x > 0;
+                if hidden_left {
+                    let widest = scrolled_text.lines.iter().map(Line::width).max();
+                    let overflow = widest
+                        .unwrap_or(0)
+                        .saturating_sub(pane.width.saturating_sub(2) as usize);
+                    ui.logs.scroll_x = ui.logs.scroll_x.min(overflow);
+                }
+                let clipped_rows: Vec<u16> = scrolled_text
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| hidden_left && line.width() > 0)
+                    .map(|(row, _)| row as u16)
+                    .collect();
 
-                let cyan = Style::default().fg(Color::Cyan);
-                let dim = Style::default().fg(Color::DarkGray);
-                let green = Style::default().fg(Color::Green);
+                // Special rendering for Inspect view with charts; "all" gets
+                // the text summary instead
+                if *view == View::Inspect && selected_id.is_some_and(|id| id != "all") {
+                    let id = selected_id.unwrap();
+                    let right_height = pane.height;
+
+                    // Adaptive layout based on available height
+                    // - Full layout (>= 30): Summary + 5 charts + Health
+                    // - Medium layout (20-29): Summary + 2 charts + Health
+                    // - Compact layout (< 20): Summary only
+                    let layout_mode = if right_height >= 30 {
+                        "full"
+                    } else if right_height >= 20 {
+                        "medium"
+                    } else {
+                        "compact"
+                    };
 
-                // Determine focused section for visual indicators
-                let focused_section = match ui.focus {
-                    Focus::InspectPanel(section) => Some(section),
-                    _ => None,
-                };
-                let summary_border = if focused_section == Some(InspectSection::Summary) {
-                    cyan
-                } else {
-                    dim
-                };
-                let metrics_border = if focused_section == Some(InspectSection::Metrics) {
-                    cyan
-                } else {
-                    dim
-                };
-                let health_border = if focused_section == Some(InspectSection::Health) {
-                    cyan
-                } else {
-                    dim
-                };
+                    // Most recent notes, shown in their own section below Health
+                    let notes: &[UserNote] = snapshot.notes.get(id).map_or(&[], |n| n.as_slice());
+                    let notes_shown = &notes[notes.len().saturating_sub(MAX_INSPECT_NOTES)..];
+                    let notes_h = if notes.is_empty() {
+                        0
+                    } else {
+                        notes_shown.len() as u16 + 2
+                    };
 
-                // ─────────────── Summary Section ───────────────
-                let node = snapshot.graph.nodes.get(id);
-                let unit = units_map.get(id);
+                    // Metric watches, shown below Notes
+                    let watches = snapshot.watches.get(id);
+                    let automation_h = watches.map_or(0, |w| w.len() as u16 + 2);
 
-                let mut summary_lines: Vec<Line> = vec![];
+                    // Split right pane into sections based on available height
+                    let inspect_layout = match layout_mode {
+                        "full" => Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([
+                                Constraint::Length(9),            // Summary
+                                Constraint::Min(12),              // Metrics charts
+                                Constraint::Length(5),            // Health
+                                Constraint::Length(notes_h),      // Notes
+                                Constraint::Length(automation_h), // Automation
+                            ])
+                            .split(pane),
+                        "medium" => Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([
+                                Constraint::Length(6),            // Summary (compact)
+                                Constraint::Min(8),               // Single chart row
+                                Constraint::Length(4),            // Health (compact)
+                                Constraint::Length(notes_h),      // Notes
+                                Constraint::Length(automation_h), // Automation
+                            ])
+                            .split(pane),
+                        _ => Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([
+                                Constraint::Min(1), // Summary fills space
+                            ])
+                            .split(pane),
+                    };
 
-                if let Some(node) = node {
-                    // Status
-                    let status_str = format!(
-                        "{} {}",
-                        status_icon(&node.observed.status),
-                        status_label(&node.observed.status)
-                    );
-                    summary_lines.push(Line::from(vec![
-                        Span::styled("Status   ", dim),
-                        Span::styled(status_str, status_style(&node.observed.status)),
-                    ]));
+                    let cyan = Style::default().fg(Color::Cyan);
+                    let dim = Style::default().fg(Color::DarkGray);
+                    let green = Style::default().fg(Color::Green);
 
-                    // Health
-                    let health_str = match &node.observed.health {
-                        HealthStatus::Healthy => {
-                            format!("{} healthy", health_icon(&node.observed.health))
-                        }
-                        HealthStatus::Unhealthy { reason } => {
-                            format!("{} {}", health_icon(&node.observed.health), reason)
-                        }
-                        HealthStatus::Degraded { reason } => {
-                            format!("{} {}", health_icon(&node.observed.health), reason)
-                        }
-                        HealthStatus::Unknown => {
-                            format!("{} unknown", health_icon(&node.observed.health))
-                        }
+                    // Determine focused section for visual indicators
+                    let focused_section = match ui.focus {
+                        Focus::InspectPanel(section) => Some(section),
+                        _ => None,
+                    };
+                    let summary_border = if focused_section == Some(InspectSection::Summary) {
+                        cyan
+                    } else {
+                        dim
+                    };
+                    let metrics_border = if focused_section == Some(InspectSection::Metrics) {
+                        cyan
+                    } else {
+                        dim
+                    };
+                    let health_border = if focused_section == Some(InspectSection::Health) {
+                        cyan
+                    } else {
+                        dim
                     };
-                    summary_lines.push(Line::from(vec![
-                        Span::styled("Health   ", dim),
-                        Span::styled(health_str, health_style(&node.observed.health)),
-                    ]));
 
-                    // PID and Uptime if running
-                    if let Some(metrics) = snapshot.metrics.get(id) {
-                        if let Some(pid) = metrics.pid {
-                            summary_lines.push(Line::from(vec![
-                                Span::styled("PID      ", dim),
-                                Span::raw(format!("{}", pid)),
-                            ]));
-                        }
-                        let mins = metrics.uptime_secs / 60;
-                        let secs = metrics.uptime_secs % 60;
-                        summary_lines.push(Line::from(vec![
-                            Span::styled("Uptime   ", dim),
-                            Span::raw(format!("{}m {}s", mins, secs)),
-                        ]));
+                    // ─────────────── Summary Section ───────────────
+                    let node = snapshot.graph.nodes.get(id);
+                    let unit = units_map.get(id);
+
+                    let mut summary_lines: Vec<Line> = vec![];
+
+                    if let Some(node) = node {
+                        // Status
+                        let status_str = format!(
+                            "{} {}",
+                            status_icon(&node.observed.status),
+                            status_label(&node.observed.status)
+                        );
                         summary_lines.push(Line::from(vec![
-                            Span::styled("CPU      ", dim),
-                            Span::styled(format!("{:.1}%", metrics.cpu_percent.abs()), green),
+                            Span::styled("Status   ", dim),
+                            Span::styled(status_str, status_style(&node.observed.status)),
                         ]));
+
+                        // Health
+                        let health_str = match &node.observed.health {
+                            HealthStatus::Healthy => {
+                                format!("{} healthy", health_icon(&node.observed.health))
+                            }
+                            HealthStatus::Unhealthy { reason } => {
+                                format!("{} {}", health_icon(&node.observed.health), reason)
+                            }
+                            HealthStatus::Degraded { reason } => {
+                                format!("{} {}", health_icon(&node.observed.health), reason)
+                            }
+                            HealthStatus::Unknown => {
+                                format!("{} unknown", health_icon(&node.observed.health))
+                            }
+                        };
                         summary_lines.push(Line::from(vec![
-                            Span::styled("Memory   ", dim),
-                            Span::styled(adapters::format_bytes(metrics.memory_bytes), green),
+                            Span::styled("Health   ", dim),
+                            Span::styled(health_str, health_style(&node.observed.health)),
                         ]));
-                    }
-                }
 
-                // Command from unit config
-                if let Some(unit) = unit {
-                    summary_lines.push(Line::from(vec![
-                        Span::styled("Command  ", dim),
-                        Span::raw(unit.start.clone()),
-                    ]));
-                }
+                        // PID and Uptime if running
+                        if let Some(metrics) = snapshot.metrics.get(id) {
+                            if let Some(pid) = metrics.pid {
+                                summary_lines.push(Line::from(vec![
+                                    Span::styled("PID      ", dim),
+                                    Span::raw(format!("{}", pid)),
+                                ]));
+                            }
+                            let mins = metrics.uptime_secs / 60;
+                            let secs = metrics.uptime_secs % 60;
+                            summary_lines.push(Line::from(vec![
+                                Span::styled("Uptime   ", dim),
+                                Span::raw(format!("{}m {}s", mins, secs)),
+                            ]));
+                            summary_lines.push(Line::from(vec![
+                                Span::styled("CPU      ", dim),
+                                Span::styled(format!("{:.1}%", metrics.cpu_percent.abs()), green),
+                            ]));
+                            summary_lines.push(Line::from(vec![
+                                Span::styled("Memory   ", dim),
+                                Span::styled(adapters::format_bytes(metrics.memory_bytes), green),
+                            ]));
+                            summary_lines.push(Line::from(vec![
+                                Span::styled("I/O      ", dim),
+                                Span::styled(
+                                    format!(
+                                        "{} read, {} written",
+                                        adapters::format_bytes(metrics.io_read_bytes),
+                                        adapters::format_bytes(metrics.io_write_bytes)
+                                    ),
+                                    green,
+                                ),
+                            ]));
+                        }
+                    }
 
-                let summary_title = if focused_section == Some(InspectSection::Summary) {
-                    format!(" {} ★ ", id)
-                } else {
-                    format!(" {} ", id)
-                };
-                let summary = Paragraph::new(summary_lines).block(
-                    Block::default()
-                        .title(Span::styled(
-                            summary_title,
-                            title_style.add_modifier(Modifier::BOLD),
-                        ))
-                        .borders(Borders::ALL)
-                        .border_style(summary_border),
-                );
-                f.render_widget(summary, inspect_layout[0]);
-
-                // ─────────────── Metrics Charts Section ───────────────
-                // Only render charts in full or medium layout modes
-                if layout_mode != "compact" && inspect_layout.len() > 1 {
-                    // Get time-series data
-                    let cpu_data = snapshot.metrics_series.system_cpu.as_vec();
-                    let mem_data = snapshot.metrics_series.system_mem.as_vec();
-                    let net_data = snapshot.metrics_series.system_net.as_vec();
-                    let log_rate_data = snapshot
-                        .metrics_series
-                        .logs_rate
-                        .get(id)
-                        .map(|s| s.as_vec())
-                        .unwrap_or_default();
+                    // Command from unit config
+                    if let Some(unit) = unit {
+                        summary_lines.push(Line::from(vec![
+                            Span::styled("Command  ", dim),
+                            Span::raw(unit.start.clone()),
+                        ]));
+                    }
 
-                    // Calculate bounds with time labels
-                    let (t_min, t_max) = if !cpu_data.is_empty() {
-                        let min_t = cpu_data.first().map(|(t, _)| *t).unwrap_or(0.0);
-                        let max_t = cpu_data.last().map(|(t, _)| *t).unwrap_or(60.0);
-                        (min_t, max_t.max(min_t + 1.0))
-                    } else {
-                        (0.0, 60.0)
-                    };
+                    let note_lines: Vec<Line> = notes_shown
+                        .iter()
+                        .map(|note| {
+                            Line::from(vec![
+                                Span::styled(
+                                    format!("{} ", ui.logs.timestamps.format(note.at)),
+                                    dim,
+                                ),
+                                Span::raw(note.text.clone()),
+                            ])
+                        })
+                        .collect();
+                    // Compact layout has no room for a separate section
+                    if layout_mode == "compact" && !note_lines.is_empty() {
+                        summary_lines.push(Line::from(""));
+                        summary_lines.push(Line::from(vec![Span::styled(
+                            "NOTES",
+                            cyan.add_modifier(Modifier::BOLD),
+                        )]));
+                        summary_lines.extend(note_lines.iter().cloned());
+                    }
 
-                    // Build x-axis labels: show relative time (e.g., "-60s", "-30s", "now")
-                    let time_range = t_max - t_min;
-                    let x_labels = if time_range > 0.0 {
-                        vec![
-                            Span::styled(format!("-{:.0}s", time_range), dim),
-                            Span::styled("now", dim),
-                        ]
-                    } else {
-                        vec![Span::styled("0s", dim), Span::styled("60s", dim)]
-                    };
+                    let automation_lines: Vec<Line> = watches
+                        .into_iter()
+                        .flatten()
+                        .map(|(watch_id, watch)| {
+                            let threshold = if watch.metric == METRIC_MEMORY_BYTES {
+                                adapters::format_bytes(watch.threshold as u64)
+                            } else {
+                                watch.threshold.to_string()
+                            };
+                            let mut spans = vec![
+                                Span::styled(format!("{:<4}", watch_id), dim),
+                                Span::raw(format!("{} {} {}", watch.metric, watch.op, threshold)),
+                                Span::styled(" → ", dim),
+                                Span::raw(watch.action.clone()),
+                            ];
+                            if watch.fired > 0 {
+                                spans.push(Span::styled(format!("  fired {}×", watch.fired), dim));
+                            }
+                            Line::from(spans)
+                        })
+                        .collect();
+                    if layout_mode == "compact" && !automation_lines.is_empty() {
+                        summary_lines.push(Line::from(""));
+                        summary_lines.push(Line::from(vec![Span::styled(
+                            "AUTOMATION",
+                            cyan.add_modifier(Modifier::BOLD),
+                        )]));
+                        summary_lines.extend(automation_lines.iter().cloned());
+                    }
 
-                    let metrics_title_suffix = if focused_section == Some(InspectSection::Metrics) {
-                        " ★"
+                    let summary_title = if focused_section == Some(InspectSection::Summary) {
+                        format!(" {} ★ ", id)
                     } else {
-                        ""
+                        format!(" {} ", id)
                     };
+                    let summary = Paragraph::new(summary_lines).block(
+                        Block::default()
+                            .title(Span::styled(
+                                summary_title,
+                                title_style.add_modifier(Modifier::BOLD),
+                            ))
+                            .borders(Borders::ALL)
+                            .border_style(summary_border),
+                    );
+                    f.render_widget(summary, inspect_layout[0]);
+
+                    // ─────────────── Metrics Charts Section ───────────────
+                    // Only render charts in full or medium layout modes
+                    if layout_mode != "compact" && inspect_layout.len() > 1 {
+                        // Get time-series data, trimmed to the chart window
+                        let window = ui.chart_window;
+                        let cpu_data = snapshot.metrics_series.system_cpu.window(window.secs());
+                        let mem_data = snapshot.metrics_series.system_mem.window(window.secs());
+                        let net_data = snapshot.metrics_series.system_net.window(window.secs());
+                        let oldest_t = cpu_data.first().map(|(t, _)| *t).unwrap_or(0.0);
+                        let mut cpu_anomalies = snapshot.metrics_series.system_cpu.anomaly_points();
+                        let mut mem_anomalies = snapshot.metrics_series.system_mem.anomaly_points();
+                        cpu_anomalies.retain(|(t, _)| *t >= oldest_t);
+                        mem_anomalies.retain(|(t, _)| *t >= oldest_t);
+                        let log_rate_data = snapshot
+                            .metrics_series
+                            .logs_rate
+                            .get(id)
+                            .map(|s| s.window(window.secs()))
+                            .unwrap_or_default();
+                        let unit_window = |series: &BTreeMap<ServiceId, Series>| {
+                            series
+                                .get(id)
+                                .map(|s| s.window(window.secs()))
+                                .unwrap_or_default()
+                        };
+                        let io_read_data = unit_window(&snapshot.metrics_series.svc_io_read);
+                        let io_write_data = unit_window(&snapshot.metrics_series.svc_io_write);
+
+                        // Calculate bounds with time labels
+                        let (t_min, t_max) = if !cpu_data.is_empty() {
+                            let min_t = cpu_data.first().map(|(t, _)| *t).unwrap_or(0.0);
+                            let max_t = cpu_data.last().map(|(t, _)| *t).unwrap_or(60.0);
+                            (min_t, max_t.max(min_t + 1.0))
+                        } else {
+                            (0.0, 60.0)
+                        };
 
-                    if layout_mode == "full" {
-                        // Full layout: 2x2 grid of charts
-                        let chart_rows = Layout::default()
-                            .direction(Direction::Vertical)
-                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                            .split(inspect_layout[1]);
-
-                        let chart_top = Layout::default()
-                            .direction(Direction::Horizontal)
-                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                            .split(chart_rows[0]);
-
-                        let chart_bottom = Layout::default()
-                            .direction(Direction::Horizontal)
-                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                            .split(chart_rows[1]);
-
-                        // CPU Chart
-                        let cpu_dataset = Dataset::default()
-                            .name("CPU")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Cyan))
-                            .data(&cpu_data);
-
-                        let cpu_chart = Chart::new(vec![cpu_dataset])
-                            .block(
-                                Block::default()
-                                    .title(format!(" CPU %{} ", metrics_title_suffix))
-                                    .borders(Borders::ALL)
-                                    .border_style(metrics_border),
-                            )
-                            .x_axis(
-                                Axis::default()
-                                    .bounds([t_min, t_max])
-                                    .labels(x_labels.clone()),
-                            )
-                            .y_axis(
-                                Axis::default()
-                                    .bounds([0.0, 100.0])
-                                    .labels(vec![Span::raw("0"), Span::raw("100")]),
-                            );
+                        // Build x-axis labels: show relative time (e.g., "-60s", "-30s", "now")
+                        let time_range = t_max - t_min;
+                        let x_labels = if time_range > 0.0 {
+                            vec![
+                                Span::styled(format!("-{:.0}s", time_range), dim),
+                                Span::styled("now", dim),
+                            ]
+                        } else {
+                            vec![Span::styled("0s", dim), Span::styled("60s", dim)]
+                        };
 
-                        f.render_widget(cpu_chart, chart_top[0]);
-
-                        // Memory Chart
-                        let mem_max = mem_data.iter().map(|(_, v)| *v).fold(100.0_f64, f64::max);
-                        let mem_dataset = Dataset::default()
-                            .name("MEM")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Green))
-                            .data(&mem_data);
-
-                        let mem_chart = Chart::new(vec![mem_dataset])
-                            .block(
-                                Block::default()
-                                    .title(" Memory MB ")
-                                    .borders(Borders::ALL)
-                                    .border_style(metrics_border),
-                            )
-                            .x_axis(
-                                Axis::default()
-                                    .bounds([t_min, t_max])
-                                    .labels(x_labels.clone()),
-                            )
-                            .y_axis(Axis::default().bounds([0.0, mem_max.max(100.0)]).labels(
-                                vec![Span::raw("0"), Span::raw(format!("{:.0}", mem_max))],
-                            ));
+                        let metrics_title_suffix =
+                            if focused_section == Some(InspectSection::Metrics) {
+                                " ★"
+                            } else {
+                                ""
+                            };
 
-                        f.render_widget(mem_chart, chart_top[1]);
+                        if layout_mode == "full" {
+                            // Full layout: CPU and memory on top, network, disk
+                            // I/O and log rate below
+                            let chart_rows = Layout::default()
+                                .direction(Direction::Vertical)
+                                .constraints([
+                                    Constraint::Percentage(50),
+                                    Constraint::Percentage(50),
+                                ])
+                                .split(inspect_layout[1]);
+
+                            let chart_top = Layout::default()
+                                .direction(Direction::Horizontal)
+                                .constraints([
+                                    Constraint::Percentage(50),
+                                    Constraint::Percentage(50),
+                                ])
+                                .split(chart_rows[0]);
+
+                            let chart_bottom = Layout::default()
+                                .direction(Direction::Horizontal)
+                                .constraints([
+                                    Constraint::Ratio(1, 3),
+                                    Constraint::Ratio(1, 3),
+                                    Constraint::Ratio(1, 3),
+                                ])
+                                .split(chart_rows[1]);
+
+                            // CPU Chart
+                            let cpu_dataset = Dataset::default()
+                                .name("CPU")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Cyan))
+                                .data(&cpu_data);
+
+                            let cpu_chart =
+                                Chart::new(vec![cpu_dataset, anomaly_dataset(&cpu_anomalies)])
+                                    .block(
+                                        Block::default()
+                                            .title(format!(
+                                                " CPU % [{}]{} ",
+                                                window.label(),
+                                                metrics_title_suffix
+                                            ))
+                                            .borders(Borders::ALL)
+                                            .border_style(metrics_border),
+                                    )
+                                    .x_axis(
+                                        Axis::default()
+                                            .bounds([t_min, t_max])
+                                            .labels(x_labels.clone()),
+                                    )
+                                    .y_axis(
+                                        Axis::default()
+                                            .bounds([0.0, 100.0])
+                                            .labels(vec![Span::raw("0"), Span::raw("100")]),
+                                    );
+
+                            f.render_widget(cpu_chart, chart_top[0]);
+                            fill_anomaly_markers(f.buffer_mut(), chart_top[0]);
+
+                            // Memory Chart
+                            let mem_max =
+                                mem_data.iter().map(|(_, v)| *v).fold(100.0_f64, f64::max);
+                            let mem_dataset = Dataset::default()
+                                .name("MEM")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Green))
+                                .data(&mem_data);
+
+                            let mem_chart =
+                                Chart::new(vec![mem_dataset, anomaly_dataset(&mem_anomalies)])
+                                    .block(
+                                        Block::default()
+                                            .title(format!(" Memory MB [{}] ", window.label()))
+                                            .borders(Borders::ALL)
+                                            .border_style(metrics_border),
+                                    )
+                                    .x_axis(
+                                        Axis::default()
+                                            .bounds([t_min, t_max])
+                                            .labels(x_labels.clone()),
+                                    )
+                                    .y_axis(
+                                        Axis::default().bounds([0.0, mem_max.max(100.0)]).labels(
+                                            vec![
+                                                Span::raw("0"),
+                                                Span::raw(format!("{:.0}", mem_max)),
+                                            ],
+                                        ),
+                                    );
+
+                            f.render_widget(mem_chart, chart_top[1]);
+                            fill_anomaly_markers(f.buffer_mut(), chart_top[1]);
 
-                        // Network Chart
-                        let net_max = net_data.iter().map(|(_, v)| *v).fold(10.0_f64, f64::max);
-                        let net_dataset = Dataset::default()
-                            .name("NET")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Yellow))
-                            .data(&net_data);
+                            // Network Chart
+                            let net_max = net_data.iter().map(|(_, v)| *v).fold(10.0_f64, f64::max);
+                            let net_dataset = Dataset::default()
+                                .name("NET")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Yellow))
+                                .data(&net_data);
 
-                        let net_chart =
-                            Chart::new(vec![net_dataset])
+                            let net_chart = Chart::new(vec![net_dataset])
                                 .block(
                                     Block::default()
-                                        .title(" Network KB/s ")
+                                        .title(format!(" Network KB/s [{}] ", window.label()))
                                         .borders(Borders::ALL)
                                         .border_style(metrics_border),
                                 )
@@ -3541,151 +6647,232 @@ async fn tui_loop(
                                     vec![Span::raw("0"), Span::raw(format!("{:.0}", net_max))],
                                 ));
 
-                        f.render_widget(net_chart, chart_bottom[0]);
-
-                        // Log Rate Chart
-                        let log_max = log_rate_data
-                            .iter()
-                            .map(|(_, v)| *v)
-                            .fold(10.0_f64, f64::max);
-                        let log_dataset = Dataset::default()
-                            .name("LOGS")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Magenta))
-                            .data(&log_rate_data);
-
-                        let log_chart = Chart::new(vec![log_dataset])
-                            .block(
-                                Block::default()
-                                    .title(" Logs/s ")
-                                    .borders(Borders::ALL)
-                                    .border_style(metrics_border),
-                            )
-                            .x_axis(Axis::default().bounds([t_min, t_max]).labels(x_labels))
-                            .y_axis(Axis::default().bounds([0.0, log_max.max(1.0)]).labels(vec![
-                                Span::raw("0"),
-                                Span::raw(format!("{:.0}", log_max)),
-                            ]));
+                            f.render_widget(net_chart, chart_bottom[0]);
 
-                        f.render_widget(log_chart, chart_bottom[1]);
-                    } else {
-                        // Medium layout: 1x2 grid (CPU + Log rate only)
-                        let chart_cols = Layout::default()
-                            .direction(Direction::Horizontal)
-                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                            .split(inspect_layout[1]);
-
-                        // CPU Chart
-                        let cpu_dataset = Dataset::default()
-                            .name("CPU")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Cyan))
-                            .data(&cpu_data);
-
-                        let cpu_chart = Chart::new(vec![cpu_dataset])
-                            .block(
-                                Block::default()
-                                    .title(format!(" CPU %{} ", metrics_title_suffix))
-                                    .borders(Borders::ALL)
-                                    .border_style(metrics_border),
-                            )
-                            .x_axis(
+                            // Disk I/O Chart
+                            let io_chart = io_chart(
+                                format!(" I/O KB/s [{}] ", window.label()),
+                                &io_read_data,
+                                &io_write_data,
+                                metrics_border,
                                 Axis::default()
                                     .bounds([t_min, t_max])
                                     .labels(x_labels.clone()),
-                            )
-                            .y_axis(
-                                Axis::default()
-                                    .bounds([0.0, 100.0])
-                                    .labels(vec![Span::raw("0"), Span::raw("100")]),
                             );
+                            f.render_widget(io_chart, chart_bottom[1]);
 
-                        f.render_widget(cpu_chart, chart_cols[0]);
+                            // Log Rate Chart
+                            let log_max = log_rate_data
+                                .iter()
+                                .map(|(_, v)| *v)
+                                .fold(10.0_f64, f64::max);
+                            let log_dataset = Dataset::default()
+                                .name("LOGS")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Magenta))
+                                .data(&log_rate_data);
+
+                            let log_chart = Chart::new(vec![log_dataset])
+                                .block(
+                                    Block::default()
+                                        .title(format!(" Logs/s [{}] ", window.label()))
+                                        .borders(Borders::ALL)
+                                        .border_style(metrics_border),
+                                )
+                                .x_axis(Axis::default().bounds([t_min, t_max]).labels(x_labels))
+                                .y_axis(Axis::default().bounds([0.0, log_max.max(1.0)]).labels(
+                                    vec![Span::raw("0"), Span::raw(format!("{:.0}", log_max))],
+                                ));
 
-                        // Log Rate Chart
-                        let log_max = log_rate_data
-                            .iter()
-                            .map(|(_, v)| *v)
-                            .fold(10.0_f64, f64::max);
-                        let log_dataset = Dataset::default()
-                            .name("LOGS")
-                            .marker(symbols::Marker::Braille)
-                            .graph_type(GraphType::Line)
-                            .style(Style::default().fg(Color::Magenta))
-                            .data(&log_rate_data);
-
-                        let log_chart = Chart::new(vec![log_dataset])
-                            .block(
-                                Block::default()
-                                    .title(" Logs/s ")
-                                    .borders(Borders::ALL)
-                                    .border_style(metrics_border),
-                            )
-                            .x_axis(Axis::default().bounds([t_min, t_max]).labels(x_labels))
-                            .y_axis(Axis::default().bounds([0.0, log_max.max(1.0)]).labels(vec![
-                                Span::raw("0"),
-                                Span::raw(format!("{:.0}", log_max)),
-                            ]));
+                            f.render_widget(log_chart, chart_bottom[2]);
+                        } else {
+                            // Medium layout: 1x2 grid (CPU + Log rate only)
+                            let chart_cols = Layout::default()
+                                .direction(Direction::Horizontal)
+                                .constraints([
+                                    Constraint::Percentage(50),
+                                    Constraint::Percentage(50),
+                                ])
+                                .split(inspect_layout[1]);
+
+                            // CPU Chart
+                            let cpu_dataset = Dataset::default()
+                                .name("CPU")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Cyan))
+                                .data(&cpu_data);
+
+                            let cpu_chart =
+                                Chart::new(vec![cpu_dataset, anomaly_dataset(&cpu_anomalies)])
+                                    .block(
+                                        Block::default()
+                                            .title(format!(
+                                                " CPU % [{}]{} ",
+                                                window.label(),
+                                                metrics_title_suffix
+                                            ))
+                                            .borders(Borders::ALL)
+                                            .border_style(metrics_border),
+                                    )
+                                    .x_axis(
+                                        Axis::default()
+                                            .bounds([t_min, t_max])
+                                            .labels(x_labels.clone()),
+                                    )
+                                    .y_axis(
+                                        Axis::default()
+                                            .bounds([0.0, 100.0])
+                                            .labels(vec![Span::raw("0"), Span::raw("100")]),
+                                    );
+
+                            f.render_widget(cpu_chart, chart_cols[0]);
+                            fill_anomaly_markers(f.buffer_mut(), chart_cols[0]);
+
+                            // Log Rate Chart
+                            let log_max = log_rate_data
+                                .iter()
+                                .map(|(_, v)| *v)
+                                .fold(10.0_f64, f64::max);
+                            let log_dataset = Dataset::default()
+                                .name("LOGS")
+                                .marker(symbols::Marker::Braille)
+                                .graph_type(GraphType::Line)
+                                .style(Style::default().fg(Color::Magenta))
+                                .data(&log_rate_data);
+
+                            let log_chart = Chart::new(vec![log_dataset])
+                                .block(
+                                    Block::default()
+                                        .title(format!(" Logs/s [{}] ", window.label()))
+                                        .borders(Borders::ALL)
+                                        .border_style(metrics_border),
+                                )
+                                .x_axis(Axis::default().bounds([t_min, t_max]).labels(x_labels))
+                                .y_axis(Axis::default().bounds([0.0, log_max.max(1.0)]).labels(
+                                    vec![Span::raw("0"), Span::raw(format!("{:.0}", log_max))],
+                                ));
 
-                        f.render_widget(log_chart, chart_cols[1]);
+                            f.render_widget(log_chart, chart_cols[1]);
+                        }
                     }
-                }
 
-                // ─────────────── Health Section ───────────────
-                // Only render Health in full/medium layouts (index 2 exists)
-                if layout_mode != "compact" && inspect_layout.len() > 2 {
-                    let mut health_lines: Vec<Line> = vec![];
+                    // ─────────────── Health Section ───────────────
+                    // Only render Health in full/medium layouts (index 2 exists)
+                    if layout_mode != "compact" && inspect_layout.len() > 2 {
+                        let mut health_lines: Vec<Line> = vec![];
+
+                        if let Some(node) = node {
+                            let health_str = match &node.observed.health {
+                                HealthStatus::Healthy => "All checks passing".to_string(),
+                                HealthStatus::Unhealthy { reason } => format!("Failed: {}", reason),
+                                HealthStatus::Degraded { reason } => {
+                                    format!("Degraded: {}", reason)
+                                }
+                                HealthStatus::Unknown => "No health checks configured".to_string(),
+                            };
+                            health_lines.push(Line::from(vec![
+                                Span::styled("Status  ", dim),
+                                Span::styled(health_str, health_style(&node.observed.health)),
+                            ]));
 
-                    if let Some(node) = node {
-                        let health_str = match &node.observed.health {
-                            HealthStatus::Healthy => "All checks passing".to_string(),
-                            HealthStatus::Unhealthy { reason } => format!("Failed: {}", reason),
-                            HealthStatus::Degraded { reason } => format!("Degraded: {}", reason),
-                            HealthStatus::Unknown => "No health checks configured".to_string(),
-                        };
-                        health_lines.push(Line::from(vec![
-                            Span::styled("Status  ", dim),
-                            Span::styled(health_str, health_style(&node.observed.health)),
-                        ]));
+                            // Most recent check outcomes as dots, newest last
+                            if let Some(history) = snapshot.health_history.get(id) {
+                                let room = inspect_layout[2].width.saturating_sub(2 + 8 + 8);
+                                let shown = history
+                                    .results
+                                    .iter()
+                                    .skip(history.results.len().saturating_sub(room as usize));
+                                let mut spans = vec![Span::styled("History ", dim)];
+                                spans.extend(
+                                    shown.map(|r| Span::styled("●", health_style(&r.outcome))),
+                                );
+                                if let Some(ms) = history.results.back().and_then(|r| r.latency_ms)
+                                {
+                                    spans.push(Span::styled(format!(" {}ms", ms), dim));
+                                }
+                                health_lines.push(Line::from(spans));
+                            }
 
-                        // Show check interval if configured
-                        if let Some(unit) = unit {
-                            if unit.health.is_some() {
-                                health_lines.push(Line::from(vec![
-                                    Span::styled("Check   ", dim),
-                                    Span::raw("HTTP health endpoint"),
-                                ]));
+                            // Show check interval if configured
+                            if let Some(unit) = unit {
+                                if unit.health.is_some() {
+                                    health_lines.push(Line::from(vec![
+                                        Span::styled("Check   ", dim),
+                                        Span::raw("HTTP health endpoint"),
+                                    ]));
+                                }
                             }
                         }
+
+                        let health_title = if focused_section == Some(InspectSection::Health) {
+                            " Health ★ "
+                        } else {
+                            " Health "
+                        };
+                        let health_section = Paragraph::new(health_lines).block(
+                            Block::default()
+                                .title(health_title)
+                                .borders(Borders::ALL)
+                                .border_style(health_border),
+                        );
+                        f.render_widget(health_section, inspect_layout[2]);
                     }
 
-                    let health_title = if focused_section == Some(InspectSection::Health) {
-                        " Health ★ "
-                    } else {
-                        " Health "
-                    };
-                    let health_section = Paragraph::new(health_lines).block(
-                        Block::default()
-                            .title(health_title)
-                            .borders(Borders::ALL)
-                            .border_style(health_border),
-                    );
-                    f.render_widget(health_section, inspect_layout[2]);
-                }
-            } else {
-                // Default rendering for other views
-                let right = Paragraph::new(scrolled_text)
-                    .block(
+                    // ─────────────── Notes Section ───────────────
+                    if notes_h > 0 && inspect_layout.len() > 3 {
+                        let title = if notes.len() > notes_shown.len() {
+                            format!(" Notes ({} of {}) ", notes_shown.len(), notes.len())
+                        } else {
+                            " Notes ".to_string()
+                        };
+                        let notes_section = Paragraph::new(note_lines).block(
+                            Block::default()
+                                .title(title)
+                                .borders(Borders::ALL)
+                                .border_style(dim),
+                        );
+                        f.render_widget(notes_section, inspect_layout[3]);
+                    }
+
+                    // ─────────────── Automation Section ───────────────
+                    if automation_h > 0 && inspect_layout.len() > 4 {
+                        let automation_section = Paragraph::new(automation_lines).block(
+                            Block::default()
+                                .title(" Automation ")
+                                .borders(Borders::ALL)
+                                .border_style(dim),
+                        );
+                        f.render_widget(automation_section, inspect_layout[4]);
+                    }
+                } else {
+                    // Default rendering for other views
+                    let right = Paragraph::new(scrolled_text).block(
                         Block::default()
                             .title(Span::styled(title, title_style))
                             .borders(Borders::ALL)
                             .border_style(right_border_style),
-                    )
-                    .wrap(Wrap { trim: false });
+                    );
+                    // Horizontal scrolling only applies to unwrapped lines
+                    let right = if *view == View::Logs && ui.logs.scroll_x > 0 {
+                        right.scroll((0, ui.logs.scroll_x.min(u16::MAX as usize) as u16))
+                    } else {
+                        right.wrap(Wrap { trim: false })
+                    };
 
-                f.render_widget(right, main[1]);
+                    f.render_widget(right, pane);
+
+                    // `<` on the left border beside lines cut off by the scroll
+                    for row in clipped_rows {
+                        let y = pane.y + 1 + row;
+                        if y + 1 < pane.y + pane.height {
+                            f.buffer_mut()[(pane.x, y)]
+                                .set_symbol("<")
+                                .set_style(styles::text_dim());
+                        }
+                    }
+                }
             }
 
             // ---------------- Footer (always visible, context-sensitive) ----------------
@@ -3731,7 +6918,7 @@ async fn tui_loop(
             ];
 
             // Context-sensitive hints based on focus + view (keys in key_hint, labels in text_dim)
-            let context_hints: Vec<Span> = match (ui.focus, ui.view) {
+            let context_hints: Vec<Span> = match (ui.focus, &ui.view) {
                 (Focus::Palette, _) => vec![
                     Span::styled("↑↓", styles::key_hint()),
                     Span::styled(" select  ", styles::text_dim()),
@@ -3740,6 +6927,14 @@ async fn tui_loop(
                     Span::styled("Esc", styles::key_hint()),
                     Span::styled(" close", styles::text_dim()),
                 ],
+                (Focus::Units, View::Metrics) => vec![
+                    Span::styled("↑↓", styles::key_hint()),
+                    Span::styled(" select  ", styles::text_dim()),
+                    Span::styled("Enter", styles::key_hint()),
+                    Span::styled(" unit charts  ", styles::text_dim()),
+                    Span::styled("r", styles::key_hint()),
+                    Span::styled(" restart", styles::text_dim()),
+                ],
                 (Focus::Units, _) => vec![
                     Span::styled("↑↓", styles::key_hint()),
                     Span::styled(" select  ", styles::text_dim()),
@@ -3801,11 +6996,19 @@ async fn tui_loop(
                         InspectSection::Metrics => "Metrics",
                         InspectSection::Health => "Health",
                     };
-                    vec![
+                    let mut hints = vec![
                         Span::styled("Tab", styles::key_hint()),
                         Span::styled(" section  ", styles::text_dim()),
-                        Span::styled(format!("[{}]", section_name), styles::accent()),
-                    ]
+                    ];
+                    if section == InspectSection::Metrics {
+                        hints.push(Span::styled("[ ]", styles::key_hint()));
+                        hints.push(Span::styled(" window  ", styles::text_dim()));
+                    }
+                    hints.push(Span::styled(
+                        format!("[{}]", section_name),
+                        styles::accent(),
+                    ));
+                    hints
                 }
                 (Focus::RightPane, _) => vec![
                     Span::styled("↑↓", styles::key_hint()),
@@ -3834,9 +7037,25 @@ async fn tui_loop(
 
             // ---------------- VS Code Style Command Picker Modal ----------------
             if ui.palette_open {
+                let history_mode = matches!(ui.palette_mode, PaletteMode::History { .. });
+                let show_sections = ui.palette_input.is_empty() && !history_mode;
+                let section_count = if show_sections {
+                    picker_items
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, item)| {
+                            *i == 0 || picker_items[i - 1].category != item.category
+                        })
+                        .count()
+                } else {
+                    0
+                };
+
                 // Centered modal - 60% width, max 50 chars, vertically centered
                 let modal_width = (area.width * 60 / 100).min(60).max(30);
-                let modal_height = (picker_items.len() as u16 + 4).min(area.height - 4).max(6);
+                let modal_height = ((picker_items.len() + section_count) as u16 + 5)
+                    .min(area.height - 4)
+                    .max(7);
                 let modal_x = (area.width.saturating_sub(modal_width)) / 2;
                 let modal_y = (area.height.saturating_sub(modal_height)) / 2;
 
@@ -3853,15 +7072,23 @@ async fn tui_loop(
                 let block = Block::default()
                     .borders(Borders::ALL)
                     .border_style(styles::border_focused())
-                    .title(" Commands ");
+                    .title(if history_mode {
+                        " History "
+                    } else {
+                        " Commands "
+                    });
 
                 let inner = block.inner(modal_rect);
                 f.render_widget(block, modal_rect);
 
-                // Layout: input line at top, then items
+                // Layout: input line at top, then items, then key hints
                 let modal_parts = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Length(1), Constraint::Min(1)])
+                    .constraints([
+                        Constraint::Length(1),
+                        Constraint::Min(1),
+                        Constraint::Length(1),
+                    ])
                     .split(inner);
 
                 // Input line with > prompt
@@ -3882,40 +7109,24 @@ async fn tui_loop(
                 ]);
                 f.render_widget(Paragraph::new(input_line), input_area);
 
-                // Items list
-                let items_area = modal_parts[1];
-                let visible_count = items_area.height as usize;
-
-                // Adjust scroll offset to keep selection visible
-                if ui.palette_pick < ui.palette_sugg_offset {
-                    ui.palette_sugg_offset = ui.palette_pick;
-                } else if visible_count > 0
-                    && ui.palette_pick >= ui.palette_sugg_offset + visible_count
-                {
-                    ui.palette_sugg_offset = ui.palette_pick.saturating_sub(visible_count - 1);
-                }
-
-                // Build list items with category grouping
-                let mut list_items: Vec<ListItem> = vec![];
-                let mut last_category: Option<PickerCategory> = None;
-
-                for (i, item) in picker_items
-                    .iter()
-                    .skip(ui.palette_sugg_offset)
-                    .take(visible_count)
-                    .enumerate()
-                {
-                    let actual_idx = ui.palette_sugg_offset + i;
-                    let is_selected = actual_idx == ui.palette_pick;
+                // Build rows: category headers (not selectable) interleaved with items
+                let header_style = styles::text_dim().add_modifier(Modifier::BOLD);
+                let mut rows: Vec<ListItem> = vec![];
+                let mut selected_row = 0;
+                let mut last_category: Option<&PickerCategory> = None;
+
+                for (idx, item) in picker_items.iter().enumerate() {
+                    if show_sections && last_category != Some(&item.category) {
+                        rows.push(ListItem::new(Line::from(vec![
+                            Span::styled(format!("{} ", item.category.icon()), header_style),
+                            Span::styled(format!("── {} ──", item.category.label()), header_style),
+                        ])));
+                        last_category = Some(&item.category);
+                    }
 
-                    // Category header (only when not filtering and category changes)
-                    if ui.palette_input.is_empty() && last_category.as_ref() != Some(&item.category)
-                    {
-                        if last_category.is_some() && list_items.len() < visible_count {
-                            // Add separator line between categories
-                            list_items.push(ListItem::new(Line::from("")));
-                        }
-                        last_category = Some(item.category.clone());
+                    let is_selected = idx == ui.palette_pick;
+                    if is_selected {
+                        selected_row = rows.len();
                     }
 
                     // Build item line
@@ -3931,8 +7142,25 @@ async fn tui_loop(
                     let mut spans = vec![
                         Span::raw(prefix),
                         Span::styled(format!("{} ", icon), styles::text_muted()),
-                        Span::styled(&item.label, item_style),
                     ];
+                    // Highlight the chars the query matched, in runs
+                    let matched = item
+                        .fuzzy_match_indices(&ui.palette_input)
+                        .unwrap_or_default();
+                    let match_style = item_style.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let mut run = String::new();
+                    let mut run_matched = false;
+                    for (i, c) in item.label.chars().enumerate() {
+                        let is_match = matched.contains(&i);
+                        if is_match != run_matched && !run.is_empty() {
+                            let style = if run_matched { match_style } else { item_style };
+                            spans.push(Span::styled(std::mem::take(&mut run), style));
+                        }
+                        run_matched = is_match;
+                        run.push(c);
+                    }
+                    let style = if run_matched { match_style } else { item_style };
+                    spans.push(Span::styled(run, style));
 
                     // Add detail if present and space allows
                     if let Some(detail) = &item.detail {
@@ -3949,18 +7177,88 @@ async fn tui_loop(
                         }
                     }
 
-                    list_items.push(ListItem::new(Line::from(spans)));
+                    rows.push(ListItem::new(Line::from(spans)));
+                }
+
+                // Items list
+                let items_area = modal_parts[1];
+                let visible_count = items_area.height as usize;
+
+                // Adjust scroll offset (in rows) to keep selection and its header visible
+                let top_row = if show_sections && selected_row > 0 {
+                    selected_row - 1
+                } else {
+                    selected_row
+                };
+                if top_row < ui.palette_sugg_offset {
+                    ui.palette_sugg_offset = top_row;
+                } else if visible_count > 0
+                    && selected_row >= ui.palette_sugg_offset + visible_count
+                {
+                    ui.palette_sugg_offset = selected_row.saturating_sub(visible_count - 1);
                 }
+                ui.palette_sugg_offset = ui
+                    .palette_sugg_offset
+                    .min(rows.len().saturating_sub(visible_count));
+
+                let mut list_items: Vec<ListItem> = rows
+                    .into_iter()
+                    .skip(ui.palette_sugg_offset)
+                    .take(visible_count)
+                    .collect();
 
                 if list_items.is_empty() {
+                    let empty_text = if history_mode && ui.history.is_empty() {
+                        "  No command history yet"
+                    } else {
+                        "  No matching commands"
+                    };
                     list_items.push(ListItem::new(Line::from(vec![Span::styled(
-                        "  No matching commands",
+                        empty_text,
                         styles::text_muted(),
                     )])));
                 }
 
                 f.render_widget(List::new(list_items), items_area);
 
+                // Key hints; Enter label depends on what the selection does
+                let enter_label = match picker_items.get(ui.palette_pick) {
+                    Some(item) if item.target_view.is_some() => " navigate to view  ",
+                    _ => " execute  ",
+                };
+                let hints = Line::from(vec![
+                    Span::styled("↑↓", styles::key_hint()),
+                    Span::styled(" navigate  ", styles::text_dim()),
+                    Span::styled("Enter", styles::key_hint()),
+                    Span::styled(enter_label, styles::text_dim()),
+                    Span::styled(
+                        if history_mode { "Ctrl+R" } else { "Tab" },
+                        styles::key_hint(),
+                    ),
+                    Span::styled(
+                        if history_mode {
+                            " next match  "
+                        } else {
+                            " autocomplete  "
+                        },
+                        styles::text_dim(),
+                    ),
+                    Span::styled("Esc", styles::key_hint()),
+                    Span::styled(
+                        if ui.palette_mode
+                            == (PaletteMode::History {
+                                from_commands: true,
+                            })
+                        {
+                            " back"
+                        } else {
+                            " close"
+                        },
+                        styles::text_dim(),
+                    ),
+                ]);
+                f.render_widget(Paragraph::new(hints), modal_parts[2]);
+
                 // Position cursor at end of input
                 let cursor_x = input_area.x + prompt.len() as u16 + input_display.len() as u16;
                 let cursor_y = input_area.y;
@@ -3979,7 +7277,9 @@ async fn tui_loop(
 
                 f.render_widget(Clear, search_rect);
 
-                let match_info = if ui.logs.matches.is_empty() {
+                let match_info = if let Some(error) = &ui.logs.search_error {
+                    format!(" (invalid regex: {})", error)
+                } else if ui.logs.matches.is_empty() {
                     if query.is_empty() {
                         String::new()
                     } else {
@@ -3989,7 +7289,11 @@ async fn tui_loop(
                     format!(" ({}/{})", ui.logs.match_idx + 1, ui.logs.matches.len())
                 };
 
-                let title = format!(" Search{} ", match_info);
+                let context = match ui.logs.context_lines {
+                    0 => String::new(),
+                    n => format!(" ±{}", n),
+                };
+                let title = format!(" Search{}{} ", match_info, context);
                 let block = Block::default()
                     .title(title)
                     .borders(Borders::ALL)
@@ -4003,11 +7307,121 @@ async fn tui_loop(
                 f.set_cursor_position((cursor_x, cursor_y));
             }
 
+            // ---------------- Run Input Bar (stdin for the selected run) ----------------
+            if let Some(input) = &ui.run_input {
+                let input_h = 3u16;
+                let input_rect = Rect {
+                    x: main[1].x,
+                    width: main[1].width,
+                    height: input_h,
+                    y: main[1].y + main[1].height.saturating_sub(input_h),
+                };
+
+                f.render_widget(Clear, input_rect);
+
+                let run_name = snapshot
+                    .run_order
+                    .get(ui.selected_run)
+                    .and_then(|id| snapshot.runs.get(id))
+                    .map(|r| r.display_name.as_str())
+                    .unwrap_or("run");
+                let block = Block::default()
+                    .title(format!(" stdin → {} (Enter send, Esc close) ", run_name))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan));
+
+                let input_text = format!("> {}", input);
+                f.render_widget(Paragraph::new(input_text).block(block), input_rect);
+
+                let cursor_x = input_rect.x + 3 + input.len() as u16;
+                let cursor_y = input_rect.y + 1;
+                f.set_cursor_position((cursor_x, cursor_y));
+            }
+
+            // ---------------- Note Input Bar ----------------
+            if let Some((id, input)) = &ui.note_input {
+                let input_h = 3u16;
+                let input_rect = Rect {
+                    x: main[1].x,
+                    width: main[1].width,
+                    height: input_h,
+                    y: main[1].y + main[1].height.saturating_sub(input_h),
+                };
+
+                f.render_widget(Clear, input_rect);
+
+                let block = Block::default()
+                    .title(format!(" Note → {} (Enter save, Esc cancel) ", id))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan));
+
+                let input_text = format!("{}{}", NOTE_PREFIX, input);
+                f.render_widget(Paragraph::new(input_text).block(block), input_rect);
+
+                // Border plus the two-column emoji and its space
+                let cursor_x = input_rect.x + 4 + input.len() as u16;
+                let cursor_y = input_rect.y + 1;
+                f.set_cursor_position((cursor_x, cursor_y));
+            }
+
+            // ---------------- Log Export Input Bar ----------------
+            if let Some(input) = &ui.log_export_input {
+                let input_h = 3u16;
+                let input_rect = Rect {
+                    x: main[1].x,
+                    width: main[1].width,
+                    height: input_h,
+                    y: main[1].y + main[1].height.saturating_sub(input_h),
+                };
+
+                f.render_widget(Clear, input_rect);
+
+                let block = Block::default()
+                    .title(" Write logs to (Enter save, Esc cancel) ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan));
+
+                let input_text = format!("> {}", input);
+                f.render_widget(Paragraph::new(input_text).block(block), input_rect);
+
+                let cursor_x = input_rect.x + 3 + input.len() as u16;
+                let cursor_y = input_rect.y + 1;
+                f.set_cursor_position((cursor_x, cursor_y));
+            }
+
+            // ---------------- Metrics Drill-down ----------------
+            if let View::MetricsDrill { id } = &ui.view {
+                draw_metrics_drill(f, area, id, &snapshot.metrics_series, ui.chart_window);
+            }
+
+            // ---------------- Toasts ----------------
+            ui::toast::render(f, outer[1], &ui.toasts);
+
+            // ---------------- Confirmation ----------------
+            if let Some(confirm) = &ui.pending_confirm {
+                let width = (confirm.prompt.chars().count() as u16 + 4).min(area.width);
+                let confirm_rect = Rect {
+                    x: area.x + (area.width - width) / 2,
+                    y: area.y + area.height.saturating_sub(3) / 2,
+                    width,
+                    height: 3u16.min(area.height),
+                };
+
+                f.render_widget(Clear, confirm_rect);
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(styles::warn());
+                f.render_widget(
+                    Paragraph::new(confirm.prompt.as_str()).block(block),
+                    confirm_rect,
+                );
+            }
+
             // ---------------- Help Overlay ----------------
             if ui.help_open {
                 // Centered modal
                 let help_width = 50u16.min(area.width - 4);
-                let help_height = 24u16.min(area.height - 4);
+                let help_height = 40u16.min(area.height - 4);
                 let help_x = (area.width.saturating_sub(help_width)) / 2;
                 let help_y = (area.height.saturating_sub(help_height)) / 2;
 
@@ -4059,6 +7473,10 @@ async fn tui_loop(
                         Span::styled("  Tab ", styles::key_hint()),
                         Span::styled("Switch focus (Services ↔ Right)", styles::text()),
                     ]),
+                    Line::from(vec![
+                        Span::styled("  [ ] ", styles::key_hint()),
+                        Span::styled("Inspect chart window (30s/60s/5m)", styles::text()),
+                    ]),
                     Line::from(vec![
                         Span::styled("  j/k ↑↓ ", styles::key_hint()),
                         Span::styled("Move selection / scroll", styles::text()),
@@ -4072,6 +7490,10 @@ async fn tui_loop(
                         Span::styled("  Enter ", styles::key_hint()),
                         Span::styled("Toggle service (start/stop)", styles::text()),
                     ]),
+                    Line::from(vec![
+                        Span::styled("        ", styles::key_hint()),
+                        Span::styled("In Metrics: full-screen unit charts", styles::text()),
+                    ]),
                     Line::from(vec![
                         Span::styled("  r     ", styles::key_hint()),
                         Span::styled("Restart service", styles::text()),
@@ -4089,8 +7511,32 @@ async fn tui_loop(
                         Span::styled("Kill service", styles::text()),
                     ]),
                     Line::from(vec![
-                        Span::styled("  c     ", styles::key_hint()),
-                        Span::styled("Clear logs", styles::text()),
+                        Span::styled("  Space ", styles::key_hint()),
+                        Span::styled("Mark service for r/s/t/x", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  c     ", styles::key_hint()),
+                        Span::styled("Clear logs", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  N     ", styles::key_hint()),
+                        Span::styled("Add a note to the unit", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  P     ", styles::key_hint()),
+                        Span::styled("Pin unit to the top of the list", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  O F   ", styles::key_hint()),
+                        Span::styled("Units: cycle sort, status filter", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  4     ", styles::key_hint()),
+                        Span::styled("Groups: r/s/t/x act on the whole group", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  s S f ", styles::key_hint()),
+                        Span::styled("Runs: sort, reverse, filter", styles::text()),
                     ]),
                     Line::from(""),
                     Line::from(vec![Span::styled(
@@ -4101,14 +7547,46 @@ async fn tui_loop(
                         Span::styled("  /     ", styles::key_hint()),
                         Span::styled("Open command picker", styles::text()),
                     ]),
+                    Line::from(vec![
+                        Span::styled("  ^R    ", styles::key_hint()),
+                        Span::styled("Search command history", styles::text()),
+                    ]),
                     Line::from(vec![
                         Span::styled("  s     ", styles::key_hint()),
                         Span::styled("Search logs (in Logs view)", styles::text()),
                     ]),
+                    Line::from(vec![
+                        Span::styled("  + -   ", styles::key_hint()),
+                        Span::styled("Context lines around matches", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  o     ", styles::key_hint()),
+                        Span::styled("Open file:line from logs in $EDITOR", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  C     ", styles::key_hint()),
+                        Span::styled("Toggle log level colors", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  N     ", styles::key_hint()),
+                        Span::styled("Toggle log line numbers", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  J     ", styles::key_hint()),
+                        Span::styled("Format JSON log lines", styles::text()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  W     ", styles::key_hint()),
+                        Span::styled("Write visible logs to a file", styles::text()),
+                    ]),
                     Line::from(vec![
                         Span::styled("  ?     ", styles::key_hint()),
                         Span::styled("Toggle this help", styles::text()),
                     ]),
+                    Line::from(vec![
+                        Span::styled("  ^L    ", styles::key_hint()),
+                        Span::styled("Clear and redraw the screen", styles::text()),
+                    ]),
                     Line::from(vec![
                         Span::styled("  q     ", styles::key_hint()),
                         Span::styled("Quit Orkesy", styles::text()),
@@ -4122,17 +7600,64 @@ async fn tui_loop(
         drop(snapshot);
 
         if !event::poll(Duration::from_millis(50))? {
+            // Advancing only on idle ticks keeps the spinner at ~20 fps
+            // however fast keys arrive
+            ui.spin_frame = ui.spin_frame.wrapping_add(1);
             continue;
         }
 
         let ev = event::read()?;
-        let CEvent::Key(KeyEvent {
-            code, modifiers, ..
-        }) = ev
-        else {
-            continue;
+        let (code, modifiers) = match ev {
+            CEvent::Key(KeyEvent {
+                code, modifiers, ..
+            }) => (code, modifiers),
+            // Redraw at the new size right away instead of on the next poll tick
+            CEvent::Resize(..) => {
+                terminal.autoresize()?;
+                continue;
+            }
+            // Click a unit to select it; the wheel scrolls the logs
+            CEvent::Mouse(MouseEvent {
+                kind, column, row, ..
+            }) => {
+                if ui.palette_open
+                    || ui.help_open
+                    || ui.pending_confirm.is_some()
+                    || matches!(ui.view, View::MetricsDrill { .. })
+                {
+                    continue;
+                }
+                let pos = Position::new(column, row);
+                let in_logs = logs_area.is_some_and(|area| area.contains(pos));
+                match kind {
+                    MouseEventKind::Down(MouseButton::Left)
+                        if ui.left_mode == LeftMode::Services
+                            && units_area.inner(Margin::new(1, 1)).contains(pos) =>
+                    {
+                        let index = list_state.offset() + (row - units_area.y - 1) as usize;
+                        if index < unit_rows.len() {
+                            *selected = index;
+                            list_state.select(Some(index));
+                            ui.focus = Focus::Units;
+                            ui.enter_follow();
+                        }
+                    }
+                    MouseEventKind::ScrollUp if in_logs => ui.logs.scroll_up(3),
+                    MouseEventKind::ScrollDown if in_logs => ui.logs.scroll_down(3),
+                    _ => {}
+                }
+                continue;
+            }
+            _ => continue,
         };
 
+        // Ctrl+L: clear and repaint everything, in any mode (e.g. after
+        // another program wrote over the screen)
+        if (code, modifiers) == (KeyCode::Char('l'), KeyModifiers::CONTROL) {
+            terminal.clear()?;
+            continue;
+        }
+
         // ========== KEY HANDLING (new state machine) ==========
 
         // Helper to get the total number of log lines for scrolling calculations
@@ -4161,22 +7686,15 @@ async fn tui_loop(
             }
         };
 
-        // Helper to update search matches - searches the correct buffer based on view mode
-        let update_search_matches = |query: &str,
-                                     snapshot: &RuntimeState,
-                                     sid: Option<&str>,
-                                     left_mode: LeftMode,
-                                     selected_run: usize,
-                                     paused: bool,
-                                     frozen_logs: &[DisplayLogLine]|
-         -> Vec<usize> {
-            if query.is_empty() {
-                return vec![];
-            }
-            let search_lower = query.to_lowercase();
-
-            // Get the log texts based on current view mode (matching the display logic)
-            let logs: Vec<String> = if paused {
+        // Helper to get the log texts based on current view mode (matching the display logic)
+        let get_log_texts = |snapshot: &RuntimeState,
+                             sid: Option<&str>,
+                             left_mode: LeftMode,
+                             selected_run: usize,
+                             paused: bool,
+                             frozen_logs: &[DisplayLogLine]|
+         -> Vec<String> {
+            if paused {
                 frozen_logs.iter().map(|l| l.text.clone()).collect()
             } else if left_mode == LeftMode::Runs {
                 // Search per_run logs
@@ -4199,15 +7717,48 @@ async fn tui_loop(
                 sid.and_then(|id| snapshot.logs.per_service.get(id))
                     .map(|l| l.iter().map(|x| x.text.clone()).collect())
                     .unwrap_or_default()
-            };
+            }
+        };
+
+        // Helper to update search matches - searches the correct buffer based on view mode
+        let update_search_matches = |search: &LogsUiState,
+                                     snapshot: &RuntimeState,
+                                     sid: Option<&str>,
+                                     left_mode: LeftMode,
+                                     selected_run: usize|
+         -> Vec<usize> {
+            if search.search.as_deref().is_none_or(str::is_empty) {
+                return vec![];
+            }
+            let logs = get_log_texts(
+                snapshot,
+                sid,
+                left_mode,
+                selected_run,
+                search.paused,
+                &search.frozen_logs,
+            );
 
             logs.iter()
                 .enumerate()
-                .filter(|(_, text)| text.to_lowercase().contains(&search_lower))
+                .filter(|(_, text)| search.search_matches(&ui::ansi::strip_ansi(text)))
                 .map(|(idx, _)| idx)
                 .collect()
         };
 
+        // ---------- CONFIRM MODE ----------
+        // `y` runs the pending action; any other key cancels it
+        if let Some(confirm) = ui.pending_confirm.take() {
+            if code == KeyCode::Char('y') {
+                for action in confirm.actions {
+                    action.execute(&backend).await;
+                }
+            } else {
+                ui.toasts.push(Toast::new("Cancelled", ToastLevel::Info));
+            }
+            continue;
+        }
+
         // ---------- HELP MODE ----------
         if ui.help_open {
             match code {
@@ -4219,10 +7770,34 @@ async fn tui_loop(
             continue;
         }
 
+        // ---------- METRICS DRILL-DOWN ----------
+        if let View::MetricsDrill { .. } = ui.view {
+            match code {
+                KeyCode::Esc | KeyCode::Char('q') => ui.view = View::Metrics,
+                KeyCode::Char(']') => ui.chart_window = ui.chart_window.next(),
+                KeyCode::Char('[') => ui.chart_window = ui.chart_window.prev(),
+                _ => {}
+            }
+            continue;
+        }
+
         // ---------- PALETTE MODE ----------
         if ui.palette_open {
             match (code, modifiers) {
                 (KeyCode::Esc, _) => {
+                    if ui.palette_mode
+                        == (PaletteMode::History {
+                            from_commands: true,
+                        })
+                    {
+                        // Back to the full command list
+                        ui.palette_mode = PaletteMode::Commands;
+                        ui.palette_input.clear();
+                        ui.palette_error = None;
+                        ui.palette_pick = 0;
+                        ui.palette_sugg_offset = 0;
+                        continue;
+                    }
                     ui.palette_open = false;
                     ui.focus = Focus::Units; // Return focus to units
                     ui.palette_input.clear();
@@ -4230,6 +7805,27 @@ async fn tui_loop(
                     ui.palette_pick = 0;
                     ui.palette_scroll = 0;
                     ui.palette_sugg_offset = 0;
+                    ui.palette_mode = PaletteMode::Commands;
+                    ui.history_cursor = None;
+                }
+                (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
+                    if ui.palette_mode == PaletteMode::Commands {
+                        ui.palette_mode = PaletteMode::History {
+                            from_commands: true,
+                        };
+                        ui.palette_input.clear();
+                        ui.palette_pick = 0;
+                        ui.palette_sugg_offset = 0;
+                    } else {
+                        // Like bash reverse search: step to the next older match
+                        let count =
+                            palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs)
+                                .len();
+                        if count > 0 {
+                            ui.palette_pick = (ui.palette_pick + 1) % count;
+                        }
+                    }
+                    ui.palette_error = None;
                     ui.history_cursor = None;
                 }
                 (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
@@ -4272,8 +7868,8 @@ async fn tui_loop(
                 }
                 (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::CONTROL) => {
                     // Use picker_items for bounds check
-                    let all_items = build_picker_items(&service_ids, selected_id, &[]);
-                    let filtered = filter_picker_items(&all_items, &ui.palette_input);
+                    let filtered =
+                        palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs);
                     if ui.palette_pick + 1 < filtered.len() {
                         ui.palette_pick += 1;
                         // Keep scroll in sync - estimate visible area (~15 items typical)
@@ -4291,8 +7887,8 @@ async fn tui_loop(
                     ui.palette_sugg_offset = ui.palette_sugg_offset.saturating_sub(page_size);
                 }
                 (KeyCode::PageDown, _) => {
-                    let all_items = build_picker_items(&service_ids, selected_id, &[]);
-                    let filtered = filter_picker_items(&all_items, &ui.palette_input);
+                    let filtered =
+                        palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs);
                     let page_size = 10usize;
                     ui.palette_pick =
                         (ui.palette_pick + page_size).min(filtered.len().saturating_sub(1));
@@ -4304,17 +7900,33 @@ async fn tui_loop(
                     ui.palette_sugg_offset = 0;
                 }
                 (KeyCode::End, _) => {
-                    let all_items = build_picker_items(&service_ids, selected_id, &[]);
-                    let filtered = filter_picker_items(&all_items, &ui.palette_input);
+                    let filtered =
+                        palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs);
                     ui.palette_pick = filtered.len().saturating_sub(1);
                     ui.palette_sugg_offset = filtered.len().saturating_sub(15);
                 }
                 (KeyCode::Tab, _) => {
-                    // Tab autocomplete: fill input with selected item's label
-                    let all_items = build_picker_items(&service_ids, selected_id, &[]);
-                    let filtered = filter_picker_items(&all_items, &ui.palette_input);
-                    if let Some(item) = filtered.get(ui.palette_pick) {
-                        ui.palette_input = item.label.clone();
+                    // Tab autocomplete: extend the input to the prefix all
+                    // items share, else fill in the selected item's label
+                    // (plus the shell command for detected commands)
+                    let filtered =
+                        palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs);
+                    let labels: Vec<&str> = filtered.iter().map(|i| i.label.as_str()).collect();
+                    let prefix = ui::palette::common_prefix(&labels);
+                    let extends_input = prefix.len() > ui.palette_input.len()
+                        && prefix
+                            .to_lowercase()
+                            .starts_with(&ui.palette_input.to_lowercase());
+                    if filtered.len() > 1 && extends_input {
+                        ui.palette_input = prefix.to_string();
+                        ui.palette_error = None;
+                    } else if let Some(item) = filtered.get(ui.palette_pick) {
+                        ui.palette_input = match (&item.category, &item.detail) {
+                            (PickerCategory::DetectedCommand, Some(detail)) => {
+                                format!("{} {}", item.label, detail)
+                            }
+                            _ => item.label.clone(),
+                        };
                         ui.palette_error = None;
                     }
                 }
@@ -4334,14 +7946,14 @@ async fn tui_loop(
                 }
                 (KeyCode::Enter, _) => {
                     // Execute the selected picker item
-                    let all_items = build_picker_items(&service_ids, selected_id, &[]);
-                    let filtered = filter_picker_items(&all_items, &ui.palette_input);
+                    let filtered =
+                        palette_items(&ui, &service_ids, &groups, selected_id, &recent_runs);
 
                     if let Some(item) = filtered.get(ui.palette_pick) {
                         // Handle navigation items
-                        if let Some(view) = item.target_view {
-                            ui.view = view;
-                            if view == View::Logs {
+                        if let Some(view) = &item.target_view {
+                            ui.view = view.clone();
+                            if *view == View::Logs {
                                 ui.enter_follow();
                             }
                             ui.palette_open = false;
@@ -4351,13 +7963,50 @@ async fn tui_loop(
                             ui.palette_pick = 0;
                             ui.palette_scroll = 0;
                             ui.palette_sugg_offset = 0;
+                            ui.palette_mode = PaletteMode::Commands;
                             ui.history_cursor = None;
                             continue;
                         }
 
+                        // Re-run a recently run project command
+                        if item.category == PickerCategory::RunCommand {
+                            let snap = state.read().await;
+                            let spec = item
+                                .command
+                                .as_ref()
+                                .and_then(|id| snap.project.as_ref()?.commands.get(id).cloned());
+                            drop(snap);
+                            match spec {
+                                Some(spec) => {
+                                    let _ = runner_cmd_tx
+                                        .send(runner::RunnerCommand::Run {
+                                            spec,
+                                            interactive: false,
+                                        })
+                                        .await;
+                                    ui.left_mode = LeftMode::Runs;
+                                    ui.selected_run = 0;
+                                    ui.palette_open = false;
+                                    ui.focus = Focus::Units;
+                                    ui.palette_input.clear();
+                                    ui.palette_error = None;
+                                    ui.palette_pick = 0;
+                                    ui.palette_scroll = 0;
+                                    ui.palette_sugg_offset = 0;
+                                    ui.palette_mode = PaletteMode::Commands;
+                                    ui.history_cursor = None;
+                                }
+                                None => {
+                                    ui.palette_error =
+                                        Some("Command is no longer in the project index".into());
+                                }
+                            }
+                            continue;
+                        }
+
                         // Handle command items
                         if let Some(ref cmd_str) = item.command {
-                            match parse_command(cmd_str, &service_ids) {
+                            match parse_command(cmd_str, &service_ids, &groups) {
                                 Ok(commands) => {
                                     // Add to history
                                     if ui.history.last().map(|s| s.as_str()) != Some(&item.label) {
@@ -4381,6 +8030,7 @@ async fn tui_loop(
                                     ui.palette_pick = 0;
                                     ui.palette_scroll = 0;
                                     ui.palette_sugg_offset = 0;
+                                    ui.palette_mode = PaletteMode::Commands;
                                     ui.history_cursor = None;
                                 }
                                 Err(e) => {
@@ -4412,15 +8062,14 @@ async fn tui_loop(
                 KeyCode::Backspace => {
                     if let Some(ref mut query) = ui.logs.search {
                         query.pop();
+                        ui.logs.compile_search();
                         let snap = state.read().await;
                         ui.logs.matches = update_search_matches(
-                            query,
+                            &ui.logs,
                             &snap,
                             selected_id,
                             ui.left_mode,
                             ui.selected_run,
-                            ui.logs.paused,
-                            &ui.logs.frozen_logs,
                         );
                         ui.logs.match_idx = 0;
 
@@ -4481,18 +8130,24 @@ async fn tui_loop(
                         ui.logs.follow = false;
                     }
                 }
+                // Context lines around each match
+                KeyCode::Char('+') => {
+                    ui.logs.context_lines = (ui.logs.context_lines + 1).min(MAX_SEARCH_CONTEXT);
+                }
+                KeyCode::Char('-') => {
+                    ui.logs.context_lines = ui.logs.context_lines.saturating_sub(1);
+                }
                 KeyCode::Char(c) => {
                     if let Some(ref mut query) = ui.logs.search {
                         query.push(c);
+                        ui.logs.compile_search();
                         let snap = state.read().await;
                         ui.logs.matches = update_search_matches(
-                            query,
+                            &ui.logs,
                             &snap,
                             selected_id,
                             ui.left_mode,
                             ui.selected_run,
-                            ui.logs.paused,
-                            &ui.logs.frozen_logs,
                         );
                         ui.logs.match_idx = 0;
 
@@ -4518,9 +8173,110 @@ async fn tui_loop(
             continue;
         }
 
+        // ---------- RUN INPUT MODE ----------
+        if let Some(ref mut input) = ui.run_input {
+            match code {
+                KeyCode::Esc => {
+                    ui.run_input = None;
+                }
+                KeyCode::Enter => {
+                    let data = format!("{}\n", input);
+                    input.clear();
+                    let snap = state.read().await;
+                    if let Some(run_id) = snap.run_order.get(ui.selected_run).cloned() {
+                        drop(snap);
+                        let _ = runner_cmd_tx
+                            .send(runner::RunnerCommand::SendInput { run_id, data })
+                            .await;
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // ---------- NOTE INPUT MODE ----------
+        if let Some((id, input)) = &mut ui.note_input {
+            match code {
+                KeyCode::Esc => {
+                    ui.note_input = None;
+                }
+                KeyCode::Enter => {
+                    let text = input.trim().to_string();
+                    if !text.is_empty() {
+                        backend.send_note(id.clone(), text).await;
+                    }
+                    ui.note_input = None;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // ---------- LOG EXPORT INPUT MODE ----------
+        if let Some(input) = &mut ui.log_export_input {
+            match code {
+                KeyCode::Esc => {
+                    ui.log_export_input = None;
+                }
+                KeyCode::Enter => {
+                    let path = input.trim().to_string();
+                    ui.log_export_input = None;
+                    if !path.is_empty() {
+                        let snap = state.read().await;
+                        let lines = export_log_lines(
+                            &snap,
+                            selected_id,
+                            ui.left_mode,
+                            ui.selected_run,
+                            &ui.logs,
+                        );
+                        drop(snap);
+                        let mut contents = lines.join("\n");
+                        contents.push('\n');
+                        ui.toasts
+                            .push(match tokio::fs::write(&path, contents).await {
+                                Ok(()) => Toast::new(
+                                    format!("Saved {} lines to {}", lines.len(), path),
+                                    ToastLevel::Info,
+                                ),
+                                Err(e) => Toast::new(
+                                    format!("Could not write {}: {}", path, e),
+                                    ToastLevel::Error,
+                                ),
+                            });
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
         // ---------- GLOBAL KEYS ----------
         match (code, modifiers) {
             (KeyCode::Char('q'), _) => {
+                if let Some(path) = &state_path {
+                    ui.selected_unit = selected_id.map(str::to_string);
+                    let _ = ui.save(path);
+                }
                 return Ok(());
             }
             // Help overlay: ?
@@ -4537,12 +8293,28 @@ async fn tui_loop(
                 ui.palette_pick = 0;
                 ui.palette_scroll = 0;
                 ui.palette_sugg_offset = 0;
+                ui.palette_mode = PaletteMode::Commands;
+                ui.history_cursor = None;
+                continue;
+            }
+            // Command history search: Ctrl+R (bash style)
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
+                ui.palette_open = true;
+                ui.focus = Focus::Palette;
+                ui.palette_input.clear();
+                ui.palette_error = None;
+                ui.palette_pick = 0;
+                ui.palette_scroll = 0;
+                ui.palette_sugg_offset = 0;
+                ui.palette_mode = PaletteMode::History {
+                    from_commands: false,
+                };
                 ui.history_cursor = None;
                 continue;
             }
             // Tab: toggle focus (Units <-> RightPane), or cycle sections in Inspect view
             (KeyCode::Tab, _) => {
-                ui.focus = match (ui.focus, ui.view) {
+                ui.focus = match (ui.focus, &ui.view) {
                     // From Units to right pane
                     (Focus::Units, View::Inspect) => Focus::InspectPanel(InspectSection::Summary),
                     (Focus::Units, _) => Focus::RightPane,
@@ -4559,7 +8331,7 @@ async fn tui_loop(
             }
             // Shift+Tab: reverse cycle
             (KeyCode::BackTab, _) => {
-                ui.focus = match (ui.focus, ui.view) {
+                ui.focus = match (ui.focus, &ui.view) {
                     (Focus::Units, View::Inspect) => Focus::InspectPanel(InspectSection::Health),
                     (Focus::Units, _) => Focus::RightPane,
                     (Focus::InspectPanel(section), View::Inspect) => {
@@ -4575,9 +8347,10 @@ async fn tui_loop(
             (KeyCode::Esc, _) => {
                 ui.logs.exit_search();
                 ui.enter_follow();
+                ui.batch_selection.clear();
                 continue;
             }
-            // Left pane mode switching: 1/2/3
+            // Left pane mode switching: 1/2/3/4
             (KeyCode::Char('1'), _) => {
                 ui.left_mode = LeftMode::Services;
                 continue;
@@ -4590,6 +8363,10 @@ async fn tui_loop(
                 ui.left_mode = LeftMode::Runs;
                 continue;
             }
+            (KeyCode::Char('4'), _) => {
+                ui.left_mode = LeftMode::Groups;
+                continue;
+            }
             // Direct view switching: l/i/d/m (global, works from any focus)
             (KeyCode::Char('l'), _) => {
                 ui.view = View::Logs;
@@ -4622,6 +8399,10 @@ async fn tui_loop(
                 match ui.left_mode {
                     LeftMode::Services => {
                         // Services mode: service navigation and control
+                        let selected_header = match unit_rows.get(*selected) {
+                            Some(UnitRow::Group { name, .. }) => Some(name.as_str()),
+                            _ => None,
+                        };
                         match code {
                             KeyCode::Up | KeyCode::Char('k') => {
                                 if *selected > 0 {
@@ -4631,12 +8412,59 @@ async fn tui_loop(
                                 }
                             }
                             KeyCode::Down | KeyCode::Char('j') => {
-                                if *selected + 1 < display_ids.len() {
+                                if *selected + 1 < unit_rows.len() {
                                     *selected += 1;
                                     list_state.select(Some(*selected));
                                     ui.enter_follow();
                                 }
                             }
+                            // Cycle sort order / status filter, keeping the
+                            // selected unit selected if it is still listed
+                            KeyCode::Char(c @ ('O' | 'F')) => {
+                                if c == 'O' {
+                                    ui.unit_sort = ui.unit_sort.next();
+                                } else {
+                                    ui.unit_filter = ui.unit_filter.next();
+                                }
+                                let rows = ui.unit_rows(&*state.read().await);
+                                *selected = selected_id
+                                    .and_then(|id| unit_row_position(&rows, id))
+                                    .unwrap_or(0);
+                                list_state.select(Some(*selected));
+                            }
+                            KeyCode::Char('P') => {
+                                if let Some(id) = selected_id.filter(|id| *id != "all") {
+                                    if !ui.pinned.remove(id) {
+                                        ui.pinned.insert(id.to_string());
+                                    }
+                                    // Follow the unit to its new position
+                                    let rows = ui.unit_rows(&*state.read().await);
+                                    *selected = unit_row_position(&rows, id).unwrap_or(0);
+                                    list_state.select(Some(*selected));
+                                }
+                            }
+                            // Mark/unmark for batch restart/stop/start/kill
+                            KeyCode::Char(' ') => {
+                                if let Some(id) = selected_id.filter(|id| *id != "all")
+                                    && !ui.batch_selection.remove(id)
+                                {
+                                    ui.batch_selection.insert(id.to_string());
+                                }
+                            }
+                            // Enter on a group header expands or collapses it
+                            KeyCode::Enter if selected_header.is_some() => {
+                                if let Some(name) = selected_header
+                                    && !ui.expanded_groups.remove(name)
+                                {
+                                    ui.expanded_groups.insert(name.to_string());
+                                }
+                            }
+                            // In the Metrics view, Enter opens the unit's charts full-screen
+                            KeyCode::Enter if ui.view == View::Metrics => {
+                                if let Some(id) = selected_id.filter(|id| *id != "all") {
+                                    ui.view = View::MetricsDrill { id: id.to_string() };
+                                }
+                            }
                             KeyCode::Enter => {
                                 // "all" is virtual - just shows merged logs, no toggle action
                                 if let Some(id) = selected_id {
@@ -4645,12 +8473,16 @@ async fn tui_loop(
                                     }
                                 }
                             }
+                            KeyCode::Char('r') if !ui.batch_selection.is_empty() => {
+                                for service_id in &ui.batch_selection {
+                                    backend.send_restart(service_id.clone()).await;
+                                }
+                            }
                             KeyCode::Char('r') => {
                                 if let Some(id) = selected_id {
                                     if id == "all" {
                                         // Restart all services
-                                        for service_id in display_ids.iter().filter(|s| *s != "all")
-                                        {
+                                        for service_id in &service_ids {
                                             backend.send_restart(service_id.to_string()).await;
                                         }
                                     } else {
@@ -4658,25 +8490,35 @@ async fn tui_loop(
                                     }
                                 }
                             }
+                            KeyCode::Char('s') if !ui.batch_selection.is_empty() => {
+                                for service_id in &ui.batch_selection {
+                                    backend.send_stop(service_id.clone()).await;
+                                }
+                            }
                             KeyCode::Char('s') => {
                                 if let Some(id) = selected_id {
                                     if id == "all" {
-                                        // Stop all services
-                                        for service_id in display_ids.iter().filter(|s| *s != "all")
-                                        {
-                                            backend.send_stop(service_id.to_string()).await;
-                                        }
+                                        // Stop all services, once confirmed
+                                        ui.pending_confirm = Some(PendingConfirm::all_services(
+                                            "Stop",
+                                            &service_ids,
+                                            |id| TuiCommand::Stop { id },
+                                        ));
                                     } else {
                                         backend.send_stop(id.to_string()).await;
                                     }
                                 }
                             }
+                            KeyCode::Char('t') if !ui.batch_selection.is_empty() => {
+                                for service_id in &ui.batch_selection {
+                                    backend.send_start(service_id.clone()).await;
+                                }
+                            }
                             KeyCode::Char('t') => {
                                 if let Some(id) = selected_id {
                                     if id == "all" {
                                         // Start all services
-                                        for service_id in display_ids.iter().filter(|s| *s != "all")
-                                        {
+                                        for service_id in &service_ids {
                                             backend.send_start(service_id.to_string()).await;
                                         }
                                     } else {
@@ -4684,14 +8526,20 @@ async fn tui_loop(
                                     }
                                 }
                             }
+                            KeyCode::Char('x') if !ui.batch_selection.is_empty() => {
+                                for service_id in &ui.batch_selection {
+                                    backend.send_kill(service_id.clone()).await;
+                                }
+                            }
                             KeyCode::Char('x') => {
                                 if let Some(id) = selected_id {
                                     if id == "all" {
-                                        // Kill all services
-                                        for service_id in display_ids.iter().filter(|s| *s != "all")
-                                        {
-                                            backend.send_kill(service_id.to_string()).await;
-                                        }
+                                        // Kill all services, once confirmed
+                                        ui.pending_confirm = Some(PendingConfirm::all_services(
+                                            "Kill",
+                                            &service_ids,
+                                            |id| TuiCommand::Kill { id },
+                                        ));
                                     } else {
                                         backend.send_kill(id.to_string()).await;
                                     }
@@ -4701,8 +8549,7 @@ async fn tui_loop(
                                 if let Some(id) = selected_id {
                                     if id == "all" {
                                         // Clear logs for all services
-                                        for service_id in display_ids.iter().filter(|s| *s != "all")
-                                        {
+                                        for service_id in &service_ids {
                                             backend.send_clear_logs(service_id.to_string()).await;
                                         }
                                     } else {
@@ -4710,6 +8557,11 @@ async fn tui_loop(
                                     }
                                 }
                             }
+                            KeyCode::Char('N') => {
+                                if let Some(id) = selected_id.filter(|id| *id != "all") {
+                                    ui.note_input = Some((id.to_string(), String::new()));
+                                }
+                            }
                             // View keys (also work from left)
                             KeyCode::Char('l') => {
                                 ui.view = View::Logs;
@@ -4749,45 +8601,105 @@ async fn tui_loop(
                                     command_list_state.select(Some(ui.selected_command));
                                 }
                             }
-                            KeyCode::Enter => {
-                                // Run selected command
+                            KeyCode::Enter | KeyCode::Char('I') => {
+                                // Run selected command; `I` keeps its stdin
+                                // open and opens the input bar
+                                let interactive = code == KeyCode::Char('I');
                                 if let Some(project) = &snap.project {
                                     let cmds: Vec<_> = project.commands_sorted();
                                     if let Some(cmd) = cmds.get(ui.selected_command) {
                                         let spec = (*cmd).clone();
                                         drop(snap); // Release lock before sending
                                         let _ = runner_cmd_tx
-                                            .send(runner::RunnerCommand::Run { spec })
+                                            .send(runner::RunnerCommand::Run { spec, interactive })
                                             .await;
                                         // Switch to Runs mode to see output
                                         ui.left_mode = LeftMode::Runs;
                                         ui.selected_run = 0;
+                                        if interactive {
+                                            ui.run_input = Some(String::new());
+                                            ui.view = View::Logs;
+                                        }
                                     }
                                 }
                             }
                             _ => {}
                         }
                     }
+                    LeftMode::Groups => {
+                        // Groups mode: r/s/t/x act on every member of the group
+                        let members = groups
+                            .values()
+                            .nth(ui.selected_group)
+                            .cloned()
+                            .unwrap_or_default();
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                ui.selected_group = ui.selected_group.saturating_sub(1);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                if ui.selected_group + 1 < groups.len() {
+                                    ui.selected_group += 1;
+                                }
+                            }
+                            KeyCode::Char('r') => {
+                                for id in members {
+                                    backend.send_restart(id).await;
+                                }
+                            }
+                            KeyCode::Char('s') => {
+                                for id in members {
+                                    backend.send_stop(id).await;
+                                }
+                            }
+                            KeyCode::Char('t') => {
+                                for id in members {
+                                    backend.send_start(id).await;
+                                }
+                            }
+                            KeyCode::Char('x') => {
+                                for id in members {
+                                    backend.send_kill(id).await;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
                     LeftMode::Runs => {
                         // Runs mode: run navigation and control
                         // Re-acquire state lock for run data
                         let snap = state.read().await;
-                        let run_count = snap.run_order.len();
                         match code {
                             KeyCode::Up | KeyCode::Char('k') => {
-                                if ui.selected_run > 0 {
-                                    ui.selected_run -= 1;
-                                    run_list_state.select(Some(ui.selected_run));
+                                if ui.step_run_selection(&snap, false) {
                                     ui.enter_follow();
                                 }
                             }
                             KeyCode::Down | KeyCode::Char('j') => {
-                                if ui.selected_run + 1 < run_count {
-                                    ui.selected_run += 1;
-                                    run_list_state.select(Some(ui.selected_run));
+                                if ui.step_run_selection(&snap, true) {
                                     ui.enter_follow();
                                 }
                             }
+                            KeyCode::Char('s') => {
+                                ui.runs_sort = ui.runs_sort.next();
+                                ui.runs_sort_desc = ui.runs_sort.default_desc();
+                            }
+                            KeyCode::Char('S') => {
+                                ui.runs_sort_desc = !ui.runs_sort_desc;
+                            }
+                            KeyCode::Char('f') => {
+                                // All → running → failed → exited → all
+                                ui.runs_filter = match ui.runs_filter {
+                                    None => Some(RunStatus::Running),
+                                    Some(RunStatus::Running) => Some(RunStatus::Failed {
+                                        message: String::new(),
+                                    }),
+                                    Some(RunStatus::Failed { .. }) => {
+                                        Some(RunStatus::Exited { code: None })
+                                    }
+                                    Some(_) => None,
+                                };
+                            }
                             KeyCode::Enter => {
                                 // View logs for selected run (switch to logs view)
                                 ui.view = View::Logs;
@@ -4803,6 +8715,18 @@ async fn tui_loop(
                                         .await;
                                 }
                             }
+                            KeyCode::Char('I') => {
+                                // Send input to the selected run's stdin
+                                let running = snap
+                                    .run_order
+                                    .get(ui.selected_run)
+                                    .and_then(|id| snap.runs.get(id))
+                                    .is_some_and(|r| r.status.is_running());
+                                if running {
+                                    ui.run_input = Some(String::new());
+                                    ui.view = View::Logs;
+                                }
+                            }
                             KeyCode::Char('r') => {
                                 // Rerun selected command
                                 if let Some(run_id) = snap.run_order.get(ui.selected_run) {
@@ -4840,6 +8764,13 @@ async fn tui_loop(
                             KeyCode::Down | KeyCode::Char('j') => {
                                 ui.logs.scroll_down(1);
                             }
+                            // Horizontal scroll
+                            KeyCode::Left => {
+                                ui.logs.scroll_x = ui.logs.scroll_x.saturating_sub(8);
+                            }
+                            KeyCode::Right => {
+                                ui.logs.scroll_x = ui.logs.scroll_x.saturating_add(8);
+                            }
                             // Page up
                             KeyCode::PageUp => {
                                 ui.logs.scroll_up(20);
@@ -4877,6 +8808,8 @@ async fn tui_loop(
                                                     .map(|x| DisplayLogLine {
                                                         timestamp: Some(x.at),
                                                         text: x.text.clone(),
+                                                        render_ansi: renders_ansi(units_map, id),
+                                                        repeat_count: x.repeat_count,
                                                     })
                                                     .collect()
                                             })
@@ -4884,6 +8817,68 @@ async fn tui_loop(
                                     }
                                 }
                             }
+                            // Open the current search match, or the newest visible
+                            // line with a file:line location, in $EDITOR
+                            KeyCode::Char('o') => {
+                                let snap = state.read().await;
+                                let texts = get_log_texts(
+                                    &snap,
+                                    selected_id,
+                                    ui.left_mode,
+                                    ui.selected_run,
+                                    ui.logs.paused,
+                                    &ui.logs.frozen_logs,
+                                );
+                                drop(snap);
+                                let candidates: Vec<&String> =
+                                    match ui.logs.matches.get(ui.logs.match_idx) {
+                                        Some(&idx) => texts.get(idx).into_iter().collect(),
+                                        None => {
+                                            let end = texts.len().saturating_sub(ui.logs.scroll);
+                                            texts[end.saturating_sub(EDITOR_SCAN_LINES)..end]
+                                                .iter()
+                                                .rev()
+                                                .collect()
+                                        }
+                                    };
+                                let unit_cwd = selected_id
+                                    .and_then(|id| units_map.get(id))
+                                    .and_then(|u| u.cwd.clone());
+                                let location = candidates.into_iter().find_map(|text| {
+                                    let (path, line) =
+                                        ui::extract_file_location(&ui::ansi::strip_ansi(text))?;
+                                    let path = if path.is_absolute() {
+                                        path
+                                    } else {
+                                        unit_cwd.as_ref().map(|cwd| cwd.join(&path)).unwrap_or(path)
+                                    };
+                                    path.is_file().then_some((path, line))
+                                });
+                                if let Some((path, line)) = location {
+                                    open_in_editor(terminal, &path, line)?;
+                                }
+                            }
+                            // Line number gutter on/off
+                            KeyCode::Char('N') => {
+                                ui.logs.show_line_numbers = !ui.logs.show_line_numbers;
+                            }
+                            // Level colors on/off
+                            KeyCode::Char('C') => {
+                                ui.logs.color_mode = ui.logs.color_mode.toggle();
+                            }
+                            // Structured JSON rendering on/off
+                            KeyCode::Char('J') => {
+                                ui.logs.json_mode = !ui.logs.json_mode;
+                            }
+                            // Write the visible logs to a file
+                            KeyCode::Char('W') => {
+                                let name = if ui.left_mode == LeftMode::Runs {
+                                    "run"
+                                } else {
+                                    selected_id.unwrap_or("all")
+                                };
+                                ui.log_export_input = Some(format!("./{}.log", name));
+                            }
                             // Log level filter keys
                             KeyCode::Char('e') => {
                                 ui.logs.log_filter = LogFilterMode::ErrorOnly;
@@ -4930,6 +8925,8 @@ async fn tui_loop(
                         }
                         _ => {}
                     },
+                    // The drill-down overlay takes its keys before the global ones
+                    View::MetricsDrill { .. } => {}
                     View::Inspect | View::Deps | View::Exec => {
                         // Simple scroll for these views (future enhancement)
                         match code {
@@ -4954,12 +8951,16 @@ async fn tui_loop(
                     }
                 }
             }
-            Focus::InspectPanel(_section) => {
+            Focus::InspectPanel(section) => {
                 // Inspect panel focus - Tab cycles sections
                 // The Tab key is handled globally earlier, so this is for any section-specific keys
-                match code {
-                    // In the future, sections could have their own key bindings
-                    // For now, just acknowledge the focus state
+                match (section, code) {
+                    (InspectSection::Metrics, KeyCode::Char(']')) => {
+                        ui.chart_window = ui.chart_window.next();
+                    }
+                    (InspectSection::Metrics, KeyCode::Char('[')) => {
+                        ui.chart_window = ui.chart_window.prev();
+                    }
                     _ => {}
                 }
             }
@@ -0,0 +1,159 @@
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{CONTENT_TYPE, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+
+use orkesy_core::model::{HealthStatus, ServiceStatus};
+use orkesy_core::state::RuntimeState;
+
+use crate::commands::escape_label;
+
+/// Port `orkesy daemon` serves `/metrics` on unless `--metrics-port` is set
+pub const DEFAULT_METRICS_PORT: u16 = 9091;
+
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Binds `127.0.0.1:port` and serves `GET /metrics` from `state` in the
+/// background. Returns the bound address.
+pub async fn spawn_metrics_server(
+    port: u16,
+    state: Arc<RwLock<RuntimeState>>,
+) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let state = state.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |req| respond(req, state.clone()));
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+async fn respond(
+    req: Request<Incoming>,
+    state: Arc<RwLock<RuntimeState>>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let (status, content_type, body) = match (req.method(), req.uri().path()) {
+        (&Method::GET | &Method::HEAD, "/metrics") => (
+            StatusCode::OK,
+            OPENMETRICS_CONTENT_TYPE,
+            format_openmetrics(&*state.read().await),
+        ),
+        _ => (
+            StatusCode::NOT_FOUND,
+            "text/plain; charset=utf-8",
+            "not found, try /metrics\n".to_string(),
+        ),
+    };
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    Ok(response)
+}
+
+/// Per-unit gauges and restart counters, and the aggregate health as a
+/// stateset, in the OpenMetrics text format. CPU and memory are 0 for units
+/// that aren't running.
+pub fn format_openmetrics(state: &RuntimeState) -> String {
+    let mut out = String::new();
+    let ids: Vec<&String> = state.graph.nodes.keys().collect();
+
+    let mut family = |name: &str, kind: &str, help: &str, value: &dyn Fn(&str) -> String| {
+        out.push_str(&format!(
+            "# TYPE {} {}\n# HELP {} {}\n",
+            name, kind, name, help
+        ));
+        // Counter samples carry the `_total` suffix, the family doesn't
+        let sample = if kind == "counter" {
+            format!("{}_total", name)
+        } else {
+            name.to_string()
+        };
+        for id in &ids {
+            out.push_str(&format!(
+                "{}{{unit=\"{}\"}} {}\n",
+                sample,
+                escape_label(id),
+                value(id)
+            ));
+        }
+    };
+
+    family(
+        "orkesy_unit_running",
+        "gauge",
+        "Whether the unit is running.",
+        &|id| {
+            let running = matches!(
+                state.graph.nodes[id].observed.status,
+                ServiceStatus::Running
+            );
+            u8::from(running).to_string()
+        },
+    );
+    family(
+        "orkesy_unit_cpu_percent",
+        "gauge",
+        "CPU usage of the unit's processes in percent.",
+        &|id| {
+            let cpu = state.metrics.get(id).map_or(0.0, |m| m.cpu_percent);
+            format!("{:.1}", cpu)
+        },
+    );
+    family(
+        "orkesy_unit_memory_bytes",
+        "gauge",
+        "Resident memory of the unit's processes.",
+        &|id| {
+            let memory = state.metrics.get(id).map_or(0, |m| m.memory_bytes);
+            memory.to_string()
+        },
+    );
+    family(
+        "orkesy_unit_restart",
+        "counter",
+        "Times the unit has been started again after its first start.",
+        &|id| state.restart_count(id).to_string(),
+    );
+
+    let overall = match state.overall_health() {
+        HealthStatus::Healthy => "healthy",
+        HealthStatus::Degraded { .. } => "degraded",
+        HealthStatus::Unhealthy { .. } => "unhealthy",
+        HealthStatus::Unknown => "unknown",
+    };
+    out.push_str(
+        "# TYPE orkesy_health stateset\n# HELP orkesy_health Aggregate health of the running units.\n",
+    );
+    for health in ["healthy", "degraded", "unhealthy", "unknown"] {
+        out.push_str(&format!(
+            "orkesy_health{{orkesy_health=\"{}\"}} {}\n",
+            health,
+            u8::from(health == overall)
+        ));
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
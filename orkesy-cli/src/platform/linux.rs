@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+/// Limit names accepted in a unit's `ulimits`
+pub const ULIMIT_NAMES: [&str; 6] = ["nofile", "nproc", "stack", "data", "core", "memlock"];
+
+/// Rejects unknown limit names before spawning, so the error reaches the
+/// user instead of failing inside the forked child.
+pub fn check_ulimits(limits: &BTreeMap<String, u64>) -> Result<(), String> {
+    match limits
+        .keys()
+        .find(|name| !ULIMIT_NAMES.contains(&name.as_str()))
+    {
+        Some(name) => Err(format!(
+            "unknown ulimit '{}' (supported: {})",
+            name,
+            ULIMIT_NAMES.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Applies `limits` to the current process with `setrlimit`. Sets the soft
+/// limit to the value and only raises the hard limit when it is lower.
+///
+/// Runs in the child between fork and exec, so it only allocates on error.
+pub fn apply_ulimits(limits: &BTreeMap<String, u64>) -> Result<(), String> {
+    for (name, &value) in limits {
+        let resource = match name.as_str() {
+            "nofile" => libc::RLIMIT_NOFILE,
+            "nproc" => libc::RLIMIT_NPROC,
+            "stack" => libc::RLIMIT_STACK,
+            "data" => libc::RLIMIT_DATA,
+            "core" => libc::RLIMIT_CORE,
+            "memlock" => libc::RLIMIT_MEMLOCK,
+            _ => return Err(format!("unknown ulimit '{}'", name)),
+        };
+
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        let value = value as libc::rlim_t;
+        unsafe {
+            if libc::getrlimit(resource, &mut limit) != 0 {
+                return Err(format!(
+                    "getrlimit {}: {}",
+                    name,
+                    std::io::Error::last_os_error()
+                ));
+            }
+            limit.rlim_cur = value;
+            if limit.rlim_max != libc::RLIM_INFINITY && limit.rlim_max < value {
+                limit.rlim_max = value;
+            }
+            if libc::setrlimit(resource, &limit) != 0 {
+                return Err(format!(
+                    "setrlimit {}={}: {}",
+                    name,
+                    value,
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! The config profile for this process: `--profile`, or `ORKESY_PROFILE`
+//! when the flag isn't given. The CLI loads the config through
+//! [`load_config`] and [`discover_config`], so the TUI, the daemon and the
+//! one-shot commands all apply the same profile.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use orkesy_core::config::{ConfigError, OrkesyConfig};
+
+/// Environment variable naming the profile when `--profile` isn't given
+const PROFILE_ENV_VAR: &str = "ORKESY_PROFILE";
+
+static SELECTED_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Selects the profile for the rest of the process, taking precedence over
+/// `ORKESY_PROFILE`. Only the first call counts.
+pub fn select_profile(profile: String) {
+    let _ = SELECTED_PROFILE.set(profile);
+}
+
+/// The profile set by [`select_profile`] or, failing that, `ORKESY_PROFILE`
+pub fn active_profile() -> Option<String> {
+    SELECTED_PROFILE.get().cloned().or_else(|| {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .filter(|p| !p.is_empty())
+    })
+}
+
+/// [`OrkesyConfig::load_with_profile`] with the [`active_profile`]
+pub fn load_config(path: &Path) -> Result<OrkesyConfig, ConfigError> {
+    OrkesyConfig::load_with_profile(path, active_profile().as_deref())
+}
+
+/// [`OrkesyConfig::discover_with_profile`] with the [`active_profile`]
+pub fn discover_config(start_dir: &Path) -> Result<(PathBuf, OrkesyConfig), ConfigError> {
+    OrkesyConfig::discover_with_profile(start_dir, active_profile().as_deref())
+}
@@ -1,9 +1,11 @@
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::SystemTime;
 
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::{broadcast, mpsc};
 use uuid::Uuid;
 
@@ -11,7 +13,8 @@ use uuid::Uuid;
 #[allow(unused_imports)]
 use std::os::unix::process::CommandExt;
 
-use orkesy_core::command::{CommandSpec, RunId};
+use orkesy_core::command::{CLEAN_ENV_PASSTHROUGH, CommandSpec, DetectedTool, RunId};
+use orkesy_core::config::OrkesyConfig;
 use orkesy_core::reducer::{EventEnvelope, RuntimeEvent};
 use orkesy_core::state::LogStream;
 
@@ -20,6 +23,9 @@ use orkesy_core::state::LogStream;
 pub enum RunnerCommand {
     Run {
         spec: CommandSpec,
+        /// Keep stdin open for `SendInput`; otherwise it's `/dev/null`, or
+        /// closed once `stdin_input` is written
+        interactive: bool,
     },
     RunArbitrary {
         title: String,
@@ -32,17 +38,43 @@ pub enum RunnerCommand {
     Rerun {
         run_id: RunId,
     },
+    SendInput {
+        run_id: RunId,
+        data: String,
+    },
     Shutdown,
 }
 
 struct ProcessHandle {
     child: Child,
     pgid: i32,
+    /// `--env-file` written for a `clean_env` docker compose command
+    env_file: Option<PathBuf>,
+}
+
+impl Drop for ProcessHandle {
+    fn drop(&mut self) {
+        if let Some(path) = &self.env_file {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// What `Rerun` needs to start a run again
+#[derive(Clone)]
+struct RunRecord {
+    spec: CommandSpec,
+    /// Started with `interactive`, so reruns keep stdin open too
+    interactive: bool,
 }
 
 pub struct CommandRunner {
     processes: BTreeMap<RunId, ProcessHandle>,
-    specs: BTreeMap<RunId, CommandSpec>,
+    /// Input for the stdin writer of each interactive run
+    stdins: BTreeMap<RunId, mpsc::UnboundedSender<String>>,
+    runs: BTreeMap<RunId, RunRecord>,
+    /// Source of `commands.<id>` settings (`clean_env`, `unit`)
+    config: Option<Arc<OrkesyConfig>>,
     next_event_id: u64,
 }
 
@@ -50,12 +82,19 @@ impl CommandRunner {
     pub fn new() -> Self {
         Self {
             processes: BTreeMap::new(),
-            specs: BTreeMap::new(),
+            stdins: BTreeMap::new(),
+            runs: BTreeMap::new(),
+            config: None,
             // Start high to avoid collision with adapter events
             next_event_id: 1_000_000,
         }
     }
 
+    pub fn with_config(mut self, config: Option<OrkesyConfig>) -> Self {
+        self.config = config.map(Arc::new);
+        self
+    }
+
     pub async fn run(
         &mut self,
         mut command_rx: mpsc::Receiver<RunnerCommand>,
@@ -76,6 +115,7 @@ impl CommandRunner {
 
                     for (run_id, code) in finished {
                         self.processes.remove(&run_id);
+                        self.stdins.remove(&run_id);
                         let _ = event_tx.send(EventEnvelope {
                             id: self.next_event_id,
                             at: SystemTime::now(),
@@ -96,6 +136,7 @@ impl CommandRunner {
                         RunnerCommand::Shutdown => {
                             // Kill all running processes
                             let run_ids: Vec<_> = self.processes.keys().cloned().collect();
+                            self.stdins.clear();
                             for run_id in run_ids {
                                 if let Some(handle) = self.processes.remove(&run_id) {
                                     Self::kill_process(handle).await;
@@ -104,12 +145,15 @@ impl CommandRunner {
                             break;
                         }
 
-                        RunnerCommand::Run { spec } => {
+                        RunnerCommand::Run { spec, interactive } => {
                             let run_id = Uuid::new_v4().to_string();
-                            if let Err(e) = self.spawn_command(&spec, &run_id, &event_tx).await {
+                            if let Err(e) = self
+                                .spawn_command(&spec, &run_id, interactive, &event_tx)
+                                .await
+                            {
                                 self.emit_error(&event_tx, &run_id, &e);
                             } else {
-                                self.specs.insert(run_id, spec);
+                                self.runs.insert(run_id, RunRecord { spec, interactive });
                             }
                         }
 
@@ -124,15 +168,21 @@ impl CommandRunner {
                                 cwd,
                                 description: None,
                                 category: orkesy_core::command::CommandCategory::Script,
+                                stdin_input: None,
+                                clean_env: None,
+                                env: BTreeMap::new(),
                             };
-                            if let Err(e) = self.spawn_command(&spec, &run_id, &event_tx).await {
+                            if let Err(e) =
+                                self.spawn_command(&spec, &run_id, false, &event_tx).await
+                            {
                                 self.emit_error(&event_tx, &run_id, &e);
                             } else {
-                                self.specs.insert(run_id, spec);
+                                self.runs.insert(run_id, RunRecord { spec, interactive: false });
                             }
                         }
 
                         RunnerCommand::Kill { run_id } => {
+                            self.stdins.remove(&run_id);
                             if let Some(handle) = self.processes.remove(&run_id) {
                                 Self::kill_process(handle).await;
                                 let _ = event_tx.send(EventEnvelope {
@@ -145,21 +195,32 @@ impl CommandRunner {
                         }
 
                         RunnerCommand::Rerun { run_id } => {
-                            if let Some(spec) = self.specs.get(&run_id).cloned() {
+                            let record = self.runs.get(&run_id).cloned();
+                            if let Some(RunRecord { spec, interactive }) = record {
                                 // Kill existing if still running
+                                self.stdins.remove(&run_id);
                                 if let Some(handle) = self.processes.remove(&run_id) {
                                     Self::kill_process(handle).await;
                                 }
 
                                 // Start new run with same spec
                                 let new_run_id = Uuid::new_v4().to_string();
-                                if let Err(e) = self.spawn_command(&spec, &new_run_id, &event_tx).await {
+                                if let Err(e) = self
+                                    .spawn_command(&spec, &new_run_id, interactive, &event_tx)
+                                    .await
+                                {
                                     self.emit_error(&event_tx, &new_run_id, &e);
                                 } else {
-                                    self.specs.insert(new_run_id, spec);
+                                    self.runs.insert(new_run_id, RunRecord { spec, interactive });
                                 }
                             }
                         }
+
+                        RunnerCommand::SendInput { run_id, data } => {
+                            if let Err(e) = self.send_input(&run_id, data) {
+                                self.emit_error(&event_tx, &run_id, &e);
+                            }
+                        }
                     }
                 }
             }
@@ -170,25 +231,34 @@ impl CommandRunner {
         &mut self,
         spec: &CommandSpec,
         run_id: &str,
+        interactive: bool,
         event_tx: &broadcast::Sender<EventEnvelope>,
     ) -> Result<(), String> {
+        let mut spec = spec.clone();
+        if let Some(config) = &self.config {
+            config.configure_command(&mut spec);
+        }
+        let spec = &spec;
+        let (command_line, env_file) = prepare_command(spec, run_id)?;
+
         #[cfg(unix)]
         let mut cmd = {
             let mut c = Command::new("sh");
             c.arg("-c");
-            c.arg(&spec.command);
+            c.arg(&command_line);
             c
         };
         #[cfg(windows)]
         let mut cmd = {
             let mut c = Command::new("cmd");
-            c.args(["/C", &spec.command]);
+            c.args(["/C", &command_line]);
             c
         };
 
         if let Some(cwd) = &spec.cwd {
             cmd.current_dir(cwd);
         }
+        apply_env(&mut cmd, spec);
 
         // Create new process group for reliable cleanup (Unix only)
         #[cfg(unix)]
@@ -201,9 +271,19 @@ impl CommandRunner {
 
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
-        cmd.stdin(Stdio::null());
+        // Commands that read stdin get EOF unless there's input for them
+        if interactive || spec.stdin_input.is_some() {
+            cmd.stdin(Stdio::piped());
+        } else {
+            cmd.stdin(Stdio::null());
+        }
 
-        let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+        let mut child = cmd.spawn().map_err(|e| {
+            if let Some(path) = &env_file {
+                let _ = std::fs::remove_file(path);
+            }
+            e.to_string()
+        })?;
         let pgid = child.id().map(|pid| pid as i32).unwrap_or(-1);
         let pid = child.id();
 
@@ -214,7 +294,7 @@ impl CommandRunner {
             event: RuntimeEvent::CommandStarted {
                 run_id: run_id.to_string(),
                 command_id: spec.id.clone(),
-                command: spec.command.clone(),
+                command: command_line.clone(),
                 display_name: spec.display_name.clone(),
                 pid,
             },
@@ -263,12 +343,39 @@ impl CommandRunner {
             });
         }
 
-        self.processes
-            .insert(run_id.to_string(), ProcessHandle { child, pgid });
+        if let Some(stdin) = child.stdin.take() {
+            let input = spawn_stdin_writer(stdin, run_id, event_tx);
+            if let Some(canned) = &spec.stdin_input {
+                let _ = input.send(canned.clone());
+            }
+            // Without `interactive`, dropping the sender closes stdin once the
+            // canned input is written, so the command sees EOF
+            if interactive {
+                self.stdins.insert(run_id.to_string(), input);
+            }
+        }
+
+        self.processes.insert(
+            run_id.to_string(),
+            ProcessHandle {
+                child,
+                pgid,
+                env_file,
+            },
+        );
 
         Ok(())
     }
 
+    fn send_input(&self, run_id: &str, data: String) -> Result<(), String> {
+        let input = self.stdins.get(run_id).ok_or_else(|| {
+            "run is not accepting input; start the command with I to send it input".to_string()
+        })?;
+        input
+            .send(data)
+            .map_err(|_| "run's stdin is closed".to_string())
+    }
+
     fn emit_error(
         &mut self,
         event_tx: &broadcast::Sender<EventEnvelope>,
@@ -333,3 +440,300 @@ impl Default for CommandRunner {
         Self::new()
     }
 }
+
+/// Writes what arrives on the returned sender to `stdin` from its own task,
+/// so a command that isn't reading its input can't stall the runner loop.
+/// `stdin` is closed once the sender is dropped and everything sent before
+/// is written, or after the first failed write.
+fn spawn_stdin_writer(
+    mut stdin: ChildStdin,
+    run_id: &str,
+    event_tx: &broadcast::Sender<EventEnvelope>,
+) -> mpsc::UnboundedSender<String> {
+    let (input_tx, mut input_rx) = mpsc::unbounded_channel::<String>();
+    let tx = event_tx.clone();
+    let rid = run_id.to_string();
+    tokio::spawn(async move {
+        while let Some(data) = input_rx.recv().await {
+            let written = async {
+                stdin.write_all(data.as_bytes()).await?;
+                stdin.flush().await
+            };
+            if let Err(e) = written.await {
+                let _ = tx.send(EventEnvelope {
+                    id: 0,
+                    at: SystemTime::now(),
+                    event: RuntimeEvent::CommandOutput {
+                        run_id: rid,
+                        stream: LogStream::System,
+                        text: format!("[error] {}", e),
+                    },
+                });
+                break;
+            }
+        }
+    });
+    input_tx
+}
+
+/// Command line to run for `spec`. Docker compose commands with
+/// `clean_env` get `--env-file` with the command's env, since compose reads
+/// its variables from the (now empty) environment; the file path is
+/// returned so it can be removed when the run ends.
+pub fn prepare_command(
+    spec: &CommandSpec,
+    run_id: &str,
+) -> Result<(String, Option<PathBuf>), String> {
+    let compose_args = match &spec.tool {
+        DetectedTool::DockerCompose { .. } if spec.uses_clean_env() => {
+            spec.command.strip_prefix("docker compose ")
+        }
+        _ => None,
+    };
+    let Some(args) = compose_args else {
+        return Ok((spec.command.clone(), None));
+    };
+
+    let path = std::env::temp_dir().join(format!("orkesy-{}.env", run_id));
+    write_env_file(&path, &spec.env).map_err(|e| format!("env file: {}", e))?;
+    let command = format!("docker compose --env-file {} {}", path.display(), args);
+    Ok((command, Some(path)))
+}
+
+/// Sets `spec.env`, clearing the inherited environment first for
+/// `clean_env` commands
+pub fn apply_env(cmd: &mut Command, spec: &CommandSpec) {
+    if spec.uses_clean_env() {
+        cmd.env_clear();
+        for key in CLEAN_ENV_PASSTHROUGH {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
+        }
+    }
+    cmd.envs(&spec.env);
+}
+
+fn write_env_file(path: &Path, env: &BTreeMap<String, String>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // The env may hold secrets
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    for (key, value) in env {
+        writeln!(file, "{}={}", key, value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orkesy_core::command::CommandCategory;
+    use std::time::Duration;
+
+    fn make_spec(command: &str) -> CommandSpec {
+        CommandSpec {
+            id: "test".to_string(),
+            tool: DetectedTool::Rust,
+            name: "test".to_string(),
+            display_name: "test".to_string(),
+            command: command.to_string(),
+            cwd: None,
+            description: None,
+            category: CommandCategory::Script,
+            stdin_input: None,
+            clean_env: None,
+            env: BTreeMap::new(),
+        }
+    }
+
+    /// Waits for the first event `f` picks something out of
+    async fn next_matching<T>(
+        rx: &mut broadcast::Receiver<EventEnvelope>,
+        f: impl Fn(&RuntimeEvent) -> Option<T>,
+    ) -> T {
+        let wait = async {
+            loop {
+                if let Ok(env) = rx.recv().await
+                    && let Some(found) = f(&env.event)
+                {
+                    return found;
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), wait)
+            .await
+            .expect("timed out waiting for a runner event")
+    }
+
+    #[test]
+    fn test_prepare_command_passes_most_commands_through() {
+        let mut spec = make_spec("cargo test");
+        spec.clean_env = Some(true);
+        assert_eq!(
+            prepare_command(&spec, "run-1").unwrap(),
+            ("cargo test".to_string(), None)
+        );
+
+        // Compose without clean_env reads the inherited environment itself
+        let mut spec = make_spec("docker compose up -d");
+        spec.tool = DetectedTool::DockerCompose {
+            file: PathBuf::from("compose.yml"),
+        };
+        assert_eq!(
+            prepare_command(&spec, "run-2").unwrap(),
+            ("docker compose up -d".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_prepare_command_writes_compose_env_file() {
+        let mut spec = make_spec("docker compose up -d");
+        spec.tool = DetectedTool::DockerCompose {
+            file: PathBuf::from("compose.yml"),
+        };
+        spec.clean_env = Some(true);
+        spec.env = BTreeMap::from([
+            ("API_KEY".to_string(), "secret".to_string()),
+            ("PORT".to_string(), "8080".to_string()),
+        ]);
+        let run_id = format!("prepare-{}", std::process::id());
+
+        let (command, env_file) = prepare_command(&spec, &run_id).unwrap();
+        let env_file = env_file.unwrap();
+        assert_eq!(
+            command,
+            format!("docker compose --env-file {} up -d", env_file.display())
+        );
+        assert_eq!(
+            std::fs::read_to_string(&env_file).unwrap(),
+            "API_KEY=secret\nPORT=8080\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&env_file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(env_file).unwrap();
+    }
+
+    /// `KEY=value` lines of the environment `spec` runs with
+    #[cfg(unix)]
+    async fn env_of(spec: &CommandSpec) -> Vec<String> {
+        let mut cmd = Command::new("env");
+        apply_env(&mut cmd, spec);
+        let output = cmd.output().await.unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_apply_env() {
+        let mut spec = make_spec("env");
+        spec.env = BTreeMap::from([("ORKESY_TEST_VAR".to_string(), "1".to_string())]);
+
+        let inherited = env_of(&spec).await;
+        assert!(inherited.contains(&"ORKESY_TEST_VAR=1".to_string()));
+        let path = std::env::var("PATH").unwrap();
+        assert!(inherited.contains(&format!("PATH={}", path)));
+
+        spec.clean_env = Some(true);
+        let clean = env_of(&spec).await;
+        assert!(clean.contains(&"ORKESY_TEST_VAR=1".to_string()));
+        assert!(clean.contains(&format!("PATH={}", path)));
+        let allowed = |line: &String| {
+            let key = line.split_once('=').map_or(line.as_str(), |(key, _)| key);
+            key == "ORKESY_TEST_VAR" || CLEAN_ENV_PASSTHROUGH.contains(&key)
+        };
+        assert!(clean.iter().all(allowed), "{:?}", clean);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unread_stdin_input_does_not_block_the_runner() {
+        let (event_tx, mut event_rx) = broadcast::channel(1024);
+        let (command_tx, command_rx) = mpsc::channel(8);
+        let runner = tokio::spawn({
+            let event_tx = event_tx.clone();
+            async move { CommandRunner::new().run(command_rx, event_tx).await }
+        });
+
+        // Far more than a pipe buffer holds, for a command that never reads it
+        let mut spec = make_spec("sleep 30");
+        spec.stdin_input = Some("y\n".repeat(512 * 1024));
+        command_tx
+            .send(RunnerCommand::Run {
+                spec,
+                interactive: false,
+            })
+            .await
+            .unwrap();
+        let run_id = next_matching(&mut event_rx, |event| match event {
+            RuntimeEvent::CommandStarted { run_id, .. } => Some(run_id.clone()),
+            _ => None,
+        })
+        .await;
+
+        command_tx
+            .send(RunnerCommand::Kill {
+                run_id: run_id.clone(),
+            })
+            .await
+            .unwrap();
+        let killed = next_matching(&mut event_rx, |event| match event {
+            RuntimeEvent::CommandKilled { run_id } => Some(run_id.clone()),
+            _ => None,
+        })
+        .await;
+        assert_eq!(killed, run_id);
+
+        command_tx.send(RunnerCommand::Shutdown).await.unwrap();
+        runner.await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdin_input_closes_stdin_after_writing() {
+        let (event_tx, mut event_rx) = broadcast::channel(1024);
+        let (command_tx, command_rx) = mpsc::channel(8);
+        tokio::spawn({
+            let event_tx = event_tx.clone();
+            async move { CommandRunner::new().run(command_rx, event_tx).await }
+        });
+
+        let mut spec = make_spec("wc -l");
+        spec.stdin_input = Some("a\nb\nc\n".to_string());
+        command_tx
+            .send(RunnerCommand::Run {
+                spec,
+                interactive: false,
+            })
+            .await
+            .unwrap();
+        let lines = next_matching(&mut event_rx, |event| match event {
+            RuntimeEvent::CommandOutput {
+                stream: LogStream::Stdout,
+                text,
+                ..
+            } => Some(text.trim().to_string()),
+            _ => None,
+        })
+        .await;
+        assert_eq!(lines, "3");
+        let code = next_matching(&mut event_rx, |event| match event {
+            RuntimeEvent::CommandFinished { exit_code, .. } => Some(*exit_code),
+            _ => None,
+        })
+        .await;
+        assert_eq!(code, Some(0));
+    }
+}
@@ -1,13 +1,88 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use sysinfo::{Networks, System};
-use tokio::sync::{RwLock, broadcast};
+use tokio::sync::{RwLock, broadcast, watch};
 
+use orkesy_core::model::{ServiceId, ServiceStatus};
 use orkesy_core::reducer::{EventEnvelope, RuntimeEvent};
 use orkesy_core::state::RuntimeState;
 
-const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Sampling interval unless a view asks for another
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Sampling interval while charts are on screen
+pub const FAST_SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+/// Sampling interval while only logs are on screen
+pub const SLOW_SAMPLE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// How often the sampler runs. The TUI sends a new one through a `watch`
+/// channel when the visible views change; the sampler picks it up before its
+/// next sleep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SamplerConfig {
+    pub interval: Duration,
+}
+
+impl Default for SamplerConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_SAMPLE_INTERVAL,
+        }
+    }
+}
+
+/// Cumulative I/O counters of one process
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProcessIo {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+/// Bytes `pid` has read and written since it started: `rchar`/`wchar` from
+/// `/proc/<pid>/io`, which include page cache hits
+#[cfg(target_os = "linux")]
+pub fn process_io(pid: u32) -> Option<ProcessIo> {
+    let text = std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some(ProcessIo {
+        read_bytes: field("rchar")?,
+        write_bytes: field("wchar")?,
+    })
+}
+
+/// Bytes `pid` has read from and written to disk since it started, from
+/// `proc_pid_rusage`
+#[cfg(target_os = "macos")]
+pub fn process_io(pid: u32) -> Option<ProcessIo> {
+    let mut info = std::mem::MaybeUninit::<libc::rusage_info_v2>::zeroed();
+    // SAFETY: RUSAGE_INFO_V2 makes the kernel fill a rusage_info_v2
+    let rc = unsafe {
+        libc::proc_pid_rusage(
+            pid as libc::c_int,
+            libc::RUSAGE_INFO_V2,
+            info.as_mut_ptr() as *mut libc::rusage_info_t,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    // SAFETY: zeroed, then filled in by a successful call
+    let info = unsafe { info.assume_init() };
+    Some(ProcessIo {
+        read_bytes: info.ri_diskio_bytesread,
+        write_bytes: info.ri_diskio_byteswritten,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn process_io(_pid: u32) -> Option<ProcessIo> {
+    None
+}
 
 pub struct MetricsSampler {
     system: System,
@@ -16,6 +91,8 @@ pub struct MetricsSampler {
     event_id: u64,
     prev_net_rx: u64,
     prev_net_tx: u64,
+    /// Last I/O counters per unit, with the pid they were read from
+    prev_io: BTreeMap<ServiceId, (u32, ProcessIo)>,
 }
 
 impl MetricsSampler {
@@ -27,6 +104,7 @@ impl MetricsSampler {
             event_id: 1_000_000, // Start high to avoid collision with other event sources
             prev_net_rx: 0,
             prev_net_tx: 0,
+            prev_io: BTreeMap::new(),
         }
     }
 
@@ -40,7 +118,7 @@ impl MetricsSampler {
         id
     }
 
-    fn sample_system(&mut self) -> RuntimeEvent {
+    fn sample_system(&mut self, elapsed_secs: f64) -> RuntimeEvent {
         // Refresh system info
         self.system.refresh_cpu_all();
         self.system.refresh_memory();
@@ -64,7 +142,7 @@ impl MetricsSampler {
         // Calculate delta and convert to KB/s
         let rx_delta = total_rx.saturating_sub(self.prev_net_rx);
         let tx_delta = total_tx.saturating_sub(self.prev_net_tx);
-        let net_kbps = (rx_delta + tx_delta) as f64 / 1024.0 / SAMPLE_INTERVAL.as_secs_f64();
+        let net_kbps = (rx_delta + tx_delta) as f64 / 1024.0 / elapsed_secs;
 
         self.prev_net_rx = total_rx;
         self.prev_net_tx = total_tx;
@@ -80,11 +158,11 @@ impl MetricsSampler {
     fn compute_log_rates(
         &self,
         state: &mut orkesy_core::metrics::MetricsState,
+        elapsed_secs: f64,
     ) -> Vec<RuntimeEvent> {
         let t = self.timestamp();
-        let interval_secs = SAMPLE_INTERVAL.as_secs_f64();
 
-        let rates = state.compute_log_rates(interval_secs);
+        let rates = state.compute_log_rates(elapsed_secs);
 
         rates
             .into_iter()
@@ -92,34 +170,82 @@ impl MetricsSampler {
             .collect()
     }
 
+    /// Disk I/O rates of running units from the growth of their counters since
+    /// the last tick. A unit's first tick with a new pid only primes them.
+    fn sample_io(&mut self, pids: Vec<(ServiceId, u32)>, elapsed_secs: f64) -> Vec<RuntimeEvent> {
+        let t = self.timestamp();
+        let mut events = Vec::new();
+        let mut current = BTreeMap::new();
+
+        for (id, pid) in pids {
+            let Some(io) = process_io(pid) else {
+                continue;
+            };
+            if let Some((prev_pid, prev)) = self.prev_io.get(&id)
+                && *prev_pid == pid
+            {
+                let rate = |now: u64, before: u64| {
+                    now.saturating_sub(before) as f64 / 1024.0 / elapsed_secs
+                };
+                events.push(RuntimeEvent::IoRateSample {
+                    t,
+                    id: id.clone(),
+                    read_kbps: rate(io.read_bytes, prev.read_bytes),
+                    write_kbps: rate(io.write_bytes, prev.write_bytes),
+                });
+            }
+            current.insert(id, (pid, io));
+        }
+
+        self.prev_io = current;
+        events
+    }
+
     pub async fn run(
         mut self,
         event_tx: broadcast::Sender<EventEnvelope>,
         state: Arc<RwLock<RuntimeState>>,
+        config: watch::Receiver<SamplerConfig>,
     ) {
-        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
-
-        // Skip the first tick (happens immediately)
-        interval.tick().await;
+        let mut last_sample = Instant::now();
 
         loop {
-            interval.tick().await;
+            let interval = config.borrow().interval;
+            tokio::time::sleep(interval).await;
+            // Rates are per measured second, so an interval change mid-sleep
+            // doesn't skew them
+            let elapsed_secs = last_sample.elapsed().as_secs_f64();
+            last_sample = Instant::now();
 
             // Sample system metrics
-            let system_event = self.sample_system();
+            let system_event = self.sample_system(elapsed_secs);
             let _ = event_tx.send(EventEnvelope {
                 id: self.next_event_id(),
                 at: std::time::SystemTime::now(),
                 event: system_event,
             });
 
-            // Compute and emit log rates
+            // Compute and emit log and I/O rates
             {
                 let mut state_guard = state.write().await;
-                let log_rate_events = self.compute_log_rates(&mut state_guard.metrics_series);
+                let mut events =
+                    self.compute_log_rates(&mut state_guard.metrics_series, elapsed_secs);
+                let pids: Vec<(ServiceId, u32)> = state_guard
+                    .metrics
+                    .iter()
+                    .filter(|(id, _)| {
+                        state_guard
+                            .graph
+                            .nodes
+                            .get(*id)
+                            .is_some_and(|n| n.observed.status == ServiceStatus::Running)
+                    })
+                    .filter_map(|(id, m)| Some((id.clone(), m.pid?)))
+                    .collect();
                 drop(state_guard);
+                events.extend(self.sample_io(pids, elapsed_secs));
 
-                for event in log_rate_events {
+                for event in events {
                     let _ = event_tx.send(EventEnvelope {
                         id: self.next_event_id(),
                         at: std::time::SystemTime::now(),
@@ -137,9 +263,13 @@ impl Default for MetricsSampler {
     }
 }
 
-pub fn spawn_sampler(event_tx: broadcast::Sender<EventEnvelope>, state: Arc<RwLock<RuntimeState>>) {
+pub fn spawn_sampler(
+    event_tx: broadcast::Sender<EventEnvelope>,
+    state: Arc<RwLock<RuntimeState>>,
+    config: watch::Receiver<SamplerConfig>,
+) {
     let sampler = MetricsSampler::new();
     tokio::spawn(async move {
-        sampler.run(event_tx, state).await;
+        sampler.run(event_tx, state, config).await;
     });
 }
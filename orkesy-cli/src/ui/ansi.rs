@@ -0,0 +1,180 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+const ESC: char = '\u{1b}';
+
+pub fn contains_ansi(text: &str) -> bool {
+    text.contains(ESC)
+}
+
+/// Removes all escape sequences, leaving the printable text.
+pub fn strip_ansi(text: &str) -> String {
+    if !contains_ansi(text) {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ESC {
+            skip_sequence(&mut chars);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Converts a line containing SGR escape sequences into styled spans.
+///
+/// Supports the 8/16-color palette, 256-color (`38;5;n`) and truecolor
+/// (`38;2;r;g;b`) sequences plus bold, dim, italic and underline. Other
+/// escape sequences (cursor movement, OSC titles) are dropped.
+pub fn ansi_to_spans(text: &str, base: Style) -> Vec<Span<'static>> {
+    if !contains_ansi(text) {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+
+    let mut spans = Vec::new();
+    let mut style = base;
+    let mut buf = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            buf.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            skip_sequence(&mut chars);
+            continue;
+        }
+        chars.next();
+
+        let mut params = String::new();
+        let mut final_byte = None;
+        for p in chars.by_ref() {
+            if ('\u{40}'..='\u{7e}').contains(&p) {
+                final_byte = Some(p);
+                break;
+            }
+            params.push(p);
+        }
+
+        if final_byte == Some('m') {
+            if !buf.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut buf), style));
+            }
+            style = apply_sgr(style, base, &params);
+        }
+    }
+
+    if !buf.is_empty() {
+        spans.push(Span::styled(buf, style));
+    }
+    spans
+}
+
+fn skip_sequence(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    match chars.next() {
+        Some('[') => {
+            for p in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&p) {
+                    break;
+                }
+            }
+        }
+        Some(']') => {
+            // OSC: terminated by BEL or ESC '\'
+            while let Some(p) = chars.next() {
+                if p == '\u{7}' {
+                    break;
+                }
+                if p == ESC {
+                    chars.next();
+                    break;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_sgr(mut style: Style, base: Style, params: &str) -> Style {
+    let codes: Vec<u16> = if params.is_empty() {
+        vec![0]
+    } else {
+        params.split(';').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => style = base,
+            1 => style = style.add_modifier(Modifier::BOLD),
+            2 => style = style.add_modifier(Modifier::DIM),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style = style.remove_modifier(Modifier::ITALIC),
+            24 => style = style.remove_modifier(Modifier::UNDERLINED),
+            27 => style = style.remove_modifier(Modifier::REVERSED),
+            n @ 30..=37 => style = style.fg(basic_color(n - 30)),
+            n @ 90..=97 => style = style.fg(bright_color(n - 90)),
+            n @ 40..=47 => style = style.bg(basic_color(n - 40)),
+            n @ 100..=107 => style = style.bg(bright_color(n - 100)),
+            39 => style.fg = base.fg,
+            49 => style.bg = base.bg,
+            38 | 48 => {
+                let (color, used) = extended_color(&codes[i + 1..]);
+                if let Some(color) = color {
+                    style = if codes[i] == 38 {
+                        style.fg(color)
+                    } else {
+                        style.bg(color)
+                    };
+                }
+                i += used;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    style
+}
+
+/// Parses the tail of a `38;...`/`48;...` sequence. Returns the color and
+/// how many extra parameters were consumed.
+fn extended_color(rest: &[u16]) -> (Option<Color>, usize) {
+    match rest {
+        [5, n, ..] => (Some(Color::Indexed(*n as u8)), 2),
+        [2, r, g, b, ..] => (Some(Color::Rgb(*r as u8, *g as u8, *b as u8)), 4),
+        _ => (None, rest.len()),
+    }
+}
+
+fn basic_color(n: u16) -> Color {
+    match n {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::Gray,
+    }
+}
+
+fn bright_color(n: u16) -> Color {
+    match n {
+        0 => Color::DarkGray,
+        1 => Color::LightRed,
+        2 => Color::LightGreen,
+        3 => Color::LightYellow,
+        4 => Color::LightBlue,
+        5 => Color::LightMagenta,
+        6 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
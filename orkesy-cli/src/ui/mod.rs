@@ -1,3 +1,36 @@
+pub mod ansi;
+pub mod palette;
 pub mod theme;
+pub mod toast;
 
 pub use theme::styles;
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Finds a source location in a log line, e.g. `src/main.rs:42:5` from a
+/// Rust panic, `File "app.py", line 23` from a Python traceback, or
+/// `(/app/index.js:10:15)` from a Node stack frame.
+pub fn extract_file_location(line: &str) -> Option<(PathBuf, u32)> {
+    static PATTERNS: OnceLock<[Regex; 2]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            // Python: File "app.py", line 23
+            Regex::new(r#"File "(?P<path>[^"]+)", line (?P<line>\d+)"#).unwrap(),
+            // path:line[:col], the file needs an extension starting with a letter
+            // so `localhost:8080` and `10.0.0.1:80` don't match
+            Regex::new(
+                r"(?:file://)?(?P<path>(?:[A-Za-z]:)?[\w./\\~@+-]*[\w-]\.[A-Za-z][\w]*):(?P<line>\d+)",
+            )
+            .unwrap(),
+        ]
+    });
+
+    patterns.iter().find_map(|re| {
+        let caps = re.captures(line)?;
+        let line = caps["line"].parse().ok().filter(|n| *n > 0)?;
+        Some((PathBuf::from(&caps["path"]), line))
+    })
+}
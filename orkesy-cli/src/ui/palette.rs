@@ -0,0 +1,18 @@
+/// Longest prefix shared by all `items`, like shell tab completion; empty
+/// when there are no items
+pub fn common_prefix<'a>(items: &[&'a str]) -> &'a str {
+    let Some((first, rest)) = items.split_first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for item in rest {
+        len = first
+            .char_indices()
+            .zip(item.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, c), _)| i + c.len_utf8())
+            .min(len);
+    }
+    &first[..len]
+}
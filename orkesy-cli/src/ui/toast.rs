@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use ratatui::Frame;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Only the newest toasts are drawn; older ones wait out their time hidden
+const MAX_VISIBLE: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Error,
+}
+
+impl ToastLevel {
+    fn color(self) -> Color {
+        match self {
+            ToastLevel::Info => Color::Cyan,
+            ToastLevel::Success => Color::Green,
+            ToastLevel::Error => Color::Red,
+        }
+    }
+}
+
+/// Short message shown in the bottom-right corner until `expires_at`
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub message: String,
+    pub level: ToastLevel,
+    pub expires_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self {
+            message: message.into(),
+            level,
+            expires_at: Instant::now() + TOAST_DURATION,
+        }
+    }
+}
+
+/// Draws the newest `MAX_VISIBLE` toasts stacked upwards from the
+/// bottom-right corner of `area`, newest at the bottom. They sit one cell
+/// in so the pane borders underneath stay visible.
+pub fn render(f: &mut Frame, area: Rect, toasts: &[Toast]) {
+    let area = area.inner(Margin::new(1, 1));
+    let mut bottom = area.y + area.height;
+    for toast in toasts.iter().rev().take(MAX_VISIBLE) {
+        if bottom < area.y + 3 {
+            break;
+        }
+        let width = (toast.message.chars().count() as u16 + 4).min(area.width);
+        let rect = Rect {
+            x: area.x + area.width - width,
+            y: bottom - 3,
+            width,
+            height: 3,
+        };
+        bottom -= 3;
+
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(toast.level.color()));
+        f.render_widget(Paragraph::new(toast.message.as_str()).block(block), rect);
+    }
+}
@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use std::fmt;
-use tokio::sync::broadcast;
+use std::time::SystemTime;
+use tokio::sync::{broadcast, mpsc, oneshot};
 
+use crate::state::{LogLine, WatchInfo};
 use crate::unit::{Unit, UnitHealth, UnitMetrics, UnitStatus};
 
 pub use crate::state::LogStream;
@@ -62,21 +64,237 @@ pub enum AdapterEvent {
         id: String,
         metrics: UnitMetrics,
     },
+    /// A metric crossed one of the unit's `alerts` thresholds
+    ResourceWarning {
+        id: String,
+        metric: String,
+        value: f64,
+        threshold: f64,
+    },
+    /// The unit's `ready_when` check passed after it started
+    ReadinessReached {
+        id: String,
+    },
+    /// Reply to `AdapterCommand::AddNote`
+    UserNote {
+        id: String,
+        text: String,
+        at: SystemTime,
+    },
+    /// A watch was added, fired or updated; `None` once it is cancelled
+    WatchChanged {
+        id: String,
+        watch_id: String,
+        watch: Option<WatchInfo>,
+    },
 }
 
-#[derive(Clone, Debug)]
+/// Comparison used by `AdapterCommand::Watch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Gt,
+    Lt,
+    Gte,
+    Lte,
+}
+
+impl CompareOp {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            ">" | "gt" => Some(CompareOp::Gt),
+            "<" | "lt" => Some(CompareOp::Lt),
+            ">=" | "gte" => Some(CompareOp::Gte),
+            "<=" | "lte" => Some(CompareOp::Lte),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, value: f64, threshold: f64) -> bool {
+        match self {
+            CompareOp::Gt => value > threshold,
+            CompareOp::Lt => value < threshold,
+            CompareOp::Gte => value >= threshold,
+            CompareOp::Lte => value <= threshold,
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            CompareOp::Gt => ">",
+            CompareOp::Lt => "<",
+            CompareOp::Gte => ">=",
+            CompareOp::Lte => "<=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Debug)]
 pub enum AdapterCommand {
-    Start { id: String },
-    Stop { id: String },
-    Restart { id: String },
-    Kill { id: String },
-    Toggle { id: String },
-    Exec { id: String, cmd: Vec<String> },
-    ClearLogs { id: String },
-    Install { id: String },
+    Start {
+        id: String,
+    },
+    /// Start several units in dependency order: a unit starts only once the
+    /// units in `ids` it depends on are running, and is marked errored if
+    /// one of them failed to start
+    StartMany {
+        ids: Vec<String>,
+    },
+    Stop {
+        id: String,
+    },
+    Restart {
+        id: String,
+    },
+    Kill {
+        id: String,
+    },
+    Toggle {
+        id: String,
+    },
+    Exec {
+        id: String,
+        cmd: Vec<String>,
+    },
+    ClearLogs {
+        id: String,
+    },
+    Install {
+        id: String,
+    },
+    /// Attach a note to a unit; echoed back as `AdapterEvent::UserNote`
+    AddNote {
+        id: String,
+        text: String,
+    },
+    /// Reply with the last `last_n` buffered log lines of a unit, oldest first
+    GetLogs {
+        id: String,
+        last_n: usize,
+        reply_tx: oneshot::Sender<Vec<LogLine>>,
+    },
+    /// Run `then` each time `metric` of unit `id` starts matching `op threshold`.
+    /// The adapter assigns a watch id, reported via `AdapterEvent::WatchChanged`.
+    Watch {
+        id: String,
+        metric: String,
+        op: CompareOp,
+        threshold: f64,
+        then: Box<AdapterCommand>,
+    },
+    CancelWatch {
+        watch_id: String,
+    },
     Shutdown,
 }
 
+impl AdapterCommand {
+    /// Copies unit actions; `None` for commands that carry a reply channel or
+    /// make no sense to repeat (`GetLogs`, `Watch`, `CancelWatch`, `Shutdown`).
+    pub fn try_clone(&self) -> Option<Self> {
+        Some(match self {
+            AdapterCommand::Start { id } => AdapterCommand::Start { id: id.clone() },
+            AdapterCommand::StartMany { ids } => AdapterCommand::StartMany { ids: ids.clone() },
+            AdapterCommand::Stop { id } => AdapterCommand::Stop { id: id.clone() },
+            AdapterCommand::Restart { id } => AdapterCommand::Restart { id: id.clone() },
+            AdapterCommand::Kill { id } => AdapterCommand::Kill { id: id.clone() },
+            AdapterCommand::Toggle { id } => AdapterCommand::Toggle { id: id.clone() },
+            AdapterCommand::Exec { id, cmd } => AdapterCommand::Exec {
+                id: id.clone(),
+                cmd: cmd.clone(),
+            },
+            AdapterCommand::ClearLogs { id } => AdapterCommand::ClearLogs { id: id.clone() },
+            AdapterCommand::Install { id } => AdapterCommand::Install { id: id.clone() },
+            AdapterCommand::AddNote { id, text } => AdapterCommand::AddNote {
+                id: id.clone(),
+                text: text.clone(),
+            },
+            AdapterCommand::GetLogs { .. }
+            | AdapterCommand::Watch { .. }
+            | AdapterCommand::CancelWatch { .. }
+            | AdapterCommand::Shutdown => return None,
+        })
+    }
+
+    /// The unit a command targets; `None` for `StartMany`, `CancelWatch`
+    /// and `Shutdown`
+    pub fn unit_id(&self) -> Option<&str> {
+        match self {
+            AdapterCommand::Start { id }
+            | AdapterCommand::Stop { id }
+            | AdapterCommand::Restart { id }
+            | AdapterCommand::Kill { id }
+            | AdapterCommand::Toggle { id }
+            | AdapterCommand::Exec { id, .. }
+            | AdapterCommand::ClearLogs { id }
+            | AdapterCommand::Install { id }
+            | AdapterCommand::AddNote { id, .. }
+            | AdapterCommand::GetLogs { id, .. }
+            | AdapterCommand::Watch { id, .. } => Some(id),
+            AdapterCommand::StartMany { .. }
+            | AdapterCommand::CancelWatch { .. }
+            | AdapterCommand::Shutdown => None,
+        }
+    }
+
+    /// Short form for logs and the Inspect view, e.g. `restart api`
+    pub fn describe(&self) -> String {
+        match self {
+            AdapterCommand::Start { id } => format!("start {}", id),
+            AdapterCommand::StartMany { ids } => format!("start {}", ids.join(" ")),
+            AdapterCommand::Stop { id } => format!("stop {}", id),
+            AdapterCommand::Restart { id } => format!("restart {}", id),
+            AdapterCommand::Kill { id } => format!("kill {}", id),
+            AdapterCommand::Toggle { id } => format!("toggle {}", id),
+            AdapterCommand::Exec { id, cmd } => format!("exec {} {}", id, cmd.join(" ")),
+            AdapterCommand::ClearLogs { id } => format!("clear {}", id),
+            AdapterCommand::Install { id } => format!("install {}", id),
+            AdapterCommand::AddNote { id, .. } => format!("note {}", id),
+            AdapterCommand::GetLogs { id, .. } => format!("logs {}", id),
+            AdapterCommand::Watch { id, metric, .. } => format!("watch {} {}", id, metric),
+            AdapterCommand::CancelWatch { watch_id } => format!("unwatch {}", watch_id),
+            AdapterCommand::Shutdown => "shutdown".into(),
+        }
+    }
+}
+
+/// Convenience wrapper around an adapter's command channel.
+#[derive(Clone, Debug)]
+pub struct AdapterHandle {
+    cmd_tx: mpsc::Sender<AdapterCommand>,
+}
+
+impl AdapterHandle {
+    pub fn new(cmd_tx: mpsc::Sender<AdapterCommand>) -> Self {
+        Self { cmd_tx }
+    }
+
+    pub async fn send(&self, cmd: AdapterCommand) -> Result<(), AdapterError> {
+        self.cmd_tx
+            .send(cmd)
+            .await
+            .map_err(|_| AdapterError::Other {
+                message: "adapter is not running".into(),
+            })
+    }
+
+    /// Returns an empty list if the adapter has stopped or keeps no logs.
+    pub async fn get_logs(&self, id: &str, last_n: usize) -> Vec<LogLine> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let cmd = AdapterCommand::GetLogs {
+            id: id.to_string(),
+            last_n,
+            reply_tx,
+        };
+        if self.send(cmd).await.is_err() {
+            return Vec::new();
+        }
+        reply_rx.await.unwrap_or_default()
+    }
+}
+
 #[async_trait]
 pub trait Adapter: Send + Sync {
     fn name(&self) -> &'static str;
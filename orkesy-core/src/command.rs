@@ -44,15 +44,42 @@ pub enum PythonPackageManager {
     Poetry,
 }
 
+impl PythonPackageManager {
+    /// Runs `program` inside the project's environment; pip projects are
+    /// expected to have theirs activated
+    pub fn run(&self, program: &str) -> String {
+        match self {
+            PythonPackageManager::Pip => program.to_string(),
+            PythonPackageManager::Uv => format!("uv run {}", program),
+            PythonPackageManager::Poetry => format!("poetry run {}", program),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DetectedTool {
-    Node { pm: PackageManager },
-    Python { pm: PythonPackageManager },
+    Node {
+        pm: PackageManager,
+    },
+    Python {
+        pm: PythonPackageManager,
+    },
     Rust,
     Go,
-    DockerCompose { file: PathBuf },
-    Make { file: PathBuf },
-    Just { file: PathBuf },
+    DockerCompose {
+        file: PathBuf,
+    },
+    Make {
+        file: PathBuf,
+    },
+    Just {
+        file: PathBuf,
+    },
+    /// `library` is set for JSR packages, which have no app entry point
+    Deno {
+        file: PathBuf,
+        library: bool,
+    },
 }
 
 impl DetectedTool {
@@ -65,6 +92,7 @@ impl DetectedTool {
             DetectedTool::DockerCompose { .. } => "Docker",
             DetectedTool::Make { .. } => "Make",
             DetectedTool::Just { .. } => "Just",
+            DetectedTool::Deno { .. } => "Deno",
         }
     }
 
@@ -77,6 +105,7 @@ impl DetectedTool {
             DetectedTool::DockerCompose { .. } => "D",
             DetectedTool::Make { .. } => "M",
             DetectedTool::Just { .. } => "J",
+            DetectedTool::Deno { .. } => "d",
         }
     }
 }
@@ -115,6 +144,70 @@ impl CommandCategory {
     }
 }
 
+/// Guesses a `CommandCategory` for a script from its name and command line.
+pub struct Heuristics;
+
+impl Heuristics {
+    /// Keywords looked up as whole words of the command, most specific first
+    const COMMAND_KEYWORDS: [(CommandCategory, &'static [&'static str]); 4] = [
+        (
+            CommandCategory::Test,
+            &["jest", "vitest", "mocha", "pytest", "cargo test", "go test"],
+        ),
+        (
+            CommandCategory::Lint,
+            &["lint", "check", "fmt", "format", "eslint", "clippy"],
+        ),
+        (
+            CommandCategory::Build,
+            &["compile", "bundle", "dist", "emit"],
+        ),
+        (CommandCategory::Dev, &["watch", "serve", "start", "hot"]),
+    ];
+
+    /// The name wins when it and the command point at different categories.
+    pub fn categorize(name: &str, command: &str) -> CommandCategory {
+        Self::from_name(name)
+            .or_else(|| Self::from_command(command))
+            .unwrap_or(CommandCategory::Script)
+    }
+
+    fn from_name(name: &str) -> Option<CommandCategory> {
+        let lower = name.to_lowercase();
+        let has = |parts: &[&str]| parts.iter().any(|p| lower.contains(p));
+        if has(&["dev", "start", "serve", "watch"]) {
+            Some(CommandCategory::Dev)
+        } else if has(&["build", "compile"]) {
+            Some(CommandCategory::Build)
+        } else if has(&["test", "spec", "coverage"]) {
+            Some(CommandCategory::Test)
+        } else if has(&["lint", "format", "prettier", "check"]) {
+            Some(CommandCategory::Lint)
+        } else {
+            None
+        }
+    }
+
+    fn from_command(command: &str) -> Option<CommandCategory> {
+        // Split on anything but word characters so `--watch` and
+        // `tsc -w && node dist/index.js` yield plain words
+        let lower = command.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let has = |keyword: &str| {
+            let parts: Vec<&str> = keyword.split(' ').collect();
+            words.windows(parts.len()).any(|w| w == parts.as_slice())
+        };
+
+        Self::COMMAND_KEYWORDS
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|k| has(k)))
+            .map(|(category, _)| category.clone())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommandSpec {
     pub id: CommandId,
@@ -125,6 +218,25 @@ pub struct CommandSpec {
     pub cwd: Option<PathBuf>,
     pub description: Option<String>,
     pub category: CommandCategory,
+    /// Written to stdin right after spawn, for commands that prompt (e.g. "y\n")
+    #[serde(default)]
+    pub stdin_input: Option<String>,
+    /// Start from an empty environment instead of inheriting orkesy's;
+    /// only [`CLEAN_ENV_PASSTHROUGH`] vars and `env` are set
+    #[serde(default)]
+    pub clean_env: Option<bool>,
+    /// Extra environment, e.g. the env of the unit the command belongs to
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// System vars kept for `clean_env` commands
+pub const CLEAN_ENV_PASSTHROUGH: [&str; 4] = ["PATH", "HOME", "USER", "TERM"];
+
+impl CommandSpec {
+    pub fn uses_clean_env(&self) -> bool {
+        self.clean_env.unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -150,6 +262,36 @@ impl RunStatus {
         matches!(self, RunStatus::Running)
     }
 
+    /// Running first, then failures, kills and successful exits
+    pub fn sort_rank(&self) -> u8 {
+        match self {
+            RunStatus::Running => 0,
+            RunStatus::Failed { .. } => 1,
+            RunStatus::Exited { code } if *code != Some(0) => 2,
+            RunStatus::Killed => 3,
+            RunStatus::Exited { .. } => 4,
+        }
+    }
+
+    /// Whether this status passes a Runs filter. Only the filter's variant
+    /// matters, except that `Failed` also matches non-zero exits.
+    pub fn matches_filter(&self, filter: &RunStatus) -> bool {
+        match (filter, self) {
+            (RunStatus::Failed { .. }, RunStatus::Exited { code }) => *code != Some(0),
+            _ => std::mem::discriminant(filter) == std::mem::discriminant(self),
+        }
+    }
+
+    /// Filter name shown in the Runs pane title
+    pub fn filter_label(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Exited { .. } => "exited",
+            RunStatus::Killed => "killed",
+            RunStatus::Failed { .. } => "failed",
+        }
+    }
+
     pub fn is_success(&self) -> bool {
         matches!(self, RunStatus::Exited { code: Some(0) })
     }
@@ -193,6 +335,9 @@ pub struct ProjectIndex {
     pub tools: Vec<DetectedTool>,
     pub commands: BTreeMap<CommandId, CommandSpec>,
     pub indexed_at: SystemTime,
+    /// SHA-256 of the files read while indexing, used to detect a stale index
+    #[serde(default)]
+    pub file_hashes: BTreeMap<PathBuf, [u8; 32]>,
 }
 
 impl Default for ProjectIndex {
@@ -202,6 +347,7 @@ impl Default for ProjectIndex {
             tools: Vec::new(),
             commands: BTreeMap::new(),
             indexed_at: SystemTime::UNIX_EPOCH,
+            file_hashes: BTreeMap::new(),
         }
     }
 }
@@ -213,6 +359,7 @@ impl ProjectIndex {
             tools: Vec::new(),
             commands: BTreeMap::new(),
             indexed_at: SystemTime::now(),
+            file_hashes: BTreeMap::new(),
         }
     }
 
@@ -796,6 +943,16 @@ mod tests {
         assert_eq!(PackageManager::Bun.run_prefix(), "bun run");
     }
 
+    #[test]
+    fn test_python_package_manager_run() {
+        assert_eq!(PythonPackageManager::Pip.run("pytest"), "pytest");
+        assert_eq!(PythonPackageManager::Uv.run("pytest"), "uv run pytest");
+        assert_eq!(
+            PythonPackageManager::Poetry.run("task dev"),
+            "poetry run task dev"
+        );
+    }
+
     #[test]
     fn test_command_category_icons() {
         assert_eq!(CommandCategory::Dev.icon(), "◉");
@@ -803,6 +960,55 @@ mod tests {
         assert_eq!(CommandCategory::Test.icon(), "✓");
     }
 
+    #[test]
+    fn test_categorize_heuristics() {
+        // Unrecognised names fall back to the command
+        assert_eq!(
+            Heuristics::categorize("storybook", "storybook dev -p 6006 --watch"),
+            CommandCategory::Dev
+        );
+        assert_eq!(
+            Heuristics::categorize("types", "tsc --emitDeclarationOnly false && tsc --emit"),
+            CommandCategory::Build
+        );
+        assert_eq!(
+            Heuristics::categorize("unit", "vitest run --watch"),
+            CommandCategory::Test
+        );
+        assert_eq!(
+            Heuristics::categorize("ci", "cargo fmt --check && cargo test"),
+            CommandCategory::Test
+        );
+        assert_eq!(
+            Heuristics::categorize("style", "prettier --check ."),
+            CommandCategory::Lint
+        );
+        // Names match on substrings
+        for (name, category) in [
+            ("start:prod", CommandCategory::Dev),
+            ("build-prod", CommandCategory::Build),
+            ("unit-test", CommandCategory::Test),
+            ("spec", CommandCategory::Test),
+            ("prettier:fix", CommandCategory::Lint),
+        ] {
+            assert_eq!(Heuristics::categorize(name, "node run.js"), category);
+        }
+        // Command keywords only match whole words
+        assert_eq!(
+            Heuristics::categorize("release", "./scripts/restart-hotfix.sh"),
+            CommandCategory::Script
+        );
+        // The name wins over the command
+        assert_eq!(
+            Heuristics::categorize("test", "jest --watch"),
+            CommandCategory::Test
+        );
+        assert_eq!(
+            Heuristics::categorize("build", "webpack serve"),
+            CommandCategory::Build
+        );
+    }
+
     #[test]
     fn test_detected_tool_short_name() {
         assert_eq!(
@@ -814,6 +1020,14 @@ mod tests {
         );
         assert_eq!(DetectedTool::Rust.short_name(), "Rust");
         assert_eq!(DetectedTool::Go.short_name(), "Go");
+        assert_eq!(
+            DetectedTool::Deno {
+                file: PathBuf::from("deno.json"),
+                library: false
+            }
+            .short_name(),
+            "Deno"
+        );
     }
 
     #[test]
@@ -833,6 +1047,9 @@ mod tests {
                 cwd: None,
                 description: None,
                 category: CommandCategory::Test,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
         );
 
@@ -849,6 +1066,9 @@ mod tests {
                 cwd: None,
                 description: None,
                 category: CommandCategory::Build,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             },
         );
 
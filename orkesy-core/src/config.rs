@@ -3,12 +3,15 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::command::CommandSpec;
+
 use crate::model::{
     DesiredState, Edge, EdgeKind, HealthStatus, ObservedState, RuntimeGraph, ServiceKind,
     ServiceNode, ServiceStatus,
 };
 use crate::unit::{
-    EdgeKind as UnitEdgeKind, HealthCheck as UnitHealthCheck, StopBehavior, StopSignal, Unit,
+    DEFAULT_READY_TIMEOUT_MS, DEFAULT_STOP_TIMEOUT_MS, EdgeKind as UnitEdgeKind,
+    HealthCheck as UnitHealthCheck, LogSinkConfig, ResourceAlerts, StopBehavior, StopSignal, Unit,
     UnitEdge, UnitKind,
 };
 
@@ -17,22 +20,144 @@ use crate::unit::{
 pub enum HealthCheck {
     Http {
         path: String,
+        /// Request method; `GET` when unset
+        #[serde(default)]
+        method: Option<String>,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        /// Status the response must have; any 2xx when unset
+        #[serde(default)]
+        expected_status: Option<u16>,
+        /// Text the response body must contain
+        #[serde(default)]
+        body_contains: Option<String>,
         #[serde(default = "default_health_interval")]
         interval_ms: u64,
+        /// Cap for the interval while probes fail (default 60000)
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
         #[serde(default = "default_health_timeout")]
         timeout_ms: u64,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
     Tcp {
         #[serde(default = "default_health_interval")]
         interval_ms: u64,
+        /// Cap for the interval while probes fail (default 60000)
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
     Exec {
         command: Vec<String>,
         #[serde(default = "default_health_interval")]
         interval_ms: u64,
+        /// Cap for the interval while probes fail (default 60000)
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
+    },
+    /// gRPC health checking protocol against the service's port
+    Grpc {
+        /// Service name to ask about; empty for the whole server
+        #[serde(default)]
+        service: String,
+        #[serde(default = "default_health_interval")]
+        interval_ms: u64,
+        /// Cap for the interval while probes fail (default 60000)
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default = "default_health_timeout")]
+        timeout_ms: u64,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
 }
 
+impl HealthCheck {
+    /// Resolves the check against the service's `host` and `port`
+    fn to_unit(&self, host: &str, port: Option<u16>) -> UnitHealthCheck {
+        match self {
+            HealthCheck::Tcp {
+                interval_ms,
+                max_interval_ms,
+                failure_threshold,
+                success_threshold,
+            } => UnitHealthCheck::Tcp {
+                port: port.unwrap_or(8000),
+                interval_ms: *interval_ms,
+                max_interval_ms: *max_interval_ms,
+                failure_threshold: *failure_threshold,
+                success_threshold: *success_threshold,
+            },
+            HealthCheck::Http {
+                path,
+                method,
+                headers,
+                expected_status,
+                body_contains,
+                interval_ms,
+                max_interval_ms,
+                timeout_ms,
+                failure_threshold,
+                success_threshold,
+            } => UnitHealthCheck::Http {
+                url: format!("http://localhost:{}{}", port.unwrap_or(8000), path),
+                method: method.clone(),
+                headers: headers.clone(),
+                expected_status: *expected_status,
+                body_contains: body_contains.clone(),
+                interval_ms: *interval_ms,
+                max_interval_ms: *max_interval_ms,
+                timeout_ms: *timeout_ms,
+                failure_threshold: *failure_threshold,
+                success_threshold: *success_threshold,
+            },
+            HealthCheck::Exec {
+                command,
+                interval_ms,
+                max_interval_ms,
+                failure_threshold,
+                success_threshold,
+            } => UnitHealthCheck::Exec {
+                command: command.join(" "),
+                interval_ms: *interval_ms,
+                max_interval_ms: *max_interval_ms,
+                failure_threshold: *failure_threshold,
+                success_threshold: *success_threshold,
+            },
+            HealthCheck::Grpc {
+                service,
+                interval_ms,
+                max_interval_ms,
+                timeout_ms,
+                failure_threshold,
+                success_threshold,
+            } => UnitHealthCheck::Grpc {
+                host: host.to_string(),
+                port: port.unwrap_or(8000),
+                service: service.clone(),
+                interval_ms: *interval_ms,
+                max_interval_ms: *max_interval_ms,
+                timeout_ms: *timeout_ms,
+                failure_threshold: *failure_threshold,
+                success_threshold: *success_threshold,
+            },
+        }
+    }
+}
+
 fn default_health_interval() -> u64 {
     5000
 }
@@ -41,6 +166,35 @@ fn default_health_timeout() -> u64 {
     2000
 }
 
+/// An `env_from` entry: a service id, or a service plus renames for its vars,
+/// e.g. `{ unit: postgres, map: { POSTGRES_USER: PGUSER } }`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum EnvFrom {
+    Unit(String),
+    Mapped {
+        unit: String,
+        #[serde(default)]
+        map: BTreeMap<String, String>,
+    },
+}
+
+impl EnvFrom {
+    pub fn unit(&self) -> &str {
+        match self {
+            Self::Unit(unit) | Self::Mapped { unit, .. } => unit,
+        }
+    }
+
+    /// Name `key` is inherited under
+    pub fn rename<'a>(&'a self, key: &'a str) -> &'a str {
+        match self {
+            Self::Mapped { map, .. } => map.get(key).map(String::as_str).unwrap_or(key),
+            Self::Unit(_) => key,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum RestartPolicy {
@@ -55,37 +209,131 @@ pub struct ServiceConfig {
     #[serde(default)]
     pub name: Option<String>,
 
+    /// Optional for services with a `docker_image`, where it overrides the
+    /// image's command
+    #[serde(default)]
     pub command: Vec<String>,
 
+    #[serde(default)]
+    pub shell: Option<bool>,
+
+    /// Run the service on a pseudo-terminal instead of pipes
+    #[serde(default)]
+    pub pty: bool,
+
     #[serde(default)]
     pub cwd: Option<PathBuf>,
 
     #[serde(default)]
     pub env: BTreeMap<String, String>,
 
+    /// Services whose env is merged in before `env`, in order
+    #[serde(default)]
+    pub env_from: Vec<EnvFrom>,
+
+    /// `.env` file loaded before `env` when the service starts
+    #[serde(default)]
+    pub env_file: Option<PathBuf>,
+
     #[serde(default)]
     pub port: Option<u16>,
 
     #[serde(default = "default_kind")]
     pub kind: String,
 
+    /// Image to run as a container; implies `kind: docker`
+    #[serde(default)]
+    pub docker_image: Option<String>,
+
+    /// Published ports as `host:container`, e.g. `"5432:5432"`
+    #[serde(default)]
+    pub docker_ports: Vec<String>,
+
+    /// Remote host to run `command` on; implies `kind: ssh`
+    #[serde(default)]
+    pub ssh: Option<SshConfig>,
+
     #[serde(default = "default_true")]
     pub autostart: bool,
 
     #[serde(default)]
     pub health_check: Option<HealthCheck>,
 
+    /// Health check results kept for the Inspect view (default 100)
+    #[serde(default)]
+    pub health_history_size: Option<usize>,
+
     #[serde(default)]
     pub depends_on: Vec<String>,
 
     #[serde(default)]
     pub description: Option<String>,
 
+    #[serde(default)]
+    pub render_ansi: Option<bool>,
+
+    #[serde(default)]
+    pub max_log_line_bytes: Option<usize>,
+
+    /// Lines of output kept in memory; overrides `global_log_buffer_size`
+    #[serde(default)]
+    pub log_buffer_size: Option<usize>,
+
+    /// Show repeated identical lines once with a count (default true)
+    #[serde(default = "default_true")]
+    pub deduplicate_logs: bool,
+
+    #[serde(default)]
+    pub ulimits: Option<BTreeMap<String, u64>>,
+
+    #[serde(default)]
+    pub alerts: Option<ResourceAlerts>,
+
+    #[serde(default)]
+    pub pid_file: Option<PathBuf>,
+
+    /// Check that must pass before dependents are started
+    #[serde(default)]
+    pub ready_when: Option<HealthCheck>,
+
+    /// How long to wait for `ready_when` (default 30000)
+    #[serde(default)]
+    pub ready_timeout_ms: Option<u64>,
+
+    /// Milliseconds between SIGTERM and SIGKILL on stop (default 5000)
+    #[serde(default)]
+    pub stop_timeout_ms: Option<u64>,
+
+    #[serde(default)]
+    pub log_sink: Option<LogSinkConfig>,
+
     #[serde(default)]
     pub restart: RestartPolicy,
 
     #[serde(default)]
     pub restart_delay_ms: Option<u64>,
+
+    /// Template this service was merged from (resolved at load time)
+    #[serde(default)]
+    pub extends: Option<String>,
+
+    /// Env var values to expand into `<id>-0`, `<id>-1`, ... (resolved at
+    /// load time, so this is always empty on loaded configs)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub matrix: BTreeMap<String, Vec<serde_yaml::Value>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SshConfig {
+    /// Host name, address or `~/.ssh/config` alias
+    pub host: String,
+
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// Private key passed to `ssh -i`
+    #[serde(default)]
+    pub identity_file: Option<PathBuf>,
 }
 
 fn default_kind() -> String {
@@ -96,22 +344,209 @@ fn default_true() -> bool {
     true
 }
 
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%H:%M:%S";
+
+/// Timezone used to display log timestamps. Written as `local`, `utc`,
+/// an offset like `+05:30`, or `fixed: <seconds>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "TimestampTzRepr", into = "TimestampTzRepr")]
+pub enum TimestampTz {
+    #[default]
+    Local,
+    Utc,
+    /// Fixed offset from UTC in seconds
+    Fixed(i32),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum TimestampTzRepr {
+    Name(String),
+    Fixed { fixed: i32 },
+}
+
+impl TryFrom<TimestampTzRepr> for TimestampTz {
+    type Error = String;
+
+    fn try_from(repr: TimestampTzRepr) -> Result<Self, Self::Error> {
+        let name = match repr {
+            TimestampTzRepr::Fixed { fixed } => return Ok(TimestampTz::Fixed(fixed)),
+            TimestampTzRepr::Name(name) => name,
+        };
+        match name.to_lowercase().as_str() {
+            "local" => Ok(TimestampTz::Local),
+            "utc" | "z" => Ok(TimestampTz::Utc),
+            offset => parse_utc_offset(offset)
+                .map(TimestampTz::Fixed)
+                .ok_or_else(|| format!("invalid timestamp_tz '{}'", name)),
+        }
+    }
+}
+
+impl From<TimestampTz> for TimestampTzRepr {
+    fn from(tz: TimestampTz) -> Self {
+        match tz {
+            TimestampTz::Local => TimestampTzRepr::Name("local".into()),
+            TimestampTz::Utc => TimestampTzRepr::Name("utc".into()),
+            TimestampTz::Fixed(fixed) => TimestampTzRepr::Fixed { fixed },
+        }
+    }
+}
+
+/// Parses `+HH:MM`, `-HH:MM` or `+HH` into seconds east of UTC.
+fn parse_utc_offset(s: &str) -> Option<i32> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// A partial service definition that services pull in with `extends`.
+/// Accepts any `ServiceConfig` field; the service's own fields win.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct UnitTemplate(pub serde_yaml::Mapping);
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct OrkesyConfig {
     #[serde(default)]
     pub name: Option<String>,
 
+    /// Files merged into this one by [`OrkesyConfig::load`], relative to its
+    /// directory; `*` and `?` match within a file or directory name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<PathBuf>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, UnitTemplate>,
+
+    #[serde(default)]
+    pub timestamp_tz: TimestampTz,
+
+    /// strftime-style format for log timestamps (default `%H:%M:%S`)
+    #[serde(default)]
+    pub timestamp_format: Option<String>,
+
+    /// `.env` file loaded for every service, before its own `env_file`
+    #[serde(default)]
+    pub global_env_file: Option<PathBuf>,
+
+    /// `log_buffer_size` for services that don't set their own
+    #[serde(default)]
+    pub global_log_buffer_size: Option<usize>,
+
     pub services: BTreeMap<String, ServiceConfig>,
+
+    /// Named sets of services, usable wherever a service id or `all` is,
+    /// e.g. `start backend` in the command palette
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+
+    /// Per-service overrides by profile name, merged into `services` when
+    /// the profile is selected (see [`OrkesyConfig::load_with_profile`])
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, BTreeMap<String, serde_yaml::Value>>,
+
+    /// Per-command settings, keyed by detected command id (e.g. `cargo:test`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, CommandConfig>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CommandConfig {
+    /// Services that must be running before `orkesy run` executes the command
+    #[serde(default)]
+    pub depends_on_ready: Vec<String>,
+
+    /// Run without the inherited environment (see [`CommandSpec::clean_env`])
+    #[serde(default)]
+    pub clean_env: Option<bool>,
+
+    /// Service whose resolved env the command runs with
+    #[serde(default)]
+    pub unit: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum ConfigError {
     Io(std::io::Error),
     Yaml(serde_yaml::Error),
-    InvalidDependency { service: String, dependency: String },
-    MissingCommand { service: String },
-    CyclicDependency { cycle: Vec<String> },
-    NotFound { searched: Vec<PathBuf> },
+    Toml(toml::de::Error),
+    InvalidDependency {
+        service: String,
+        dependency: String,
+    },
+    MissingCommand {
+        service: String,
+    },
+    CyclicDependency {
+        cycle: Vec<String>,
+    },
+    NotFound {
+        searched: Vec<PathBuf>,
+    },
+    UnknownTemplate {
+        service: String,
+        template: String,
+    },
+    InvalidMatrix {
+        service: String,
+        reason: String,
+    },
+    InvalidCommandDependency {
+        command: String,
+        dependency: String,
+    },
+    UnknownService {
+        service: String,
+    },
+    InvalidEnvFrom {
+        service: String,
+        source: String,
+    },
+    CyclicEnvFrom {
+        cycle: Vec<String>,
+    },
+    InvalidInclude {
+        path: PathBuf,
+        reason: String,
+    },
+    CyclicInclude {
+        cycle: Vec<PathBuf>,
+    },
+    DuplicateEntry {
+        section: String,
+        key: String,
+        path: PathBuf,
+    },
+    UnknownProfile {
+        profile: String,
+        available: Vec<String>,
+    },
+    InvalidProfile {
+        profile: String,
+        reason: String,
+    },
+    InvalidGroup {
+        group: String,
+        reason: String,
+    },
+    InvalidProcfile {
+        line: usize,
+        reason: String,
+    },
+    InvalidCompose {
+        service: String,
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for ConfigError {
@@ -119,6 +554,7 @@ impl std::fmt::Display for ConfigError {
         match self {
             Self::Io(e) => write!(f, "IO error: {}", e),
             Self::Yaml(e) => write!(f, "YAML parse error: {}", e),
+            Self::Toml(e) => write!(f, "{}", e.to_string().trim_end()),
             Self::InvalidDependency {
                 service,
                 dependency,
@@ -138,6 +574,83 @@ impl std::fmt::Display for ConfigError {
             Self::NotFound { searched } => {
                 write!(f, "no config file found, searched: {:?}", searched)
             }
+            Self::UnknownTemplate { service, template } => {
+                write!(
+                    f,
+                    "service '{}' extends unknown template '{}'",
+                    service, template
+                )
+            }
+            Self::InvalidMatrix { service, reason } => {
+                write!(f, "service '{}' has an invalid matrix: {}", service, reason)
+            }
+            Self::InvalidCommandDependency {
+                command,
+                dependency,
+            } => {
+                write!(
+                    f,
+                    "command '{}' depends on unknown service '{}'",
+                    command, dependency
+                )
+            }
+            Self::InvalidEnvFrom { service, source } => {
+                write!(
+                    f,
+                    "service '{}' takes env_from unknown service '{}'",
+                    service, source
+                )
+            }
+            Self::CyclicEnvFrom { cycle } => {
+                write!(f, "cyclic env_from detected: {}", cycle.join(" -> "))
+            }
+            Self::UnknownService { service } => {
+                write!(f, "no service '{}' is defined in the config", service)
+            }
+            Self::InvalidInclude { path, reason } => {
+                write!(f, "cannot include '{}': {}", path.display(), reason)
+            }
+            Self::CyclicInclude { cycle } => {
+                let files: Vec<String> = cycle.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "cyclic include detected: {}", files.join(" -> "))
+            }
+            Self::DuplicateEntry { section, key, path } => {
+                let kind = section.strip_suffix('s').unwrap_or(section);
+                write!(
+                    f,
+                    "{} '{}' in '{}' is already defined",
+                    kind,
+                    key,
+                    path.display()
+                )
+            }
+            Self::InvalidGroup { group, reason } => {
+                write!(f, "group '{}' is invalid: {}", group, reason)
+            }
+            Self::UnknownProfile { profile, available } if available.is_empty() => {
+                write!(
+                    f,
+                    "unknown profile '{}': the config defines no profiles",
+                    profile
+                )
+            }
+            Self::UnknownProfile { profile, available } => {
+                write!(
+                    f,
+                    "unknown profile '{}', available: {}",
+                    profile,
+                    available.join(", ")
+                )
+            }
+            Self::InvalidProfile { profile, reason } => {
+                write!(f, "profile '{}' is invalid: {}", profile, reason)
+            }
+            Self::InvalidProcfile { line, reason } => {
+                write!(f, "Procfile line {} is invalid: {}", line, reason)
+            }
+            Self::InvalidCompose { service, reason } => {
+                write!(f, "compose service '{}' is invalid: {}", service, reason)
+            }
         }
     }
 }
@@ -156,38 +669,257 @@ impl From<serde_yaml::Error> for ConfigError {
     }
 }
 
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Toml(e)
+    }
+}
+
 impl OrkesyConfig {
+    /// Reads `path` (TOML for `.toml` files, YAML otherwise) and merges its
+    /// `include` files, recursively
     pub fn load(path: &Path) -> Result<Self, ConfigError> {
-        let content = std::fs::read_to_string(path)?;
-        let config: OrkesyConfig = serde_yaml::from_str(&content)?;
-        config.validate()?;
-        Ok(config)
+        Self::load_with_profile(path, None)
+    }
+
+    /// Like [`OrkesyConfig::load`], then merges in the overrides of
+    /// `profile`, if any
+    pub fn load_with_profile(path: &Path, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let raw = load_with_includes(path, &mut Vec::new())?;
+        Self::from_raw(raw, profile)
+    }
+
+    /// Like [`OrkesyConfig::load`], but reads `path` as TOML whatever its
+    /// extension
+    pub fn load_toml(path: &Path) -> Result<Self, ConfigError> {
+        let raw = toml_value(&std::fs::read_to_string(path)?)?;
+        let raw = merge_includes(path, raw, &mut Vec::new())?;
+        Self::from_raw(raw, None)
     }
 
     pub fn parse(content: &str) -> Result<Self, ConfigError> {
-        let config: OrkesyConfig = serde_yaml::from_str(content)?;
+        Self::from_raw(serde_yaml::from_str(content)?, None)
+    }
+
+    /// Parses `content` with the overrides of `profile` merged in
+    pub fn parse_with_profile(content: &str, profile: &str) -> Result<Self, ConfigError> {
+        Self::from_raw(serde_yaml::from_str(content)?, Some(profile))
+    }
+
+    pub fn parse_toml(content: &str) -> Result<Self, ConfigError> {
+        Self::from_raw(toml_value(content)?, None)
+    }
+
+    /// Reads a Procfile as a config with a service per process type
+    pub fn from_procfile(path: &Path) -> Result<Self, ConfigError> {
+        Self::parse_procfile(&std::fs::read_to_string(path)?)
+    }
+
+    /// Each `name: command` line becomes a service run through a shell, as
+    /// Procfile runners do. Services don't autostart: a Procfile lists what
+    /// can run, not what should.
+    pub fn parse_procfile(content: &str) -> Result<Self, ConfigError> {
+        use serde_yaml::{Mapping, Value};
+
+        let mut services = Mapping::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| ConfigError::InvalidProcfile {
+                line: idx + 1,
+                reason: reason.to_string(),
+            };
+            let (name, command) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("expected `name: command`"))?;
+            let (name, command) = (name.trim(), command.trim());
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(invalid(&format!("'{}' is not a valid process name", name)));
+            }
+            if command.is_empty() {
+                return Err(invalid(&format!("process '{}' has no command", name)));
+            }
+
+            let mut service = Mapping::new();
+            service.insert("command".into(), Value::Sequence(vec![command.into()]));
+            service.insert("shell".into(), true.into());
+            service.insert("autostart".into(), false.into());
+            if services
+                .insert(name.into(), Value::Mapping(service))
+                .is_some()
+            {
+                return Err(invalid(&format!("process '{}' is already defined", name)));
+            }
+        }
+
+        let mut raw = Mapping::new();
+        raw.insert("services".into(), Value::Mapping(services));
+        Self::from_raw(Value::Mapping(raw), None)
+    }
+
+    /// Reads a docker-compose file as a config with a `docker_image` service
+    /// per compose service
+    pub fn from_docker_compose(path: &Path) -> Result<Self, ConfigError> {
+        Self::parse_docker_compose(&std::fs::read_to_string(path)?)
+    }
+
+    /// Maps `image`, `command`, `environment`, `ports` and `depends_on` onto
+    /// the matching service fields; `port` is the first published port.
+    /// Services without an `image` are built by compose and are skipped,
+    /// along with any `depends_on` on them.
+    pub fn parse_docker_compose(content: &str) -> Result<Self, ConfigError> {
+        use serde_yaml::{Mapping, Value};
+
+        let compose: Value = serde_yaml::from_str(content)?;
+        let empty = Mapping::new();
+        let compose_services = compose
+            .get("services")
+            .and_then(Value::as_mapping)
+            .unwrap_or(&empty);
+        let imported: Vec<&str> = compose_services
+            .iter()
+            .filter(|(_, svc)| svc.get("image").is_some())
+            .filter_map(|(id, _)| id.as_str())
+            .collect();
+
+        let mut services = Mapping::new();
+        for (id, svc) in compose_services {
+            let Some(id) = id.as_str().filter(|id| imported.contains(id)) else {
+                continue;
+            };
+            let invalid = |reason: String| ConfigError::InvalidCompose {
+                service: id.to_string(),
+                reason,
+            };
+            let mut service = Mapping::new();
+
+            let image = compose_scalar(&svc["image"])
+                .ok_or_else(|| invalid("`image` is not a string".into()))?;
+            service.insert("docker_image".into(), image.into());
+
+            let command: Vec<Value> = match &svc["command"] {
+                Value::Null => vec![],
+                // Split like compose does, honouring quotes; no shell runs it
+                Value::String(command) => shell_words::split(command)
+                    .map_err(|e| invalid(format!("`command` is not valid: {}", e)))?
+                    .into_iter()
+                    .map(Value::from)
+                    .collect(),
+                Value::Sequence(args) => args
+                    .iter()
+                    .map(|arg| compose_scalar(arg).map(Value::from))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| invalid("`command` has a non-string argument".into()))?,
+                _ => return Err(invalid("`command` is not a string or list".into())),
+            };
+            if !command.is_empty() {
+                service.insert("command".into(), Value::Sequence(command));
+            }
+
+            // `KEY: value` or `KEY=value` entries; a bare `KEY` passes the
+            // host's value through, which orkesy units inherit anyway
+            let env: Mapping = match &svc["environment"] {
+                Value::Mapping(vars) => vars
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), compose_scalar(value)?.into())))
+                    .collect(),
+                Value::Sequence(vars) => vars
+                    .iter()
+                    .filter_map(|var| var.as_str()?.split_once('='))
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+                _ => Mapping::new(),
+            };
+            if !env.is_empty() {
+                service.insert("env".into(), Value::Mapping(env));
+            }
+
+            let ports = match &svc["ports"] {
+                Value::Sequence(ports) => ports
+                    .iter()
+                    .map(|port| {
+                        compose_port(port)
+                            .ok_or_else(|| invalid(format!("unsupported port {:?}", port)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => vec![],
+            };
+            if let Some(host) = ports
+                .first()
+                .and_then(|port| port.split(['/', ':']).next())
+                .and_then(|host| host.parse::<u16>().ok())
+            {
+                service.insert("port".into(), host.into());
+            }
+            if !ports.is_empty() {
+                let ports = ports.into_iter().map(Value::from).collect();
+                service.insert("docker_ports".into(), Value::Sequence(ports));
+            }
+
+            // A list of ids, or a map of id to start condition
+            let depends_on: Vec<Value> = match &svc["depends_on"] {
+                Value::Sequence(deps) => deps.iter().filter_map(Value::as_str).collect(),
+                Value::Mapping(deps) => deps.keys().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            }
+            .into_iter()
+            .filter(|dep| imported.contains(dep))
+            .map(Value::from)
+            .collect();
+            if !depends_on.is_empty() {
+                service.insert("depends_on".into(), Value::Sequence(depends_on));
+            }
+
+            services.insert(id.into(), Value::Mapping(service));
+        }
+
+        let mut raw = Mapping::new();
+        if let Some(name) = compose.get("name").and_then(Value::as_str) {
+            raw.insert("name".into(), name.into());
+        }
+        raw.insert("services".into(), Value::Mapping(services));
+        Self::from_raw(Value::Mapping(raw), None)
+    }
+
+    fn from_raw(mut raw: serde_yaml::Value, profile: Option<&str>) -> Result<Self, ConfigError> {
+        if let Some(profile) = profile {
+            apply_profile(&mut raw, profile)?;
+        }
+        let config: OrkesyConfig = serde_yaml::from_value(resolve_templates(raw)?)?;
         config.validate()?;
         Ok(config)
     }
 
-    pub fn discover(start_dir: &Path) -> Result<(PathBuf, Self), ConfigError> {
-        let names = ["orkesy.yaml", "orkesy.yml", ".orkesy.yaml", ".orkesy.yml"];
+    /// Whether `path` is read as TOML by [`OrkesyConfig::load`]
+    pub fn is_toml(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "toml")
+    }
+
+    /// Finds the config file: `$ORKESY_CONFIG` if it exists, otherwise the
+    /// first of [`CONFIG_FILE_NAMES`] in `start_dir` or its ancestors
+    pub fn find(start_dir: &Path) -> Result<PathBuf, ConfigError> {
         let mut searched = Vec::new();
 
         if let Ok(env_path) = std::env::var("ORKESY_CONFIG") {
             let path = PathBuf::from(&env_path);
             if path.exists() {
-                return Ok((path.clone(), Self::load(&path)?));
+                return Ok(path);
             }
             searched.push(path);
         }
 
         let mut dir = Some(start_dir);
         while let Some(current) = dir {
-            for name in &names {
+            for name in &CONFIG_FILE_NAMES {
                 let path = current.join(name);
                 if path.exists() {
-                    return Ok((path.clone(), Self::load(&path)?));
+                    return Ok(path);
                 }
                 searched.push(path);
             }
@@ -197,6 +929,21 @@ impl OrkesyConfig {
         Err(ConfigError::NotFound { searched })
     }
 
+    pub fn discover(start_dir: &Path) -> Result<(PathBuf, Self), ConfigError> {
+        Self::discover_with_profile(start_dir, None)
+    }
+
+    /// Like [`OrkesyConfig::discover`], with the overrides of `profile`
+    /// merged in
+    pub fn discover_with_profile(
+        start_dir: &Path,
+        profile: Option<&str>,
+    ) -> Result<(PathBuf, Self), ConfigError> {
+        let path = Self::find(start_dir)?;
+        let config = Self::load_with_profile(&path, profile)?;
+        Ok((path, config))
+    }
+
     fn validate(&self) -> Result<(), ConfigError> {
         for (id, svc) in &self.services {
             for dep in &svc.depends_on {
@@ -208,73 +955,126 @@ impl OrkesyConfig {
                 }
             }
 
-            if svc.command.is_empty() {
+            if svc.command.is_empty() && svc.docker_image.is_none() {
                 return Err(ConfigError::MissingCommand {
                     service: id.clone(),
                 });
             }
+
+            if let Some(from) = svc
+                .env_from
+                .iter()
+                .find(|from| !self.services.contains_key(from.unit()))
+            {
+                return Err(ConfigError::InvalidEnvFrom {
+                    service: id.clone(),
+                    source: from.unit().to_string(),
+                });
+            }
         }
 
-        self.check_cycles()?;
-        Ok(())
-    }
+        for (group, members) in &self.groups {
+            let invalid = |reason: String| ConfigError::InvalidGroup {
+                group: group.clone(),
+                reason,
+            };
+            if group == "all" || self.services.contains_key(group) {
+                return Err(invalid(
+                    "the name is already used by a service or `all`".into(),
+                ));
+            }
+            if let Some(member) = members.iter().find(|m| !self.services.contains_key(*m)) {
+                return Err(invalid(format!("unknown service '{}'", member)));
+            }
+        }
 
-    fn check_cycles(&self) -> Result<(), ConfigError> {
-        #[derive(Clone, Copy, PartialEq)]
-        enum State {
-            Unvisited,
-            Visiting,
-            Visited,
+        for (id, cmd) in &self.commands {
+            if let Some(dep) = cmd
+                .depends_on_ready
+                .iter()
+                .chain(&cmd.unit)
+                .find(|dep| !self.services.contains_key(*dep))
+            {
+                return Err(ConfigError::InvalidCommandDependency {
+                    command: id.clone(),
+                    dependency: dep.clone(),
+                });
+            }
         }
 
-        let mut states: BTreeMap<&str, State> = self
-            .services
-            .keys()
-            .map(|k| (k.as_str(), State::Unvisited))
-            .collect();
+        self.check_cycles()?;
+        self.check_env_from_cycles()?;
+        Ok(())
+    }
 
-        fn dfs<'a>(
+    fn check_env_from_cycles(&self) -> Result<(), ConfigError> {
+        fn visit<'a>(
             node: &'a str,
             config: &'a OrkesyConfig,
-            states: &mut BTreeMap<&'a str, State>,
+            done: &mut BTreeSet<&'a str>,
             path: &mut Vec<&'a str>,
         ) -> Result<(), Vec<String>> {
-            states.insert(node, State::Visiting);
+            if let Some(start) = path.iter().position(|n| *n == node) {
+                let mut cycle: Vec<String> = path[start..].iter().map(|s| s.to_string()).collect();
+                cycle.push(node.to_string());
+                return Err(cycle);
+            }
+            if done.contains(node) {
+                return Ok(());
+            }
             path.push(node);
-
-            if let Some(svc) = config.services.get(node) {
-                for dep in &svc.depends_on {
-                    match states.get(dep.as_str()) {
-                        Some(State::Visiting) => {
-                            let cycle_start = path.iter().position(|&n| n == dep.as_str()).unwrap();
-                            let mut cycle: Vec<String> =
-                                path[cycle_start..].iter().map(|s| s.to_string()).collect();
-                            cycle.push(dep.clone());
-                            return Err(cycle);
-                        }
-                        Some(State::Unvisited) | None => {
-                            dfs(dep, config, states, path)?;
-                        }
-                        Some(State::Visited) => {}
-                    }
-                }
+            for from in config
+                .services
+                .get(node)
+                .into_iter()
+                .flat_map(|s| &s.env_from)
+            {
+                visit(from.unit(), config, done, path)?;
             }
-
             path.pop();
-            states.insert(node, State::Visited);
+            done.insert(node);
             Ok(())
         }
 
+        let mut done = BTreeSet::new();
         for id in self.services.keys() {
-            if states.get(id.as_str()) == Some(&State::Unvisited) {
-                let mut path = Vec::new();
-                if let Err(cycle) = dfs(id, self, &mut states, &mut path) {
-                    return Err(ConfigError::CyclicDependency { cycle });
-                }
+            visit(id, self, &mut done, &mut Vec::new())
+                .map_err(|cycle| ConfigError::CyclicEnvFrom { cycle })?;
+        }
+        Ok(())
+    }
+
+    /// `id`'s env with its `env_from` services' (resolved) env merged in
+    /// first, plus the service each inherited var was defined by
+    fn resolve_env(&self, id: &str) -> (BTreeMap<String, String>, BTreeMap<String, String>) {
+        let Some(svc) = self.services.get(id) else {
+            return Default::default();
+        };
+        let mut env = BTreeMap::new();
+        let mut inherited = BTreeMap::new();
+        for from in &svc.env_from {
+            let (source_env, source_inherited) = self.resolve_env(from.unit());
+            for (key, value) in source_env {
+                let origin = source_inherited
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or_else(|| from.unit().to_string());
+                let key = from.rename(&key).to_string();
+                inherited.insert(key.clone(), origin);
+                env.insert(key, value);
             }
         }
+        for (key, value) in &svc.env {
+            inherited.remove(key);
+            env.insert(key.clone(), value.clone());
+        }
+        (env, inherited)
+    }
 
-        Ok(())
+    fn check_cycles(&self) -> Result<(), ConfigError> {
+        crate::unit::check_acyclic(self.to_edges())
+            .map(|_| ())
+            .map_err(|e| ConfigError::CyclicDependency { cycle: e.cycle })
     }
 
     pub fn to_graph(&self) -> RuntimeGraph {
@@ -355,47 +1155,95 @@ impl OrkesyConfig {
         result
     }
 
+    /// Groups `ids` into start stages: each stage only depends (directly or
+    /// through other services) on earlier ones, so a stage can start in parallel.
+    pub fn start_levels(&self, ids: &[String]) -> Vec<Vec<String>> {
+        fn depth(
+            id: &str,
+            config: &OrkesyConfig,
+            depths: &mut BTreeMap<String, usize>,
+            visiting: &mut BTreeSet<String>,
+        ) -> usize {
+            if let Some(d) = depths.get(id) {
+                return *d;
+            }
+            // Cycles are rejected at load time; this only guards against recursion
+            if !visiting.insert(id.to_string()) {
+                return 0;
+            }
+            let d = config.services.get(id).map_or(0, |svc| {
+                svc.depends_on
+                    .iter()
+                    .map(|dep| depth(dep, config, depths, visiting) + 1)
+                    .max()
+                    .unwrap_or(0)
+            });
+            depths.insert(id.to_string(), d);
+            d
+        }
+
+        let mut depths = BTreeMap::new();
+        let mut levels: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for id in ids {
+            let d = depth(id, self, &mut depths, &mut BTreeSet::new());
+            levels.entry(d).or_default().push(id.clone());
+        }
+        levels.into_values().collect()
+    }
+
     pub fn to_units(&self) -> Vec<Unit> {
         self.services
             .iter()
             .map(|(id, svc)| {
-                let kind = match svc.kind.to_lowercase().as_str() {
-                    "docker" => UnitKind::Docker,
+                let kind = match (&svc.ssh, svc.kind.to_lowercase().as_str()) {
+                    (Some(ssh), _) => UnitKind::Ssh {
+                        host: ssh.host.clone(),
+                        user: ssh.user.clone(),
+                        identity_file: ssh.identity_file.clone(),
+                    },
+                    (None, "docker") => UnitKind::Docker,
+                    _ if svc.docker_image.is_some() => UnitKind::Docker,
                     _ => UnitKind::Process,
                 };
 
+                let (env, inherited_env) = self.resolve_env(id);
+                // Where gRPC checks connect, as `Unit::host`
+                let host = svc
+                    .ssh
+                    .as_ref()
+                    .map_or("127.0.0.1", |ssh| ssh.host.as_str());
+
                 Unit {
                     id: id.clone(),
                     name: svc.name.clone(),
                     kind,
                     cwd: svc.cwd.clone(),
-                    env: svc.env.clone(),
+                    env,
+                    env_from: (!svc.env_from.is_empty())
+                        .then(|| svc.env_from.iter().map(|f| f.unit().to_string()).collect()),
+                    inherited_env,
+                    env_file: svc.env_file.clone(),
                     install: vec![],
                     start: svc.command.join(" "),
+                    shell: svc.shell,
+                    pty: svc.pty,
+                    docker_image: svc.docker_image.clone(),
+                    docker_ports: svc.docker_ports.clone(),
                     stop: StopBehavior::Signal(StopSignal::SigTerm),
+                    stop_timeout_ms: svc.stop_timeout_ms.unwrap_or(DEFAULT_STOP_TIMEOUT_MS),
                     logs: None,
-                    health: svc.health_check.as_ref().map(|h| match h {
-                        HealthCheck::Tcp { interval_ms, .. } => UnitHealthCheck::Tcp {
-                            port: svc.port.unwrap_or(8000),
-                            interval_ms: *interval_ms,
-                        },
-                        HealthCheck::Http {
-                            path,
-                            interval_ms,
-                            timeout_ms,
-                        } => UnitHealthCheck::Http {
-                            url: format!("http://localhost:{}{}", svc.port.unwrap_or(8000), path),
-                            interval_ms: *interval_ms,
-                            timeout_ms: *timeout_ms,
-                        },
-                        HealthCheck::Exec {
-                            command,
-                            interval_ms,
-                        } => UnitHealthCheck::Exec {
-                            command: command.join(" "),
-                            interval_ms: *interval_ms,
-                        },
-                    }),
+                    render_ansi: svc.render_ansi,
+                    max_log_line_bytes: svc.max_log_line_bytes,
+                    log_buffer_size: svc.log_buffer_size.or(self.global_log_buffer_size),
+                    deduplicate_logs: svc.deduplicate_logs,
+                    ulimits: svc.ulimits.clone(),
+                    alerts: svc.alerts.clone(),
+                    pid_file: svc.pid_file.clone(),
+                    log_sink: svc.log_sink.clone(),
+                    health: svc.health_check.as_ref().map(|h| h.to_unit(host, svc.port)),
+                    health_history_size: svc.health_history_size,
+                    ready_when: svc.ready_when.as_ref().map(|h| h.to_unit(host, svc.port)),
+                    ready_timeout_ms: svc.ready_timeout_ms.unwrap_or(DEFAULT_READY_TIMEOUT_MS),
                     description: svc.description.clone(),
                     port: svc.port,
                     autostart: svc.autostart,
@@ -417,9 +1265,681 @@ impl OrkesyConfig {
             .collect()
     }
 
+    /// Applies `commands.<id>` settings to a detected command
+    pub fn configure_command(&self, spec: &mut CommandSpec) {
+        let Some(cmd) = self.commands.get(&spec.id) else {
+            return;
+        };
+        if cmd.clean_env.is_some() {
+            spec.clean_env = cmd.clean_env;
+        }
+        if let Some(unit) = &cmd.unit {
+            let (env, _) = self.resolve_env(unit);
+            spec.env.extend(env);
+        }
+    }
+
     pub fn project_name(&self) -> Option<&str> {
         self.name.as_deref()
     }
+
+    pub fn timestamp_format(&self) -> &str {
+        self.timestamp_format
+            .as_deref()
+            .unwrap_or(DEFAULT_TIMESTAMP_FORMAT)
+    }
+}
+
+/// Result of [`remove_service`]
+#[derive(Clone, Debug)]
+pub struct ServiceRemoval {
+    /// Updated config source
+    pub content: String,
+    /// Removed dependency edges as `(from, to)`, including
+    /// `commands.<id>.depends_on_ready` entries
+    pub edges: Vec<(String, String)>,
+    /// False when the source had to be re-serialized, dropping comments
+    pub preserved_formatting: bool,
+}
+
+/// Removes service `id` from config source along with every `depends_on` /
+/// `depends_on_ready` entry naming it. The text is edited line by line so
+/// comments and formatting elsewhere survive; layouts that can't be edited
+/// in place fall back to a plain `serde_yaml` round trip.
+pub fn remove_service(content: &str, id: &str) -> Result<ServiceRemoval, ConfigError> {
+    let config = OrkesyConfig::parse(content)?;
+    let raw: serde_yaml::Value = serde_yaml::from_str(content)?;
+    let defined = raw
+        .get("services")
+        .and_then(|s| s.as_mapping())
+        .is_some_and(|services| services.contains_key(id));
+    if !defined {
+        return Err(ConfigError::UnknownService {
+            service: id.to_string(),
+        });
+    }
+
+    let mut edges: Vec<(String, String)> = config
+        .services
+        .get(id)
+        .map(|svc| {
+            svc.depends_on
+                .iter()
+                .map(|dep| (id.to_string(), dep.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    for (other, svc) in &config.services {
+        if svc.depends_on.iter().any(|dep| dep == id) {
+            edges.push((other.clone(), id.to_string()));
+        }
+    }
+    for (command, cmd) in &config.commands {
+        if cmd.depends_on_ready.iter().any(|dep| dep == id) {
+            edges.push((command.clone(), id.to_string()));
+        }
+    }
+
+    let is_removed =
+        |updated: &str| OrkesyConfig::parse(updated).is_ok_and(|c| !c.services.contains_key(id));
+    if let Some(updated) = remove_service_text(content, id).filter(|u| is_removed(u)) {
+        return Ok(ServiceRemoval {
+            content: updated,
+            edges,
+            preserved_formatting: true,
+        });
+    }
+
+    let updated = serde_yaml::to_string(&remove_service_value(raw, id))?;
+    OrkesyConfig::parse(&updated)?;
+    Ok(ServiceRemoval {
+        content: updated,
+        edges,
+        preserved_formatting: false,
+    })
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Line-based removal for [`remove_service`]; `None` if the service block
+/// can't be located
+fn remove_service_text(content: &str, id: &str) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    let services = lines
+        .iter()
+        .position(|l| l.starts_with("services:") && unquote(&l["services:".len()..]).is_empty())?;
+    let child_indent = lines[services + 1..]
+        .iter()
+        .find(|l| !is_blank_or_comment(l))
+        .map(|l| indent_of(l))
+        .filter(|indent| *indent > 0)?;
+
+    let is_key = |line: &str| {
+        indent_of(line) == child_indent
+            && line
+                .trim_start()
+                .split_once(':')
+                .is_some_and(|(key, _)| unquote(key) == id)
+    };
+    let mut start = services + 1;
+    loop {
+        let line = lines.get(start)?;
+        if !is_blank_or_comment(line) && indent_of(line) < child_indent {
+            return None;
+        }
+        if is_key(line) {
+            break;
+        }
+        start += 1;
+    }
+
+    let mut end = start + 1;
+    while let Some(line) = lines.get(end) {
+        let blank = line.trim().is_empty();
+        if !blank && indent_of(line) <= child_indent {
+            break;
+        }
+        end += 1;
+    }
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    // Comments directly above the key describe the service
+    while start > services + 1 {
+        let above = &lines[start - 1];
+        if above.trim_start().starts_with('#') && indent_of(above) == child_indent {
+            start -= 1;
+        } else {
+            break;
+        }
+    }
+    // Don't leave a double blank line (or a leading one) where the block was
+    if (start == services + 1 || lines[start - 1].trim().is_empty())
+        && lines.get(end).is_some_and(|l| l.trim().is_empty())
+    {
+        end += 1;
+    }
+    lines.drain(start..end);
+
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        let key = ["depends_on:", "depends_on_ready:"]
+            .into_iter()
+            .find(|k| trimmed.starts_with(k));
+        let Some(key) = key else {
+            i += 1;
+            continue;
+        };
+        let indent = indent_of(&lines[i]);
+        let rest = trimmed[key.len()..].trim();
+
+        if let Some(flow) = rest.strip_prefix('[') {
+            let (items, tail) = flow.split_once(']')?;
+            let kept: Vec<&str> = items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty() && unquote(item) != id)
+                .collect();
+            lines[i] = format!(
+                "{}{} [{}]{}",
+                &lines[i][..indent],
+                key,
+                kept.join(", "),
+                tail
+            );
+            i += 1;
+        } else if rest.is_empty() || rest.starts_with('#') {
+            // Block sequence; items may sit at the key's own indentation
+            let mut j = i + 1;
+            let mut kept = 0;
+            while let Some(line) = lines.get(j) {
+                if is_blank_or_comment(line) {
+                    j += 1;
+                    continue;
+                }
+                let Some(item) = line.trim_start().strip_prefix('-') else {
+                    break;
+                };
+                if indent_of(line) < indent {
+                    break;
+                }
+                let value = item.split(" #").next().unwrap_or(item);
+                if unquote(value) == id {
+                    lines.remove(j);
+                } else {
+                    kept += 1;
+                    j += 1;
+                }
+            }
+            if kept == 0 {
+                lines[i] = format!("{}{} []", &lines[i][..indent], key);
+            }
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// Structural fallback for [`remove_service`]
+fn remove_service_value(mut raw: serde_yaml::Value, id: &str) -> serde_yaml::Value {
+    use serde_yaml::Value;
+
+    fn strip_dependency(entries: Option<&mut Value>, key: &str, id: &str) {
+        let Some(Value::Mapping(entries)) = entries else {
+            return;
+        };
+        for (_, entry) in entries.iter_mut() {
+            if let Some(Value::Sequence(deps)) = entry.get_mut(key) {
+                deps.retain(|dep| dep.as_str() != Some(id));
+            }
+        }
+    }
+
+    if let Some(Value::Mapping(services)) = raw.get_mut("services") {
+        services.remove(id);
+    }
+    strip_dependency(raw.get_mut("services"), "depends_on", id);
+    strip_dependency(raw.get_mut("templates"), "depends_on", id);
+    strip_dependency(raw.get_mut("commands"), "depends_on_ready", id);
+    if let Some(Value::Mapping(groups)) = raw.get_mut("groups") {
+        for (_, members) in groups.iter_mut() {
+            if let Value::Sequence(members) = members {
+                members.retain(|m| m.as_str() != Some(id));
+            }
+        }
+    }
+    raw
+}
+
+/// Config file names, in lookup order
+pub const CONFIG_FILE_NAMES: [&str; 6] = [
+    "orkesy.yaml",
+    "orkesy.yml",
+    "orkesy.toml",
+    ".orkesy.yaml",
+    ".orkesy.yml",
+    ".orkesy.toml",
+];
+
+/// Parses a TOML document into a `serde_yaml::Value`, so TOML and YAML
+/// configs share one pipeline. Dates are kept as strings.
+pub fn toml_value(content: &str) -> Result<serde_yaml::Value, toml::de::Error> {
+    use serde_yaml::{Mapping, Value};
+
+    fn convert(value: toml::Value) -> Value {
+        match value {
+            toml::Value::String(s) => Value::String(s),
+            toml::Value::Integer(i) => Value::from(i),
+            toml::Value::Float(f) => Value::from(f),
+            toml::Value::Boolean(b) => Value::Bool(b),
+            toml::Value::Datetime(d) => Value::String(d.to_string()),
+            toml::Value::Array(items) => Value::Sequence(items.into_iter().map(convert).collect()),
+            toml::Value::Table(table) => Value::Mapping(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (Value::String(key), convert(value)))
+                    .collect::<Mapping>(),
+            ),
+        }
+    }
+
+    Ok(convert(toml::Value::Table(toml::from_str(content)?)))
+}
+
+/// Sections merged from included files; a key defined twice is an error
+const INCLUDE_SECTIONS: [&str; 4] = ["services", "templates", "commands", "groups"];
+
+/// Parses `path` (by extension) and merges its includes into it. `stack`
+/// holds the files currently being loaded, to detect cycles.
+fn load_with_includes(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<serde_yaml::Value, ConfigError> {
+    let content = std::fs::read_to_string(path)?;
+    let raw = if OrkesyConfig::is_toml(path) {
+        toml_value(&content)?
+    } else {
+        serde_yaml::from_str(&content)?
+    };
+    merge_includes(path, raw, stack)
+}
+
+/// Merges the files named by `raw`'s `include` key into it. Other top-level
+/// keys are only taken from an included file when `raw` leaves them out.
+fn merge_includes(
+    path: &Path,
+    mut raw: serde_yaml::Value,
+    stack: &mut Vec<PathBuf>,
+) -> Result<serde_yaml::Value, ConfigError> {
+    use serde_yaml::{Mapping, Value};
+
+    let canonical = path.canonicalize()?;
+    if let Some(pos) = stack.iter().position(|p| *p == canonical) {
+        let mut cycle = stack[pos..].to_vec();
+        cycle.push(canonical);
+        return Err(ConfigError::CyclicInclude { cycle });
+    }
+
+    let Some(root) = raw.as_mapping_mut() else {
+        return Err(ConfigError::InvalidInclude {
+            path: path.to_path_buf(),
+            reason: "not a YAML mapping".into(),
+        });
+    };
+    let patterns: Vec<PathBuf> = match root.get("include") {
+        Some(value) => serde_yaml::from_value(value.clone())?,
+        None => return Ok(raw),
+    };
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    stack.push(canonical);
+    for pattern in &patterns {
+        for file in expand_include(base, pattern)? {
+            let Value::Mapping(mut included) = load_with_includes(&file, stack)? else {
+                continue;
+            };
+            included.remove("include");
+            for (key, value) in included {
+                let section = key.as_str().filter(|k| INCLUDE_SECTIONS.contains(k));
+                match (section, value) {
+                    (Some(section), Value::Mapping(entries)) => {
+                        let target = root
+                            .entry(key.clone())
+                            .or_insert_with(|| Value::Mapping(Mapping::new()));
+                        let Value::Mapping(target) = target else {
+                            continue;
+                        };
+                        for (id, entry) in entries {
+                            if target.contains_key(&id) {
+                                return Err(ConfigError::DuplicateEntry {
+                                    section: section.to_string(),
+                                    key: id.as_str().unwrap_or_default().to_string(),
+                                    path: file,
+                                });
+                            }
+                            target.insert(id, entry);
+                        }
+                    }
+                    (_, value) => {
+                        root.entry(key).or_insert(value);
+                    }
+                }
+            }
+        }
+    }
+    stack.pop();
+    Ok(raw)
+}
+
+/// Resolves an include pattern against `base`. Glob patterns (`*`, `?`,
+/// `**`, `[...]`) expand to the matching files in sorted order (possibly
+/// none); a plain path must exist.
+fn expand_include(base: &Path, pattern: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let invalid = |reason: String| ConfigError::InvalidInclude {
+        path: base.join(pattern),
+        reason,
+    };
+
+    let is_glob = |s: &str| s.contains(['*', '?', '[']);
+    if !pattern.to_str().is_some_and(is_glob) {
+        let path = base.join(pattern);
+        if !path.is_file() {
+            return Err(invalid("file not found".into()));
+        }
+        return Ok(vec![path]);
+    }
+
+    // The base directory is literal even if its name contains `[` or `*`
+    let base = glob::Pattern::escape(&base.to_string_lossy());
+    let full = Path::new(&base).join(pattern);
+    let matches = glob::glob(&full.to_string_lossy()).map_err(|e| invalid(e.msg.into()))?;
+    // Entries that can't be read are skipped, like files that don't match
+    let mut paths: Vec<PathBuf> = matches.flatten().filter(|p| p.is_file()).collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Applies `extends` templates and expands `matrix` services on the raw YAML,
+/// before it is deserialized into `ServiceConfig`s.
+fn resolve_templates(mut raw: serde_yaml::Value) -> Result<serde_yaml::Value, ConfigError> {
+    use serde_yaml::{Mapping, Value};
+
+    let Some(root) = raw.as_mapping_mut() else {
+        return Ok(raw);
+    };
+
+    let templates: BTreeMap<String, UnitTemplate> = match root.get("templates") {
+        Some(t) => serde_yaml::from_value(t.clone())?,
+        None => BTreeMap::new(),
+    };
+    let Some(Value::Mapping(services)) = root.get("services") else {
+        return Ok(raw);
+    };
+
+    let mut resolved = Mapping::new();
+    let mut expanded: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    // Matrix service each generated id came from
+    let mut generated: BTreeMap<String, String> = BTreeMap::new();
+    let clash = |matrix: &str, instance: &str| ConfigError::InvalidMatrix {
+        service: matrix.to_string(),
+        reason: format!("instance id clashes with service '{}'", instance),
+    };
+
+    for (id, svc) in services {
+        let id_str = id.as_str().unwrap_or_default().to_string();
+        let mut svc = svc.clone();
+
+        let template_name = svc.get("extends").and_then(Value::as_str).map(String::from);
+        if let Some(name) = template_name {
+            let template = templates
+                .get(&name)
+                .ok_or_else(|| ConfigError::UnknownTemplate {
+                    service: id_str.clone(),
+                    template: name.clone(),
+                })?;
+            let mut merged = Value::Mapping(template.0.clone());
+            deep_merge(&mut merged, svc);
+            svc = merged;
+        }
+
+        let matrix = svc
+            .as_mapping_mut()
+            .and_then(|m| m.remove("matrix"))
+            .filter(|m| !m.is_null());
+        let Some(matrix) = matrix else {
+            if let Some(matrix_id) = generated.get(&id_str) {
+                return Err(clash(matrix_id, &id_str));
+            }
+            resolved.insert(id.clone(), svc);
+            continue;
+        };
+
+        let combos = matrix_combinations(&id_str, &matrix)?;
+        let mut ids = Vec::with_capacity(combos.len());
+        for (i, vars) in combos.into_iter().enumerate() {
+            let mut instance = svc.clone();
+            if let Some(map) = instance.as_mapping_mut() {
+                let env = map
+                    .entry(Value::from("env"))
+                    .or_insert_with(|| Value::Mapping(Mapping::new()));
+                if let Some(env) = env.as_mapping_mut() {
+                    for (key, value) in vars {
+                        env.insert(Value::from(key), Value::from(value));
+                    }
+                }
+            }
+            let instance_id = format!("{}-{}", id_str, i);
+            let key = Value::from(instance_id.clone());
+            if resolved.contains_key(&key) {
+                return Err(clash(&id_str, &instance_id));
+            }
+            generated.insert(instance_id.clone(), id_str.clone());
+            resolved.insert(key, instance);
+            ids.push(instance_id);
+        }
+        expanded.insert(id_str, ids);
+    }
+
+    // Dependencies on a matrix service mean "all of its instances"
+    if !expanded.is_empty() {
+        for svc in resolved.values_mut() {
+            let Some(Value::Sequence(deps)) = svc.get_mut("depends_on") else {
+                continue;
+            };
+            *deps = std::mem::take(deps)
+                .into_iter()
+                .flat_map(|dep| match dep.as_str().and_then(|d| expanded.get(d)) {
+                    Some(ids) => ids.iter().map(|i| Value::from(i.as_str())).collect(),
+                    None => vec![dep],
+                })
+                .collect();
+        }
+    }
+
+    root.insert(Value::from("services"), Value::Mapping(resolved));
+    Ok(raw)
+}
+
+/// A compose string, number or bool as a string
+fn compose_scalar(value: &serde_yaml::Value) -> Option<String> {
+    use serde_yaml::Value;
+
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A compose `ports` entry as a `docker_ports` one (`host:container[/proto]`).
+/// Host IPs are dropped; port ranges aren't supported.
+fn compose_port(port: &serde_yaml::Value) -> Option<String> {
+    let (ports, protocol) = match port {
+        serde_yaml::Value::Mapping(_) => {
+            let target = compose_scalar(&port["target"])?;
+            let published = compose_scalar(&port["published"]).unwrap_or(target.clone());
+            let protocol = compose_scalar(&port["protocol"]).unwrap_or("tcp".into());
+            (format!("{}:{}", published, target), protocol)
+        }
+        _ => {
+            let spec = compose_scalar(port)?;
+            let (ports, protocol) = spec.split_once('/').unwrap_or((&spec, "tcp"));
+            let parts: Vec<&str> = ports.rsplitn(3, ':').collect();
+            let ports = match parts[..] {
+                [container] => format!("{}:{}", container, container),
+                [container, host, ..] => format!("{}:{}", host, container),
+                [] => return None,
+            };
+            (ports, protocol.to_string())
+        }
+    };
+    let valid = ports.split(':').all(|port| port.parse::<u16>().is_ok());
+    match protocol.as_str() {
+        "tcp" if valid => Some(ports),
+        _ if valid => Some(format!("{}/{}", ports, protocol)),
+        _ => None,
+    }
+}
+
+/// Merges `profiles.<profile>` into the services it names, before templates
+/// are resolved. Mappings such as `env` merge key by key (profile keys win);
+/// scalars and lists like `command` or `depends_on` are replaced.
+fn apply_profile(raw: &mut serde_yaml::Value, profile: &str) -> Result<(), ConfigError> {
+    use serde_yaml::Value;
+
+    let invalid = |reason: String| ConfigError::InvalidProfile {
+        profile: profile.to_string(),
+        reason,
+    };
+
+    let profiles = raw.get("profiles").and_then(Value::as_mapping);
+    let Some(overrides) = profiles.and_then(|p| p.get(profile)).cloned() else {
+        let available = profiles
+            .map(|p| {
+                p.keys()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Err(ConfigError::UnknownProfile {
+            profile: profile.to_string(),
+            available,
+        });
+    };
+    let overrides = match overrides {
+        Value::Mapping(overrides) => overrides,
+        Value::Null => return Ok(()),
+        _ => return Err(invalid("expected a map of service overrides".into())),
+    };
+
+    let Some(Value::Mapping(services)) = raw.get_mut("services") else {
+        return Err(invalid("the config defines no services".into()));
+    };
+    for (id, overlay) in overrides {
+        let name = id.as_str().unwrap_or_default().to_string();
+        let Some(service) = services.get_mut(&id) else {
+            return Err(invalid(format!("overrides unknown service '{}'", name)));
+        };
+        if !overlay.is_mapping() {
+            return Err(invalid(format!("overrides for '{}' must be a map", name)));
+        }
+        deep_merge(service, overlay);
+    }
+    Ok(())
+}
+
+/// Merges `overlay` into `base`. Mappings merge key by key; anything else
+/// in `overlay` replaces the base value.
+fn deep_merge(base: &mut serde_yaml::Value, overlay: serde_yaml::Value) {
+    use serde_yaml::Value;
+
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Cartesian product of the matrix values, as env var assignments.
+fn matrix_combinations(
+    service: &str,
+    matrix: &serde_yaml::Value,
+) -> Result<Vec<Vec<(String, String)>>, ConfigError> {
+    let invalid = |reason: &str| ConfigError::InvalidMatrix {
+        service: service.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let map = matrix
+        .as_mapping()
+        .ok_or_else(|| invalid("expected a map of env var to values"))?;
+
+    let mut combos: Vec<Vec<(String, String)>> = vec![vec![]];
+    for (key, values) in map {
+        let key = key
+            .as_str()
+            .ok_or_else(|| invalid("env var names must be strings"))?;
+        let values = values
+            .as_sequence()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| invalid("each entry needs a non-empty list of values"))?;
+        let values = values
+            .iter()
+            .map(|v| match v {
+                serde_yaml::Value::String(s) => Ok(s.clone()),
+                serde_yaml::Value::Number(n) => Ok(n.to_string()),
+                serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+                _ => Err(invalid("values must be strings, numbers or booleans")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        combos = combos
+            .into_iter()
+            .flat_map(|combo| {
+                values.iter().map(move |value| {
+                    let mut next = combo.clone();
+                    next.push((key.to_string(), value.clone()));
+                    next
+                })
+            })
+            .collect();
+    }
+    Ok(combos)
 }
 
 #[cfg(test)]
@@ -472,6 +1992,38 @@ services:
         assert!(matches!(result, Err(ConfigError::InvalidDependency { .. })));
     }
 
+    #[test]
+    fn test_groups() {
+        let yaml = r#"
+services:
+  api:
+    command: ["node"]
+  worker:
+    command: ["python"]
+  web:
+    command: ["vite"]
+groups:
+  backend: [api, worker]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        assert_eq!(config.groups["backend"], vec!["api", "worker"]);
+
+        let unknown = format!("{}  broken: [api, nope]\n", yaml);
+        assert!(matches!(
+            OrkesyConfig::parse(&unknown),
+            Err(ConfigError::InvalidGroup { .. })
+        ));
+        let shadowed = format!("{}  web: [api]\n", yaml);
+        assert!(matches!(
+            OrkesyConfig::parse(&shadowed),
+            Err(ConfigError::InvalidGroup { .. })
+        ));
+
+        let removal = remove_service(yaml, "api").unwrap();
+        let config = OrkesyConfig::parse(&removal.content).unwrap();
+        assert_eq!(config.groups["backend"], vec!["worker"]);
+    }
+
     #[test]
     fn test_start_order() {
         let yaml = r#"
@@ -496,4 +2048,981 @@ services:
         assert!(db_pos < worker_pos);
         assert!(api_pos < worker_pos);
     }
+
+    #[test]
+    fn test_start_levels() {
+        let yaml = r#"
+services:
+  postgres:
+    command: ["postgres"]
+  redis:
+    command: ["redis-server"]
+    depends_on: [postgres]
+  api:
+    command: ["node"]
+    depends_on: [redis]
+  worker:
+    command: ["python"]
+    depends_on: [postgres, redis]
+  docs:
+    command: ["mkdocs"]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        let all: Vec<String> = config.services.keys().cloned().collect();
+        assert_eq!(
+            config.start_levels(&all),
+            vec![
+                vec!["docs".to_string(), "postgres".to_string()],
+                vec!["redis".to_string()],
+                vec!["api".to_string(), "worker".to_string()],
+            ]
+        );
+
+        // Stages follow dependencies through services that are not selected
+        let some = vec!["worker".to_string(), "postgres".to_string()];
+        assert_eq!(
+            config.start_levels(&some),
+            vec![vec!["postgres".to_string()], vec!["worker".to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_shell_option() {
+        let yaml = r#"
+services:
+  api:
+    command: ["npm", "run", "dev", "2>&1", "|", "tee", "/tmp/api.log"]
+    shell: true
+  worker:
+    command: ["node", "worker.js"]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        let units = config.to_units();
+        let api = units.iter().find(|u| u.id == "api").unwrap();
+        let worker = units.iter().find(|u| u.id == "worker").unwrap();
+
+        assert!(api.uses_shell());
+        assert!(api.has_shell_syntax());
+        assert!(!worker.uses_shell());
+        assert!(!worker.has_shell_syntax());
+    }
+
+    #[test]
+    fn test_ssh_service() {
+        let yaml = r#"
+services:
+  remote:
+    command: ["./serve"]
+    port: 8080
+    ssh:
+      host: dev.example.com
+      user: deploy
+      identity_file: ~/.ssh/dev
+  local:
+    command: ["./serve"]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        let units = config.to_units();
+        let remote = units.iter().find(|u| u.id == "remote").unwrap();
+        let local = units.iter().find(|u| u.id == "local").unwrap();
+
+        assert_eq!(
+            remote.kind,
+            UnitKind::Ssh {
+                host: "dev.example.com".into(),
+                user: Some("deploy".into()),
+                identity_file: Some(PathBuf::from("~/.ssh/dev")),
+            }
+        );
+        assert_eq!(remote.host(), "dev.example.com");
+        assert_eq!(local.kind, UnitKind::Process);
+        assert_eq!(local.host(), "127.0.0.1");
+    }
+
+    #[test]
+    fn test_http_health_check_options() {
+        let yaml = r#"
+services:
+  api:
+    command: ["./api"]
+    port: 8080
+    health_check:
+      type: http
+      path: /health
+      method: POST
+      headers: { Authorization: Bearer dev-token }
+      expected_status: 204
+      body_contains: '"status":"ok"'
+  web:
+    command: ["./web"]
+    health_check: { type: http, path: / }
+"#;
+        let units = OrkesyConfig::parse(yaml).unwrap().to_units();
+        let api = units.iter().find(|u| u.id == "api").unwrap();
+        let web = units.iter().find(|u| u.id == "web").unwrap();
+
+        assert_eq!(
+            api.health,
+            Some(UnitHealthCheck::Http {
+                url: "http://localhost:8080/health".into(),
+                method: Some("POST".into()),
+                headers: BTreeMap::from([("Authorization".into(), "Bearer dev-token".into())]),
+                expected_status: Some(204),
+                body_contains: Some(r#""status":"ok""#.into()),
+                interval_ms: 5000,
+                max_interval_ms: None,
+                timeout_ms: 2000,
+                failure_threshold: None,
+                success_threshold: None,
+            })
+        );
+        assert!(matches!(
+            &web.health,
+            Some(UnitHealthCheck::Http {
+                method: None,
+                headers,
+                expected_status: None,
+                body_contains: None,
+                ..
+            }) if headers.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_grpc_health_check() {
+        let yaml = r#"
+services:
+  orders:
+    command: ["./orders"]
+    port: 50051
+    health_check:
+      type: grpc
+      service: orders.v1.Orders
+      failure_threshold: 2
+  remote:
+    command: ["./serve"]
+    ssh: { host: dev.example.com }
+    health_check: { type: grpc }
+"#;
+        let units = OrkesyConfig::parse(yaml).unwrap().to_units();
+        let orders = units.iter().find(|u| u.id == "orders").unwrap();
+        let remote = units.iter().find(|u| u.id == "remote").unwrap();
+
+        assert_eq!(
+            orders.health,
+            Some(UnitHealthCheck::Grpc {
+                host: "127.0.0.1".into(),
+                port: 50051,
+                service: "orders.v1.Orders".into(),
+                interval_ms: 5000,
+                max_interval_ms: None,
+                timeout_ms: 2000,
+                failure_threshold: Some(2),
+                success_threshold: None,
+            })
+        );
+        assert!(matches!(
+            &remote.health,
+            Some(UnitHealthCheck::Grpc { host, port: 8000, service, .. })
+                if host == "dev.example.com" && service.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_procfile() {
+        let procfile = r#"
+# processes
+web: bundle exec rails server -p $PORT
+worker:   bundle exec sidekiq
+release-tasks: ./release.sh
+"#;
+
+        let config = OrkesyConfig::parse_procfile(procfile).unwrap();
+        let ids: Vec<&str> = config.services.keys().map(String::as_str).collect();
+        assert_eq!(ids, ["release-tasks", "web", "worker"]);
+
+        let web = &config.services["web"];
+        assert_eq!(web.command, ["bundle exec rails server -p $PORT"]);
+        assert_eq!(web.shell, Some(true));
+        assert!(!web.autostart);
+        assert_eq!(config.services["worker"].command, ["bundle exec sidekiq"]);
+    }
+
+    #[test]
+    fn test_procfile_invalid_lines() {
+        for (procfile, line) in [
+            ("web: ./server\nworker\n", 2),
+            ("web server: ./server\n", 1),
+            ("web:\n", 1),
+            ("web: ./a\n\nweb: ./b\n", 3),
+        ] {
+            let result = OrkesyConfig::parse_procfile(procfile);
+            assert!(
+                matches!(result, Err(ConfigError::InvalidProcfile { line: l, .. }) if l == line),
+                "{:?}",
+                procfile
+            );
+        }
+    }
+
+    #[test]
+    fn test_docker_compose() {
+        let compose = r#"
+name: shop
+services:
+  db:
+    image: postgres:16
+    environment:
+      POSTGRES_PASSWORD: secret
+      POSTGRES_PORT: 5432
+      PASSTHROUGH:
+    ports:
+      - "127.0.0.1:5433:5432"
+  api:
+    image: shop/api:latest
+    command: node server.js --verbose
+    environment:
+      - DATABASE_URL=postgres://db/shop
+      - DEBUG
+    ports:
+      - target: 3000
+        published: 8080
+      - "9229"
+      - "5353:53/udp"
+    depends_on:
+      db:
+        condition: service_healthy
+      web:
+        condition: service_started
+  web:
+    build: ./web
+"#;
+
+        let config = OrkesyConfig::parse_docker_compose(compose).unwrap();
+        assert_eq!(config.name.as_deref(), Some("shop"));
+        let ids: Vec<&str> = config.services.keys().map(String::as_str).collect();
+        assert_eq!(ids, ["api", "db"]);
+
+        let db = &config.services["db"];
+        assert_eq!(db.docker_image.as_deref(), Some("postgres:16"));
+        assert!(db.command.is_empty());
+        assert_eq!(db.env["POSTGRES_PORT"], "5432");
+        assert!(!db.env.contains_key("PASSTHROUGH"));
+        assert_eq!(db.docker_ports, ["5433:5432"]);
+        assert_eq!(db.port, Some(5433));
+
+        let api = &config.services["api"];
+        assert_eq!(api.command, ["node", "server.js", "--verbose"]);
+        assert_eq!(api.env.len(), 1);
+        assert_eq!(api.env["DATABASE_URL"], "postgres://db/shop");
+        assert_eq!(api.docker_ports, ["8080:3000", "9229:9229", "5353:53/udp"]);
+        assert_eq!(api.port, Some(8080));
+        assert_eq!(api.depends_on, ["db"]);
+    }
+
+    #[test]
+    fn test_docker_compose_command_quoting() {
+        let compose = r#"
+services:
+  worker:
+    image: alpine
+    command: sh -c "echo 'ready now' && sleep 60"
+"#;
+        let config = OrkesyConfig::parse_docker_compose(compose).unwrap();
+        assert_eq!(
+            config.services["worker"].command,
+            ["sh", "-c", "echo 'ready now' && sleep 60"]
+        );
+
+        let unterminated = "services:\n  worker:\n    image: alpine\n    command: echo \"hi\n";
+        assert!(matches!(
+            OrkesyConfig::parse_docker_compose(unterminated),
+            Err(ConfigError::InvalidCompose { service, .. }) if service == "worker"
+        ));
+    }
+
+    #[test]
+    fn test_docker_compose_port_range() {
+        let compose = r#"
+services:
+  api:
+    image: api
+    ports: ["3000-3005:3000-3005"]
+"#;
+        assert!(matches!(
+            OrkesyConfig::parse_docker_compose(compose),
+            Err(ConfigError::InvalidCompose { service, .. }) if service == "api"
+        ));
+    }
+
+    #[test]
+    fn test_log_sink_option() {
+        let yaml = r#"
+services:
+  api:
+    command: ["node", "server.js"]
+    log_sink:
+      type: journal
+      identifier: shop-api
+"#;
+        let units = OrkesyConfig::parse(yaml).unwrap().to_units();
+        assert_eq!(
+            units[0].log_sink,
+            Some(LogSinkConfig::Journal {
+                identifier: "shop-api".to_string()
+            })
+        );
+
+        let yaml = "services:\n  api:\n    command: [node]\n    log_sink: {type: file}\n";
+        assert!(OrkesyConfig::parse(yaml).is_err());
+    }
+
+    #[test]
+    fn test_toml_matches_yaml() {
+        let yaml = r#"
+name: shop
+timestamp_tz: utc
+global_env_file: .env
+global_log_buffer_size: 5000
+services:
+  postgres:
+    name: Postgres
+    command: ["postgres", "-D", "data"]
+    kind: docker
+    docker_image: postgres:16
+    docker_ports: ["5432:5432"]
+    port: 5432
+    autostart: false
+    env:
+      POSTGRES_USER: app
+    health_check:
+      type: tcp
+      interval_ms: 1000
+      failure_threshold: 5
+  api:
+    command: ["node server.js"]
+    shell: true
+    pty: true
+    cwd: ./api
+    env_file: .env.api
+    description: "Main API"
+    port: 8080
+    env:
+      PORT: "8080"
+    env_from:
+      - { unit: postgres, map: { POSTGRES_USER: PGUSER } }
+    depends_on: [postgres]
+    render_ansi: false
+    max_log_line_bytes: 8192
+    log_buffer_size: 100000
+    deduplicate_logs: false
+    ulimits: { nofile: 4096 }
+    alerts: { cpu_warn_percent: 80, memory_crit_bytes: 500000000 }
+    pid_file: /tmp/api.pid
+    stop_timeout_ms: 10000
+    ready_when: { type: exec, command: [pg_isready], interval_ms: 250 }
+    ready_timeout_ms: 60000
+    log_sink: { type: journal, identifier: shop-api }
+    health_check:
+      type: http
+      path: /health
+      timeout_ms: 500
+commands:
+  "cargo:test":
+    depends_on_ready: [postgres]
+    clean_env: true
+"#;
+        let toml = r#"
+name = "shop"
+timestamp_tz = "utc"
+global_env_file = ".env"
+global_log_buffer_size = 5000
+
+[services.postgres]
+name = "Postgres"
+command = ["postgres", "-D", "data"]
+kind = "docker"
+docker_image = "postgres:16"
+docker_ports = ["5432:5432"]
+port = 5432
+autostart = false
+env = { POSTGRES_USER = "app" }
+health_check = { type = "tcp", interval_ms = 1_000, failure_threshold = 5 }
+
+[services.api]
+command = ["node server.js"]
+shell = true
+pty = true
+cwd = './api'
+env_file = ".env.api"
+description = "Main API"
+port = 8080
+env.PORT = "8080"
+env_from = [
+  { unit = "postgres", map = { POSTGRES_USER = "PGUSER" } },  # rename
+]
+depends_on = ["postgres"]
+render_ansi = false
+max_log_line_bytes = 8192
+log_buffer_size = 100000
+deduplicate_logs = false
+ulimits = { nofile = 4096 }
+alerts = { cpu_warn_percent = 80, memory_crit_bytes = 500_000_000 }
+pid_file = "/tmp/api.pid"
+stop_timeout_ms = 10_000
+ready_when = { type = "exec", command = ["pg_isready"], interval_ms = 250 }
+ready_timeout_ms = 60_000
+log_sink = { type = "journal", identifier = "shop-api" }
+
+[services.api.health_check]
+type = "http"
+path = "/health"
+timeout_ms = 500
+
+[commands."cargo:test"]
+depends_on_ready = ["postgres"]
+clean_env = true
+"#;
+        let from_yaml = OrkesyConfig::parse(yaml).unwrap();
+        let from_toml = OrkesyConfig::parse_toml(toml).unwrap();
+
+        let units = |config: &OrkesyConfig| -> Vec<(String, serde_json::Value)> {
+            config
+                .to_units()
+                .into_iter()
+                .map(|u| (u.id.clone(), serde_json::to_value(&u).unwrap()))
+                .collect()
+        };
+        assert_eq!(units(&from_yaml), units(&from_toml));
+        assert_eq!(from_yaml.to_edges(), from_toml.to_edges());
+        assert_eq!(from_toml.name.as_deref(), Some("shop"));
+        assert_eq!(from_toml.timestamp_tz, TimestampTz::Utc);
+        assert_eq!(from_toml.global_env_file, from_yaml.global_env_file);
+        assert_eq!(
+            from_toml.global_env_file.as_deref(),
+            Some(Path::new(".env"))
+        );
+        let stop_timeouts: Vec<u64> = from_toml
+            .to_units()
+            .iter()
+            .map(|u| u.stop_timeout_ms)
+            .collect();
+        assert_eq!(stop_timeouts, [10_000, DEFAULT_STOP_TIMEOUT_MS]);
+        let api = &from_toml.to_units()[0];
+        assert!(matches!(
+            &api.ready_when,
+            Some(UnitHealthCheck::Exec { command, interval_ms: 250, .. }) if command == "pg_isready"
+        ));
+        assert_eq!(api.ready_timeout_ms, 60_000);
+        assert!(api.pty);
+        assert_eq!(api.env_file.as_deref(), Some(Path::new(".env.api")));
+        let buffer_sizes: Vec<_> = from_toml
+            .to_units()
+            .iter()
+            .map(|u| u.log_buffer_size)
+            .collect();
+        assert_eq!(buffer_sizes, [Some(100_000), Some(5000)]);
+        assert!(!api.deduplicate_logs);
+        assert!(from_toml.to_units()[1].deduplicate_logs);
+
+        // An image is enough for a docker service
+        let config = OrkesyConfig::parse(
+            "services:\n  redis:\n    docker_image: redis:7\n    docker_ports: [\"6379:6379\"]\n",
+        )
+        .unwrap();
+        let redis = &config.to_units()[0];
+        assert_eq!(redis.kind, UnitKind::Docker);
+        assert_eq!(redis.docker_image.as_deref(), Some("redis:7"));
+        assert_eq!(redis.docker_ports, ["6379:6379"]);
+        assert!(matches!(
+            OrkesyConfig::parse("services:\n  redis:\n    kind: docker\n"),
+            Err(ConfigError::MissingCommand { .. })
+        ));
+        assert!(from_toml.commands["cargo:test"].clean_env == Some(true));
+
+        assert!(matches!(
+            OrkesyConfig::parse_toml("[services.api]\ncommand = [\"node\"\n"),
+            Err(ConfigError::Toml(_))
+        ));
+        // A table may only be defined once
+        assert!(matches!(
+            OrkesyConfig::parse_toml(
+                "[services.api]\ncommand = [\"node\"]\n[services.api]\nport = 3000\n"
+            ),
+            Err(ConfigError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_overrides() {
+        let yaml = r#"
+services:
+  api:
+    command: ["node", "server.js"]
+    env:
+      NODE_ENV: development
+      LOG_LEVEL: debug
+  seed:
+    command: ["node", "seed.js"]
+    depends_on: [api]
+profiles:
+  prod:
+    api:
+      command: ["node", "dist/server.js"]
+      env: { NODE_ENV: production }
+    seed:
+      autostart: false
+"#;
+        let base = OrkesyConfig::parse(yaml).unwrap().to_units();
+        assert!(base.iter().all(|u| u.autostart));
+
+        let prod = OrkesyConfig::parse_with_profile(yaml, "prod")
+            .unwrap()
+            .to_units();
+        let api = prod.iter().find(|u| u.id == "api").unwrap();
+        let seed = prod.iter().find(|u| u.id == "seed").unwrap();
+        assert_eq!(api.start, "node dist/server.js");
+        assert_eq!(api.env["NODE_ENV"], "production");
+        assert_eq!(api.env["LOG_LEVEL"], "debug");
+        assert!(api.autostart);
+        assert!(!seed.autostart);
+        assert_eq!(seed.start, "node seed.js");
+
+        let err = OrkesyConfig::parse_with_profile(yaml, "staging").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown profile 'staging', available: prod"
+        );
+        assert!(matches!(
+            OrkesyConfig::parse_with_profile(
+                "services:\n  api:\n    command: [node]\nprofiles:\n  prod:\n    web: {}\n",
+                "prod"
+            ),
+            Err(ConfigError::InvalidProfile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_template_extends() {
+        let yaml = r#"
+templates:
+  node:
+    command: ["node", "index.js"]
+    kind: worker
+    env:
+      NODE_ENV: development
+      LOG_LEVEL: info
+services:
+  api:
+    extends: node
+    port: 3000
+    env:
+      LOG_LEVEL: debug
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        let api = &config.services["api"];
+
+        assert_eq!(api.command, vec!["node", "index.js"]);
+        assert_eq!(api.kind, "worker");
+        assert_eq!(api.port, Some(3000));
+        assert_eq!(api.env["NODE_ENV"], "development");
+        assert_eq!(api.env["LOG_LEVEL"], "debug");
+    }
+
+    #[test]
+    fn test_unknown_template() {
+        let yaml = r#"
+services:
+  api:
+    extends: missing
+    command: ["echo"]
+"#;
+        let result = OrkesyConfig::parse(yaml);
+        assert!(matches!(result, Err(ConfigError::UnknownTemplate { .. })));
+    }
+
+    #[test]
+    fn test_command_dependencies() {
+        let yaml = r#"
+services:
+  postgres:
+    command: ["postgres"]
+commands:
+  "cargo:test":
+    depends_on_ready: [postgres]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        assert_eq!(
+            config.commands["cargo:test"].depends_on_ready,
+            vec!["postgres"]
+        );
+
+        let yaml = r#"
+services:
+  postgres:
+    command: ["postgres"]
+commands:
+  "cargo:test":
+    depends_on_ready: [redis]
+"#;
+        let result = OrkesyConfig::parse(yaml);
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidCommandDependency { .. })
+        ));
+    }
+
+    #[test]
+    fn test_configure_command() {
+        use crate::command::{CommandCategory, DetectedTool};
+
+        let yaml = r#"
+services:
+  api:
+    command: ["node", "server.js"]
+    env:
+      DATABASE_URL: postgres://localhost/shop
+commands:
+  "cargo:test":
+    clean_env: true
+    unit: api
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        let mut spec = CommandSpec {
+            id: "cargo:test".into(),
+            tool: DetectedTool::Rust,
+            name: "test".into(),
+            display_name: "cargo test".into(),
+            command: "cargo test".into(),
+            cwd: None,
+            description: None,
+            category: CommandCategory::Test,
+            stdin_input: None,
+            clean_env: None,
+            env: BTreeMap::new(),
+        };
+        config.configure_command(&mut spec);
+        assert!(spec.uses_clean_env());
+        assert_eq!(spec.env["DATABASE_URL"], "postgres://localhost/shop");
+
+        let yaml = "services: {}\ncommands:\n  \"cargo:test\":\n    unit: api\n";
+        assert!(matches!(
+            OrkesyConfig::parse(yaml),
+            Err(ConfigError::InvalidCommandDependency { .. })
+        ));
+    }
+
+    #[test]
+    fn test_matrix_expansion() {
+        let yaml = r#"
+services:
+  worker:
+    command: ["node", "worker.js"]
+    env:
+      QUEUE: jobs
+    matrix:
+      PORT: [8001, 8002, 8003]
+  gateway:
+    command: ["node", "gateway.js"]
+    depends_on: [worker]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+
+        assert!(!config.services.contains_key("worker"));
+        for (i, port) in ["8001", "8002", "8003"].iter().enumerate() {
+            let svc = &config.services[&format!("worker-{}", i)];
+            assert_eq!(svc.env["PORT"], *port);
+            assert_eq!(svc.env["QUEUE"], "jobs");
+        }
+        assert_eq!(
+            config.services["gateway"].depends_on,
+            vec!["worker-0", "worker-1", "worker-2"]
+        );
+        assert_eq!(config.to_units().len(), 4);
+    }
+
+    #[test]
+    fn test_matrix_id_clash() {
+        let matrix = "  worker:\n    command: [\"w\"]\n    matrix:\n      N: [1, 2]\n";
+        let explicit = "  worker-0:\n    command: [\"x\"]\n";
+        // Whichever is declared first
+        for services in [
+            format!("{}{}", matrix, explicit),
+            format!("{}{}", explicit, matrix),
+        ] {
+            let err = OrkesyConfig::parse(&format!("services:\n{}", services)).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "service 'worker' has an invalid matrix: instance id clashes with service 'worker-0'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamp_settings() {
+        let yaml = r#"
+timestamp_tz:
+  fixed: 19800
+timestamp_format: "%Y-%m-%dT%H:%M:%S%z"
+services:
+  api:
+    command: ["echo"]
+"#;
+        let config = OrkesyConfig::parse(yaml).unwrap();
+        assert_eq!(config.timestamp_tz, TimestampTz::Fixed(19800));
+        assert_eq!(config.timestamp_format(), "%Y-%m-%dT%H:%M:%S%z");
+
+        let config = OrkesyConfig::parse("timestamp_tz: utc\nservices: {}\n").unwrap();
+        assert_eq!(config.timestamp_tz, TimestampTz::Utc);
+        assert_eq!(config.timestamp_format(), DEFAULT_TIMESTAMP_FORMAT);
+
+        let config = OrkesyConfig::parse("timestamp_tz: \"-03:30\"\nservices: {}\n").unwrap();
+        assert_eq!(config.timestamp_tz, TimestampTz::Fixed(-12600));
+
+        assert!(OrkesyConfig::parse("timestamp_tz: mars\nservices: {}\n").is_err());
+    }
+
+    #[test]
+    fn test_remove_service() {
+        let yaml = r#"name: shop
+services:
+  # Postgres for local dev
+  postgres:
+    command: ["postgres"]
+
+  # The API
+  api:
+    command: ["node", "server.js"]
+    depends_on: [postgres]   # db first
+
+  worker:
+    command: ["python", "worker.py"]
+    depends_on:
+      - api
+      - postgres
+
+commands:
+  cargo:test:
+    depends_on_ready:
+      - api
+"#;
+        let removal = remove_service(yaml, "api").unwrap();
+        assert!(removal.preserved_formatting);
+        assert_eq!(
+            removal.edges,
+            vec![
+                ("api".to_string(), "postgres".to_string()),
+                ("worker".to_string(), "api".to_string()),
+                ("cargo:test".to_string(), "api".to_string()),
+            ]
+        );
+        assert_eq!(
+            removal.content,
+            r#"name: shop
+services:
+  # Postgres for local dev
+  postgres:
+    command: ["postgres"]
+
+  worker:
+    command: ["python", "worker.py"]
+    depends_on:
+      - postgres
+
+commands:
+  cargo:test:
+    depends_on_ready: []
+"#
+        );
+
+        let removal = remove_service(yaml, "postgres").unwrap();
+        assert!(
+            removal
+                .content
+                .contains("    depends_on: []   # db first\n")
+        );
+        assert!(
+            removal
+                .content
+                .starts_with("name: shop\nservices:\n  # The API\n")
+        );
+        let config = OrkesyConfig::parse(&removal.content).unwrap();
+        assert_eq!(config.services["worker"].depends_on, vec!["api"]);
+
+        assert!(matches!(
+            remove_service(yaml, "missing"),
+            Err(ConfigError::UnknownService { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_service_fallback() {
+        let yaml =
+            "services: {api: {command: [node]}, web: {command: [vite], depends_on: [api]}}\n";
+        let removal = remove_service(yaml, "api").unwrap();
+        assert!(!removal.preserved_formatting);
+        let config = OrkesyConfig::parse(&removal.content).unwrap();
+        assert_eq!(config.services.len(), 1);
+        assert!(config.services["web"].depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_env_from() {
+        let yaml = r#"
+services:
+  postgres:
+    command: ["postgres"]
+    env:
+      POSTGRES_USER: app
+      POSTGRES_DB: shop
+  api:
+    command: ["node", "server.js"]
+    env_from:
+      - unit: postgres
+        map: { POSTGRES_USER: PGUSER, POSTGRES_DB: PGDATABASE }
+    env:
+      PGDATABASE: shop_dev
+  worker:
+    command: ["python", "worker.py"]
+    env_from: [api]
+"#;
+        let units = OrkesyConfig::parse(yaml).unwrap().to_units();
+        let api = units.iter().find(|u| u.id == "api").unwrap();
+        assert_eq!(api.env["PGUSER"], "app");
+        assert_eq!(api.env["PGDATABASE"], "shop_dev");
+        assert_eq!(api.inherited_env["PGUSER"], "postgres");
+        assert!(!api.inherited_env.contains_key("PGDATABASE"));
+        assert_eq!(api.env_from, Some(vec!["postgres".to_string()]));
+
+        // Transitive vars keep the service that defined them
+        let worker = units.iter().find(|u| u.id == "worker").unwrap();
+        assert_eq!(worker.env["PGUSER"], "app");
+        assert_eq!(worker.inherited_env["PGUSER"], "postgres");
+        assert_eq!(worker.inherited_env["PGDATABASE"], "api");
+
+        let cyclic = "services:\n  a:\n    command: [a]\n    env_from: [b]\n  b:\n    command: [b]\n    env_from: [a]\n";
+        assert!(matches!(
+            OrkesyConfig::parse(cyclic),
+            Err(ConfigError::CyclicEnvFrom { .. })
+        ));
+        let unknown = "services:\n  a:\n    command: [a]\n    env_from: [nope]\n";
+        assert!(matches!(
+            OrkesyConfig::parse(unknown),
+            Err(ConfigError::InvalidEnvFrom { .. })
+        ));
+    }
+
+    #[test]
+    fn test_include() {
+        let dir = std::env::temp_dir().join(format!("orkesy-include-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("services")).unwrap();
+        let write = |name: &str, content: &str| std::fs::write(dir.join(name), content).unwrap();
+
+        write(
+            "orkesy.yml",
+            r#"
+name: split
+include: ["services/*.yml", "infra.yml"]
+services:
+  web:
+    command: ["npm", "start"]
+    depends_on: [api]
+"#,
+        );
+        write(
+            "infra.yml",
+            r#"
+name: ignored
+services:
+  postgres:
+    command: ["postgres"]
+"#,
+        );
+        write(
+            "services/api.yml",
+            r#"
+templates:
+  node:
+    env:
+      NODE_ENV: development
+services:
+  api:
+    extends: node
+    command: ["node", "api.js"]
+    depends_on: [postgres]
+"#,
+        );
+        write("services/notes.txt", "not yaml: [");
+
+        let config = OrkesyConfig::load(&dir.join("orkesy.yml")).unwrap();
+        assert_eq!(config.name.as_deref(), Some("split"));
+        let ids: Vec<&String> = config.services.keys().collect();
+        assert_eq!(ids, ["api", "postgres", "web"]);
+        assert_eq!(config.services["api"].env["NODE_ENV"], "development");
+
+        // Same service id in two files
+        write(
+            "services/db.yml",
+            "services:\n  postgres:\n    command: [\"pg\"]\n",
+        );
+        assert!(matches!(
+            OrkesyConfig::load(&dir.join("orkesy.yml")),
+            Err(ConfigError::DuplicateEntry { key, .. }) if key == "postgres"
+        ));
+        std::fs::remove_file(dir.join("services/db.yml")).unwrap();
+
+        // infra.yml including the root file again
+        write(
+            "infra.yml",
+            "include: [orkesy.yml]\nservices:\n  postgres:\n    command: [\"postgres\"]\n",
+        );
+        assert!(matches!(
+            OrkesyConfig::load(&dir.join("orkesy.yml")),
+            Err(ConfigError::CyclicInclude { cycle }) if cycle.len() == 3
+        ));
+
+        write("orkesy.yml", "include: [missing.yml]\nservices: {}\n");
+        assert!(matches!(
+            OrkesyConfig::load(&dir.join("orkesy.yml")),
+            Err(ConfigError::InvalidInclude { .. })
+        ));
+
+        // Profiles apply to services from included files
+        write(
+            "orkesy.yml",
+            "include: [infra.yml]\nservices: {}\nprofiles:\n  ci:\n    postgres:\n      autostart: false\n",
+        );
+        write(
+            "infra.yml",
+            "services:\n  postgres:\n    command: [\"postgres\"]\n",
+        );
+        let path = dir.join("orkesy.yml");
+        assert!(OrkesyConfig::load(&path).unwrap().to_units()[0].autostart);
+        let ci = OrkesyConfig::load_with_profile(&path, Some("ci")).unwrap();
+        assert!(!ci.to_units()[0].autostart);
+
+        let base = dir.join("services");
+        write("services/svc-1.yaml", "");
+        write("services/svc-2.yaml", "");
+        std::fs::create_dir_all(base.join("nested")).unwrap();
+        write("services/nested/worker.yml", "");
+        let names = |pattern: &str| -> Vec<String> {
+            expand_include(&base, Path::new(pattern))
+                .unwrap()
+                .iter()
+                .map(|p| p.strip_prefix(&base).unwrap().display().to_string())
+                .collect()
+        };
+        assert_eq!(names("*.yml"), ["api.yml"]);
+        assert_eq!(names("svc-?.y*l"), ["svc-1.yaml", "svc-2.yaml"]);
+        assert_eq!(names("svc-[!1].yaml"), ["svc-2.yaml"]);
+        assert_eq!(names("**/*.yml"), ["api.yml", "nested/worker.yml"]);
+        assert!(names("*.toml").is_empty());
+        assert!(matches!(
+            expand_include(&base, Path::new("[.yml")),
+            Err(ConfigError::InvalidInclude { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
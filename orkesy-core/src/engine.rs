@@ -14,6 +14,7 @@ pub enum EngineCommand {
     ClearLogs { id: ServiceId },
     Exec { id: ServiceId, cmd: Vec<String> },
     EmitLog { id: ServiceId, text: String },
+    AddNote { id: ServiceId, text: String },
     Shutdown,
 }
 
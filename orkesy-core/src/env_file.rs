@@ -0,0 +1,143 @@
+//! `.env` files: `KEY=VALUE` lines, with `#` comments and blank lines skipped.
+//!
+//! Values may be wrapped in single quotes (taken literally) or double quotes
+//! (`\n`, `\t`, `\"` and `\\` escapes). Unquoted values end at a ` #`
+//! comment and are trimmed. A leading `export ` is ignored.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum EnvFileError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for EnvFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvFileError::Io(e) => write!(f, "{}", e),
+            EnvFileError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for EnvFileError {}
+
+/// Reads and parses the env file at `path`.
+pub fn load(path: &Path) -> Result<BTreeMap<String, String>, EnvFileError> {
+    let contents = std::fs::read_to_string(path).map_err(EnvFileError::Io)?;
+    parse(&contents)
+}
+
+/// Parses env file contents; later assignments of a key win.
+pub fn parse(contents: &str) -> Result<BTreeMap<String, String>, EnvFileError> {
+    let mut vars = BTreeMap::new();
+    for (i, line) in contents.lines().enumerate() {
+        let error = |message: &str| EnvFileError::Parse {
+            line: i + 1,
+            message: message.to_string(),
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected KEY=VALUE"))?;
+
+        let key = key.trim();
+        let valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(error(&format!("invalid variable name '{}'", key)));
+        }
+
+        let value = parse_value(value.trim()).ok_or_else(|| error("unterminated quote"))?;
+        vars.insert(key.to_string(), value);
+    }
+    Ok(vars)
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        return rest.split_once('\'').map(|(v, _)| v.to_string());
+    }
+
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(out),
+                '\\' => match chars.next()? {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    other => out.push(other),
+                },
+                c => out.push(c),
+            }
+        }
+        return None;
+    }
+
+    let value = match value.find(" #") {
+        Some(i) => &value[..i],
+        None => value,
+    };
+    Some(value.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let vars = parse(
+            r#"
+# database
+DATABASE_URL=postgres://localhost/shop
+export PORT=8080   # api port
+EMPTY=
+SINGLE='a # b $HOME'
+DOUBLE="line1\nline2 \"quoted\""
+_UNDER = spaced
+PORT=9090
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(vars["DATABASE_URL"], "postgres://localhost/shop");
+        assert_eq!(vars["PORT"], "9090");
+        assert_eq!(vars["EMPTY"], "");
+        assert_eq!(vars["SINGLE"], "a # b $HOME");
+        assert_eq!(vars["DOUBLE"], "line1\nline2 \"quoted\"");
+        assert_eq!(vars["_UNDER"], "spaced");
+        assert_eq!(vars.len(), 6);
+
+        assert!(matches!(
+            parse("A=1\nnot a var\n"),
+            Err(EnvFileError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse("1A=x"),
+            Err(EnvFileError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse("A=\"open"),
+            Err(EnvFileError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            load(Path::new("/nonexistent/.env")),
+            Err(EnvFileError::Io(_))
+        ));
+    }
+}
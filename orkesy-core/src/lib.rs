@@ -2,6 +2,7 @@ pub mod adapter;
 pub mod command;
 pub mod config;
 pub mod engine;
+pub mod env_file;
 pub mod job;
 pub mod log_filter;
 pub mod metrics;
@@ -2,10 +2,22 @@ use std::collections::{BTreeMap, VecDeque};
 
 use crate::model::ServiceId;
 
+/// Points a new value is compared against when looking for spikes
+pub const ANOMALY_WINDOW: usize = 30;
+/// Points needed before spikes are detected at all
+pub const ANOMALY_MIN_POINTS: usize = 10;
+/// Standard deviations above the window mean that make a spike
+pub const ANOMALY_SIGMA: f64 = 3.0;
+/// Points kept per series: five minutes at the sampler's fastest interval,
+/// 200ms
+pub const SERIES_CAPACITY: usize = 1500;
+
 #[derive(Clone, Debug)]
 pub struct Series {
     pub cap: usize,
     pub points: VecDeque<(f64, f64)>,
+    /// Times of points that spiked above the recent window, oldest first
+    pub anomaly_timestamps: Vec<f64>,
 }
 
 impl Series {
@@ -13,20 +25,72 @@ impl Series {
         Self {
             cap,
             points: VecDeque::with_capacity(cap),
+            anomaly_timestamps: Vec::new(),
         }
     }
 
     pub fn push(&mut self, t: f64, v: f64) {
+        if self.is_spike(v) {
+            self.anomaly_timestamps.push(t);
+        }
         if self.points.len() >= self.cap {
             self.points.pop_front();
+            if let Some(&(oldest, _)) = self.points.front() {
+                self.anomaly_timestamps.retain(|at| *at >= oldest);
+            }
         }
         self.points.push_back((t, v));
     }
 
+    /// Whether `v` is more than `ANOMALY_SIGMA` standard deviations above the
+    /// mean of the last `ANOMALY_WINDOW` points. The deviation is floored at
+    /// 10% of the mean so a flat series doesn't flag every small bump.
+    fn is_spike(&self, v: f64) -> bool {
+        if self.points.len() < ANOMALY_MIN_POINTS {
+            return false;
+        }
+        let window: Vec<f64> = self
+            .points
+            .iter()
+            .rev()
+            .take(ANOMALY_WINDOW)
+            .map(|(_, v)| *v)
+            .collect();
+        let n = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / n;
+        let std_dev = (window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+        v - mean > f64::EPSILON && v > mean + ANOMALY_SIGMA * std_dev.max(mean.abs() * 0.1)
+    }
+
+    pub fn is_anomaly(&self, t: f64) -> bool {
+        self.anomaly_timestamps.contains(&t)
+    }
+
+    /// The points flagged in `anomaly_timestamps`
+    pub fn anomaly_points(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .filter(|(t, _)| self.is_anomaly(*t))
+            .copied()
+            .collect()
+    }
+
     pub fn as_vec(&self) -> Vec<(f64, f64)> {
         self.points.iter().copied().collect()
     }
 
+    /// The points no more than `secs` older than the newest one
+    pub fn window(&self, secs: f64) -> Vec<(f64, f64)> {
+        let Some(&(newest, _)) = self.points.back() else {
+            return Vec::new();
+        };
+        self.points
+            .iter()
+            .filter(|(t, _)| newest - *t <= secs)
+            .copied()
+            .collect()
+    }
+
     pub fn latest(&self) -> Option<f64> {
         self.points.back().map(|(_, v)| *v)
     }
@@ -59,6 +123,7 @@ impl Series {
 
     pub fn clear(&mut self) {
         self.points.clear();
+        self.anomaly_timestamps.clear();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -72,7 +137,7 @@ impl Series {
 
 impl Default for Series {
     fn default() -> Self {
-        Self::new(120)
+        Self::new(SERIES_CAPACITY)
     }
 }
 
@@ -84,6 +149,10 @@ pub struct MetricsState {
     pub svc_cpu: BTreeMap<ServiceId, Series>,
     pub svc_mem: BTreeMap<ServiceId, Series>,
     pub svc_net: BTreeMap<ServiceId, Series>,
+    /// Disk read rate per unit in KB/s
+    pub svc_io_read: BTreeMap<ServiceId, Series>,
+    /// Disk write rate per unit in KB/s
+    pub svc_io_write: BTreeMap<ServiceId, Series>,
     pub logs_rate: BTreeMap<ServiceId, Series>,
     pub log_counts: BTreeMap<ServiceId, u64>,
     pub prev_log_counts: BTreeMap<ServiceId, u64>,
@@ -91,7 +160,7 @@ pub struct MetricsState {
 
 impl MetricsState {
     pub fn new() -> Self {
-        Self::with_capacity(120)
+        Self::with_capacity(SERIES_CAPACITY)
     }
 
     pub fn with_capacity(cap: usize) -> Self {
@@ -102,6 +171,8 @@ impl MetricsState {
             svc_cpu: BTreeMap::new(),
             svc_mem: BTreeMap::new(),
             svc_net: BTreeMap::new(),
+            svc_io_read: BTreeMap::new(),
+            svc_io_write: BTreeMap::new(),
             logs_rate: BTreeMap::new(),
             log_counts: BTreeMap::new(),
             prev_log_counts: BTreeMap::new(),
@@ -133,6 +204,17 @@ impl MetricsState {
         }
     }
 
+    pub fn push_io_rate(&mut self, t: f64, id: &ServiceId, read_kbps: f64, write_kbps: f64) {
+        self.svc_io_read
+            .entry(id.clone())
+            .or_default()
+            .push(t, read_kbps);
+        self.svc_io_write
+            .entry(id.clone())
+            .or_default()
+            .push(t, write_kbps);
+    }
+
     pub fn push_log_rate(&mut self, t: f64, id: &ServiceId, logs_per_sec: f64) {
         self.logs_rate
             .entry(id.clone())
@@ -162,6 +244,8 @@ impl MetricsState {
         self.svc_cpu.remove(id);
         self.svc_mem.remove(id);
         self.svc_net.remove(id);
+        self.svc_io_read.remove(id);
+        self.svc_io_write.remove(id);
         self.log_counts.remove(id);
         self.prev_log_counts.remove(id);
     }
@@ -223,10 +307,43 @@ mod tests {
         assert_eq!(s.len(), 0);
     }
 
+    #[test]
+    fn test_series_anomalies() {
+        let mut s = Series::new(20);
+        for t in 0..15 {
+            s.push(t as f64, 10.0 + (t % 2) as f64);
+        }
+        assert!(s.anomaly_timestamps.is_empty());
+
+        s.push(15.0, 60.0);
+        s.push(16.0, 11.0);
+        assert_eq!(s.anomaly_timestamps, vec![15.0]);
+        assert!(s.is_anomaly(15.0));
+        assert_eq!(s.anomaly_points(), vec![(15.0, 60.0)]);
+
+        // Dropped with its point once the series wraps
+        for t in 17..40 {
+            s.push(t as f64, 10.0);
+        }
+        assert!(s.anomaly_timestamps.is_empty());
+    }
+
+    #[test]
+    fn test_series_window() {
+        let mut s = Series::new(10);
+        assert!(s.window(30.0).is_empty());
+
+        for t in 0..5 {
+            s.push(t as f64 * 10.0, t as f64);
+        }
+        assert_eq!(s.window(20.0), vec![(20.0, 2.0), (30.0, 3.0), (40.0, 4.0)]);
+        assert_eq!(s.window(300.0).len(), 5);
+    }
+
     #[test]
     fn test_series_default() {
         let s = Series::default();
-        assert_eq!(s.cap, 120);
+        assert_eq!(s.cap, SERIES_CAPACITY);
         assert!(s.is_empty());
     }
 
@@ -250,6 +367,21 @@ mod tests {
         assert!(!state.svc_net.contains_key("api"));
     }
 
+    #[test]
+    fn test_metrics_state_push_io_rate() {
+        let mut state = MetricsState::new();
+        state.push_io_rate(1.0, &"api".to_string(), 12.5, 3.0);
+        state.push_io_rate(1.5, &"api".to_string(), 0.0, 8.0);
+
+        assert_eq!(state.svc_io_read.get("api").unwrap().latest(), Some(0.0));
+        assert_eq!(state.svc_io_write.get("api").unwrap().latest(), Some(8.0));
+        assert_eq!(state.svc_io_read.get("api").unwrap().len(), 2);
+
+        state.clear_service(&"api".to_string());
+        assert!(!state.svc_io_read.contains_key("api"));
+        assert!(!state.svc_io_write.contains_key("api"));
+    }
+
     #[test]
     fn test_metrics_state_log_rate_calculation() {
         let mut state = MetricsState::new();
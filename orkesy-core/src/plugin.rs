@@ -2,8 +2,8 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use crate::command::{
-    CommandCategory, CommandRegistry, CommandSource, CommandSpec, DetectedTool, PackageManager,
-    PythonPackageManager, RegistryCommand,
+    CommandCategory, CommandRegistry, CommandSource, CommandSpec, DetectedTool, Heuristics,
+    PackageManager, PythonPackageManager, RegistryCommand,
 };
 use crate::model::RuntimeGraph;
 use crate::unit::Unit;
@@ -132,8 +132,8 @@ impl OrkesyPlugin for NodePlugin {
             && let Some(scripts) = json.get("scripts").and_then(|s| s.as_object())
         {
             let script_count = scripts.len();
-            for (name, _cmd) in scripts {
-                let category = categorize_npm_script(name);
+            for (name, script) in scripts {
+                let category = Heuristics::categorize(name, script.as_str().unwrap_or_default());
                 let cmd = CommandSpec {
                     id: format!("node:{}", name),
                     tool: DetectedTool::Node { pm: pm.clone() },
@@ -143,6 +143,9 @@ impl OrkesyPlugin for NodePlugin {
                     cwd: None,
                     description: None,
                     category,
+                    stdin_input: None,
+                    clean_env: None,
+                    env: BTreeMap::new(),
                 };
                 result.add_command(cmd);
             }
@@ -170,24 +173,6 @@ impl OrkesyPlugin for NodePlugin {
     }
 }
 
-fn categorize_npm_script(name: &str) -> CommandCategory {
-    let name_lower = name.to_lowercase();
-    if name_lower.contains("dev") || name_lower.contains("start") || name_lower.contains("serve") {
-        CommandCategory::Dev
-    } else if name_lower.contains("build") || name_lower.contains("compile") {
-        CommandCategory::Build
-    } else if name_lower.contains("test") || name_lower.contains("spec") {
-        CommandCategory::Test
-    } else if name_lower.contains("lint")
-        || name_lower.contains("format")
-        || name_lower.contains("prettier")
-    {
-        CommandCategory::Lint
-    } else {
-        CommandCategory::Script
-    }
-}
-
 pub struct RustPlugin;
 
 impl OrkesyPlugin for RustPlugin {
@@ -231,6 +216,9 @@ impl OrkesyPlugin for RustPlugin {
                 cwd: None,
                 description: None,
                 category,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             });
         }
 
@@ -373,6 +361,9 @@ fn create_python_cmd(name: &str, command: &str, category: CommandCategory) -> Co
         cwd: None,
         description: None,
         category,
+        stdin_input: None,
+        clean_env: None,
+        env: BTreeMap::new(),
     }
 }
 
@@ -425,6 +416,9 @@ impl OrkesyPlugin for DockerPlugin {
                 cwd: None,
                 description: None,
                 category,
+                stdin_input: None,
+                clean_env: None,
+                env: BTreeMap::new(),
             });
         }
 
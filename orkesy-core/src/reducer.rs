@@ -1,14 +1,27 @@
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 
 use crate::command::{CommandId, CommandRun, ProjectIndex, RunId, RunStatus};
 use crate::model::{HealthStatus, RuntimeGraph, ServiceId, ServiceStatus};
-use crate::state::{LogLine, LogStream, RuntimeState};
+use crate::state::{
+    HealthCheckResult, LogLine, LogStream, ResourceWarning, RuntimeState, UnitLogOptions, UserNote,
+    WatchInfo,
+};
 use crate::unit::UnitMetrics;
 
+/// Marks note lines in the log stream
+pub const NOTE_PREFIX: &str = "📝 ";
+
 #[derive(Clone, Debug)]
 pub enum RuntimeEvent {
     TopologyLoaded {
         graph: RuntimeGraph,
+        /// Units with non-default log settings
+        log_options: BTreeMap<ServiceId, UnitLogOptions>,
+    },
+    /// A temporary unit left the graph, e.g. an `orkesy fork` copy that exited
+    UnitRemoved {
+        id: ServiceId,
     },
     StatusChanged {
         id: ServiceId,
@@ -17,6 +30,8 @@ pub enum RuntimeEvent {
     HealthChanged {
         id: ServiceId,
         health: HealthStatus,
+        /// Time the probe took, for results of orkesy's own health checks
+        latency_ms: Option<u64>,
     },
     LogLine {
         id: ServiceId,
@@ -30,8 +45,38 @@ pub enum RuntimeEvent {
         id: ServiceId,
         metrics: UnitMetrics,
     },
+    /// A unit's declared port accepted its first connection after start
+    PortReady {
+        id: ServiceId,
+        port: u16,
+    },
+    /// A unit's `ready_when` check passed
+    ReadinessReached {
+        id: ServiceId,
+    },
+    /// A unit metric crossed one of its alert thresholds
+    ResourceWarning {
+        id: ServiceId,
+        metric: String,
+        value: f64,
+        threshold: f64,
+    },
+    /// A note attached to a unit by the user
+    UserNote {
+        id: ServiceId,
+        text: String,
+        at: SystemTime,
+    },
+    /// A metric watch was added or updated, or removed when `watch` is `None`
+    WatchChanged {
+        id: ServiceId,
+        watch_id: String,
+        watch: Option<WatchInfo>,
+    },
 
     // Commands + Runs feature
+    /// Project files changed and the index is being rebuilt
+    ProjectIndexing,
     /// Project was indexed (tools and commands detected)
     ProjectIndexed {
         project: ProjectIndex,
@@ -86,6 +131,13 @@ pub enum RuntimeEvent {
         id: ServiceId,
         per_sec: f64,
     },
+    /// Disk read/write rate sample for a service, in KB/s
+    IoRateSample {
+        t: f64,
+        id: ServiceId,
+        read_kbps: f64,
+        write_kbps: f64,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -99,11 +151,28 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
     state.last_event_id = env.id;
 
     match &env.event {
-        RuntimeEvent::TopologyLoaded { graph } => {
+        RuntimeEvent::TopologyLoaded { graph, log_options } => {
             state.graph = graph.clone();
+            state.logs.set_unit_options(log_options.clone());
+        }
+        RuntimeEvent::UnitRemoved { id } => {
+            state.graph.nodes.remove(id);
+            state.graph.edges.retain(|e| &e.from != id && &e.to != id);
+            state.metrics.remove(id);
+            state.warnings.remove(id);
+            state.watches.remove(id);
+            state.starts.remove(id);
+            state.health_history.remove(id);
         }
         RuntimeEvent::StatusChanged { id, status } => {
             if let Some(node) = state.graph.nodes.get_mut(id) {
+                // Engines that report Restarting then Starting count once
+                let starting = |s: &ServiceStatus| {
+                    matches!(s, ServiceStatus::Starting | ServiceStatus::Restarting)
+                };
+                if starting(status) && !starting(&node.observed.status) {
+                    *state.starts.entry(id.clone()).or_default() += 1;
+                }
                 node.observed.status = status.clone();
             }
             // Clear metrics when service is no longer running
@@ -113,14 +182,27 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
                 | ServiceStatus::Errored { .. } => {
                     state.metrics.remove(id);
                     state.metrics_series.clear_service(id);
+                    state.warnings.remove(id);
                 }
                 _ => {}
             }
         }
-        RuntimeEvent::HealthChanged { id, health } => {
+        RuntimeEvent::HealthChanged {
+            id,
+            health,
+            latency_ms,
+        } => {
             if let Some(node) = state.graph.nodes.get_mut(id) {
                 node.observed.health = health.clone();
             }
+            state.record_health(
+                id,
+                HealthCheckResult {
+                    at: env.at,
+                    outcome: health.clone(),
+                    latency_ms: *latency_ms,
+                },
+            );
         }
         RuntimeEvent::LogLine { id, stream, text } => {
             state.logs.push(
@@ -130,6 +212,7 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
                     service_id: id.clone(),
                     stream: *stream,
                     text: text.clone(),
+                    repeat_count: 1,
                 },
             );
             // Increment log count for rate calculation
@@ -138,11 +221,101 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
         RuntimeEvent::ClearLogs { id } => state.logs.clear(id),
         RuntimeEvent::MetricsUpdated { id, metrics } => {
             state.metrics.insert(id.clone(), metrics.clone());
+            // Warnings clear once the metric is back below the hysteresis band
+            if let Some(warnings) = state.warnings.get_mut(id) {
+                warnings.retain(|metric, w| {
+                    metrics
+                        .value(metric)
+                        .is_none_or(|value| value >= w.clears_at())
+                });
+                if warnings.is_empty() {
+                    state.warnings.remove(id);
+                }
+            }
+        }
+        RuntimeEvent::ResourceWarning {
+            id,
+            metric,
+            value,
+            threshold,
+        } => {
+            let warnings = state.warnings.entry(id.clone()).or_default();
+            // A critical warning is not downgraded by a later warning-level one
+            if warnings
+                .get(metric)
+                .is_none_or(|w| *threshold >= w.threshold)
+            {
+                warnings.insert(
+                    metric.clone(),
+                    ResourceWarning {
+                        value: *value,
+                        threshold: *threshold,
+                    },
+                );
+            }
+        }
+        RuntimeEvent::UserNote { id, text, at } => {
+            state.notes.entry(id.clone()).or_default().push(UserNote {
+                at: *at,
+                text: text.clone(),
+            });
+            state.logs.push(
+                id,
+                LogLine {
+                    at: *at,
+                    service_id: id.clone(),
+                    stream: LogStream::System,
+                    text: format!("{}{}", NOTE_PREFIX, text),
+                    repeat_count: 1,
+                },
+            );
+        }
+        RuntimeEvent::WatchChanged {
+            id,
+            watch_id,
+            watch,
+        } => match watch {
+            Some(watch) => {
+                state
+                    .watches
+                    .entry(id.clone())
+                    .or_default()
+                    .insert(watch_id.clone(), watch.clone());
+            }
+            None => {
+                if let Some(watches) = state.watches.get_mut(id) {
+                    watches.remove(watch_id);
+                    if watches.is_empty() {
+                        state.watches.remove(id);
+                    }
+                }
+            }
+        },
+        RuntimeEvent::PortReady { id, port } => {
+            // A bound port means the unit is ready to serve
+            if let Some(node) = state.graph.nodes.get_mut(id)
+                && node.port == Some(*port)
+                && node.observed.status == ServiceStatus::Starting
+            {
+                node.observed.status = ServiceStatus::Running;
+            }
+        }
+
+        RuntimeEvent::ReadinessReached { id } => {
+            if let Some(node) = state.graph.nodes.get_mut(id)
+                && node.observed.status == ServiceStatus::Starting
+            {
+                node.observed.status = ServiceStatus::Running;
+            }
         }
 
         // Commands + Runs feature
+        RuntimeEvent::ProjectIndexing => {
+            state.project_stale = true;
+        }
         RuntimeEvent::ProjectIndexed { project } => {
             state.project = Some(project.clone());
+            state.project_stale = false;
         }
         RuntimeEvent::CommandStarted {
             run_id,
@@ -176,6 +349,7 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
                     service_id: run_id.clone(), // Reuse field for run_id
                     stream: *stream,
                     text: text.clone(),
+                    repeat_count: 1,
                 },
             );
         }
@@ -221,13 +395,26 @@ pub fn reduce(state: &mut RuntimeState, env: &EventEnvelope) {
         RuntimeEvent::LogRateSample { t, id, per_sec } => {
             state.metrics_series.push_log_rate(*t, id, *per_sec);
         }
+        RuntimeEvent::IoRateSample {
+            t,
+            id,
+            read_kbps,
+            write_kbps,
+        } => {
+            state
+                .metrics_series
+                .push_io_rate(*t, id, *read_kbps, *write_kbps);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{DesiredState, ObservedState, RuntimeGraph, ServiceKind, ServiceNode};
+    use crate::model::{
+        DesiredState, Edge, EdgeKind, ObservedState, RuntimeGraph, ServiceKind, ServiceNode,
+    };
+    use crate::state::DEFAULT_LOG_BUFFER_SIZE;
     use std::collections::{BTreeMap, BTreeSet};
 
     fn make_envelope(id: u64, event: RuntimeEvent) -> EventEnvelope {
@@ -274,6 +461,7 @@ mod tests {
             1,
             RuntimeEvent::TopologyLoaded {
                 graph: graph.clone(),
+                log_options: BTreeMap::new(),
             },
         );
         reduce(&mut state, &env);
@@ -283,6 +471,32 @@ mod tests {
         assert_eq!(state.last_event_id, 1);
     }
 
+    #[test]
+    fn test_unit_removed() {
+        let mut graph = make_test_graph();
+        let mut fork = graph.nodes["api"].clone();
+        fork.id = "api-debug".to_string();
+        graph.nodes.insert(fork.id.clone(), fork);
+        graph.edges.insert(Edge {
+            from: "api-debug".to_string(),
+            to: "api".to_string(),
+            kind: EdgeKind::DependsOn,
+        });
+        let mut state = RuntimeState::new(graph);
+
+        let env = make_envelope(
+            1,
+            RuntimeEvent::UnitRemoved {
+                id: "api-debug".to_string(),
+            },
+        );
+        reduce(&mut state, &env);
+
+        assert_eq!(state.graph.nodes.len(), 1);
+        assert!(state.graph.nodes.contains_key("api"));
+        assert!(state.graph.edges.is_empty());
+    }
+
     #[test]
     fn test_status_changed() {
         let graph = make_test_graph();
@@ -301,6 +515,118 @@ mod tests {
         assert!(matches!(node.observed.status, ServiceStatus::Running));
     }
 
+    #[test]
+    fn test_restart_count() {
+        let graph = make_test_graph();
+        let mut state = RuntimeState::new(graph);
+
+        let statuses = [
+            ServiceStatus::Starting,
+            ServiceStatus::Running,
+            ServiceStatus::Stopped,
+            ServiceStatus::Starting,
+            ServiceStatus::Running,
+            ServiceStatus::Restarting,
+            ServiceStatus::Starting,
+            ServiceStatus::Running,
+        ];
+        for (i, status) in statuses.into_iter().enumerate() {
+            reduce(
+                &mut state,
+                &make_envelope(
+                    i as u64 + 1,
+                    RuntimeEvent::StatusChanged {
+                        id: "api".to_string(),
+                        status,
+                    },
+                ),
+            );
+        }
+
+        assert_eq!(state.restart_count("api"), 2);
+        assert_eq!(state.restart_count("web"), 0);
+    }
+
+    #[test]
+    fn test_port_ready_promotes_starting() {
+        let graph = make_test_graph();
+        let mut state = RuntimeState::new(graph);
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::StatusChanged {
+                    id: "api".to_string(),
+                    status: ServiceStatus::Starting,
+                },
+            ),
+        );
+
+        // Wrong port is ignored
+        reduce(
+            &mut state,
+            &make_envelope(
+                2,
+                RuntimeEvent::PortReady {
+                    id: "api".to_string(),
+                    port: 9999,
+                },
+            ),
+        );
+        assert_eq!(
+            state.graph.nodes["api"].observed.status,
+            ServiceStatus::Starting
+        );
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                3,
+                RuntimeEvent::PortReady {
+                    id: "api".to_string(),
+                    port: 8080,
+                },
+            ),
+        );
+        assert_eq!(
+            state.graph.nodes["api"].observed.status,
+            ServiceStatus::Running
+        );
+    }
+
+    #[test]
+    fn test_readiness_reached_promotes_starting() {
+        let graph = make_test_graph();
+        let mut state = RuntimeState::new(graph);
+
+        let ready = RuntimeEvent::ReadinessReached {
+            id: "api".to_string(),
+        };
+        // Only a starting unit is promoted
+        reduce(&mut state, &make_envelope(1, ready.clone()));
+        assert_eq!(
+            state.graph.nodes["api"].observed.status,
+            ServiceStatus::Stopped
+        );
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                2,
+                RuntimeEvent::StatusChanged {
+                    id: "api".to_string(),
+                    status: ServiceStatus::Starting,
+                },
+            ),
+        );
+        reduce(&mut state, &make_envelope(3, ready));
+        assert_eq!(
+            state.graph.nodes["api"].observed.status,
+            ServiceStatus::Running
+        );
+    }
+
     #[test]
     fn test_status_stopped_clears_metrics() {
         let graph = make_test_graph();
@@ -313,6 +639,7 @@ mod tests {
                 memory_bytes: 1024,
                 uptime_secs: 100,
                 pid: Some(1234),
+                ..Default::default()
             },
         );
 
@@ -338,12 +665,128 @@ mod tests {
             RuntimeEvent::HealthChanged {
                 id: "api".to_string(),
                 health: HealthStatus::Healthy,
+                latency_ms: Some(12),
             },
         );
         reduce(&mut state, &env);
 
         let node = state.graph.nodes.get("api").unwrap();
         assert!(matches!(node.observed.health, HealthStatus::Healthy));
+        let history = &state.health_history["api"].results;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].latency_ms, Some(12));
+    }
+
+    #[test]
+    fn test_overall_health() {
+        let mut graph = make_test_graph();
+        for id in ["web", "worker"] {
+            let mut node = graph.nodes["api"].clone();
+            node.id = id.to_string();
+            graph.nodes.insert(node.id.clone(), node);
+        }
+        let mut state = RuntimeState::new(graph);
+        assert!(matches!(state.overall_health(), HealthStatus::Unknown));
+
+        let mut event_id = 0;
+        let mut apply = |state: &mut RuntimeState, event: RuntimeEvent| {
+            event_id += 1;
+            reduce(state, &make_envelope(event_id, event));
+        };
+        for id in ["api", "web"] {
+            apply(
+                &mut state,
+                RuntimeEvent::StatusChanged {
+                    id: id.to_string(),
+                    status: ServiceStatus::Running,
+                },
+            );
+            apply(
+                &mut state,
+                RuntimeEvent::HealthChanged {
+                    id: id.to_string(),
+                    health: HealthStatus::Healthy,
+                    latency_ms: None,
+                },
+            );
+        }
+        // Stopped units don't count
+        apply(
+            &mut state,
+            RuntimeEvent::HealthChanged {
+                id: "worker".to_string(),
+                health: HealthStatus::Unhealthy {
+                    reason: "down".into(),
+                },
+                latency_ms: None,
+            },
+        );
+        assert!(matches!(state.overall_health(), HealthStatus::Healthy));
+
+        apply(
+            &mut state,
+            RuntimeEvent::HealthChanged {
+                id: "web".to_string(),
+                health: HealthStatus::Degraded {
+                    reason: "slow".into(),
+                },
+                latency_ms: None,
+            },
+        );
+        assert!(matches!(
+            state.overall_health(),
+            HealthStatus::Degraded { reason } if reason == "web degraded"
+        ));
+
+        apply(
+            &mut state,
+            RuntimeEvent::HealthChanged {
+                id: "api".to_string(),
+                health: HealthStatus::Unhealthy {
+                    reason: "refused".into(),
+                },
+                latency_ms: None,
+            },
+        );
+        assert!(matches!(
+            state.overall_health(),
+            HealthStatus::Unhealthy { reason } if reason == "api unhealthy"
+        ));
+    }
+
+    #[test]
+    fn test_health_history_size() {
+        let graph = make_test_graph();
+        let mut state = RuntimeState::new(graph);
+        state.health_history_sizes.insert("api".to_string(), 3);
+
+        for i in 0..5 {
+            let health = if i % 2 == 0 {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Degraded {
+                    reason: "slow".into(),
+                }
+            };
+            reduce(
+                &mut state,
+                &make_envelope(
+                    i + 1,
+                    RuntimeEvent::HealthChanged {
+                        id: "api".to_string(),
+                        health,
+                        latency_ms: Some(i),
+                    },
+                ),
+            );
+        }
+
+        let latencies: Vec<Option<u64>> = state.health_history["api"]
+            .results
+            .iter()
+            .map(|r| r.latency_ms)
+            .collect();
+        assert_eq!(latencies, [Some(2), Some(3), Some(4)]);
     }
 
     #[test]
@@ -365,6 +808,106 @@ mod tests {
         assert_eq!(state.logs.merged.len(), 1);
     }
 
+    #[test]
+    fn test_log_buffer_size() {
+        let mut state = RuntimeState::new(make_test_graph());
+        let cap = 3;
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::TopologyLoaded {
+                    graph: make_test_graph(),
+                    log_options: BTreeMap::from([(
+                        "api".to_string(),
+                        UnitLogOptions {
+                            buffer_size: Some(cap),
+                            deduplicate: true,
+                        },
+                    )]),
+                },
+            ),
+        );
+
+        for i in 0..=cap {
+            let env = make_envelope(
+                2 + i as u64,
+                RuntimeEvent::LogLine {
+                    id: "api".to_string(),
+                    stream: LogStream::Stdout,
+                    text: format!("line {}", i),
+                },
+            );
+            reduce(&mut state, &env);
+        }
+
+        let lines = &state.logs.per_service["api"];
+        assert_eq!(lines.len(), cap);
+        assert_eq!(lines.front().unwrap().text, "line 1");
+        assert_eq!(lines.back().unwrap().text, "line 3");
+        // Other buffers keep the default capacity
+        assert_eq!(state.logs.merged.len(), cap + 1);
+        assert_eq!(state.logs.unit_cap("web"), DEFAULT_LOG_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_log_dedup() {
+        let mut state = RuntimeState::new(make_test_graph());
+        let log = |id: &str, text: &str| RuntimeEvent::LogLine {
+            id: id.to_string(),
+            stream: LogStream::Stdout,
+            text: text.to_string(),
+        };
+
+        for (i, event) in [
+            log("api", "OK"),
+            log("api", "OK"),
+            log("api", "OK"),
+            log("web", "OK"),
+            log("api", "OK"),
+            log("api", "done"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            reduce(&mut state, &make_envelope(i as u64, event));
+        }
+
+        let api: Vec<_> = state.logs.per_service["api"]
+            .iter()
+            .map(|l| (l.text.as_str(), l.repeat_count))
+            .collect();
+        assert_eq!(api, [("OK", 4), ("done", 1)]);
+        // The merged buffer only folds repeats that follow each other
+        let merged: Vec<_> = state
+            .logs
+            .merged
+            .iter()
+            .map(|l| (l.service_id.as_str(), l.repeat_count))
+            .collect();
+        assert_eq!(merged, [("api", 3), ("web", 1), ("api", 1), ("api", 1)]);
+
+        // With deduplicate_logs off every line is kept
+        reduce(
+            &mut state,
+            &make_envelope(
+                10,
+                RuntimeEvent::TopologyLoaded {
+                    graph: make_test_graph(),
+                    log_options: BTreeMap::from([(
+                        "web".to_string(),
+                        UnitLogOptions {
+                            buffer_size: None,
+                            deduplicate: false,
+                        },
+                    )]),
+                },
+            ),
+        );
+        reduce(&mut state, &make_envelope(11, log("web", "OK")));
+        assert_eq!(state.logs.per_service["web"].len(), 2);
+    }
+
     #[test]
     fn test_clear_logs() {
         let graph = make_test_graph();
@@ -401,6 +944,8 @@ mod tests {
             memory_bytes: 1024 * 1024,
             uptime_secs: 3600,
             pid: Some(5678),
+            io_read_bytes: 4096,
+            io_write_bytes: 512,
         };
 
         let env = make_envelope(
@@ -415,6 +960,8 @@ mod tests {
         let stored = state.metrics.get("api").unwrap();
         assert_eq!(stored.cpu_percent, 25.5);
         assert_eq!(stored.pid, Some(5678));
+        assert_eq!(stored.io_read_bytes, 4096);
+        assert_eq!(stored.io_write_bytes, 512);
     }
 
     #[test]
@@ -519,4 +1066,243 @@ mod tests {
         let run = state.runs.get("run-1").unwrap();
         assert!(matches!(run.status, RunStatus::Killed));
     }
+
+    #[test]
+    fn test_project_reindex_marks_stale() {
+        let mut state = RuntimeState::new(make_test_graph());
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::ProjectIndexed {
+                    project: ProjectIndex::default(),
+                },
+            ),
+        );
+        assert!(!state.project_stale);
+
+        reduce(&mut state, &make_envelope(2, RuntimeEvent::ProjectIndexing));
+        assert!(state.project_stale);
+        assert!(state.project.is_some());
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                3,
+                RuntimeEvent::ProjectIndexed {
+                    project: ProjectIndex::default(),
+                },
+            ),
+        );
+        assert!(!state.project_stale);
+    }
+
+    #[test]
+    fn test_resource_warning_clears_with_hysteresis() {
+        let mut state = RuntimeState::new(make_test_graph());
+        let metrics = |cpu_percent: f32| UnitMetrics {
+            cpu_percent,
+            memory_bytes: 0,
+            uptime_secs: 1,
+            pid: Some(1),
+            ..Default::default()
+        };
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::ResourceWarning {
+                    id: "api".to_string(),
+                    metric: "cpu_percent".to_string(),
+                    value: 85.0,
+                    threshold: 80.0,
+                },
+            ),
+        );
+        assert!(state.warnings["api"].contains_key("cpu_percent"));
+
+        // Below the threshold but inside the 10% band: still active
+        reduce(
+            &mut state,
+            &make_envelope(
+                2,
+                RuntimeEvent::MetricsUpdated {
+                    id: "api".to_string(),
+                    metrics: metrics(75.0),
+                },
+            ),
+        );
+        assert!(state.warnings.contains_key("api"));
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                3,
+                RuntimeEvent::MetricsUpdated {
+                    id: "api".to_string(),
+                    metrics: metrics(70.0),
+                },
+            ),
+        );
+        assert!(!state.warnings.contains_key("api"));
+    }
+
+    #[test]
+    fn test_user_note_survives_restart() {
+        let mut state = RuntimeState::new(make_test_graph());
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::UserNote {
+                    id: "api".to_string(),
+                    text: "reduced heap to 512M".to_string(),
+                    at: SystemTime::now(),
+                },
+            ),
+        );
+        let logs = state.logs.tail("api", 1);
+        assert_eq!(logs[0].stream, LogStream::System);
+        assert_eq!(logs[0].text, "📝 reduced heap to 512M");
+
+        for (id, status) in [
+            (2, ServiceStatus::Exited { code: Some(137) }),
+            (3, ServiceStatus::Running),
+        ] {
+            reduce(
+                &mut state,
+                &make_envelope(
+                    id,
+                    RuntimeEvent::StatusChanged {
+                        id: "api".to_string(),
+                        status,
+                    },
+                ),
+            );
+        }
+        reduce(
+            &mut state,
+            &make_envelope(
+                4,
+                RuntimeEvent::ClearLogs {
+                    id: "api".to_string(),
+                },
+            ),
+        );
+
+        assert_eq!(state.notes["api"].len(), 1);
+        assert_eq!(state.notes["api"][0].text, "reduced heap to 512M");
+    }
+
+    #[test]
+    fn test_watch_changed_adds_and_removes() {
+        let mut state = RuntimeState::new(make_test_graph());
+        let watch = WatchInfo {
+            metric: "memory_bytes".to_string(),
+            op: crate::adapter::CompareOp::Gt,
+            threshold: 500_000_000.0,
+            action: "restart api".to_string(),
+            fired: 0,
+        };
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                1,
+                RuntimeEvent::WatchChanged {
+                    id: "api".to_string(),
+                    watch_id: "w1".to_string(),
+                    watch: Some(watch.clone()),
+                },
+            ),
+        );
+        reduce(
+            &mut state,
+            &make_envelope(
+                2,
+                RuntimeEvent::WatchChanged {
+                    id: "api".to_string(),
+                    watch_id: "w1".to_string(),
+                    watch: Some(WatchInfo { fired: 1, ..watch }),
+                },
+            ),
+        );
+        assert_eq!(state.watches["api"].len(), 1);
+        assert_eq!(state.watches["api"]["w1"].fired, 1);
+
+        reduce(
+            &mut state,
+            &make_envelope(
+                3,
+                RuntimeEvent::WatchChanged {
+                    id: "api".to_string(),
+                    watch_id: "w1".to_string(),
+                    watch: None,
+                },
+            ),
+        );
+        assert!(!state.watches.contains_key("api"));
+    }
+
+    #[test]
+    fn test_runs_ordered_by() {
+        use crate::state::RunsSortKey;
+        use std::time::Duration;
+
+        let mut state = RuntimeState::new(make_test_graph());
+        let start = SystemTime::now() - Duration::from_secs(100);
+        for (i, (name, secs, status)) in [
+            ("build", 30, RunStatus::Exited { code: Some(0) }),
+            ("lint", 5, RunStatus::Exited { code: Some(1) }),
+            ("test", 60, RunStatus::Running),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let started_at = start + Duration::from_secs(i as u64);
+            state.add_run(CommandRun {
+                id: format!("run-{}", i),
+                command_id: name.to_string(),
+                command: name.to_string(),
+                display_name: name.to_string(),
+                finished_at: (!status.is_running()).then(|| started_at + Duration::from_secs(secs)),
+                status,
+                started_at,
+                exit_code: None,
+                pid: None,
+            });
+        }
+        let names = |runs: Vec<&CommandRun>| -> Vec<String> {
+            runs.iter().map(|r| r.display_name.clone()).collect()
+        };
+
+        assert_eq!(
+            names(state.runs_ordered_by(RunsSortKey::StartTime, true)),
+            ["test", "lint", "build"]
+        );
+        assert_eq!(
+            names(state.runs_ordered_by(RunsSortKey::Duration, true)),
+            ["test", "build", "lint"]
+        );
+        assert_eq!(
+            names(state.runs_ordered_by(RunsSortKey::Status, false)),
+            ["test", "lint", "build"]
+        );
+        assert_eq!(
+            names(state.runs_ordered_by(RunsSortKey::Name, false)),
+            ["build", "lint", "test"]
+        );
+
+        let failed = RunStatus::Failed {
+            message: String::new(),
+        };
+        assert!(RunStatus::Exited { code: Some(1) }.matches_filter(&failed));
+        assert!(!RunStatus::Exited { code: Some(0) }.matches_filter(&failed));
+        assert!(
+            RunStatus::Exited { code: Some(0) }.matches_filter(&RunStatus::Exited { code: None })
+        );
+    }
 }
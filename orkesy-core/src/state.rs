@@ -1,9 +1,10 @@
 use std::collections::{BTreeMap, VecDeque};
 use std::time::SystemTime;
 
+use crate::adapter::CompareOp;
 use crate::command::{CommandRun, ProjectIndex, RunId};
 use crate::metrics::MetricsState;
-use crate::model::{RuntimeGraph, ServiceId};
+use crate::model::{HealthStatus, RuntimeGraph, ServiceId, ServiceStatus};
 use crate::unit::UnitMetrics;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -13,17 +14,61 @@ pub enum LogStream {
     System,
 }
 
+/// Appended to log lines cut short by `truncate_log_line`
+pub const TRUNCATED_MARKER: &str = "... [truncated]";
+
+/// Cuts `text` to at most `max_bytes` (on a char boundary) and appends
+/// `TRUNCATED_MARKER`. Lines within the limit are returned unchanged.
+pub fn truncate_log_line(mut text: String, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str(TRUNCATED_MARKER);
+    text
+}
+
 #[derive(Clone, Debug)]
 pub struct LogLine {
     pub at: SystemTime,
     pub service_id: String,
     pub stream: LogStream,
     pub text: String,
+    /// Times the line arrived in a row; repeats of the previous line are
+    /// counted here instead of stored (see `UnitLogOptions::deduplicate`)
+    pub repeat_count: u32,
+}
+
+/// Per-unit log buffer settings, from the unit config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitLogOptions {
+    /// Lines kept for the unit, overriding `LogStore::cap`
+    pub buffer_size: Option<usize>,
+    /// Count a line identical to the unit's previous one instead of storing it
+    pub deduplicate: bool,
+}
+
+impl Default for UnitLogOptions {
+    fn default() -> Self {
+        Self {
+            buffer_size: None,
+            deduplicate: true,
+        }
+    }
 }
 
+/// Lines kept per unit, and in the merged and per-run buffers
+pub const DEFAULT_LOG_BUFFER_SIZE: usize = 10_000;
+
 #[derive(Debug)]
 pub struct LogStore {
     pub cap: usize,
+    /// Units with non-default settings
+    pub unit_options: BTreeMap<ServiceId, UnitLogOptions>,
     pub per_service: BTreeMap<ServiceId, VecDeque<LogLine>>,
     pub merged: VecDeque<LogLine>,
     pub per_run: BTreeMap<RunId, VecDeque<LogLine>>,
@@ -33,27 +78,77 @@ impl LogStore {
     pub fn new(cap: usize) -> Self {
         Self {
             cap,
+            unit_options: BTreeMap::new(),
             per_service: BTreeMap::new(),
             merged: VecDeque::new(),
             per_run: BTreeMap::new(),
         }
     }
 
+    /// Capacity of a unit's buffer
+    pub fn unit_cap(&self, id: &str) -> usize {
+        self.unit_options
+            .get(id)
+            .and_then(|o| o.buffer_size)
+            .unwrap_or(self.cap)
+    }
+
+    /// Replaces the per-unit settings, dropping the oldest lines of buffers
+    /// that are now over their limit
+    pub fn set_unit_options(&mut self, options: BTreeMap<ServiceId, UnitLogOptions>) {
+        self.unit_options = options;
+        let ids: Vec<ServiceId> = self.per_service.keys().cloned().collect();
+        for id in ids {
+            let cap = self.unit_cap(&id);
+            if let Some(q) = self.per_service.get_mut(&id) {
+                let excess = q.len().saturating_sub(cap);
+                q.drain(..excess);
+            }
+        }
+    }
+
     pub fn push(&mut self, id: &ServiceId, line: LogLine) {
-        // Push to per-service buffer
+        let deduplicate = self.unit_options.get(id).is_none_or(|o| o.deduplicate);
+        let repeats = |last: &LogLine| {
+            deduplicate
+                && last.service_id == line.service_id
+                && last.stream == line.stream
+                && last.text == line.text
+        };
+
+        // Push to per-service buffer, or count a repeat of its last line
+        let cap = self.unit_cap(id);
         let q = self.per_service.entry(id.clone()).or_default();
-        q.push_back(line.clone());
-        while q.len() > self.cap {
-            q.pop_front();
+        match q.back_mut() {
+            Some(last) if repeats(last) => last.repeat_count += 1,
+            _ => {
+                q.push_back(line.clone());
+                while q.len() > cap {
+                    q.pop_front();
+                }
+            }
         }
 
-        // Push to merged buffer
-        self.merged.push_back(line);
-        while self.merged.len() > self.cap {
-            self.merged.pop_front();
+        // Same for the merged buffer, where another unit's line may be last
+        match self.merged.back_mut() {
+            Some(last) if repeats(last) => last.repeat_count += 1,
+            _ => {
+                self.merged.push_back(line);
+                while self.merged.len() > self.cap {
+                    self.merged.pop_front();
+                }
+            }
         }
     }
 
+    /// The last `n` lines for a service, oldest first.
+    pub fn tail(&self, id: &str, n: usize) -> Vec<LogLine> {
+        let Some(q) = self.per_service.get(id) else {
+            return Vec::new();
+        };
+        q.iter().skip(q.len().saturating_sub(n)).cloned().collect()
+    }
+
     pub fn clear(&mut self, id: &ServiceId) {
         self.per_service.remove(id);
         // Note: merged logs are not cleared per-service (would be expensive)
@@ -74,32 +169,178 @@ impl LogStore {
 
 const MAX_RUNS: usize = 200;
 
+/// Warnings clear once the metric drops this far below the threshold
+pub const WARNING_HYSTERESIS: f64 = 0.10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceWarning {
+    pub value: f64,
+    pub threshold: f64,
+}
+
+impl ResourceWarning {
+    pub fn clears_at(&self) -> f64 {
+        self.threshold * (1.0 - WARNING_HYSTERESIS)
+    }
+}
+
+/// A free-text note attached to a unit from the TUI
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserNote {
+    pub at: SystemTime,
+    pub text: String,
+}
+
+/// A metric watch registered with `AdapterCommand::Watch`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchInfo {
+    pub metric: String,
+    pub op: CompareOp,
+    pub threshold: f64,
+    /// `AdapterCommand::describe` of the action, e.g. `restart api`
+    pub action: String,
+    /// Times the action has run
+    pub fired: u32,
+}
+
+/// Health check results kept per unit unless `health_history_size` is set
+pub const DEFAULT_HEALTH_HISTORY_SIZE: usize = 100;
+
+/// One reported health check
+#[derive(Clone, Debug)]
+pub struct HealthCheckResult {
+    pub at: SystemTime,
+    pub outcome: HealthStatus,
+    /// How long the probe took; `None` for health reported by something
+    /// other than orkesy's own checks, e.g. the fake engine
+    pub latency_ms: Option<u64>,
+}
+
+/// A unit's most recent health check results, oldest first
+#[derive(Clone, Debug)]
+pub struct HealthHistory {
+    pub cap: usize,
+    pub results: VecDeque<HealthCheckResult>,
+}
+
+impl HealthHistory {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            cap,
+            results: VecDeque::new(),
+        }
+    }
+
+    /// Appends `result`, dropping the oldest ones beyond `cap`
+    pub fn push(&mut self, result: HealthCheckResult) {
+        self.results.push_back(result);
+        while self.results.len() > self.cap {
+            self.results.pop_front();
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RuntimeState {
     pub graph: RuntimeGraph,
     pub logs: LogStore,
     pub metrics: BTreeMap<ServiceId, UnitMetrics>,
+    /// Active resource warnings per unit, keyed by metric name
+    pub warnings: BTreeMap<ServiceId, BTreeMap<String, ResourceWarning>>,
+    /// Notes per unit, oldest first; kept across restarts and log clears
+    pub notes: BTreeMap<ServiceId, Vec<UserNote>>,
+    /// Metric watches per unit, keyed by watch id
+    pub watches: BTreeMap<ServiceId, BTreeMap<String, WatchInfo>>,
     pub last_event_id: u64,
     pub project: Option<ProjectIndex>,
+    /// Project files changed since `project` was indexed
+    pub project_stale: bool,
     pub runs: BTreeMap<RunId, CommandRun>,
     pub run_order: Vec<RunId>,
     pub metrics_series: MetricsState,
+    /// Named service groups from the config
+    pub groups: BTreeMap<String, Vec<ServiceId>>,
+    /// Times each unit has gone to Starting or Restarting
+    pub starts: BTreeMap<ServiceId, u32>,
+    pub health_history: BTreeMap<ServiceId, HealthHistory>,
+    /// `health_history_size` of units that set one
+    pub health_history_sizes: BTreeMap<ServiceId, usize>,
 }
 
 impl RuntimeState {
     pub fn new(graph: RuntimeGraph) -> Self {
         Self {
             graph,
-            logs: LogStore::new(10_000),
+            logs: LogStore::new(DEFAULT_LOG_BUFFER_SIZE),
             metrics: BTreeMap::new(),
+            warnings: BTreeMap::new(),
+            notes: BTreeMap::new(),
+            watches: BTreeMap::new(),
             last_event_id: 0,
             project: None,
+            project_stale: false,
             runs: BTreeMap::new(),
             run_order: Vec::new(),
             metrics_series: MetricsState::new(),
+            groups: BTreeMap::new(),
+            starts: BTreeMap::new(),
+            health_history: BTreeMap::new(),
+            health_history_sizes: BTreeMap::new(),
+        }
+    }
+
+    /// Health of the running units taken together: unhealthy if any is,
+    /// degraded if any is, healthy if all that report health are, and
+    /// unknown when none do. The reason names the units at fault.
+    pub fn overall_health(&self) -> HealthStatus {
+        let mut healthy = false;
+        let mut degraded = Vec::new();
+        let mut unhealthy = Vec::new();
+        for (id, node) in &self.graph.nodes {
+            if node.observed.status != ServiceStatus::Running {
+                continue;
+            }
+            match node.observed.health {
+                HealthStatus::Unknown => {}
+                HealthStatus::Healthy => healthy = true,
+                HealthStatus::Degraded { .. } => degraded.push(id.as_str()),
+                HealthStatus::Unhealthy { .. } => unhealthy.push(id.as_str()),
+            }
+        }
+
+        if !unhealthy.is_empty() {
+            HealthStatus::Unhealthy {
+                reason: format!("{} unhealthy", unhealthy.join(", ")),
+            }
+        } else if !degraded.is_empty() {
+            HealthStatus::Degraded {
+                reason: format!("{} degraded", degraded.join(", ")),
+            }
+        } else if healthy {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Unknown
         }
     }
 
+    /// Adds a check result to the unit's history
+    pub fn record_health(&mut self, id: &ServiceId, result: HealthCheckResult) {
+        let cap = self
+            .health_history_sizes
+            .get(id)
+            .copied()
+            .unwrap_or(DEFAULT_HEALTH_HISTORY_SIZE);
+        self.health_history
+            .entry(id.clone())
+            .or_insert_with(|| HealthHistory::new(cap))
+            .push(result);
+    }
+
+    /// Starts of `id` after its first one
+    pub fn restart_count(&self, id: &str) -> u32 {
+        self.starts.get(id).map_or(0, |n| n.saturating_sub(1))
+    }
+
     /// Add a command run to history (maintains MAX_RUNS limit)
     pub fn add_run(&mut self, run: CommandRun) {
         let run_id = run.id.clone();
@@ -122,4 +363,65 @@ impl RuntimeState {
             .filter_map(|id| self.runs.get(id))
             .collect()
     }
+
+    /// Runs sorted by `key`; ties keep the most recent run first
+    pub fn runs_ordered_by(&self, key: RunsSortKey, desc: bool) -> Vec<&CommandRun> {
+        let mut runs = self.runs_ordered();
+        match key {
+            // `run_order` is already newest first
+            RunsSortKey::StartTime => {
+                if !desc {
+                    runs.reverse();
+                }
+            }
+            RunsSortKey::Duration => runs.sort_by(|a, b| {
+                let order = a.duration().cmp(&b.duration());
+                if desc { order.reverse() } else { order }
+            }),
+            RunsSortKey::Status => runs.sort_by(|a, b| {
+                let order = a.status.sort_rank().cmp(&b.status.sort_rank());
+                if desc { order.reverse() } else { order }
+            }),
+            RunsSortKey::Name => runs.sort_by(|a, b| {
+                let order = a.display_name.cmp(&b.display_name);
+                if desc { order.reverse() } else { order }
+            }),
+        }
+        runs
+    }
+}
+
+/// Sort order for the Runs list
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RunsSortKey {
+    #[default]
+    StartTime,
+    Duration,
+    Status,
+    Name,
+}
+
+impl RunsSortKey {
+    pub fn next(self) -> Self {
+        match self {
+            RunsSortKey::StartTime => RunsSortKey::Duration,
+            RunsSortKey::Duration => RunsSortKey::Status,
+            RunsSortKey::Status => RunsSortKey::Name,
+            RunsSortKey::Name => RunsSortKey::StartTime,
+        }
+    }
+
+    /// Whether this key sorts descending by default (newest, longest first)
+    pub fn default_desc(self) -> bool {
+        matches!(self, RunsSortKey::StartTime | RunsSortKey::Duration)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RunsSortKey::StartTime => "start",
+            RunsSortKey::Duration => "duration",
+            RunsSortKey::Status => "status",
+            RunsSortKey::Name => "name",
+        }
+    }
 }
@@ -11,6 +11,14 @@ pub enum UnitKind {
     Process,
     Docker,
     Generic,
+    /// Run `start` on a remote host over SSH
+    Ssh {
+        host: String,
+        #[serde(default)]
+        user: Option<String>,
+        #[serde(default)]
+        identity_file: Option<PathBuf>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -45,21 +53,148 @@ pub enum HealthCheck {
         port: u16,
         #[serde(default = "default_interval_ms")]
         interval_ms: u64,
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
     Http {
         url: String,
+        /// Request method; `GET` when unset
+        #[serde(default)]
+        method: Option<String>,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        /// Status the response must have; any 2xx when unset
+        #[serde(default)]
+        expected_status: Option<u16>,
+        /// Text the response body must contain
+        #[serde(default)]
+        body_contains: Option<String>,
         #[serde(default = "default_interval_ms")]
         interval_ms: u64,
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
         #[serde(default = "default_timeout_ms")]
         timeout_ms: u64,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
     Exec {
         command: String,
         #[serde(default = "default_interval_ms")]
         interval_ms: u64,
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
+    },
+    /// The standard `grpc.health.v1.Health/Check` call; an empty `service`
+    /// asks for the server's overall health
+    Grpc {
+        #[serde(default = "default_health_host")]
+        host: String,
+        #[serde(default = "default_health_port")]
+        port: u16,
+        #[serde(default)]
+        service: String,
+        #[serde(default = "default_interval_ms")]
+        interval_ms: u64,
+        #[serde(default)]
+        max_interval_ms: Option<u64>,
+        #[serde(default = "default_timeout_ms")]
+        timeout_ms: u64,
+        #[serde(default)]
+        failure_threshold: Option<u32>,
+        #[serde(default)]
+        success_threshold: Option<u32>,
     },
 }
 
+pub const DEFAULT_HEALTH_FAILURE_THRESHOLD: u32 = 3;
+pub const DEFAULT_HEALTH_SUCCESS_THRESHOLD: u32 = 1;
+/// Cap for the probe interval, which doubles on each consecutive failure
+pub const DEFAULT_HEALTH_MAX_INTERVAL_MS: u64 = 60_000;
+
+impl HealthCheck {
+    /// Consecutive failures before the unit is reported unhealthy; fewer
+    /// report it as degraded
+    pub fn failure_threshold(&self) -> u32 {
+        let (Self::Tcp {
+            failure_threshold, ..
+        }
+        | Self::Http {
+            failure_threshold, ..
+        }
+        | Self::Exec {
+            failure_threshold, ..
+        }
+        | Self::Grpc {
+            failure_threshold, ..
+        }) = self;
+        failure_threshold
+            .unwrap_or(DEFAULT_HEALTH_FAILURE_THRESHOLD)
+            .max(1)
+    }
+
+    /// Milliseconds between probes
+    pub fn interval_ms(&self) -> u64 {
+        let (Self::Tcp { interval_ms, .. }
+        | Self::Http { interval_ms, .. }
+        | Self::Exec { interval_ms, .. }
+        | Self::Grpc { interval_ms, .. }) = self;
+        *interval_ms
+    }
+
+    /// Longest the interval grows to while probes keep failing
+    pub fn max_interval_ms(&self) -> u64 {
+        let (Self::Tcp {
+            max_interval_ms, ..
+        }
+        | Self::Http {
+            max_interval_ms, ..
+        }
+        | Self::Exec {
+            max_interval_ms, ..
+        }
+        | Self::Grpc {
+            max_interval_ms, ..
+        }) = self;
+        max_interval_ms
+            .unwrap_or(DEFAULT_HEALTH_MAX_INTERVAL_MS)
+            .max(self.interval_ms())
+    }
+
+    /// Consecutive successes before a failing unit is healthy again
+    pub fn success_threshold(&self) -> u32 {
+        let (Self::Tcp {
+            success_threshold, ..
+        }
+        | Self::Http {
+            success_threshold, ..
+        }
+        | Self::Exec {
+            success_threshold, ..
+        }
+        | Self::Grpc {
+            success_threshold, ..
+        }) = self;
+        success_threshold
+            .unwrap_or(DEFAULT_HEALTH_SUCCESS_THRESHOLD)
+            .max(1)
+    }
+}
+
+fn default_health_host() -> String {
+    "127.0.0.1".to_string()
+}
+
 fn default_health_port() -> u16 {
     8000
 }
@@ -89,20 +224,105 @@ pub struct Unit {
     #[serde(default)]
     pub env: BTreeMap<String, String>,
 
+    /// Units whose env was merged in before this unit's own `env`
+    #[serde(default)]
+    pub env_from: Option<Vec<UnitId>>,
+
+    /// Vars in `env` inherited through `env_from`, keyed to the unit that
+    /// defined them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inherited_env: BTreeMap<String, UnitId>,
+
+    /// `.env` file loaded when the process is spawned; keys in `env` win
+    #[serde(default)]
+    pub env_file: Option<PathBuf>,
+
     #[serde(default)]
     pub install: Vec<String>,
 
     pub start: String,
 
+    /// Run `start` through `sh -c` (or `cmd /C` on Windows) instead of
+    /// splitting it on whitespace. Needed for pipes, redirects and `&&`.
+    #[serde(default)]
+    pub shell: Option<bool>,
+
+    /// Run the process on a pseudo-terminal so it keeps its colors and line
+    /// buffering (Unix only; other platforms fall back to pipes)
+    #[serde(default)]
+    pub pty: bool,
+
+    /// Image for `kind: docker` units run by the Docker adapter; `start`, if
+    /// set, replaces the image's command
+    #[serde(default)]
+    pub docker_image: Option<String>,
+
+    /// Ports the Docker adapter publishes, as `host:container` or
+    /// `host:container/udp`
+    #[serde(default)]
+    pub docker_ports: Vec<String>,
+
     #[serde(default)]
     pub stop: StopBehavior,
 
+    /// How long a stop signal gets before the process group is killed
+    #[serde(default = "default_stop_timeout_ms")]
+    pub stop_timeout_ms: u64,
+
     #[serde(default)]
     pub logs: Option<String>,
 
+    /// Render ANSI color codes in log output. `None` auto-detects per line,
+    /// `Some(false)` strips the escape sequences instead.
+    #[serde(default)]
+    pub render_ansi: Option<bool>,
+
+    /// Longer stdout/stderr lines are truncated (default 4096 bytes)
+    #[serde(default)]
+    pub max_log_line_bytes: Option<usize>,
+
+    /// Lines of output kept in memory for the unit (default 10 000)
+    #[serde(default)]
+    pub log_buffer_size: Option<usize>,
+
+    /// Fold a line identical to the previous one into a repeat count
+    #[serde(default = "default_deduplicate_logs")]
+    pub deduplicate_logs: bool,
+
+    /// Resource limits applied before exec (Linux only), e.g. `nofile: 65536`.
+    /// Supported names: nofile, nproc, stack, data, core, memlock.
+    #[serde(default)]
+    pub ulimits: Option<BTreeMap<String, u64>>,
+
+    /// CPU/memory thresholds that raise resource warnings
+    #[serde(default)]
+    pub alerts: Option<ResourceAlerts>,
+
+    /// File holding the unit's PID. A live PID found here at start is
+    /// adopted instead of spawning a new process.
+    #[serde(default)]
+    pub pid_file: Option<PathBuf>,
+
+    /// Extra destination for the unit's log lines
+    #[serde(default)]
+    pub log_sink: Option<LogSinkConfig>,
+
     #[serde(default)]
     pub health: Option<HealthCheck>,
 
+    /// Health check results kept for the Inspect view (default 100)
+    #[serde(default)]
+    pub health_history_size: Option<usize>,
+
+    /// Check that must pass after the unit is running before units that
+    /// depend on it are started
+    #[serde(default)]
+    pub ready_when: Option<HealthCheck>,
+
+    /// How long `ready_when` may take before dependents are given up on
+    #[serde(default = "default_ready_timeout_ms")]
+    pub ready_timeout_ms: u64,
+
     #[serde(default)]
     pub description: Option<String>,
 
@@ -117,10 +337,108 @@ fn default_autostart() -> bool {
     false
 }
 
+fn default_deduplicate_logs() -> bool {
+    true
+}
+
+pub const DEFAULT_STOP_TIMEOUT_MS: u64 = 5000;
+pub const DEFAULT_READY_TIMEOUT_MS: u64 = 30_000;
+
+fn default_stop_timeout_ms() -> u64 {
+    DEFAULT_STOP_TIMEOUT_MS
+}
+
+fn default_ready_timeout_ms() -> u64 {
+    DEFAULT_READY_TIMEOUT_MS
+}
+
+pub const DEFAULT_MAX_LOG_LINE_BYTES: usize = 4096;
+
 impl Unit {
     pub fn display_name(&self) -> &str {
         self.name.as_deref().unwrap_or(&self.id)
     }
+
+    pub fn log_line_limit(&self) -> usize {
+        self.max_log_line_bytes
+            .unwrap_or(DEFAULT_MAX_LOG_LINE_BYTES)
+    }
+
+    pub fn uses_shell(&self) -> bool {
+        self.shell.unwrap_or(false)
+    }
+
+    /// Host the unit's port and TCP checks are reached on: the SSH host for
+    /// remote units, otherwise the loopback address
+    pub fn host(&self) -> &str {
+        match &self.kind {
+            UnitKind::Ssh { host, .. } => host,
+            _ => "127.0.0.1",
+        }
+    }
+
+    /// Units with a port but no health check are only considered running
+    /// once the port accepts connections.
+    pub fn waits_for_port(&self) -> bool {
+        self.port.is_some() && self.health.is_none() && self.ready_when.is_none()
+    }
+
+    /// Units that are reported starting until their port binds or their
+    /// `ready_when` check passes
+    pub fn gates_running(&self) -> bool {
+        self.waits_for_port() || self.ready_when.is_some()
+    }
+
+    /// Returns true if `start` contains syntax that only a shell understands.
+    /// `$VAR` doesn't count, it is expanded by [`interpolate`].
+    pub fn has_shell_syntax(&self) -> bool {
+        const TOKENS: [&str; 6] = ["|", ">", "<", "&&", "||", "`"];
+        TOKENS.iter().any(|t| self.start.contains(t))
+    }
+}
+
+/// Where a unit's log lines are forwarded, in addition to the TUI
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogSinkConfig {
+    /// systemd journal, readable with `journalctl -t <identifier>`
+    Journal { identifier: String },
+}
+
+pub const METRIC_CPU_PERCENT: &str = "cpu_percent";
+pub const METRIC_MEMORY_BYTES: &str = "memory_bytes";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceAlerts {
+    #[serde(default)]
+    pub cpu_warn_percent: Option<f64>,
+    #[serde(default)]
+    pub cpu_crit_percent: Option<f64>,
+    #[serde(default)]
+    pub memory_warn_bytes: Option<u64>,
+    #[serde(default)]
+    pub memory_crit_bytes: Option<u64>,
+}
+
+impl ResourceAlerts {
+    /// Configured `(metric, threshold)` pairs, warning levels first
+    pub fn thresholds(&self) -> Vec<(&'static str, f64)> {
+        [
+            (METRIC_CPU_PERCENT, self.cpu_warn_percent),
+            (METRIC_CPU_PERCENT, self.cpu_crit_percent),
+            (
+                METRIC_MEMORY_BYTES,
+                self.memory_warn_bytes.map(|b| b as f64),
+            ),
+            (
+                METRIC_MEMORY_BYTES,
+                self.memory_crit_bytes.map(|b| b as f64),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(metric, threshold)| threshold.map(|t| (metric, t)))
+        .collect()
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -147,6 +465,44 @@ impl UnitStatus {
     pub fn is_stopped(&self) -> bool {
         matches!(self, UnitStatus::Stopped | UnitStatus::Exited { .. })
     }
+
+    /// Lowercase variant name, e.g. `running` or `errored`
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitStatus::Unknown => "unknown",
+            UnitStatus::Starting => "starting",
+            UnitStatus::Running => "running",
+            UnitStatus::Stopping => "stopping",
+            UnitStatus::Stopped => "stopped",
+            UnitStatus::Exited { .. } => "exited",
+            UnitStatus::Errored { .. } => "errored",
+        }
+    }
+
+    /// Parses a variant name case-insensitively; `exited` and `errored` come
+    /// back without an exit code or message
+    pub fn parse(name: &str) -> Option<Self> {
+        let status = match name.to_ascii_lowercase().as_str() {
+            "unknown" => UnitStatus::Unknown,
+            "starting" => UnitStatus::Starting,
+            "running" => UnitStatus::Running,
+            "stopping" => UnitStatus::Stopping,
+            "stopped" => UnitStatus::Stopped,
+            "exited" => UnitStatus::Exited { code: None },
+            "errored" => UnitStatus::Errored {
+                message: String::new(),
+            },
+            _ => return None,
+        };
+        Some(status)
+    }
+
+    /// Whether `self` is the same variant as `other`, ignoring exit codes and
+    /// messages. `stopped` and `exited` match each other.
+    pub fn matches(&self, other: &UnitStatus) -> bool {
+        (self.is_stopped() && other.is_stopped())
+            || std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -168,6 +524,21 @@ pub struct UnitMetrics {
     pub memory_bytes: u64,
     pub uptime_secs: u64,
     pub pid: Option<u32>,
+    /// Bytes the unit's process has read since it started, including cache hits
+    pub io_read_bytes: u64,
+    /// Bytes the unit's process has written since it started
+    pub io_write_bytes: u64,
+}
+
+impl UnitMetrics {
+    /// Value of a named metric (`cpu_percent`, `memory_bytes`)
+    pub fn value(&self, metric: &str) -> Option<f64> {
+        match metric {
+            METRIC_CPU_PERCENT => Some(self.cpu_percent as f64),
+            METRIC_MEMORY_BYTES => Some(self.memory_bytes as f64),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -194,3 +565,325 @@ pub enum EdgeKind {
     Produces,
     Consumes,
 }
+
+/// A `DependsOn` cycle, e.g. `["a", "b", "a"]`
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("cyclic dependency detected: {}", .cycle.join(" -> "))]
+pub struct CycleError {
+    pub cycle: Vec<UnitId>,
+}
+
+/// Checks that the `DependsOn` edges form no cycle (Kahn's algorithm) and
+/// hands them back unchanged, or names the units of one cycle.
+pub fn check_acyclic(edges: Vec<UnitEdge>) -> Result<Vec<UnitEdge>, CycleError> {
+    let depends_on: Vec<&UnitEdge> = edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::DependsOn)
+        .collect();
+
+    // Unplaced dependencies per unit
+    let mut pending: BTreeMap<&UnitId, usize> = BTreeMap::new();
+    for edge in &depends_on {
+        *pending.entry(&edge.from).or_default() += 1;
+        pending.entry(&edge.to).or_default();
+    }
+
+    let mut ready: Vec<&UnitId> = pending
+        .iter()
+        .filter(|(_, n)| **n == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    while let Some(id) = ready.pop() {
+        pending.remove(id);
+        for edge in depends_on.iter().filter(|e| e.to == *id) {
+            if let Some(n) = pending.get_mut(&edge.from) {
+                *n -= 1;
+                if *n == 0 {
+                    ready.push(&edge.from);
+                }
+            }
+        }
+    }
+
+    // Every unit left waits on another one left, so following dependencies
+    // from any of them runs into a cycle
+    let Some(mut node) = pending.keys().next().copied() else {
+        return Ok(edges);
+    };
+    let mut path: Vec<&UnitId> = Vec::new();
+    while !path.contains(&node) {
+        path.push(node);
+        node = depends_on
+            .iter()
+            .find(|e| e.from == *node && pending.contains_key(&e.to))
+            .map(|e| &e.to)
+            .expect("a unit left unplaced has an unplaced dependency");
+    }
+    let start = path.iter().position(|id| *id == node).unwrap_or(0);
+    let mut cycle: Vec<UnitId> = path[start..].iter().map(|id| id.to_string()).collect();
+    cycle.push(node.clone());
+    Err(CycleError { cycle })
+}
+
+/// Orders `ids` so every unit comes after the units among `ids` it
+/// `DependsOn`. Units are taken in rounds of those whose dependencies are
+/// all placed, in input order, so independent units aren't queued behind a
+/// dependency chain. Units on a cycle are appended last.
+pub fn topological_order(ids: &[UnitId], edges: &[UnitEdge]) -> Vec<UnitId> {
+    let mut remaining: Vec<&UnitId> = ids.iter().collect();
+    let mut order: Vec<UnitId> = Vec::with_capacity(ids.len());
+
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<&UnitId>, Vec<&UnitId>) = remaining.iter().partition(|id| {
+            !edges.iter().any(|e| {
+                e.kind == EdgeKind::DependsOn
+                    && e.from == ***id
+                    && e.to != ***id
+                    && remaining.contains(&&e.to)
+            })
+        });
+        if ready.is_empty() {
+            order.extend(blocked.into_iter().cloned());
+            break;
+        }
+        order.extend(ready.into_iter().cloned());
+        remaining = blocked;
+    }
+    order
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum InterpolationError {
+    /// `$NAME` or `${NAME}` with no such variable and no `:-` default
+    #[error("variable '{name}' is not set")]
+    Unset { name: String },
+    /// `${` without a closing `}`
+    #[error("unterminated '${{' in interpolation")]
+    Unterminated,
+}
+
+/// Expands `$VAR`, `${VAR}` and `${VAR:-default}` from `env`. `$$` is a
+/// literal `$`, as is a `$` not followed by a name or `{`.
+pub fn interpolate(
+    cmd: &str,
+    env: &BTreeMap<String, String>,
+) -> Result<String, InterpolationError> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let mut out = String::with_capacity(cmd.len());
+    let mut rest = cmd;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+
+        if let Some(after) = after.strip_prefix('$') {
+            out.push('$');
+            rest = after;
+        } else if let Some(inner) = after.strip_prefix('{') {
+            // Find the matching brace; defaults may contain `${...}` themselves
+            let mut depth = 1;
+            let end = inner
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })
+                .map(|(end, _)| end)
+                .ok_or(InterpolationError::Unterminated)?;
+            let (name, default) = match inner[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&inner[..end], None),
+            };
+            match (env.get(name).filter(|v| !v.is_empty()), default) {
+                (Some(value), _) => out.push_str(value),
+                (None, Some(default)) => out.push_str(&interpolate(default, env)?),
+                (None, None) if env.contains_key(name) => {}
+                (None, None) => {
+                    return Err(InterpolationError::Unset {
+                        name: name.to_string(),
+                    });
+                }
+            }
+            rest = &inner[end + 1..];
+        } else {
+            let len = after
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(after.len());
+            let name = &after[..len];
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                out.push('$');
+                rest = after;
+                continue;
+            }
+            let value = env.get(name).ok_or_else(|| InterpolationError::Unset {
+                name: name.to_string(),
+            })?;
+            out.push_str(value);
+            rest = &after[len..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depends_on(from: &str, to: &str) -> UnitEdge {
+        UnitEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: EdgeKind::DependsOn,
+        }
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let ids: Vec<UnitId> = ["web", "api", "db", "cache"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let edges = vec![depends_on("web", "api"), depends_on("api", "db")];
+
+        assert_eq!(
+            topological_order(&ids, &edges),
+            ["db", "cache", "api", "web"]
+        );
+
+        // Dependencies outside `ids` don't hold anything back
+        let ids = vec!["web".to_string(), "api".to_string()];
+        assert_eq!(
+            topological_order(&ids, &[depends_on("api", "db")]),
+            ["web", "api"]
+        );
+        assert_eq!(topological_order(&ids, &edges), ["api", "web"]);
+
+        // A cycle keeps its input order after the rest
+        let cyclic = vec![depends_on("web", "api"), depends_on("api", "web")];
+        let ids: Vec<UnitId> = ["web", "api", "db"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(topological_order(&ids, &cyclic), ["db", "web", "api"]);
+    }
+
+    #[test]
+    fn test_check_acyclic() {
+        let chain = vec![depends_on("web", "api"), depends_on("api", "db")];
+        assert_eq!(check_acyclic(chain.clone()), Ok(chain));
+
+        let err = check_acyclic(vec![
+            depends_on("web", "api"),
+            depends_on("api", "db"),
+            depends_on("db", "web"),
+            depends_on("cache", "db"),
+        ])
+        .unwrap_err();
+        assert_eq!(err.cycle, ["api", "db", "web", "api"]);
+        assert_eq!(
+            err.to_string(),
+            "cyclic dependency detected: api -> db -> web -> api"
+        );
+
+        let err = check_acyclic(vec![depends_on("a", "a")]).unwrap_err();
+        assert_eq!(err.cycle, ["a", "a"]);
+
+        // Other edge kinds may point both ways
+        let talks = UnitEdge {
+            from: "db".to_string(),
+            to: "web".to_string(),
+            kind: EdgeKind::TalksTo,
+        };
+        assert!(check_acyclic(vec![depends_on("web", "db"), talks]).is_ok());
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let env: BTreeMap<String, String> =
+            [("PORT", "8080"), ("HOST", "localhost"), ("EMPTY", "")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+        assert_eq!(
+            interpolate("node server.js --port ${PORT} --host $HOST", &env).unwrap(),
+            "node server.js --port 8080 --host localhost"
+        );
+        assert_eq!(
+            interpolate("$PORT/$HOST.x", &env).unwrap(),
+            "8080/localhost.x"
+        );
+        assert_eq!(
+            interpolate(
+                "${LEVEL:-info} ${EMPTY:-fallback} ${MISSING:-${PORT}}",
+                &env
+            )
+            .unwrap(),
+            "info fallback 8080"
+        );
+        assert_eq!(interpolate("[${EMPTY}]", &env).unwrap(), "[]");
+        assert_eq!(
+            interpolate("cost $$5 $ $1 end$", &env).unwrap(),
+            "cost $5 $ $1 end$"
+        );
+        assert_eq!(
+            interpolate("serve --port $NOPE", &env),
+            Err(InterpolationError::Unset {
+                name: "NOPE".to_string()
+            })
+        );
+        assert_eq!(
+            interpolate("${NOPE}", &env).unwrap_err().to_string(),
+            "variable 'NOPE' is not set"
+        );
+        assert_eq!(
+            interpolate("${PORT", &env),
+            Err(InterpolationError::Unterminated)
+        );
+        assert_eq!(
+            InterpolationError::Unterminated.to_string(),
+            "unterminated '${' in interpolation"
+        );
+    }
+
+    #[test]
+    fn test_unit_status_parse() {
+        assert!(matches!(
+            UnitStatus::parse("Running"),
+            Some(UnitStatus::Running)
+        ));
+        assert!(matches!(
+            UnitStatus::parse("ERRORED"),
+            Some(UnitStatus::Errored { .. })
+        ));
+        assert!(UnitStatus::parse("healthy").is_none());
+
+        let exited = UnitStatus::Exited { code: Some(1) };
+        assert!(UnitStatus::parse("stopped").unwrap().matches(&exited));
+        assert!(UnitStatus::parse("exited").unwrap().matches(&exited));
+        assert!(!UnitStatus::Running.matches(&UnitStatus::Starting));
+        assert_eq!(exited.name(), "exited");
+    }
+
+    #[test]
+    fn test_health_max_interval() {
+        let check = |json: &str| -> HealthCheck { serde_json::from_str(json).unwrap() };
+
+        assert_eq!(
+            check(r#"{"type": "tcp"}"#).max_interval_ms(),
+            DEFAULT_HEALTH_MAX_INTERVAL_MS
+        );
+        assert_eq!(
+            check(r#"{"type": "exec", "command": "true", "max_interval_ms": 8000}"#)
+                .max_interval_ms(),
+            8000
+        );
+        // Never below the base interval
+        assert_eq!(
+            check(r#"{"type": "tcp", "interval_ms": 90000}"#).max_interval_ms(),
+            90000
+        );
+    }
+}